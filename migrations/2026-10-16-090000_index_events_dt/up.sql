@@ -0,0 +1,2 @@
+-- Speed up reading the events of a period
+CREATE INDEX events_dt ON events (dt)
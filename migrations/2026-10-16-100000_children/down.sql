@@ -0,0 +1,21 @@
+-- Events of other children that share a timestamp with an earlier child are dropped
+CREATE TABLE events_old (
+    id INTEGER NOT NULL PRIMARY KEY,
+    dt DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP UNIQUE,
+    urine BOOLEAN NOT NULL DEFAULT FALSE,
+    stool BOOLEAN NOT NULL DEFAULT FALSE,
+    skin2skin INTEGER NOT NULL DEFAULT 0,
+    breastfeed INTEGER NOT NULL DEFAULT 0,
+    breastmilk INTEGER NOT NULL DEFAULT 0,
+    formula INTEGER NOT NULL DEFAULT 0,
+    pump INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT OR IGNORE INTO events_old (id, dt, urine, stool, skin2skin, breastfeed, breastmilk, formula, pump)
+SELECT id, dt, urine, stool, skin2skin, breastfeed, breastmilk, formula, pump FROM events ORDER BY child_id, id;
+
+DROP TABLE events;
+ALTER TABLE events_old RENAME TO events;
+CREATE INDEX events_dt ON events (dt);
+
+DROP TABLE children;
@@ -0,0 +1,32 @@
+CREATE TABLE children (
+    id INTEGER NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL,
+    birth_date DATE,
+    sex TEXT,
+    birth_weight INTEGER
+);
+
+-- Existing events belong to a default child, which can be renamed later
+INSERT INTO children (id, name) VALUES (1, 'Baby');
+
+-- SQLite cannot change constraints in place, so the events table is rebuilt with timestamps unique per child
+CREATE TABLE events_new (
+    id INTEGER NOT NULL PRIMARY KEY,
+    child_id INTEGER NOT NULL DEFAULT 1 REFERENCES children (id),
+    dt DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    urine BOOLEAN NOT NULL DEFAULT FALSE,
+    stool BOOLEAN NOT NULL DEFAULT FALSE,
+    skin2skin INTEGER NOT NULL DEFAULT 0,
+    breastfeed INTEGER NOT NULL DEFAULT 0,
+    breastmilk INTEGER NOT NULL DEFAULT 0,
+    formula INTEGER NOT NULL DEFAULT 0,
+    pump INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (child_id, dt)
+);
+
+INSERT INTO events_new (id, child_id, dt, urine, stool, skin2skin, breastfeed, breastmilk, formula, pump)
+SELECT id, 1, dt, urine, stool, skin2skin, breastfeed, breastmilk, formula, pump FROM events;
+
+DROP TABLE events;
+ALTER TABLE events_new RENAME TO events;
+CREATE INDEX events_dt ON events (child_id, dt);
@@ -0,0 +1,11 @@
+-- Weights are in grams, lengths and head circumferences in millimetres
+CREATE TABLE measurements (
+    id INTEGER NOT NULL PRIMARY KEY,
+    child_id INTEGER NOT NULL DEFAULT 1 REFERENCES children (id),
+    dt DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    weight INTEGER,
+    length INTEGER,
+    head_circumference INTEGER
+);
+
+CREATE INDEX measurements_child_dt ON measurements (child_id, dt);
@@ -0,0 +1,13 @@
+-- A session without an end is in progress
+CREATE TABLE sleep_sessions (
+    id INTEGER NOT NULL PRIMARY KEY,
+    child_id INTEGER NOT NULL DEFAULT 1 REFERENCES children (id),
+    start_dt DATETIME NOT NULL,
+    end_dt DATETIME,
+    CHECK (end_dt IS NULL OR end_dt > start_dt)
+);
+
+CREATE INDEX sleep_sessions_child_start ON sleep_sessions (child_id, start_dt);
+
+-- A child can only be asleep once at a time
+CREATE UNIQUE INDEX sleep_sessions_open ON sleep_sessions (child_id) WHERE end_dt IS NULL;
@@ -0,0 +1,3 @@
+DROP TABLE medication_schedules;
+DROP TABLE doses;
+DROP TABLE medications;
@@ -0,0 +1,30 @@
+-- Intervals are in minutes, daily limits apply to any 24 hours
+CREATE TABLE medications (
+    id INTEGER NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    unit TEXT NOT NULL,
+    dose REAL NOT NULL,
+    min_interval_minutes INTEGER,
+    max_daily_doses INTEGER,
+    max_daily_amount REAL
+);
+
+CREATE TABLE doses (
+    id INTEGER NOT NULL PRIMARY KEY,
+    child_id INTEGER NOT NULL DEFAULT 1 REFERENCES children (id),
+    medication_id INTEGER NOT NULL REFERENCES medications (id),
+    dt DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    amount REAL NOT NULL
+);
+
+CREATE INDEX doses_child_medication_dt ON doses (child_id, medication_id, dt);
+
+-- A scheduled medication is due every interval, counting from the first dose
+CREATE TABLE medication_schedules (
+    id INTEGER NOT NULL PRIMARY KEY,
+    child_id INTEGER NOT NULL DEFAULT 1 REFERENCES children (id),
+    medication_id INTEGER NOT NULL REFERENCES medications (id),
+    first_dt DATETIME NOT NULL,
+    interval_minutes INTEGER NOT NULL,
+    UNIQUE (child_id, medication_id)
+);
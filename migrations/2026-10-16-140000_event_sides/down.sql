@@ -0,0 +1,5 @@
+ALTER TABLE events DROP COLUMN pump_duration;
+ALTER TABLE events DROP COLUMN pump_right;
+ALTER TABLE events DROP COLUMN pump_left;
+ALTER TABLE events DROP COLUMN breastfeed_right;
+ALTER TABLE events DROP COLUMN breastfeed_left;
@@ -0,0 +1,7 @@
+-- Durations are in minutes and volumes in millilitres. The breastfeed and pump columns stay the totals, so existing
+-- events keep their totals with the sides unknown.
+ALTER TABLE events ADD COLUMN breastfeed_left INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE events ADD COLUMN breastfeed_right INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE events ADD COLUMN pump_left INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE events ADD COLUMN pump_right INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE events ADD COLUMN pump_duration INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+DROP TABLE diaper_details;
@@ -0,0 +1,9 @@
+-- Optional details of a diaper, stored by code; an event has at most one row
+CREATE TABLE diaper_details (
+    event_id INTEGER NOT NULL PRIMARY KEY REFERENCES events (id),
+    stool_color TEXT,
+    stool_consistency TEXT,
+    stool_size TEXT,
+    urine_amount TEXT,
+    urate_crystals BOOLEAN NOT NULL DEFAULT FALSE
+);
@@ -0,0 +1,5 @@
+DROP TRIGGER event_notes_update;
+DROP TRIGGER event_notes_delete;
+DROP TRIGGER event_notes_insert;
+DROP TABLE event_notes_fts;
+DROP TABLE event_notes;
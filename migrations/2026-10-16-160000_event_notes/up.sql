@@ -0,0 +1,25 @@
+-- Free-text notes on events, at most one per event
+CREATE TABLE event_notes (
+    event_id INTEGER NOT NULL PRIMARY KEY REFERENCES events (id),
+    text TEXT NOT NULL
+);
+
+-- Full-text index of the notes, kept in sync by the triggers below
+CREATE VIRTUAL TABLE event_notes_fts USING fts5 (
+    text,
+    content = 'event_notes',
+    content_rowid = 'event_id'
+);
+
+CREATE TRIGGER event_notes_insert AFTER INSERT ON event_notes BEGIN
+    INSERT INTO event_notes_fts (rowid, text) VALUES (new.event_id, new.text);
+END;
+
+CREATE TRIGGER event_notes_delete AFTER DELETE ON event_notes BEGIN
+    INSERT INTO event_notes_fts (event_notes_fts, rowid, text) VALUES ('delete', old.event_id, old.text);
+END;
+
+CREATE TRIGGER event_notes_update AFTER UPDATE ON event_notes BEGIN
+    INSERT INTO event_notes_fts (event_notes_fts, rowid, text) VALUES ('delete', old.event_id, old.text);
+    INSERT INTO event_notes_fts (rowid, text) VALUES (new.event_id, new.text);
+END;
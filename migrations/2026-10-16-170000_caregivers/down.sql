@@ -0,0 +1,3 @@
+DROP INDEX events_caregiver_id;
+ALTER TABLE events DROP COLUMN caregiver_id;
+DROP TABLE caregivers;
@@ -0,0 +1,8 @@
+CREATE TABLE caregivers (
+    id INTEGER NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE
+);
+
+-- Events recorded before caregivers were introduced keep an unknown caregiver
+ALTER TABLE events ADD COLUMN caregiver_id INTEGER REFERENCES caregivers (id);
+CREATE INDEX events_caregiver_id ON events (caregiver_id);
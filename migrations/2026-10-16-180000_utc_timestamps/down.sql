@@ -0,0 +1,41 @@
+-- Timestamps go back to the local time zone, which keeps events recorded in other zones apart
+CREATE TABLE events_new (
+    id INTEGER NOT NULL PRIMARY KEY,
+    child_id INTEGER NOT NULL DEFAULT 1 REFERENCES children (id),
+    dt DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    urine BOOLEAN NOT NULL DEFAULT FALSE,
+    stool BOOLEAN NOT NULL DEFAULT FALSE,
+    skin2skin INTEGER NOT NULL DEFAULT 0,
+    breastfeed INTEGER NOT NULL DEFAULT 0,
+    breastmilk INTEGER NOT NULL DEFAULT 0,
+    formula INTEGER NOT NULL DEFAULT 0,
+    pump INTEGER NOT NULL DEFAULT 0,
+    breastfeed_left INTEGER NOT NULL DEFAULT 0,
+    breastfeed_right INTEGER NOT NULL DEFAULT 0,
+    pump_left INTEGER NOT NULL DEFAULT 0,
+    pump_right INTEGER NOT NULL DEFAULT 0,
+    pump_duration INTEGER NOT NULL DEFAULT 0,
+    caregiver_id INTEGER REFERENCES caregivers (id),
+    UNIQUE (child_id, dt)
+);
+
+INSERT INTO events_new (
+    id, child_id, dt, urine, stool, skin2skin, breastfeed, breastmilk, formula, pump, breastfeed_left,
+    breastfeed_right, pump_left, pump_right, pump_duration, caregiver_id
+)
+SELECT
+    id, child_id, datetime(dt, 'localtime') || substr(dt, 20), urine, stool, skin2skin, breastfeed,
+    breastmilk, formula, pump, breastfeed_left, breastfeed_right, pump_left, pump_right, pump_duration, caregiver_id
+FROM events;
+
+DROP TABLE events;
+ALTER TABLE events_new RENAME TO events;
+CREATE INDEX events_dt ON events (child_id, dt);
+CREATE INDEX events_caregiver_id ON events (caregiver_id);
+
+UPDATE sleep_sessions
+SET start_dt = datetime(start_dt, 'localtime') || substr(start_dt, 20),
+    end_dt = datetime(end_dt, 'localtime') || substr(end_dt, 20);
+UPDATE doses SET dt = datetime(dt, 'localtime') || substr(dt, 20);
+UPDATE medication_schedules SET first_dt = datetime(first_dt, 'localtime') || substr(first_dt, 20);
+UPDATE measurements SET dt = datetime(dt, 'localtime') || substr(dt, 20);
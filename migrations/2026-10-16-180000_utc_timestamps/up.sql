@@ -0,0 +1,48 @@
+-- Timestamps are stored in UTC, and events keep the offset from UTC in seconds of the place they were recorded in.
+-- Existing timestamps are assumed to be in the local time zone; the 'utc' modifier converts from the time zone of the
+-- machine running the migration, and the fractions of a second are carried over unchanged.
+
+-- The events table is rebuilt, as updating the timestamps in place could collide with the unique timestamps per child
+-- before every row is converted
+CREATE TABLE events_new (
+    id INTEGER NOT NULL PRIMARY KEY,
+    child_id INTEGER NOT NULL DEFAULT 1 REFERENCES children (id),
+    dt DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    urine BOOLEAN NOT NULL DEFAULT FALSE,
+    stool BOOLEAN NOT NULL DEFAULT FALSE,
+    skin2skin INTEGER NOT NULL DEFAULT 0,
+    breastfeed INTEGER NOT NULL DEFAULT 0,
+    breastmilk INTEGER NOT NULL DEFAULT 0,
+    formula INTEGER NOT NULL DEFAULT 0,
+    pump INTEGER NOT NULL DEFAULT 0,
+    breastfeed_left INTEGER NOT NULL DEFAULT 0,
+    breastfeed_right INTEGER NOT NULL DEFAULT 0,
+    pump_left INTEGER NOT NULL DEFAULT 0,
+    pump_right INTEGER NOT NULL DEFAULT 0,
+    pump_duration INTEGER NOT NULL DEFAULT 0,
+    caregiver_id INTEGER REFERENCES caregivers (id),
+    utc_offset INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (child_id, dt)
+);
+
+INSERT INTO events_new (
+    id, child_id, dt, urine, stool, skin2skin, breastfeed, breastmilk, formula, pump, breastfeed_left,
+    breastfeed_right, pump_left, pump_right, pump_duration, caregiver_id, utc_offset
+)
+SELECT
+    id, child_id, datetime(dt, 'utc') || substr(dt, 20), urine, stool, skin2skin, breastfeed, breastmilk, formula,
+    pump, breastfeed_left, breastfeed_right, pump_left, pump_right, pump_duration, caregiver_id,
+    CAST(round((julianday(dt) - julianday(dt, 'utc')) * 86400) AS INTEGER)
+FROM events;
+
+DROP TABLE events;
+ALTER TABLE events_new RENAME TO events;
+CREATE INDEX events_dt ON events (child_id, dt);
+CREATE INDEX events_caregiver_id ON events (caregiver_id);
+
+UPDATE sleep_sessions
+SET start_dt = datetime(start_dt, 'utc') || substr(start_dt, 20),
+    end_dt = datetime(end_dt, 'utc') || substr(end_dt, 20);
+UPDATE doses SET dt = datetime(dt, 'utc') || substr(dt, 20);
+UPDATE medication_schedules SET first_dt = datetime(first_dt, 'utc') || substr(first_dt, 20);
+UPDATE measurements SET dt = datetime(dt, 'utc') || substr(dt, 20);
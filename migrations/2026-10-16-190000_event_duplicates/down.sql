@@ -0,0 +1,40 @@
+-- Timestamps are unique per child again; of the events sharing a timestamp only the first is kept, together with its
+-- diaper details and note
+CREATE TABLE events_new (
+    id INTEGER NOT NULL PRIMARY KEY,
+    child_id INTEGER NOT NULL DEFAULT 1 REFERENCES children (id),
+    dt DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    urine BOOLEAN NOT NULL DEFAULT FALSE,
+    stool BOOLEAN NOT NULL DEFAULT FALSE,
+    skin2skin INTEGER NOT NULL DEFAULT 0,
+    breastfeed INTEGER NOT NULL DEFAULT 0,
+    breastmilk INTEGER NOT NULL DEFAULT 0,
+    formula INTEGER NOT NULL DEFAULT 0,
+    pump INTEGER NOT NULL DEFAULT 0,
+    breastfeed_left INTEGER NOT NULL DEFAULT 0,
+    breastfeed_right INTEGER NOT NULL DEFAULT 0,
+    pump_left INTEGER NOT NULL DEFAULT 0,
+    pump_right INTEGER NOT NULL DEFAULT 0,
+    pump_duration INTEGER NOT NULL DEFAULT 0,
+    caregiver_id INTEGER REFERENCES caregivers (id),
+    utc_offset INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (child_id, dt)
+);
+
+INSERT OR IGNORE INTO events_new (
+    id, child_id, dt, urine, stool, skin2skin, breastfeed, breastmilk, formula, pump, breastfeed_left, breastfeed_right,
+    pump_left, pump_right, pump_duration, caregiver_id, utc_offset
+)
+SELECT
+    id, child_id, dt, urine, stool, skin2skin, breastfeed, breastmilk, formula, pump, breastfeed_left, breastfeed_right,
+    pump_left, pump_right, pump_duration, caregiver_id, utc_offset
+FROM events
+ORDER BY id;
+
+DROP TABLE events;
+ALTER TABLE events_new RENAME TO events;
+CREATE INDEX events_dt ON events (child_id, dt);
+CREATE INDEX events_caregiver_id ON events (caregiver_id);
+
+DELETE FROM diaper_details WHERE event_id NOT IN (SELECT id FROM events);
+DELETE FROM event_notes WHERE event_id NOT IN (SELECT id FROM events);
@@ -0,0 +1,57 @@
+-- Ids of deleted events are never given to new events, so that the history of an event is not taken over by a new
+-- event reusing its id
+CREATE TABLE events_new (
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    child_id INTEGER NOT NULL DEFAULT 1 REFERENCES children (id),
+    dt DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    urine BOOLEAN NOT NULL DEFAULT FALSE,
+    stool BOOLEAN NOT NULL DEFAULT FALSE,
+    skin2skin INTEGER NOT NULL DEFAULT 0,
+    breastfeed INTEGER NOT NULL DEFAULT 0,
+    breastmilk INTEGER NOT NULL DEFAULT 0,
+    formula INTEGER NOT NULL DEFAULT 0,
+    pump INTEGER NOT NULL DEFAULT 0,
+    breastfeed_left INTEGER NOT NULL DEFAULT 0,
+    breastfeed_right INTEGER NOT NULL DEFAULT 0,
+    pump_left INTEGER NOT NULL DEFAULT 0,
+    pump_right INTEGER NOT NULL DEFAULT 0,
+    pump_duration INTEGER NOT NULL DEFAULT 0,
+    caregiver_id INTEGER REFERENCES caregivers (id),
+    utc_offset INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT INTO events_new (
+    id, child_id, dt, urine, stool, skin2skin, breastfeed, breastmilk, formula, pump, breastfeed_left, breastfeed_right,
+    pump_left, pump_right, pump_duration, caregiver_id, utc_offset
+)
+SELECT
+    id, child_id, dt, urine, stool, skin2skin, breastfeed, breastmilk, formula, pump, breastfeed_left, breastfeed_right,
+    pump_left, pump_right, pump_duration, caregiver_id, utc_offset
+FROM events;
+
+DROP TABLE events;
+ALTER TABLE events_new RENAME TO events;
+CREATE INDEX events_dt ON events (child_id, dt);
+CREATE INDEX events_caregiver_id ON events (caregiver_id);
+
+-- Every insert, update and delete of an event, with the event before and after the change as CSV records
+CREATE TABLE event_history (
+    id INTEGER NOT NULL PRIMARY KEY,
+    event_id INTEGER NOT NULL,
+    changed_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    caregiver_id INTEGER REFERENCES caregivers (id),
+    action TEXT NOT NULL,
+    before TEXT,
+    after TEXT
+);
+
+CREATE INDEX event_history_event_id ON event_history (event_id);
+
+-- The history is append-only
+CREATE TRIGGER event_history_update BEFORE UPDATE ON event_history BEGIN
+    SELECT RAISE(ABORT, 'event_history is append-only');
+END;
+
+CREATE TRIGGER event_history_delete BEFORE DELETE ON event_history BEGIN
+    SELECT RAISE(ABORT, 'event_history is append-only');
+END;
@@ -0,0 +1,6 @@
+-- The trash is emptied, as the events in it would otherwise come back
+DELETE FROM diaper_details WHERE event_id IN (SELECT id FROM events WHERE deleted_at IS NOT NULL);
+DELETE FROM event_notes WHERE event_id IN (SELECT id FROM events WHERE deleted_at IS NOT NULL);
+DELETE FROM events WHERE deleted_at IS NOT NULL;
+DROP INDEX events_deleted_at;
+ALTER TABLE events DROP COLUMN deleted_at;
@@ -0,0 +1,3 @@
+-- Deleted events are kept in the trash until they are purged
+ALTER TABLE events ADD COLUMN deleted_at DATETIME;
+CREATE INDEX events_deleted_at ON events (deleted_at);
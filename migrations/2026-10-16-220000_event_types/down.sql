@@ -0,0 +1,9 @@
+-- Events of user-defined types cannot be represented without their type
+DROP TABLE event_values;
+DELETE FROM diaper_details WHERE event_id IN (SELECT id FROM events WHERE event_type_id IS NOT NULL);
+DELETE FROM event_notes WHERE event_id IN (SELECT id FROM events WHERE event_type_id IS NOT NULL);
+DELETE FROM events WHERE event_type_id IS NOT NULL;
+DROP INDEX events_event_type_id;
+ALTER TABLE events DROP COLUMN event_type_id;
+DROP TABLE event_fields;
+DROP TABLE event_types;
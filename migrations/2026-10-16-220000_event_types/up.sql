@@ -0,0 +1,28 @@
+-- Event types defined by the user, such as tummy time or a bath, with the fields recorded for them
+CREATE TABLE event_types (
+    id INTEGER NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+
+-- The kind is bool, duration, volume, enum or text; the choices of an enum are separated by '|'
+CREATE TABLE event_fields (
+    id INTEGER NOT NULL PRIMARY KEY,
+    event_type_id INTEGER NOT NULL REFERENCES event_types (id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    choices TEXT,
+    UNIQUE (event_type_id, name)
+);
+
+-- Events of the built-in kinds have no type
+ALTER TABLE events ADD COLUMN event_type_id INTEGER REFERENCES event_types (id);
+CREATE INDEX events_event_type_id ON events (event_type_id);
+
+-- Durations are stored in minutes and volumes in millilitres, fields left empty have no value
+CREATE TABLE event_values (
+    event_id INTEGER NOT NULL REFERENCES events (id),
+    field_id INTEGER NOT NULL REFERENCES event_fields (id),
+    value TEXT NOT NULL,
+    PRIMARY KEY (event_id, field_id)
+);
@@ -0,0 +1,104 @@
+/// This file contains the error type returned by the library.
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+/// Represents the errors that can occur while working with baby events.
+///
+/// # Variants
+///
+/// - `Config`: A required setting, such as `DATABASE_URL`, is missing or invalid.
+/// - `Connection`: The database could not be opened.
+/// - `Query`: A database query failed.
+/// - `Csv`: A CSV file could not be read, parsed or written.
+/// - `Migration`: The database schema could not be migrated or backed up.
+/// - `SchemaTooNew`: The database was migrated by a newer version of babyrs, holds the unknown schema version.
+/// - `Validation`: A value was rejected, e.g. a negative quantity or a malformed timestamp.
+#[derive(Debug)]
+pub enum BabyrsError {
+    Config(String),
+    Connection(diesel::ConnectionError),
+    Query(diesel::result::Error),
+    Csv(csv::Error),
+    Migration(Box<dyn Error + Send + Sync>),
+    SchemaTooNew(String),
+    Validation(String),
+}
+
+impl Display for BabyrsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Config(message) => write!(f, "Configuration error: {}", message),
+            Self::Connection(error) => write!(f, "Could not connect to the database: {}", error),
+            Self::Query(error) => write!(f, "Database error: {}", error),
+            Self::Csv(error) => write!(f, "Could not process CSV: {}", error),
+            Self::Migration(error) => write!(f, "Could not migrate the database: {}", error),
+            Self::SchemaTooNew(version) => write!(
+                f,
+                "The database schema version {} is newer than this version of babyrs supports, please upgrade babyrs",
+                version
+            ),
+            Self::Validation(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for BabyrsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Connection(error) => Some(error),
+            Self::Query(error) => Some(error),
+            Self::Csv(error) => Some(error),
+            Self::Migration(error) => Some(error.as_ref()),
+            Self::Config(_) | Self::SchemaTooNew(_) | Self::Validation(_) => None,
+        }
+    }
+}
+
+impl From<diesel::ConnectionError> for BabyrsError {
+    fn from(error: diesel::ConnectionError) -> Self {
+        Self::Connection(error)
+    }
+}
+
+impl From<diesel::result::Error> for BabyrsError {
+    fn from(error: diesel::result::Error) -> Self {
+        Self::Query(error)
+    }
+}
+
+impl From<csv::Error> for BabyrsError {
+    fn from(error: csv::Error) -> Self {
+        Self::Csv(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            BabyrsError::Config("DATABASE_URL must be set".to_owned()).to_string(),
+            "Configuration error: DATABASE_URL must be set"
+        );
+        assert_eq!(
+            BabyrsError::Validation("Formula must not be negative".to_owned()).to_string(),
+            "Formula must not be negative"
+        );
+        assert_eq!(
+            BabyrsError::from(diesel::result::Error::NotFound).to_string(),
+            "Database error: Record not found"
+        );
+    }
+
+    #[test]
+    fn test_source() {
+        assert!(BabyrsError::from(diesel::result::Error::NotFound)
+            .source()
+            .is_some());
+        assert!(BabyrsError::Validation(String::new()).source().is_none());
+    }
+}
@@ -0,0 +1,337 @@
+/// Module comparing growth measurements with the WHO Child Growth Standards.
+///
+/// The LMS parameters for weight, length and head circumference for age are bundled for the first 24 months, one row
+/// per month, and interpolated linearly in between.
+use crate::models::{Measurement, Sex};
+use chrono::{NaiveDate, NaiveDateTime};
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+/// The oldest age in months covered by the bundled tables.
+pub const MAX_AGE_MONTHS: f64 = 24.0;
+
+/// The average length of a month in days, as used by the WHO standards.
+const DAYS_PER_MONTH: f64 = 30.4375;
+
+/// The percentile curves drawn in the growth chart, with their z-scores.
+pub const PERCENTILE_CURVES: [(u8, f64); 3] = [(3, -1.880_794), (50, 0.0), (97, 1.880_794)];
+
+lazy_static! {
+    /// The LMS parameters for every measure and sex, indexed by age in months.
+    static ref TABLES: HashMap<(Measure, Sex), Vec<Lms>> = load_tables();
+}
+
+/// Represents a measure with a WHO growth standard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Measure {
+    Weight,
+    Length,
+    HeadCircumference,
+}
+
+impl Measure {
+    /// Switches to the next measure in the sequence.
+    ///
+    /// # Returns
+    ///
+    /// The next measure, or `None` after the head circumference.
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            Self::Weight => Some(Self::Length),
+            Self::Length => Some(Self::HeadCircumference),
+            Self::HeadCircumference => None,
+        }
+    }
+
+    /// Returns the value of the measure in the unit of the WHO tables.
+    ///
+    /// # Parameters
+    ///
+    /// * `measurement`: The measurement to read.
+    ///
+    /// # Returns
+    ///
+    /// The weight in kilograms or the length or head circumference in centimetres, `None` if it was not measured.
+    pub fn value(&self, measurement: &Measurement) -> Option<f64> {
+        match self {
+            Self::Weight => measurement.weight.map(|grams| grams as f64 / 1000.0),
+            Self::Length => measurement.length.map(|mm| mm as f64 / 10.0),
+            Self::HeadCircumference => measurement.head_circumference.map(|mm| mm as f64 / 10.0),
+        }
+    }
+
+    /// Returns the unit of the values returned by [`Measure::value`].
+    ///
+    /// # Returns
+    ///
+    /// `"kg"` or `"cm"`.
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::Weight => "kg",
+            Self::Length | Self::HeadCircumference => "cm",
+        }
+    }
+}
+
+impl Display for Measure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Weight => write!(f, "Weight"),
+            Self::Length => write!(f, "Length"),
+            Self::HeadCircumference => write!(f, "Head circumference"),
+        }
+    }
+}
+
+/// The Box-Cox power `l`, median `m` and coefficient of variation `s` describing the distribution of a measure at a
+/// given age.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lms {
+    pub l: f64,
+    pub m: f64,
+    pub s: f64,
+}
+
+impl Lms {
+    /// Computes the z-score of a value.
+    ///
+    /// Beyond ±3 the distance is measured in units of the spacing between the 2nd and 3rd standard deviation, as the
+    /// WHO recommends, so that outliers are not exaggerated by the skew of the distribution.
+    ///
+    /// # Parameters
+    ///
+    /// * `value`: The measured value, in the unit of the tables.
+    ///
+    /// # Returns
+    ///
+    /// The z-score of the value.
+    pub fn z_score(&self, value: f64) -> f64 {
+        let z = ((value / self.m).powf(self.l) - 1.0) / (self.l * self.s);
+
+        if z > 3.0 {
+            let sd3 = self.value(3.0);
+            3.0 + (value - sd3) / (sd3 - self.value(2.0))
+        } else if z < -3.0 {
+            let sd3 = self.value(-3.0);
+            -3.0 - (sd3 - value) / (self.value(-2.0) - sd3)
+        } else {
+            z
+        }
+    }
+
+    /// Computes the value at a z-score, the inverse of [`Lms::z_score`] within ±3.
+    ///
+    /// # Parameters
+    ///
+    /// * `z`: The z-score.
+    ///
+    /// # Returns
+    ///
+    /// The value at the z-score, in the unit of the tables.
+    pub fn value(&self, z: f64) -> f64 {
+        self.m * (1.0 + self.l * self.s * z).powf(1.0 / self.l)
+    }
+
+    /// Interpolates linearly between two sets of parameters.
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Self {
+            l: self.l + (other.l - self.l) * t,
+            m: self.m + (other.m - self.m) * t,
+            s: self.s + (other.s - self.s) * t,
+        }
+    }
+}
+
+/// Computes the age of a child at the time of a measurement.
+///
+/// # Parameters
+///
+/// * `birth_date`: The date the child was born.
+/// * `dt`: The time of the measurement.
+///
+/// # Returns
+///
+/// The age in months, negative if `dt` is before the birth date.
+pub fn age_in_months(birth_date: NaiveDate, dt: NaiveDateTime) -> f64 {
+    (dt.date() - birth_date).num_days() as f64 / DAYS_PER_MONTH
+}
+
+/// Looks up the LMS parameters of a measure for a child of the given sex and age.
+///
+/// # Parameters
+///
+/// * `measure`: The measure.
+/// * `sex`: The sex of the child.
+/// * `months`: The age of the child in months.
+///
+/// # Returns
+///
+/// The interpolated parameters, or `None` if the age is outside of the bundled tables.
+pub fn lms(measure: Measure, sex: Sex, months: f64) -> Option<Lms> {
+    if !(0.0..=MAX_AGE_MONTHS).contains(&months) {
+        return None;
+    }
+
+    let table = TABLES.get(&(measure, sex))?;
+    let month = months.floor() as usize;
+    let lower = table.get(month)?;
+
+    Some(match table.get(month + 1) {
+        Some(upper) => lower.lerp(upper, months - month as f64),
+        None => *lower,
+    })
+}
+
+/// Computes the z-score of a measured value.
+///
+/// # Parameters
+///
+/// * `measure`: The measure.
+/// * `sex`: The sex of the child.
+/// * `months`: The age of the child in months.
+/// * `value`: The measured value, in the unit of [`Measure::value`].
+///
+/// # Returns
+///
+/// The z-score, or `None` if the age is outside of the bundled tables.
+pub fn z_score(measure: Measure, sex: Sex, months: f64, value: f64) -> Option<f64> {
+    lms(measure, sex, months).map(|lms| lms.z_score(value))
+}
+
+/// Converts a z-score into a percentile of the standard normal distribution.
+///
+/// # Parameters
+///
+/// * `z`: The z-score.
+///
+/// # Returns
+///
+/// The percentile, between 0 and 100.
+pub fn percentile(z: f64) -> f64 {
+    50.0 * (1.0 + erf(z / std::f64::consts::SQRT_2))
+}
+
+/// Approximates the error function with formula 7.1.26 of Abramowitz and Stegun, accurate to 1.5e-7.
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let polynomial = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let y = 1.0 - polynomial * (-x * x).exp();
+
+    y.copysign(x)
+}
+
+/// A row of the bundled LMS tables.
+#[derive(Deserialize)]
+struct Row {
+    measure: Measure,
+    sex: String,
+    month: usize,
+    l: f64,
+    m: f64,
+    s: f64,
+}
+
+/// Parses the bundled LMS tables.
+///
+/// # Panics
+///
+/// Panics if the bundled tables are malformed, which is caught by the tests.
+fn load_tables() -> HashMap<(Measure, Sex), Vec<Lms>> {
+    let mut tables: HashMap<(Measure, Sex), Vec<Lms>> = HashMap::new();
+    let mut reader = csv::Reader::from_reader(include_str!("../data/who_lms.csv").as_bytes());
+
+    for row in reader.deserialize::<Row>() {
+        let row = row.expect("Malformed WHO LMS table");
+        let sex = Sex::parse(&row.sex).expect("Unknown sex in WHO LMS table");
+        let table = tables.entry((row.measure, sex)).or_default();
+
+        assert_eq!(
+            table.len(),
+            row.month,
+            "WHO LMS table is not ordered by month"
+        );
+        table.push(Lms {
+            l: row.l,
+            m: row.m,
+            s: row.s,
+        });
+    }
+
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables() {
+        for measure in [Measure::Weight, Measure::Length, Measure::HeadCircumference] {
+            for sex in [Sex::Female, Sex::Male] {
+                assert_eq!(TABLES[&(measure, sex)].len(), MAX_AGE_MONTHS as usize + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lms() {
+        // the median weight of a boy at 12 months
+        let params = lms(Measure::Weight, Sex::Male, 12.0).unwrap();
+        assert_eq!(params.m, 9.6479);
+        assert!(params.z_score(9.6479).abs() < 1e-9);
+
+        // halfway between the medians of 0 and 1 month
+        let params = lms(Measure::Length, Sex::Female, 0.5).unwrap();
+        assert!((params.m - (49.1477 + 53.6872) / 2.0).abs() < 1e-9);
+
+        assert!(lms(Measure::Weight, Sex::Male, -0.1).is_none());
+        assert!(lms(Measure::Weight, Sex::Male, 24.1).is_none());
+    }
+
+    #[test]
+    fn test_z_score() {
+        // the WHO tables give 2.5 kg as -2 SD and 4.4 kg as +2 SD for boys at birth, rounded to 100 g
+        let z = z_score(Measure::Weight, Sex::Male, 0.0, 2.5).unwrap();
+        assert!((z + 2.0).abs() < 0.15, "{}", z);
+        let z = z_score(Measure::Weight, Sex::Male, 0.0, 4.4).unwrap();
+        assert!((z - 2.0).abs() < 0.15, "{}", z);
+
+        // values at the percentile curves map back onto them
+        let params = lms(Measure::HeadCircumference, Sex::Female, 6.0).unwrap();
+        for (_, z) in PERCENTILE_CURVES {
+            assert!((params.z_score(params.value(z)) - z).abs() < 1e-9);
+        }
+
+        // beyond 3 SD the distance is measured in 2-3 SD steps
+        let params = lms(Measure::Weight, Sex::Female, 3.0).unwrap();
+        let step = params.value(3.0) - params.value(2.0);
+        assert!((params.z_score(params.value(3.0) + step) - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_percentile() {
+        assert!((percentile(0.0) - 50.0).abs() < 1e-6);
+        assert!((percentile(-1.880_794) - 3.0).abs() < 1e-3);
+        assert!((percentile(1.880_794) - 97.0).abs() < 1e-3);
+        assert!(percentile(-5.0) < 0.01);
+    }
+
+    #[test]
+    fn test_age_in_months() {
+        let birth_date = NaiveDate::from_ymd_opt(2023, 6, 1).unwrap();
+        let dt = NaiveDate::from_ymd_opt(2024, 6, 1)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap();
+
+        assert!((age_in_months(birth_date, dt) - 366.0 / DAYS_PER_MONTH).abs() < 1e-9);
+    }
+}
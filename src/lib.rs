@@ -1,51 +1,306 @@
 /// Module handling database operations for baby events.
 ///
 /// This module provides functionalities for CRUD operations as well as processing CSV files.
+pub mod error;
+pub mod growth;
+pub mod medication;
 pub mod terminal;
+pub mod units;
+pub mod zone;
 
-use chrono::{Datelike, NaiveDate};
-use csv::Reader;
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+use csv::{Reader, Writer};
+use diesel::migration::MigrationSource;
 use diesel::prelude::*;
-use diesel::sqlite::SqliteConnection;
+use diesel::sqlite::{Sqlite, SqliteConnection};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use dotenvy::dotenv;
 use lazy_static::lazy_static;
-use log::{debug, info};
-use models::{BabyEvent, NewBabyEvent};
-use std::{collections::HashMap, env, error::Error, fs::File};
+use log::{debug, info, warn};
+use models::{
+    BabyEvent, Caregiver, Child, CsvEvent, DiaperDetails, Dose, EventField, EventHistory,
+    EventNote, EventRegistry, EventType, EventValue, HistoryAction, Measurement, Medication,
+    MedicationSchedule, NewBabyEvent, NewCaregiver, NewChild, NewDose, NewEventField,
+    NewEventHistory, NewEventType, NewMeasurement, NewMedication, NewMedicationSchedule,
+    NewSleepSession, SleepSession,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs,
+    fs::File,
+    path::PathBuf,
+};
+
+pub use error::BabyrsError;
+use units::VolumeUnit;
 
 pub mod models;
 pub mod schema;
 
 lazy_static! {
     static ref DB_KEY: &'static str = "DATABASE_URL";
+    static ref CAREGIVER_KEY: &'static str = "BABYRS_CAREGIVER";
+    static ref VOLUME_UNIT_KEY: &'static str = "BABYRS_VOLUME_UNIT";
+    static ref DUPLICATES_KEY: &'static str = "BABYRS_DUPLICATES";
+    static ref TRASH_RETENTION_KEY: &'static str = "BABYRS_TRASH_RETENTION_DAYS";
 }
 
+/// The id of the child that events are assigned to when no child is given.
+///
+/// The child is created by the migration that introduced children and owns every event recorded before then.
+pub const DEFAULT_CHILD_ID: i32 = 1;
+
+/// The number of days deleted events are kept in the trash when `BABYRS_TRASH_RETENTION_DAYS` is not set.
+pub const DEFAULT_TRASH_RETENTION_DAYS: i64 = 30;
+
+/// The hour at which the night starts, for splitting sleep into day and night.
+pub const NIGHT_START_HOUR: u32 = 19;
+
+/// The hour at which the night ends and the day starts, for splitting sleep into day and night.
+pub const NIGHT_END_HOUR: u32 = 7;
+
+/// The migrations in `migrations/`, embedded into the binary.
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
+
 /// Fetches the database URL from environment variables.
 ///
 /// # Returns
 ///
-/// A string containing the database URL.
-fn get_database_url() -> String {
+/// A `Result` containing the database URL, or a `BabyrsError::Config` if `DATABASE_URL` is not set.
+fn get_database_url() -> Result<String, BabyrsError> {
+    dotenv().ok();
+
+    env::var(*DB_KEY).map_err(|_| BabyrsError::Config(format!("{} must be set", *DB_KEY)))
+}
+
+/// Fetches the name of the caregiver recording events in this session from environment variables.
+///
+/// # Returns
+///
+/// The trimmed value of `BABYRS_CAREGIVER`, or `None` if it is not set or empty.
+pub fn get_default_caregiver() -> Option<String> {
+    dotenv().ok();
+
+    env::var(*CAREGIVER_KEY)
+        .ok()
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+}
+
+/// Fetches the unit volumes are displayed, entered and exported in from environment variables.
+///
+/// # Returns
+///
+/// The unit named by `BABYRS_VOLUME_UNIT`, or `None` if it is not set or names no known unit.
+pub fn get_default_volume_unit() -> Option<VolumeUnit> {
+    dotenv().ok();
+
+    let text = env::var(*VOLUME_UNIT_KEY).ok()?;
+    let unit = VolumeUnit::parse(&text);
+
+    if unit.is_none() {
+        warn!("Ignoring unknown {}: {}", *VOLUME_UNIT_KEY, text);
+    }
+
+    unit
+}
+
+/// Fetches how events sharing a timestamp with an existing event are written from environment variables.
+///
+/// # Returns
+///
+/// The policy named by `BABYRS_DUPLICATES`, or `None` if it is not set or names no known policy.
+pub fn get_default_duplicate_policy() -> Option<DuplicatePolicy> {
+    dotenv().ok();
+
+    let text = env::var(*DUPLICATES_KEY).ok()?;
+    let policy = DuplicatePolicy::parse(&text);
+
+    if policy.is_none() {
+        warn!("Ignoring unknown {}: {}", *DUPLICATES_KEY, text);
+    }
+
+    policy
+}
+
+/// Fetches how long deleted events are kept in the trash before they are purged from environment variables.
+///
+/// # Returns
+///
+/// The number of days set by `BABYRS_TRASH_RETENTION_DAYS`, or `None` if it is not set or not a whole number of days.
+pub fn get_default_trash_retention() -> Option<Duration> {
     dotenv().ok();
 
-    env::var(*DB_KEY).expect("DATABASE_URL must be set")
+    let text = env::var(*TRASH_RETENTION_KEY).ok()?;
+    let days = text.trim().parse::<i64>().ok().filter(|days| *days >= 0);
+
+    if days.is_none() {
+        warn!("Ignoring invalid {}: {}", *TRASH_RETENTION_KEY, text);
+    }
+
+    days.map(Duration::days)
 }
 
 /// Establishes a connection to the SQLite database.
 ///
 /// # Returns
 ///
-/// An established SQLiteConnection object.
-pub fn establish_connection() -> SqliteConnection {
-    let database_url: String = get_database_url();
+/// A `Result` containing an established SQLiteConnection object, or an error if `DATABASE_URL` is not set or the
+/// database cannot be opened.
+pub fn establish_connection() -> Result<SqliteConnection, BabyrsError> {
+    let database_url: String = get_database_url()?;
 
     debug!("Connecting to {}", database_url);
 
-    SqliteConnection::establish(&database_url)
-        .unwrap_or_else(|_| panic!("Error connecting to {}", database_url))
+    Ok(SqliteConnection::establish(&database_url)?)
+}
+
+/// Summary of a database migration.
+///
+/// # Fields
+///
+/// - `applied`: Versions of the migrations that were applied.
+/// - `backup`: Path of the copy taken of the database before migrating, if there was anything to back up.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MigrationSummary {
+    pub applied: Vec<String>,
+    pub backup: Option<PathBuf>,
+}
+
+/// Opens the SQLite database and brings its schema up to date.
+///
+/// This is meant to be called once on startup, before any other function accesses the database.
+///
+/// # Returns
+///
+/// A `Result` containing the migrated connection, or an error if the database cannot be opened, its schema is newer
+/// than this version of babyrs or a migration fails.
+pub fn open_database() -> Result<SqliteConnection, BabyrsError> {
+    let database_url: String = get_database_url()?;
+    let mut connection: SqliteConnection = establish_connection()?;
+
+    let summary = migrate_database(&mut connection, &database_url)?;
+
+    if !summary.applied.is_empty() {
+        info!("Applied migrations: {}", summary.applied.join(", "));
+    }
+
+    Ok(connection)
+}
+
+/// Runs the pending migrations embedded in the binary.
+///
+/// A database that already has migrations applied is copied next to itself before it is migrated. A database with a
+/// migration this binary does not know about is refused, as it was created by a newer version of babyrs.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `database_url`: The URL the connection was opened with, used to locate the database file to back up.
+///
+/// # Returns
+///
+/// Returns a `Result` containing a `MigrationSummary`, or an error if the schema is too new, the backup fails or a
+/// migration fails.
+pub fn migrate_database(
+    connection: &mut SqliteConnection,
+    database_url: &str,
+) -> Result<MigrationSummary, BabyrsError> {
+    let known: Vec<String> = MigrationSource::<Sqlite>::migrations(&MIGRATIONS)
+        .map_err(BabyrsError::Migration)?
+        .iter()
+        .map(|migration| migration.name().version().to_string())
+        .collect();
+    let applied: Vec<String> = connection
+        .applied_migrations()
+        .map_err(BabyrsError::Migration)?
+        .iter()
+        .map(|version| version.to_string())
+        .collect();
+
+    if let Some(version) = applied.iter().find(|version| !known.contains(version)) {
+        return Err(BabyrsError::SchemaTooNew(version.clone()));
+    }
+
+    let mut summary = MigrationSummary::default();
+
+    if known.len() == applied.len() {
+        return Ok(summary);
+    }
+
+    if !applied.is_empty() {
+        summary.backup = backup_database(database_url)?;
+    }
+
+    summary.applied = connection
+        .run_pending_migrations(MIGRATIONS)
+        .map_err(BabyrsError::Migration)?
+        .iter()
+        .map(|version| version.to_string())
+        .collect();
+
+    Ok(summary)
+}
+
+/// Copies the database file to `<file>.<timestamp>.bak`.
+///
+/// # Arguments
+///
+/// - `database_url`: The URL of the database.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the path of the backup, `None` for an in-memory database, or an error if the file
+/// cannot be copied.
+fn backup_database(database_url: &str) -> Result<Option<PathBuf>, BabyrsError> {
+    let path = match database_path(database_url) {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+
+    let mut backup = path.clone().into_os_string();
+    backup.push(format!(
+        ".{}.bak",
+        chrono::Local::now().format("%Y%m%d%H%M%S")
+    ));
+    let backup = PathBuf::from(backup);
+
+    info!("Backing up {} to {}", path.display(), backup.display());
+
+    fs::copy(&path, &backup).map_err(|error| BabyrsError::Migration(Box::new(error)))?;
+
+    Ok(Some(backup))
+}
+
+/// Extracts the path of the database file from a database URL.
+///
+/// # Arguments
+///
+/// - `database_url`: A plain path, or a `sqlite://` or `file:` URL.
+///
+/// # Returns
+///
+/// The path of the database file, or `None` for an in-memory database.
+fn database_path(database_url: &str) -> Option<PathBuf> {
+    let (path, query) = match database_url
+        .strip_prefix("sqlite://")
+        .or_else(|| database_url.strip_prefix("file:"))
+    {
+        Some(url) => url.split_once('?').unwrap_or((url, "")),
+        None => (database_url, ""),
+    };
+
+    if path.is_empty() || path == ":memory:" || query.contains("mode=memory") {
+        None
+    } else {
+        Some(PathBuf::from(path))
+    }
 }
 
-/// Creates a new baby event.
+/// Creates a new baby event, happening now.
+///
+/// The timestamp is in UTC, with the offset of the local time zone. The sides of the breastfeed and pump, the duration
+/// of the pump and the caregiver are left unknown, and the event is of the built-in kinds.
 ///
 /// # Arguments
 ///
@@ -66,8 +321,11 @@ pub fn create_event(
     debug!("Creating event - urine: {:?}, stool: {:?}, skin2skin: {:?}, breastfeed: {:?}, breastmilk: {:?}, formula: {:?}, pump: {:?}",
         &urine, &stool, &skin2skin, &breastfeed, &breastmilk, &formula, &pump);
 
+    let now = Utc::now().naive_utc();
+
     NewBabyEvent {
-        dt: chrono::Local::now().naive_local(),
+        child_id: DEFAULT_CHILD_ID,
+        dt: now,
         urine: urine.unwrap_or(false),
         stool: stool.unwrap_or(false),
         skin2skin: i32::from(skin2skin.unwrap_or(0)),
@@ -75,10 +333,78 @@ pub fn create_event(
         breastmilk: i32::from(breastmilk.unwrap_or(0)),
         formula: i32::from(formula.unwrap_or(0)),
         pump: i32::from(pump.unwrap_or(0)),
+        breastfeed_left: 0,
+        breastfeed_right: 0,
+        pump_left: 0,
+        pump_right: 0,
+        pump_duration: 0,
+        caregiver_id: None,
+        utc_offset: zone::offset(&Local, now),
+        event_type_id: None,
+    }
+}
+
+/// How an event is written when the child already has an event at the same timestamp.
+///
+/// Exact duplicates, recording the same diapers, durations and volumes, or the same type and values, are skipped by
+/// every policy but `KeepBoth`. Events of a user-defined type are never merged.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Skips exact duplicates and writes any other event next to the existing ones.
+    #[default]
+    SkipExact,
+    /// Skips exact duplicates and merges any other event into the first existing one of the built-in kinds, see
+    /// [`BabyEvent::merge`].
+    Merge,
+    /// Writes every event.
+    KeepBoth,
+}
+
+impl DuplicatePolicy {
+    /// Parses a policy, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// - `text`: The text to parse: `"skip"`, `"merge"` or `"keep"`.
+    ///
+    /// # Returns
+    ///
+    /// The policy, or `None` if the text names no known policy.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "skip" => Some(Self::SkipExact),
+            "merge" => Some(Self::Merge),
+            "keep" => Some(Self::KeepBoth),
+            _ => None,
+        }
+    }
+}
+
+/// What writing an event did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteOutcome {
+    /// The event was inserted with the given id.
+    Inserted(i32),
+    /// The event was an exact duplicate of the event with the given id and was not written.
+    Skipped(i32),
+    /// The event was merged into the event with the given id.
+    Merged(i32),
+}
+
+impl WriteOutcome {
+    /// Returns the id of the event holding the written values.
+    ///
+    /// # Returns
+    ///
+    /// The id of the inserted event, or of the existing event the values were skipped for or merged into.
+    pub fn id(&self) -> i32 {
+        match self {
+            Self::Inserted(id) | Self::Skipped(id) | Self::Merged(id) => *id,
+        }
     }
 }
 
-/// Writes a new baby event into the database.
+/// Writes a new baby event into the database, skipping it if it is an exact duplicate of an existing event.
 ///
 /// # Arguments
 ///
@@ -87,434 +413,3078 @@ pub fn create_event(
 ///
 /// # Returns
 ///
-/// The number of rows inserted.
-pub fn write_event(connection: &mut SqliteConnection, new_event: NewBabyEvent) -> usize {
-    debug!("Writing event: {:?}", &new_event);
+/// A `Result` containing the `WriteOutcome`, or an error if the event is invalid or the query fails.
+pub fn write_event(
+    connection: &mut SqliteConnection,
+    new_event: NewBabyEvent,
+) -> Result<WriteOutcome, BabyrsError> {
+    write_event_with_policy(connection, new_event, DuplicatePolicy::default())
+}
 
-    diesel::insert_or_ignore_into(schema::events::table)
-        .values(&new_event)
-        .execute(connection)
-        .expect("Error saving new event")
+/// Writes a new baby event into the database, deciding by a policy what to do if the child already has an event at the
+/// same timestamp.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `new_event`: The baby event to write.
+/// - `policy`: How to treat existing events at the same timestamp.
+///
+/// # Returns
+///
+/// A `Result` containing the `WriteOutcome`, or an error if the event is invalid or the query fails.
+pub fn write_event_with_policy(
+    connection: &mut SqliteConnection,
+    new_event: NewBabyEvent,
+    policy: DuplicatePolicy,
+) -> Result<WriteOutcome, BabyrsError> {
+    write_event_with_values(connection, new_event, &BTreeMap::new(), policy)
 }
 
-/// Reads baby events from the database.
+/// Writes a new baby event together with the values of the fields of its user-defined type, deciding by a policy what
+/// to do if the child already has an event at the same timestamp.
+///
+/// An event is only a duplicate of an existing event with the same values, and is never merged into an event of
+/// another type.
 ///
 /// # Arguments
 ///
 /// - `connection`: The database connection.
+/// - `new_event`: The baby event to write.
+/// - `values`: The stored values by the id of their field, see [`EventField::parse_value`].
+/// - `policy`: How to treat existing events at the same timestamp.
 ///
 /// # Returns
 ///
-/// A vector of BabyEvent objects.
-pub fn read_events(connection: &mut SqliteConnection) -> Vec<BabyEvent> {
+/// A `Result` containing the `WriteOutcome`, or an error if the event or a value is invalid or the query fails.
+pub fn write_event_with_values(
+    connection: &mut SqliteConnection,
+    new_event: NewBabyEvent,
+    values: &BTreeMap<i32, String>,
+    policy: DuplicatePolicy,
+) -> Result<WriteOutcome, BabyrsError> {
     use schema::events::dsl::*;
 
-    info!("Reading events");
+    debug!(
+        "Writing event with {:?}: {:?} {:?}",
+        policy, &new_event, values
+    );
+
+    new_event.validate()?;
+
+    connection.transaction(|connection| {
+        validate_values(connection, new_event.event_type_id, values)?;
+
+        let existing = match policy {
+            DuplicatePolicy::KeepBoth => vec![],
+            _ => events
+                .filter(child_id.eq(new_event.child_id))
+                .filter(dt.eq(new_event.dt))
+                .filter(deleted_at.is_null())
+                .order(id)
+                .select(BabyEvent::as_select())
+                .load(connection)?,
+        };
+
+        for event in existing.iter().filter(|e| e.is_duplicate(&new_event)) {
+            if read_event_values(connection, event.id)? == *values {
+                return Ok(WriteOutcome::Skipped(event.id));
+            }
+        }
+        let mergeable = existing.iter().find(|e| e.event_type_id.is_none());
+        if let (DuplicatePolicy::Merge, None, Some(event)) =
+            (policy, new_event.event_type_id, mergeable)
+        {
+            update_event(connection, event.merge(&new_event), new_event.caregiver_id)?;
+            return Ok(WriteOutcome::Merged(event.id));
+        }
 
-    let results: Vec<BabyEvent> = events
-        .select(BabyEvent::as_select())
-        .load(connection)
-        .expect("Error loading events");
+        diesel::insert_into(events)
+            .values(&new_event)
+            .execute(connection)?;
+
+        let event: BabyEvent = events
+            .order(id.desc())
+            .select(BabyEvent::as_select())
+            .first(connection)?;
+
+        record_history(
+            connection,
+            HistoryAction::Insert,
+            new_event.caregiver_id,
+            None,
+            Some(&event),
+        )?;
+        insert_values(connection, event.id, values)?;
+
+        Ok(WriteOutcome::Inserted(event.id))
+    })
+}
 
-    debug!("Read events: {:?}", &results);
+/// Appends a change of an event to its history.
+///
+/// Only the event itself is recorded. Its diaper details, note and values are stored apart, and changing them writes no
+/// history.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `action`: The kind of change.
+/// - `caregiver`: The id of the caregiver who made the change, if known.
+/// - `before`: The event before the change, `None` for an insert.
+/// - `after`: The event after the change, `None` for a delete.
+///
+/// # Returns
+///
+/// A `Result` containing `()`, or an error if an event cannot be encoded or the query fails.
+fn record_history(
+    connection: &mut SqliteConnection,
+    action: HistoryAction,
+    caregiver: Option<i32>,
+    before: Option<&BabyEvent>,
+    after: Option<&BabyEvent>,
+) -> Result<(), BabyrsError> {
+    let Some(event) = after.or(before) else {
+        return Ok(());
+    };
+    let entry = NewEventHistory {
+        event_id: event.id,
+        changed_at: Utc::now().naive_utc(),
+        caregiver_id: caregiver,
+        action,
+        before: before.map(BabyEvent::to_snapshot).transpose()?,
+        after: after.map(BabyEvent::to_snapshot).transpose()?,
+    };
+
+    debug!("Recording history: {:?}", &entry);
+
+    diesel::insert_into(schema::event_history::table)
+        .values(&entry)
+        .execute(connection)?;
 
-    results
+    Ok(())
 }
 
-/// Updates an existing baby event in the database.
+/// Reads baby events from the database, leaving out the events in the trash.
 ///
 /// # Arguments
 ///
 /// - `connection`: The database connection.
-/// - `event`: The baby event to update.
 ///
 /// # Returns
 ///
-/// The number of rows updated.
-pub fn update_event(connection: &mut SqliteConnection, event: BabyEvent) -> usize {
+/// A `Result` containing a vector of BabyEvent objects, or an error if the query fails.
+pub fn read_events(connection: &mut SqliteConnection) -> Result<Vec<BabyEvent>, BabyrsError> {
     use schema::events::dsl::*;
 
-    debug!("Updating event: {:?}", &event);
+    info!("Reading events");
+
+    let results: Vec<BabyEvent> = events
+        .filter(deleted_at.is_null())
+        .select(BabyEvent::as_select())
+        .load(connection)?;
+
+    debug!("Read events: {:?}", &results);
 
-    diesel::update(events.find(event.id))
-        .set(&event)
-        .execute(connection)
-        .expect("Error updating event")
+    Ok(results)
 }
 
-/// Deletes an existing baby event in the database.
+/// Reads the baby events of a child from the database, leaving out the events in the trash.
 ///
 /// # Arguments
 ///
 /// - `connection`: The database connection.
-/// - `event`: The baby event to delete.
+/// - `child`: The id of the child.
 ///
 /// # Returns
 ///
-/// The number of rows deleted.
-pub fn delete_event(connection: &mut SqliteConnection, event: BabyEvent) -> usize {
+/// A `Result` containing a vector of BabyEvent objects, or an error if the query fails.
+pub fn read_child_events(
+    connection: &mut SqliteConnection,
+    child: i32,
+) -> Result<Vec<BabyEvent>, BabyrsError> {
     use schema::events::dsl::*;
 
-    debug!("Deleting event: {:?}", &event);
+    info!("Reading events of child {}", child);
+
+    let results: Vec<BabyEvent> = events
+        .filter(child_id.eq(child))
+        .filter(deleted_at.is_null())
+        .select(BabyEvent::as_select())
+        .load(connection)?;
+
+    debug!("Read events: {:?}", &results);
 
-    diesel::delete(events.find(event.id))
-        .execute(connection)
-        .expect("Error deleting event")
+    Ok(results)
 }
 
-/// Processes a CSV file and writes the baby events into the database.
+/// Reads the baby events of a child within a period from the database, ordered by timestamp and leaving out the events
+/// in the trash.
 ///
 /// # Arguments
 ///
 /// - `connection`: The database connection.
-/// - `file_path`: The path of the CSV file.
+/// - `child`: The id of the child.
+/// - `start`: The start of the period in UTC, inclusive.
+/// - `end`: The end of the period in UTC, exclusive.
 ///
 /// # Returns
 ///
-/// Returns a `Result` indicating success or an error.
-pub fn process_csv(
+/// A `Result` containing a vector of BabyEvent objects, or an error if the query fails.
+pub fn read_events_between(
     connection: &mut SqliteConnection,
-    file_path: &str,
-) -> Result<(), Box<dyn Error>> {
-    info!("Processing CSV file: {}", &file_path);
+    child: i32,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Result<Vec<BabyEvent>, BabyrsError> {
+    use schema::events::dsl::*;
 
-    let mut rdr: Reader<File> = Reader::from_path(file_path)?;
+    info!(
+        "Reading events of child {} between {} and {}",
+        child, start, end
+    );
 
-    for result in rdr.deserialize() {
-        let record: NewBabyEvent = result?;
+    let results: Vec<BabyEvent> = events
+        .filter(child_id.eq(child))
+        .filter(dt.ge(start).and(dt.lt(end)))
+        .filter(deleted_at.is_null())
+        .order(dt.asc())
+        .select(BabyEvent::as_select())
+        .load(connection)?;
 
-        debug!("Read record: {:?}", &record);
+    debug!("Read events: {:?}", &results);
 
-        write_event(connection, record);
-    }
+    Ok(results)
+}
 
-    info!("Processed CSV file: {}", &file_path);
+/// Counts the baby events of a child within a period in the database, leaving out the events in the trash.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+/// - `start`: The start of the period in UTC, inclusive.
+/// - `end`: The end of the period in UTC, exclusive.
+///
+/// # Returns
+///
+/// A `Result` containing the number of events in the period, or an error if the query fails.
+pub fn count_events_between(
+    connection: &mut SqliteConnection,
+    child: i32,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Result<i64, BabyrsError> {
+    use schema::events::dsl::*;
 
-    Ok(())
+    Ok(events
+        .filter(child_id.eq(child))
+        .filter(dt.ge(start).and(dt.lt(end)))
+        .filter(deleted_at.is_null())
+        .count()
+        .get_result(connection)?)
 }
 
-/// Calculate total volume of food consumed for each day.
+/// Reads the most recent baby event of a child from the database.
 ///
 /// # Arguments
 ///
-/// - `events`: A vector of BabyEvent objects.
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
 ///
 /// # Returns
 ///
-/// A vector of tuples containing the date and total volume of food consumed.
-pub fn calculate_daily_volume(events: Vec<BabyEvent>) -> Vec<(NaiveDate, i32)> {
-    let mut daily_volume: Vec<(NaiveDate, i32)> = Vec::new();
+/// A `Result` containing the event with the latest timestamp, `None` if there are no events, or an error if the
+/// query fails.
+pub fn read_last_event(
+    connection: &mut SqliteConnection,
+    child: i32,
+) -> Result<Option<BabyEvent>, BabyrsError> {
+    use schema::events::dsl::*;
 
-    for event in events {
-        let date = event.dt.date();
-        let volume = event.breastmilk + event.formula;
+    Ok(events
+        .filter(child_id.eq(child))
+        .filter(deleted_at.is_null())
+        .order(dt.desc())
+        .select(BabyEvent::as_select())
+        .first(connection)
+        .optional()?)
+}
 
-        if let Some((_, tmp)) = daily_volume.iter_mut().find(|(d, _)| d == &date) {
-            *tmp += volume;
-        } else {
-            daily_volume.push((date, volume));
-        }
-    }
+/// Reads the most recent breastfeed of a child with the sides recorded.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+///
+/// # Returns
+///
+/// A `Result` containing the latest event with a breastfeed on either side, `None` if there is none, or an error if
+/// the query fails.
+pub fn read_last_breastfeed(
+    connection: &mut SqliteConnection,
+    child: i32,
+) -> Result<Option<BabyEvent>, BabyrsError> {
+    use schema::events::dsl::*;
 
-    daily_volume
+    Ok(events
+        .filter(child_id.eq(child))
+        .filter(breastfeed_left.gt(0).or(breastfeed_right.gt(0)))
+        .filter(deleted_at.is_null())
+        .order(dt.desc())
+        .select(BabyEvent::as_select())
+        .first(connection)
+        .optional()?)
 }
 
-/// Calculate total volume of food consumed for each week, starting on Mondays.
+/// Updates an existing baby event in the database, recording the change in its history.
+///
+/// Events in the trash are not updated, and updating never moves an event in or out of the trash. Diaper details that
+/// no longer match the event, such as the stool details of an event that no longer records a stool, are deleted.
 ///
 /// # Arguments
 ///
-/// - `daily_volume`: A vector of tuples containing the date and total volume of food consumed.
+/// - `connection`: The database connection.
+/// - `event`: The baby event to update.
+/// - `caregiver`: The id of the caregiver making the change, if known.
 ///
 /// # Returns
 ///
-/// A vector of tuples containing the start date of the week (Monday) and the total volume of food consumed for that week.
-pub fn calculate_weekly_volume(daily_volume: Vec<(NaiveDate, i32)>) -> Vec<(NaiveDate, i32)> {
-    let mut weekly_volume: Vec<(NaiveDate, i32)> = Vec::new();
+/// A `Result` containing the number of rows updated, or an error if the event is invalid or the query fails.
+pub fn update_event(
+    connection: &mut SqliteConnection,
+    event: BabyEvent,
+    caregiver: Option<i32>,
+) -> Result<usize, BabyrsError> {
+    use schema::events::dsl::*;
 
-    for (date, volume) in daily_volume {
-        let days_from_monday = date.weekday().num_days_from_monday();
-        let start_date = date - chrono::Duration::days(days_from_monday as i64);
+    debug!("Updating event: {:?}", &event);
 
-        if let Some((_, tmp)) = weekly_volume.iter_mut().find(|(d, _)| d == &start_date) {
-            *tmp += volume;
-        } else {
-            weekly_volume.push((start_date, volume));
+    event.validate()?;
+
+    let event = BabyEvent {
+        deleted_at: None,
+        ..event
+    };
+
+    connection.transaction(|connection| {
+        let Some(before) = events
+            .find(event.id)
+            .filter(deleted_at.is_null())
+            .select(BabyEvent::as_select())
+            .first(connection)
+            .optional()?
+        else {
+            return Ok(0);
+        };
+
+        let updated = diesel::update(events.find(event.id))
+            .set(&event)
+            .execute(connection)?;
+        remove_mismatched_details(connection, &event)?;
+
+        if before != event {
+            record_history(
+                connection,
+                HistoryAction::Update,
+                caregiver,
+                Some(&before),
+                Some(&event),
+            )?;
         }
-    }
 
-    weekly_volume
+        Ok(updated)
+    })
 }
 
-/// Calculate total volume of food consumed for each month.
+/// Moves an existing baby event to the trash, keeping its diaper details, note and values, and records the deletion in
+/// its history.
 ///
 /// # Arguments
 ///
-/// - `daily_volume`: A vector of tuples containing the date and total volume of food consumed.
+/// - `connection`: The database connection.
+/// - `event`: The baby event to delete.
+/// - `caregiver`: The id of the caregiver deleting the event, if known.
 ///
 /// # Returns
 ///
-/// A vector of tuples containing the start date of the month and the total volume of food consumed for that month.
-pub fn calculate_monthly_volume(daily_volume: Vec<(NaiveDate, i32)>) -> Vec<(NaiveDate, i32)> {
-    let mut monthly_volume: Vec<(NaiveDate, i32)> = Vec::new();
-
-    for (date, volume) in daily_volume {
-        let start_date = NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap();
+/// A `Result` containing the number of events deleted, 0 if the event is already in the trash, or an error if the
+/// query fails.
+pub fn delete_event(
+    connection: &mut SqliteConnection,
+    event: BabyEvent,
+    caregiver: Option<i32>,
+) -> Result<usize, BabyrsError> {
+    use schema::events::dsl::*;
 
-        if let Some((_, tmp)) = monthly_volume.iter_mut().find(|(d, _)| d == &start_date) {
-            *tmp += volume;
-        } else {
-            monthly_volume.push((start_date, volume));
-        }
-    }
+    debug!("Deleting event: {:?}", &event);
 
-    monthly_volume
+    connection.transaction(|connection| {
+        let Some(before) = events
+            .find(event.id)
+            .filter(deleted_at.is_null())
+            .select(BabyEvent::as_select())
+            .first(connection)
+            .optional()?
+        else {
+            return Ok(0);
+        };
+
+        let deleted = diesel::update(events.find(event.id))
+            .set(deleted_at.eq(Utc::now().naive_utc()))
+            .execute(connection)?;
+
+        record_history(
+            connection,
+            HistoryAction::Delete,
+            caregiver,
+            Some(&before),
+            None,
+        )?;
+
+        Ok(deleted)
+    })
 }
 
-/// Calculate total volume of milk pumped for each day.
+/// Restores a previously deleted baby event from the trash, keeping its original id and timestamp, and records it in
+/// its history. Diaper details that do not match the restored version of the event are deleted.
 ///
 /// # Arguments
 ///
-/// - `events`: A vector of BabyEvent objects.
+/// - `connection`: The database connection.
+/// - `event`: The baby event to restore, as it is restored.
+/// - `caregiver`: The id of the caregiver restoring the event, if known.
+///
+/// # Returns
+///
+/// A `Result` containing the number of events restored, 0 if the event is not in the trash, or an error if the query
+/// fails.
+pub fn restore_event(
+    connection: &mut SqliteConnection,
+    event: BabyEvent,
+    caregiver: Option<i32>,
+) -> Result<usize, BabyrsError> {
+    use schema::events::dsl::*;
+
+    debug!("Restoring event: {:?}", &event);
+
+    let event = BabyEvent {
+        deleted_at: None,
+        ..event
+    };
+
+    connection.transaction(|connection| {
+        let restored = diesel::update(events.find(event.id).filter(deleted_at.is_not_null()))
+            .set(&event)
+            .execute(connection)?;
+
+        if restored > 0 {
+            remove_mismatched_details(connection, &event)?;
+            record_history(
+                connection,
+                HistoryAction::Insert,
+                caregiver,
+                None,
+                Some(&event),
+            )?;
+        }
+
+        Ok(restored)
+    })
+}
+
+/// Reads the events of a child in the trash, most recently deleted first.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+///
+/// # Returns
+///
+/// A `Result` containing the deleted events, or an error if the query fails.
+pub fn read_deleted_events(
+    connection: &mut SqliteConnection,
+    child: i32,
+) -> Result<Vec<BabyEvent>, BabyrsError> {
+    use schema::events::dsl::*;
+
+    info!("Reading deleted events of child {}", child);
+
+    Ok(events
+        .filter(child_id.eq(child))
+        .filter(deleted_at.is_not_null())
+        .order((deleted_at.desc(), id.desc()))
+        .select(BabyEvent::as_select())
+        .load(connection)?)
+}
+
+/// Removes an event from the trash for good, together with its diaper details, note and values, and records the purge
+/// in its history.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `event`: The deleted event to purge.
+/// - `caregiver`: The id of the caregiver purging the event, if known.
+///
+/// # Returns
+///
+/// A `Result` containing the number of events purged, 0 if the event is not in the trash, or an error if the query
+/// fails.
+pub fn purge_event(
+    connection: &mut SqliteConnection,
+    event: BabyEvent,
+    caregiver: Option<i32>,
+) -> Result<usize, BabyrsError> {
+    use schema::events::dsl::*;
+
+    debug!("Purging event: {:?}", &event);
+
+    connection.transaction(|connection| {
+        let Some(before) = events
+            .find(event.id)
+            .filter(deleted_at.is_not_null())
+            .select(BabyEvent::as_select())
+            .first(connection)
+            .optional()?
+        else {
+            return Ok(0);
+        };
+
+        diesel::delete(schema::diaper_details::table.find(event.id)).execute(connection)?;
+        diesel::delete(schema::event_notes::table.find(event.id)).execute(connection)?;
+        diesel::delete(
+            schema::event_values::table.filter(schema::event_values::event_id.eq(event.id)),
+        )
+        .execute(connection)?;
+
+        let purged = diesel::delete(events.find(event.id)).execute(connection)?;
+
+        record_history(
+            connection,
+            HistoryAction::Purge,
+            caregiver,
+            Some(&before),
+            None,
+        )?;
+
+        Ok(purged)
+    })
+}
+
+/// Purges the events of every child that were moved to the trash before a point in time.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `deleted_before`: The exclusive limit of the deletion time, in UTC.
+///
+/// # Returns
+///
+/// A `Result` containing the number of events purged, or an error if the query fails.
+pub fn purge_deleted_events(
+    connection: &mut SqliteConnection,
+    deleted_before: NaiveDateTime,
+) -> Result<usize, BabyrsError> {
+    use schema::events::dsl::*;
+
+    info!("Purging events deleted before {}", deleted_before);
+
+    connection.transaction(|connection| {
+        let expired: Vec<BabyEvent> = events
+            .filter(deleted_at.lt(deleted_before))
+            .select(BabyEvent::as_select())
+            .load(connection)?;
+
+        expired
+            .into_iter()
+            .map(|event| purge_event(connection, event, None))
+            .sum()
+    })
+}
+
+/// Reads the history of an event, including the changes made before it was deleted.
+///
+/// The history covers the event itself, not the changes to its diaper details, note or values.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `event`: The id of the event.
+///
+/// # Returns
+///
+/// A `Result` containing the changes from the oldest to the newest, or an error if the query fails.
+pub fn read_event_history(
+    connection: &mut SqliteConnection,
+    event: i32,
+) -> Result<Vec<EventHistory>, BabyrsError> {
+    use schema::event_history::dsl::*;
+
+    info!("Reading history of event {}", event);
+
+    Ok(event_history
+        .filter(event_id.eq(event))
+        .order(id)
+        .select(EventHistory::as_select())
+        .load(connection)?)
+}
+
+/// Reverts an event to the version it had before a change in its history.
+///
+/// The revert is itself recorded as a change: an event is updated back to the earlier version, restored from the trash
+/// if it has since been deleted, or deleted if the change inserted it. The diaper details, note and values of the event
+/// are not part of its history and are left as they are, apart from details that no longer match the reverted version.
+///
+/// Only a change that the event is still as it left is reverted, so later changes have to be reverted first, or the
+/// version before them gone back to with [`revert_event_to_version`]. A purge is final, as the diaper details, note and
+/// values of the event went with it, and is never reverted.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `entry`: The change to undo.
+/// - `caregiver`: The id of the caregiver reverting the event, if known.
+///
+/// # Returns
+///
+/// A `Result` containing the number of events changed, or an error if the change is a purge, the snapshot cannot be
+/// decoded, the event has changed since, the earlier version is invalid or the query fails.
+pub fn revert_event(
+    connection: &mut SqliteConnection,
+    entry: &EventHistory,
+    caregiver: Option<i32>,
+) -> Result<usize, BabyrsError> {
+    use schema::events::dsl::*;
+
+    debug!("Reverting event: {:?}", entry);
+
+    if entry.action == HistoryAction::Purge {
+        return Err(BabyrsError::Validation(format!(
+            "Event {} has been purged for good and cannot be restored",
+            entry.event_id
+        )));
+    }
+
+    let version = entry.before_event()?;
+    let changed = entry.after_event()?;
+
+    connection.transaction(|connection| {
+        let current = events
+            .find(entry.event_id)
+            .select(BabyEvent::as_select())
+            .first(connection)
+            .optional()?;
+
+        // the event must still be as the change left it, an inserted or updated event unchanged and a deleted one in
+        // the trash
+        let unchanged = match (changed, current) {
+            (Some(changed), Some(current)) => current == changed,
+            (None, Some(current)) => {
+                entry.action == HistoryAction::Delete
+                    && current.is_deleted()
+                    && version
+                        == Some(BabyEvent {
+                            deleted_at: None,
+                            ..current
+                        })
+            }
+            (_, None) => false,
+        };
+        if !unchanged {
+            return Err(BabyrsError::Validation(format!(
+                "Event {} has changed since, revert its later changes first",
+                entry.event_id
+            )));
+        }
+
+        match (version, current) {
+            (Some(version), Some(current)) if !current.is_deleted() => {
+                update_event(connection, version, caregiver)
+            }
+            (Some(version), _) => restore_event(connection, version, caregiver),
+            (None, Some(current)) if !current.is_deleted() => {
+                delete_event(connection, current, caregiver)
+            }
+            (None, _) => Ok(0),
+        }
+    })
+}
+
+/// Brings an event back to the version a change in its history left it in, whatever has changed since.
+///
+/// Unlike [`revert_event`], later changes are not reverted one by one: the version is written as a single update,
+/// recorded as a change of its own. The event has to be live, so an event in the trash is restored first, and a
+/// deletion or purge leaves no version to go back to.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `entry`: The change that left the event in the version to go back to.
+/// - `caregiver`: The id of the caregiver reverting the event, if known.
+///
+/// # Returns
+///
+/// A `Result` containing the number of events updated, or an error if the change left no version, the snapshot cannot
+/// be decoded, the event is in the trash or purged, the version is invalid or the query fails.
+pub fn revert_event_to_version(
+    connection: &mut SqliteConnection,
+    entry: &EventHistory,
+    caregiver: Option<i32>,
+) -> Result<usize, BabyrsError> {
+    use schema::events::dsl::*;
+
+    debug!("Reverting event to version: {:?}", entry);
+
+    let Some(version) = entry.after_event()? else {
+        return Err(BabyrsError::Validation(format!(
+            "The {} of event {} left no version to go back to",
+            entry.action, entry.event_id
+        )));
+    };
+
+    connection.transaction(|connection| {
+        let current = events
+            .find(entry.event_id)
+            .select(BabyEvent::as_select())
+            .first(connection)
+            .optional()?;
+
+        match current {
+            Some(current) if !current.is_deleted() => update_event(connection, version, caregiver),
+            Some(_) => Err(BabyrsError::Validation(format!(
+                "Event {} is in the trash, restore it first",
+                entry.event_id
+            ))),
+            None => Err(BabyrsError::Validation(format!(
+                "Event {} has been purged for good and cannot be restored",
+                entry.event_id
+            ))),
+        }
+    })
+}
+
+/// Writes the diaper details of an event, replacing any noted before; empty details delete them.
+///
+/// Diaper details are not recorded in the history of the event, so earlier details cannot be reverted to.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `details`: The details to write.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows written or deleted, or an error if the event does not exist, the details
+/// do not match it or the query fails.
+pub fn write_diaper_details(
+    connection: &mut SqliteConnection,
+    details: DiaperDetails,
+) -> Result<usize, BabyrsError> {
+    debug!("Writing diaper details: {:?}", &details);
+
+    let event = schema::events::table
+        .find(details.event_id)
+        .select(BabyEvent::as_select())
+        .first(connection)?;
+    details.validate(&event)?;
+
+    if details.is_empty() {
+        return delete_diaper_details(connection, details.event_id);
+    }
+
+    Ok(diesel::replace_into(schema::diaper_details::table)
+        .values(&details)
+        .execute(connection)?)
+}
+
+/// Deletes the diaper details of an event that no longer match it, keeping those that do.
+fn remove_mismatched_details(
+    connection: &mut SqliteConnection,
+    event: &BabyEvent,
+) -> Result<usize, BabyrsError> {
+    let Some(details) = schema::diaper_details::table
+        .find(event.id)
+        .select(DiaperDetails::as_select())
+        .first(connection)
+        .optional()?
+    else {
+        return Ok(0);
+    };
+
+    let matching = details.matching(event);
+    if matching == details {
+        Ok(0)
+    } else if matching.is_empty() {
+        delete_diaper_details(connection, event.id)
+    } else {
+        Ok(diesel::replace_into(schema::diaper_details::table)
+            .values(&matching)
+            .execute(connection)?)
+    }
+}
+
+/// Reads the diaper details of the events of a child within a period.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+/// - `start`: The inclusive start of the period, in UTC.
+/// - `end`: The exclusive end of the period, in UTC.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of DiaperDetails objects ordered by the time of their event, or an error if the
+/// query fails.
+pub fn read_diaper_details_between(
+    connection: &mut SqliteConnection,
+    child: i32,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Result<Vec<DiaperDetails>, BabyrsError> {
+    use schema::events::dsl::*;
+
+    Ok(schema::diaper_details::table
+        .inner_join(events)
+        .filter(child_id.eq(child))
+        .filter(dt.ge(start))
+        .filter(dt.lt(end))
+        .filter(deleted_at.is_null())
+        .order(dt.asc())
+        .select(DiaperDetails::as_select())
+        .load(connection)?)
+}
+
+/// Deletes the diaper details of an event, if any.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `event`: The id of the event.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows deleted, or an error if the query fails.
+pub fn delete_diaper_details(
+    connection: &mut SqliteConnection,
+    event: i32,
+) -> Result<usize, BabyrsError> {
+    use schema::diaper_details::dsl::*;
+
+    debug!("Deleting diaper details of event {}", event);
+
+    Ok(diesel::delete(diaper_details.find(event)).execute(connection)?)
+}
+
+/// Writes the note of an event, replacing any written before; an empty note deletes it.
+///
+/// The history of the event does not record notes, and a replaced note is lost.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `event`: The id of the event.
+/// - `text`: The text of the note, trimmed before it is written.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows written or deleted, or an error if the event does not exist or the query
+/// fails.
+pub fn write_note(
+    connection: &mut SqliteConnection,
+    event: i32,
+    text: &str,
+) -> Result<usize, BabyrsError> {
+    use schema::event_notes::dsl;
+
+    debug!("Writing note of event {}: {:?}", event, text);
+
+    schema::events::table
+        .find(event)
+        .select(schema::events::id)
+        .first::<i32>(connection)?;
+
+    let text = text.trim();
+    if text.is_empty() {
+        return delete_note(connection, event);
+    }
+
+    // an upsert rather than a replace, so that the update trigger keeps the full-text index in sync
+    Ok(diesel::insert_into(dsl::event_notes)
+        .values(&EventNote {
+            event_id: event,
+            text: text.to_owned(),
+        })
+        .on_conflict(dsl::event_id)
+        .do_update()
+        .set(dsl::text.eq(text))
+        .execute(connection)?)
+}
+
+/// Reads the notes of the events of a child within a period.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+/// - `start`: The inclusive start of the period, in UTC.
+/// - `end`: The exclusive end of the period, in UTC.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of EventNote objects ordered by the time of their event, or an error if the query
+/// fails.
+pub fn read_notes_between(
+    connection: &mut SqliteConnection,
+    child: i32,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Result<Vec<EventNote>, BabyrsError> {
+    use schema::events::dsl::*;
+
+    Ok(schema::event_notes::table
+        .inner_join(events)
+        .filter(child_id.eq(child))
+        .filter(dt.ge(start))
+        .filter(dt.lt(end))
+        .filter(deleted_at.is_null())
+        .order(dt.asc())
+        .select(EventNote::as_select())
+        .load(connection)?)
+}
+
+/// Deletes the note of an event, if any.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `event`: The id of the event.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows deleted, or an error if the query fails.
+pub fn delete_note(connection: &mut SqliteConnection, event: i32) -> Result<usize, BabyrsError> {
+    use schema::event_notes::dsl::*;
+
+    debug!("Deleting note of event {}", event);
+
+    Ok(diesel::delete(event_notes.find(event)).execute(connection)?)
+}
+
+/// The id of an event whose note matches a full-text search.
+#[derive(QueryableByName)]
+struct NoteMatch {
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    event_id: i32,
+}
+
+/// Searches the notes of a child's events across all dates.
+///
+/// Every word of the query has to appear in the note, as a whole word or the start of one, ignoring case.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+/// - `query`: The words to search for.
+///
+/// # Returns
+///
+/// A `Result` containing the matching events and their notes, newest first, or an error if the query fails.
+pub fn search_notes(
+    connection: &mut SqliteConnection,
+    child: i32,
+    query: &str,
+) -> Result<Vec<(BabyEvent, EventNote)>, BabyrsError> {
+    use schema::events::dsl::*;
+
+    info!("Searching notes of child {} for {:?}", child, query);
+
+    // quote every word so that FTS5 operators and punctuation are matched literally
+    let terms = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<String>>();
+    if terms.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let matches = diesel::sql_query(
+        "SELECT rowid AS event_id FROM event_notes_fts WHERE event_notes_fts MATCH ?",
+    )
+    .bind::<diesel::sql_types::Text, _>(terms.join(" "))
+    .load::<NoteMatch>(connection)?
+    .into_iter()
+    .map(|m| m.event_id)
+    .collect::<Vec<i32>>();
+
+    Ok(events
+        .inner_join(schema::event_notes::table)
+        .filter(id.eq_any(matches))
+        .filter(child_id.eq(child))
+        .filter(deleted_at.is_null())
+        .order(dt.desc())
+        .select((BabyEvent::as_select(), EventNote::as_select()))
+        .load(connection)?)
+}
+
+/// Writes a new user-defined event type with its fields into the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `new_type`: The type to write.
+/// - `fields`: The fields of the type, in the order they are entered; their type and position are set from it.
+///
+/// # Returns
+///
+/// A `Result` containing the stored type with its id, or an error if the type or a field is invalid, the name of the
+/// type is taken, two fields share a name or the query fails.
+pub fn write_event_type(
+    connection: &mut SqliteConnection,
+    new_type: NewEventType,
+    fields: Vec<NewEventField>,
+) -> Result<EventType, BabyrsError> {
+    debug!("Writing event type: {:?} {:?}", &new_type, &fields);
+
+    new_type.validate()?;
+    for (index, field) in fields.iter().enumerate() {
+        field.validate()?;
+
+        if fields[..index]
+            .iter()
+            .any(|other| other.name.trim().eq_ignore_ascii_case(field.name.trim()))
+        {
+            return Err(BabyrsError::Validation(format!(
+                "field {} is defined twice",
+                field.name.trim()
+            )));
+        }
+    }
+
+    let new_type = NewEventType {
+        name: new_type.name.trim().to_owned(),
+    };
+
+    connection.transaction(|connection| {
+        diesel::insert_into(schema::event_types::table)
+            .values(&new_type)
+            .execute(connection)?;
+
+        let event_type: EventType = schema::event_types::table
+            .order(schema::event_types::id.desc())
+            .select(EventType::as_select())
+            .first(connection)?;
+
+        let fields = fields
+            .into_iter()
+            .zip(0..)
+            .map(|(field, position)| NewEventField {
+                event_type_id: event_type.id,
+                position,
+                name: field.name.trim().to_owned(),
+                ..field
+            })
+            .collect::<Vec<NewEventField>>();
+
+        diesel::insert_into(schema::event_fields::table)
+            .values(&fields)
+            .execute(connection)?;
+
+        Ok(event_type)
+    })
+}
+
+/// Reads the user-defined event types and their fields from the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+///
+/// # Returns
+///
+/// A `Result` containing the `EventRegistry`, or an error if the query fails.
+pub fn read_event_registry(
+    connection: &mut SqliteConnection,
+) -> Result<EventRegistry, BabyrsError> {
+    let types = schema::event_types::table
+        .order(schema::event_types::id.asc())
+        .select(EventType::as_select())
+        .load(connection)?;
+    let fields = schema::event_fields::table
+        .order((
+            schema::event_fields::event_type_id.asc(),
+            schema::event_fields::position.asc(),
+        ))
+        .select(EventField::as_select())
+        .load(connection)?;
+
+    Ok(EventRegistry { types, fields })
+}
+
+/// Checks that values are only given for the fields of an event's type, and are stored the way the fields store them.
+fn validate_values(
+    connection: &mut SqliteConnection,
+    event_type: Option<i32>,
+    values: &BTreeMap<i32, String>,
+) -> Result<(), BabyrsError> {
+    use schema::event_fields::dsl::*;
+
+    let Some(event_type) = event_type else {
+        if values.is_empty() {
+            return Ok(());
+        }
+        return Err(BabyrsError::Validation(
+            "values need an event of a user-defined type".to_owned(),
+        ));
+    };
+
+    schema::event_types::table
+        .find(event_type)
+        .select(schema::event_types::id)
+        .first::<i32>(connection)
+        .optional()?
+        .ok_or_else(|| BabyrsError::Validation(format!("Unknown event type {}", event_type)))?;
+
+    let fields: Vec<EventField> = event_fields
+        .filter(event_type_id.eq(event_type))
+        .select(EventField::as_select())
+        .load(connection)?;
+
+    for (field, value) in values {
+        fields
+            .iter()
+            .find(|f| f.id == *field)
+            .ok_or_else(|| {
+                BabyrsError::Validation(format!("Field {} is not part of the event type", field))
+            })?
+            .validate_value(value)?;
+    }
+
+    Ok(())
+}
+
+/// Inserts the values of a new event.
+fn insert_values(
+    connection: &mut SqliteConnection,
+    event: i32,
+    values: &BTreeMap<i32, String>,
+) -> Result<usize, BabyrsError> {
+    let values = values
+        .iter()
+        .map(|(field, value)| EventValue {
+            event_id: event,
+            field_id: *field,
+            value: value.clone(),
+        })
+        .collect::<Vec<EventValue>>();
+
+    Ok(diesel::insert_into(schema::event_values::table)
+        .values(&values)
+        .execute(connection)?)
+}
+
+/// Writes the values of the fields of an event of a user-defined type, replacing those written before; fields missing
+/// from `values` are left empty.
+///
+/// Values are kept out of the history of the event, so their changes cannot be reverted.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `event`: The id of the event.
+/// - `values`: The stored values by the id of their field, see [`EventField::parse_value`].
+///
+/// # Returns
+///
+/// A `Result` containing the number of values written, or an error if the event does not exist, a value is invalid or
+/// the query fails.
+pub fn write_event_values(
+    connection: &mut SqliteConnection,
+    event: i32,
+    values: &BTreeMap<i32, String>,
+) -> Result<usize, BabyrsError> {
+    use schema::event_values::dsl::*;
+
+    debug!("Writing values of event {}: {:?}", event, values);
+
+    connection.transaction(|connection| {
+        let event: BabyEvent = schema::events::table
+            .find(event)
+            .select(BabyEvent::as_select())
+            .first(connection)?;
+        validate_values(connection, event.event_type_id, values)?;
+
+        diesel::delete(event_values.filter(event_id.eq(event.id))).execute(connection)?;
+
+        insert_values(connection, event.id, values)
+    })
+}
+
+/// Reads the values of the fields of an event.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `event`: The id of the event.
+///
+/// # Returns
+///
+/// A `Result` containing the stored values by the id of their field, or an error if the query fails.
+pub fn read_event_values(
+    connection: &mut SqliteConnection,
+    event: i32,
+) -> Result<BTreeMap<i32, String>, BabyrsError> {
+    use schema::event_values::dsl::*;
+
+    Ok(event_values
+        .filter(event_id.eq(event))
+        .select((field_id, value))
+        .load::<(i32, String)>(connection)?
+        .into_iter()
+        .collect())
+}
+
+/// Reads the values of the events of a child within a period.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+/// - `start`: The inclusive start of the period, in UTC.
+/// - `end`: The exclusive end of the period, in UTC.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of EventValue objects ordered by the time of their event, or an error if the query
+/// fails.
+pub fn read_event_values_between(
+    connection: &mut SqliteConnection,
+    child: i32,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Result<Vec<EventValue>, BabyrsError> {
+    use schema::events::dsl::*;
+
+    Ok(schema::event_values::table
+        .inner_join(events)
+        .filter(child_id.eq(child))
+        .filter(dt.ge(start))
+        .filter(dt.lt(end))
+        .filter(deleted_at.is_null())
+        .order(dt.asc())
+        .select(EventValue::as_select())
+        .load(connection)?)
+}
+
+/// Writes a new child into the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `new_child`: The child to write.
+///
+/// # Returns
+///
+/// A `Result` containing the stored child with its id, or an error if the child is invalid or the query fails.
+pub fn write_child(
+    connection: &mut SqliteConnection,
+    new_child: NewChild,
+) -> Result<Child, BabyrsError> {
+    debug!("Writing child: {:?}", &new_child);
+
+    new_child.validate()?;
+
+    Ok(connection.transaction(|connection| {
+        diesel::insert_into(schema::children::table)
+            .values(&new_child)
+            .execute(connection)?;
+
+        schema::children::table
+            .order(schema::children::id.desc())
+            .select(Child::as_select())
+            .first(connection)
+    })?)
+}
+
+/// Reads the children from the database, in the order they were added.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of Child objects, or an error if the query fails.
+pub fn read_children(connection: &mut SqliteConnection) -> Result<Vec<Child>, BabyrsError> {
+    use schema::children::dsl::*;
+
+    Ok(children
+        .order(id.asc())
+        .select(Child::as_select())
+        .load(connection)?)
+}
+
+/// Updates an existing child in the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The child to update.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows updated, or an error if the child is invalid or the query fails.
+pub fn update_child(connection: &mut SqliteConnection, child: Child) -> Result<usize, BabyrsError> {
+    use schema::children::dsl::*;
+
+    debug!("Updating child: {:?}", &child);
+
+    child.validate()?;
+
+    Ok(diesel::update(children.find(child.id))
+        .set(&child)
+        .execute(connection)?)
+}
+
+/// Writes a new caregiver into the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `new_caregiver`: The caregiver to write.
+///
+/// # Returns
+///
+/// A `Result` containing the stored caregiver with its id, or an error if the caregiver is invalid, the name is taken
+/// or the query fails.
+pub fn write_caregiver(
+    connection: &mut SqliteConnection,
+    new_caregiver: NewCaregiver,
+) -> Result<Caregiver, BabyrsError> {
+    debug!("Writing caregiver: {:?}", &new_caregiver);
+
+    new_caregiver.validate()?;
+
+    let new_caregiver = NewCaregiver {
+        name: new_caregiver.name.trim().to_owned(),
+    };
+
+    Ok(connection.transaction(|connection| {
+        diesel::insert_into(schema::caregivers::table)
+            .values(&new_caregiver)
+            .execute(connection)?;
+
+        schema::caregivers::table
+            .order(schema::caregivers::id.desc())
+            .select(Caregiver::as_select())
+            .first(connection)
+    })?)
+}
+
+/// Reads the caregivers from the database, in the order they were added.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of Caregiver objects, or an error if the query fails.
+pub fn read_caregivers(connection: &mut SqliteConnection) -> Result<Vec<Caregiver>, BabyrsError> {
+    use schema::caregivers::dsl::*;
+
+    Ok(caregivers
+        .order(id.asc())
+        .select(Caregiver::as_select())
+        .load(connection)?)
+}
+
+/// Writes a new growth measurement into the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `new_measurement`: The measurement to write.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows inserted, or an error if the measurement is invalid or the query fails.
+pub fn write_measurement(
+    connection: &mut SqliteConnection,
+    new_measurement: NewMeasurement,
+) -> Result<usize, BabyrsError> {
+    debug!("Writing measurement: {:?}", &new_measurement);
+
+    new_measurement.validate()?;
+
+    Ok(diesel::insert_into(schema::measurements::table)
+        .values(&new_measurement)
+        .execute(connection)?)
+}
+
+/// Reads growth measurements from the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of Measurement objects, or an error if the query fails.
+pub fn read_measurements(
+    connection: &mut SqliteConnection,
+) -> Result<Vec<Measurement>, BabyrsError> {
+    use schema::measurements::dsl::*;
+
+    info!("Reading measurements");
+
+    let results: Vec<Measurement> = measurements
+        .select(Measurement::as_select())
+        .load(connection)?;
+
+    debug!("Read measurements: {:?}", &results);
+
+    Ok(results)
+}
+
+/// Reads the growth measurements of a child from the database, oldest first.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of Measurement objects, or an error if the query fails.
+pub fn read_child_measurements(
+    connection: &mut SqliteConnection,
+    child: i32,
+) -> Result<Vec<Measurement>, BabyrsError> {
+    use schema::measurements::dsl::*;
+
+    info!("Reading measurements of child {}", child);
+
+    let results: Vec<Measurement> = measurements
+        .filter(child_id.eq(child))
+        .order(dt.asc())
+        .select(Measurement::as_select())
+        .load(connection)?;
+
+    debug!("Read measurements: {:?}", &results);
+
+    Ok(results)
+}
+
+/// Updates an existing growth measurement in the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `measurement`: The measurement to update.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows updated, or an error if the measurement is invalid or the query fails.
+pub fn update_measurement(
+    connection: &mut SqliteConnection,
+    measurement: Measurement,
+) -> Result<usize, BabyrsError> {
+    use schema::measurements::dsl::*;
+
+    debug!("Updating measurement: {:?}", &measurement);
+
+    measurement.validate()?;
+
+    Ok(diesel::update(measurements.find(measurement.id))
+        .set(&measurement)
+        .execute(connection)?)
+}
+
+/// Deletes an existing growth measurement in the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `measurement`: The measurement to delete.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows deleted, or an error if the query fails.
+pub fn delete_measurement(
+    connection: &mut SqliteConnection,
+    measurement: Measurement,
+) -> Result<usize, BabyrsError> {
+    use schema::measurements::dsl::*;
+
+    debug!("Deleting measurement: {:?}", &measurement);
+
+    Ok(diesel::delete(measurements.find(measurement.id)).execute(connection)?)
+}
+
+/// Writes a new sleep session into the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `new_session`: The session to write, without an end if the child is still asleep.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows inserted, or an error if the session is invalid, the child already has a
+/// session in progress or the query fails.
+pub fn write_sleep_session(
+    connection: &mut SqliteConnection,
+    new_session: NewSleepSession,
+) -> Result<usize, BabyrsError> {
+    debug!("Writing sleep session: {:?}", &new_session);
+
+    new_session.validate()?;
+
+    Ok(diesel::insert_into(schema::sleep_sessions::table)
+        .values(&new_session)
+        .execute(connection)?)
+}
+
+/// Reads the sleep sessions of a child from the database, oldest first.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of SleepSession objects, or an error if the query fails.
+pub fn read_child_sleep_sessions(
+    connection: &mut SqliteConnection,
+    child: i32,
+) -> Result<Vec<SleepSession>, BabyrsError> {
+    use schema::sleep_sessions::dsl::*;
+
+    info!("Reading sleep sessions of child {}", child);
+
+    let results: Vec<SleepSession> = sleep_sessions
+        .filter(child_id.eq(child))
+        .order(start_dt.asc())
+        .select(SleepSession::as_select())
+        .load(connection)?;
+
+    debug!("Read sleep sessions: {:?}", &results);
+
+    Ok(results)
+}
+
+/// Reads the sleep sessions of a child overlapping a period, oldest first.
+///
+/// Sessions that started before `start` or end after `end` are returned whole, and a session in progress overlaps
+/// every period after it started.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+/// - `start`: The inclusive start of the period, in UTC.
+/// - `end`: The exclusive end of the period, in UTC.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of SleepSession objects, or an error if the query fails.
+pub fn read_sleep_sessions_between(
+    connection: &mut SqliteConnection,
+    child: i32,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Result<Vec<SleepSession>, BabyrsError> {
+    use schema::sleep_sessions::dsl::*;
+
+    info!(
+        "Reading sleep sessions of child {} between {} and {}",
+        child, start, end
+    );
+
+    let results: Vec<SleepSession> = sleep_sessions
+        .filter(child_id.eq(child))
+        .filter(start_dt.lt(end))
+        .filter(end_dt.is_null().or(end_dt.gt(start)))
+        .order(start_dt.asc())
+        .select(SleepSession::as_select())
+        .load(connection)?;
+
+    debug!("Read sleep sessions: {:?}", &results);
+
+    Ok(results)
+}
+
+/// Reads the sleep session a child is currently in, if any.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+///
+/// # Returns
+///
+/// A `Result` containing the session without an end, `None` if the child is awake, or an error if the query fails.
+pub fn read_open_sleep_session(
+    connection: &mut SqliteConnection,
+    child: i32,
+) -> Result<Option<SleepSession>, BabyrsError> {
+    use schema::sleep_sessions::dsl::*;
+
+    Ok(sleep_sessions
+        .filter(child_id.eq(child))
+        .filter(end_dt.is_null())
+        .select(SleepSession::as_select())
+        .first(connection)
+        .optional()?)
+}
+
+/// Updates an existing sleep session in the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `session`: The session to update.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows updated, or an error if the session is invalid or the query fails.
+pub fn update_sleep_session(
+    connection: &mut SqliteConnection,
+    session: SleepSession,
+) -> Result<usize, BabyrsError> {
+    use schema::sleep_sessions::dsl::*;
+
+    debug!("Updating sleep session: {:?}", &session);
+
+    session.validate()?;
+
+    Ok(diesel::update(sleep_sessions.find(session.id))
+        .set(&session)
+        .execute(connection)?)
+}
+
+/// Deletes an existing sleep session in the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `session`: The session to delete.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows deleted, or an error if the query fails.
+pub fn delete_sleep_session(
+    connection: &mut SqliteConnection,
+    session: SleepSession,
+) -> Result<usize, BabyrsError> {
+    use schema::sleep_sessions::dsl::*;
+
+    debug!("Deleting sleep session: {:?}", &session);
+
+    Ok(diesel::delete(sleep_sessions.find(session.id)).execute(connection)?)
+}
+
+/// Writes a new medication into the catalogue.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `new_medication`: The medication to write.
+///
+/// # Returns
+///
+/// A `Result` containing the stored medication with its id, or an error if the medication is invalid or the query
+/// fails.
+pub fn write_medication(
+    connection: &mut SqliteConnection,
+    new_medication: NewMedication,
+) -> Result<Medication, BabyrsError> {
+    debug!("Writing medication: {:?}", &new_medication);
+
+    new_medication.validate()?;
+
+    Ok(connection.transaction(|connection| {
+        diesel::insert_into(schema::medications::table)
+            .values(&new_medication)
+            .execute(connection)?;
+
+        schema::medications::table
+            .order(schema::medications::id.desc())
+            .select(Medication::as_select())
+            .first(connection)
+    })?)
+}
+
+/// Reads the medication catalogue from the database, ordered by name.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of Medication objects, or an error if the query fails.
+pub fn read_medications(connection: &mut SqliteConnection) -> Result<Vec<Medication>, BabyrsError> {
+    use schema::medications::dsl::*;
+
+    Ok(medications
+        .order(name.asc())
+        .select(Medication::as_select())
+        .load(connection)?)
+}
+
+/// Updates an existing medication in the catalogue.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `medication`: The medication to update.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows updated, or an error if the medication is invalid or the query fails.
+pub fn update_medication(
+    connection: &mut SqliteConnection,
+    medication: Medication,
+) -> Result<usize, BabyrsError> {
+    use schema::medications::dsl::*;
+
+    debug!("Updating medication: {:?}", &medication);
+
+    medication.validate()?;
+
+    Ok(diesel::update(medications.find(medication.id))
+        .set(&medication)
+        .execute(connection)?)
+}
+
+/// Writes a new dose into the database.
+///
+/// The dose is written even if it breaks the limits of the medication, so that doses given elsewhere can be logged;
+/// use [`medication::next_allowed`] to check them first.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `new_dose`: The dose to write.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows inserted, or an error if the dose is invalid or the query fails.
+pub fn write_dose(
+    connection: &mut SqliteConnection,
+    new_dose: NewDose,
+) -> Result<usize, BabyrsError> {
+    debug!("Writing dose: {:?}", &new_dose);
+
+    new_dose.validate()?;
+
+    Ok(diesel::insert_into(schema::doses::table)
+        .values(&new_dose)
+        .execute(connection)?)
+}
+
+/// Reads the doses given to a child from the database, oldest first.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of Dose objects, or an error if the query fails.
+pub fn read_child_doses(
+    connection: &mut SqliteConnection,
+    child: i32,
+) -> Result<Vec<Dose>, BabyrsError> {
+    use schema::doses::dsl::*;
+
+    info!("Reading doses of child {}", child);
+
+    let results: Vec<Dose> = doses
+        .filter(child_id.eq(child))
+        .order(dt.asc())
+        .select(Dose::as_select())
+        .load(connection)?;
+
+    debug!("Read doses: {:?}", &results);
+
+    Ok(results)
+}
+
+/// Deletes an existing dose in the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `dose`: The dose to delete.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows deleted, or an error if the query fails.
+pub fn delete_dose(connection: &mut SqliteConnection, dose: Dose) -> Result<usize, BabyrsError> {
+    use schema::doses::dsl::*;
+
+    debug!("Deleting dose: {:?}", &dose);
+
+    Ok(diesel::delete(doses.find(dose.id)).execute(connection)?)
+}
+
+/// Writes the schedule of a medication for a child, replacing any previous schedule of the same medication.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `new_schedule`: The schedule to write.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows inserted, or an error if the schedule is invalid or the query fails.
+pub fn write_medication_schedule(
+    connection: &mut SqliteConnection,
+    new_schedule: NewMedicationSchedule,
+) -> Result<usize, BabyrsError> {
+    debug!("Writing medication schedule: {:?}", &new_schedule);
+
+    new_schedule.validate()?;
+
+    Ok(diesel::replace_into(schema::medication_schedules::table)
+        .values(&new_schedule)
+        .execute(connection)?)
+}
+
+/// Reads the medication schedules of a child from the database.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `child`: The id of the child.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of MedicationSchedule objects, or an error if the query fails.
+pub fn read_medication_schedules(
+    connection: &mut SqliteConnection,
+    child: i32,
+) -> Result<Vec<MedicationSchedule>, BabyrsError> {
+    use schema::medication_schedules::dsl::*;
+
+    Ok(medication_schedules
+        .filter(child_id.eq(child))
+        .order(id.asc())
+        .select(MedicationSchedule::as_select())
+        .load(connection)?)
+}
+
+/// Deletes an existing medication schedule in the database, leaving the medication to be given as needed.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `schedule`: The schedule to delete.
+///
+/// # Returns
+///
+/// A `Result` containing the number of rows deleted, or an error if the query fails.
+pub fn delete_medication_schedule(
+    connection: &mut SqliteConnection,
+    schedule: MedicationSchedule,
+) -> Result<usize, BabyrsError> {
+    use schema::medication_schedules::dsl::*;
+
+    debug!("Deleting medication schedule: {:?}", &schedule);
+
+    Ok(diesel::delete(medication_schedules.find(schedule.id)).execute(connection)?)
+}
+
+/// Summary of a CSV import.
+///
+/// # Fields
+///
+/// - `inserted`: Number of rows written to the database as new events.
+/// - `merged`: Number of rows merged into an existing event at the same timestamp.
+/// - `skipped`: The numbers of the rows skipped as exact duplicates of an existing event, counting from 1 after the
+///   header.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub inserted: usize,
+    pub merged: usize,
+    pub skipped: Vec<usize>,
+}
+
+/// Processes a CSV file and writes the baby events into the database, skipping exact duplicates of existing events.
+///
+/// Events are assigned to the child in the optional `child_id` column, or to the default child. Volumes are converted
+/// from the unit in the optional `unit` column, or taken as millilitres. Timestamps without an offset from UTC are
+/// taken as wall-clock times in the local time zone. The optional `type` and `values` columns name a user-defined
+/// event type, which has to exist, and the values of its fields.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `file_path`: The path of the CSV file.
+///
+/// # Returns
+///
+/// Returns a `Result` containing an `ImportSummary` with the inserted, merged and skipped rows, or the first error.
+/// Rows written before the error are kept.
+pub fn process_csv(
+    connection: &mut SqliteConnection,
+    file_path: &str,
+) -> Result<ImportSummary, BabyrsError> {
+    import_csv(connection, file_path, None, DuplicatePolicy::default())
+}
+
+/// Processes a CSV file and writes the baby events into the database for a single child.
+///
+/// # Arguments
+///
+/// - `connection`: The database connection.
+/// - `file_path`: The path of the CSV file.
+/// - `child`: The id of the child, overriding any `child_id` column in the file.
+/// - `policy`: How to treat rows at the same timestamp as an existing event.
+///
+/// # Returns
+///
+/// Returns a `Result` containing an `ImportSummary` with the inserted, merged and skipped rows, or the first error.
+/// Rows written before the error are kept.
+pub fn process_child_csv(
+    connection: &mut SqliteConnection,
+    file_path: &str,
+    child: i32,
+    policy: DuplicatePolicy,
+) -> Result<ImportSummary, BabyrsError> {
+    import_csv(connection, file_path, Some(child), policy)
+}
+
+/// Writes the baby events of a CSV file into the database, optionally assigning them all to one child.
+fn import_csv(
+    connection: &mut SqliteConnection,
+    file_path: &str,
+    child: Option<i32>,
+    policy: DuplicatePolicy,
+) -> Result<ImportSummary, BabyrsError> {
+    info!("Processing CSV file: {}", &file_path);
+
+    let mut rdr: Reader<File> = Reader::from_path(file_path)?;
+    let mut summary = ImportSummary::default();
+    let registry = read_event_registry(connection)?;
+
+    for (index, result) in rdr.deserialize().enumerate() {
+        let row: CsvEvent = result?;
+        let mut record = row.to_new_event(&Local)?;
+        let (event_type, values) = row.to_values(&registry)?;
+
+        record.event_type_id = event_type;
+        if let Some(child) = child {
+            record.child_id = child;
+        }
+
+        debug!("Read record: {:?} {:?}", &record, &values);
+
+        match write_event_with_values(connection, record, &values, policy)? {
+            WriteOutcome::Inserted(_) => summary.inserted += 1,
+            WriteOutcome::Merged(_) => summary.merged += 1,
+            WriteOutcome::Skipped(_) => summary.skipped.push(index + 1),
+        }
+    }
+
+    info!("Processed CSV file: {} ({:?})", &file_path, &summary);
+
+    Ok(summary)
+}
+
+/// Parses the first rows of a CSV file without writing them to the database.
+///
+/// The `type` and `values` columns are not looked up, so events of a user-defined type are parsed as built-in events
+/// recording nothing.
+///
+/// # Arguments
+///
+/// - `file_path`: The path of the CSV file.
+/// - `rows`: The maximum number of rows to parse.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the parsed baby events with their volumes in millilitres, or the first parse error.
+pub fn preview_csv(file_path: &str, rows: usize) -> Result<Vec<NewBabyEvent>, BabyrsError> {
+    debug!("Previewing CSV file: {}", &file_path);
+
+    let mut rdr: Reader<File> = Reader::from_path(file_path)?;
+    let mut records: Vec<NewBabyEvent> = Vec::new();
+
+    for result in rdr.deserialize().take(rows) {
+        let row: CsvEvent = result?;
+        records.push(row.to_new_event(&Local)?);
+    }
+
+    Ok(records)
+}
+
+/// Writes baby events into a CSV file that can be imported again, replacing the file if it exists.
+///
+/// Events of a user-defined type are written with the name of their type and their values, and are imported into a
+/// database defining a type of the same name with the same fields.
+///
+/// # Arguments
+///
+/// - `events`: The events to write.
+/// - `values`: The values of the events of a user-defined type.
+/// - `registry`: The event types, naming the types and fields of the events.
+/// - `file_path`: The path of the CSV file.
+/// - `unit`: The unit to write the volumes in, named in the `unit` column.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the number of rows written, or an error if the file could not be written.
+pub fn export_csv(
+    events: &[BabyEvent],
+    values: &[EventValue],
+    registry: &EventRegistry,
+    file_path: &str,
+    unit: VolumeUnit,
+) -> Result<usize, BabyrsError> {
+    info!(
+        "Exporting {} events to CSV file: {}",
+        events.len(),
+        &file_path
+    );
+
+    let mut wtr: Writer<File> = Writer::from_path(file_path)?;
+
+    for event in events {
+        wtr.serialize(CsvEvent::from_event(event, values, registry, unit))?;
+    }
+    wtr.flush()
+        .map_err(|error| BabyrsError::Csv(csv::Error::from(error)))?;
+
+    Ok(events.len())
+}
+
+/// Leaves out the events in the trash, which no calculation counts.
+fn without_deleted(mut events: Vec<BabyEvent>) -> Vec<BabyEvent> {
+    events.retain(|event| !event.is_deleted());
+    events
+}
+
+/// Calculate total volume of food consumed for each day.
+///
+/// # Arguments
+///
+/// - `events`: A vector of BabyEvent objects.
+/// - `tz`: The time zone whose days the events are grouped by.
+///
+/// # Returns
+///
+/// A vector of tuples containing the date and total volume of food consumed.
+pub fn calculate_daily_volume<Tz: TimeZone>(
+    events: Vec<BabyEvent>,
+    tz: &Tz,
+) -> Vec<(NaiveDate, i32)> {
+    let events = without_deleted(events);
+    let mut daily_volume: Vec<(NaiveDate, i32)> = Vec::new();
+
+    for event in events {
+        let date = zone::to_local(tz, event.dt).date();
+        let volume = event.breastmilk + event.formula;
+
+        if let Some((_, tmp)) = daily_volume.iter_mut().find(|(d, _)| d == &date) {
+            *tmp += volume;
+        } else {
+            daily_volume.push((date, volume));
+        }
+    }
+
+    daily_volume
+}
+
+/// Calculate total volume of food consumed for each week, starting on Mondays.
+///
+/// # Arguments
+///
+/// - `daily_volume`: A vector of tuples containing the date and total volume of food consumed.
+///
+/// # Returns
+///
+/// A vector of tuples containing the start date of the week (Monday) and the total volume of food consumed for that week.
+pub fn calculate_weekly_volume(daily_volume: Vec<(NaiveDate, i32)>) -> Vec<(NaiveDate, i32)> {
+    let mut weekly_volume: Vec<(NaiveDate, i32)> = Vec::new();
+
+    for (date, volume) in daily_volume {
+        let days_from_monday = date.weekday().num_days_from_monday();
+        let start_date = date - chrono::Duration::days(days_from_monday as i64);
+
+        if let Some((_, tmp)) = weekly_volume.iter_mut().find(|(d, _)| d == &start_date) {
+            *tmp += volume;
+        } else {
+            weekly_volume.push((start_date, volume));
+        }
+    }
+
+    weekly_volume
+}
+
+/// Calculate total volume of food consumed for each month.
+///
+/// # Arguments
+///
+/// - `daily_volume`: A vector of tuples containing the date and total volume of food consumed.
+///
+/// # Returns
+///
+/// A vector of tuples containing the start date of the month and the total volume of food consumed for that month.
+pub fn calculate_monthly_volume(daily_volume: Vec<(NaiveDate, i32)>) -> Vec<(NaiveDate, i32)> {
+    let mut monthly_volume: Vec<(NaiveDate, i32)> = Vec::new();
+
+    for (date, volume) in daily_volume {
+        let start_date = NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap();
+
+        if let Some((_, tmp)) = monthly_volume.iter_mut().find(|(d, _)| d == &start_date) {
+            *tmp += volume;
+        } else {
+            monthly_volume.push((start_date, volume));
+        }
+    }
+
+    monthly_volume
+}
+
+/// Calculate total volume of milk pumped for each day.
+///
+/// # Arguments
+///
+/// - `events`: A vector of BabyEvent objects.
+/// - `tz`: The time zone whose days the events are grouped by.
 ///
 /// # Returns
 ///
 /// A vector of tuples containing the date and total volume of milk pumped.
-pub fn calculate_daily_pumped(events: Vec<BabyEvent>) -> Vec<(NaiveDate, i32)> {
+pub fn calculate_daily_pumped<Tz: TimeZone>(
+    events: Vec<BabyEvent>,
+    tz: &Tz,
+) -> Vec<(NaiveDate, i32)> {
+    let events = without_deleted(events);
     let mut daily_pumped: Vec<(NaiveDate, i32)> = Vec::new();
 
     for event in events {
-        let date = event.dt.date();
+        let date = zone::to_local(tz, event.dt).date();
+        let volume = event.pump;
+
+        if let Some((_, tmp)) = daily_pumped.iter_mut().find(|(d, _)| d == &date) {
+            *tmp += volume;
+        } else {
+            daily_pumped.push((date, volume));
+        }
+    }
+
+    daily_pumped
+}
+
+/// Calculate total volume of milk pumped for each week, starting on Mondays.
+///
+/// # Arguments
+///
+/// - `daily_pumped`: A vector of tuples containing the date and total volume of milk pumped.
+///
+/// # Returns
+///
+/// A vector of tuples containing the start date of the week (Monday) and the total volume of milk pumped for that week.
+pub fn calculate_weekly_pumped(daily_pumped: Vec<(NaiveDate, i32)>) -> Vec<(NaiveDate, i32)> {
+    let mut weekly_pumped: Vec<(NaiveDate, i32)> = Vec::new();
+
+    for (date, volume) in daily_pumped {
+        let days_from_monday = date.weekday().num_days_from_monday();
+        let start_date = date - chrono::Duration::days(days_from_monday as i64);
+
+        if let Some((_, tmp)) = weekly_pumped.iter_mut().find(|(d, _)| d == &start_date) {
+            *tmp += volume;
+        } else {
+            weekly_pumped.push((start_date, volume));
+        }
+    }
+
+    weekly_pumped
+}
+
+/// Calculate total volume of milk pumped for each month.
+///
+/// # Arguments
+///
+/// - `daily_pumped`: A vector of tuples containing the date and total volume of milk pumped.
+///
+/// # Returns
+///
+/// A vector of tuples containing the start date of the month and the total volume of milk pumped for that month.
+pub fn calculate_monthly_pumped(daily_pumped: Vec<(NaiveDate, i32)>) -> Vec<(NaiveDate, i32)> {
+    let mut monthly_pumped: Vec<(NaiveDate, i32)> = Vec::new();
+
+    for (date, volume) in daily_pumped {
+        let start_date = NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap();
+
+        if let Some((_, tmp)) = monthly_pumped.iter_mut().find(|(d, _)| d == &start_date) {
+            *tmp += volume;
+        } else {
+            monthly_pumped.push((start_date, volume));
+        }
+    }
+
+    monthly_pumped
+}
+
+/// Calculate number of wet diapers for each day.
+///
+/// # Arguments
+///
+/// - `events`: A vector of BabyEvent objects.
+/// - `tz`: The time zone whose days the events are grouped by.
+///
+/// # Returns
+///
+/// A vector of tuples containing the date and number of wet diapers.
+pub fn calculate_daily_wet_diapers<Tz: TimeZone>(
+    events: Vec<BabyEvent>,
+    tz: &Tz,
+) -> Vec<(NaiveDate, i32)> {
+    let events = without_deleted(events);
+    let mut daily_wet_diapers: Vec<(NaiveDate, i32)> = Vec::new();
+
+    for event in events {
+        let date = zone::to_local(tz, event.dt).date();
+        let wet_diapers = if event.urine { 1 } else { 0 };
+
+        if let Some((_, tmp)) = daily_wet_diapers.iter_mut().find(|(d, _)| d == &date) {
+            *tmp += wet_diapers;
+        } else {
+            daily_wet_diapers.push((date, wet_diapers));
+        }
+    }
+
+    daily_wet_diapers
+}
+
+/// Calculate number of wet diapers for each week, starting on Mondays.
+///
+/// # Arguments
+///
+/// - `daily_wet_diapers`: A vector of tuples containing the date and number of wet diapers.
+///
+/// # Returns
+///
+/// A vector of tuples containing the start date of the week (Monday) and the number of wet diapers for that week.
+pub fn calculate_weekly_wet_diapers(
+    daily_wet_diapers: Vec<(NaiveDate, i32)>,
+) -> Vec<(NaiveDate, i32)> {
+    let mut weekly_wet_diapers: Vec<(NaiveDate, i32)> = Vec::new();
+
+    for (date, wet_diapers) in daily_wet_diapers {
+        let days_from_monday = date.weekday().num_days_from_monday();
+        let start_date = date - chrono::Duration::days(days_from_monday as i64);
+
+        if let Some((_, tmp)) = weekly_wet_diapers
+            .iter_mut()
+            .find(|(d, _)| d == &start_date)
+        {
+            *tmp += wet_diapers;
+        } else {
+            weekly_wet_diapers.push((start_date, wet_diapers));
+        }
+    }
+
+    weekly_wet_diapers
+}
+
+/// Calculate number of poopy diapers for each day.
+///
+/// # Arguments
+///
+/// - `events`: A vector of BabyEvent objects.
+/// - `tz`: The time zone whose days the events are grouped by.
+///
+/// # Returns
+///
+/// A vector of tuples containing the date and number of poopy diapers.
+pub fn calculate_daily_poopy_diapers<Tz: TimeZone>(
+    events: Vec<BabyEvent>,
+    tz: &Tz,
+) -> Vec<(NaiveDate, i32)> {
+    let events = without_deleted(events);
+    let mut daily_poopy_diapers: Vec<(NaiveDate, i32)> = Vec::new();
+
+    for event in events {
+        let date = zone::to_local(tz, event.dt).date();
+        let poopy_diapers = if event.stool { 1 } else { 0 };
+
+        if let Some((_, tmp)) = daily_poopy_diapers.iter_mut().find(|(d, _)| d == &date) {
+            *tmp += poopy_diapers;
+        } else {
+            daily_poopy_diapers.push((date, poopy_diapers));
+        }
+    }
+
+    daily_poopy_diapers
+}
+
+/// Calculate number of poopy diapers for each week, starting on Mondays.
+///
+/// # Arguments
+///
+/// - `daily_poopy_diapers`: A vector of tuples containing the date and number of poopy diapers.
+///
+/// # Returns
+///
+/// A vector of tuples containing the start date of the week (Monday) and the number of poopy diapers for that week.
+pub fn calculate_weekly_poopy_diapers(
+    daily_poopy_diapers: Vec<(NaiveDate, i32)>,
+) -> Vec<(NaiveDate, i32)> {
+    let mut weekly_poopy_diapers: Vec<(NaiveDate, i32)> = Vec::new();
+
+    for (date, poopy_diapers) in daily_poopy_diapers {
+        let days_from_monday = date.weekday().num_days_from_monday();
+        let start_date = date - chrono::Duration::days(days_from_monday as i64);
+
+        if let Some((_, tmp)) = weekly_poopy_diapers
+            .iter_mut()
+            .find(|(d, _)| d == &start_date)
+        {
+            *tmp += poopy_diapers;
+        } else {
+            weekly_poopy_diapers.push((start_date, poopy_diapers));
+        }
+    }
+
+    weekly_poopy_diapers
+}
+
+/// Calculate average time (minutes) between feedings for each day.
+///
+/// # Arguments
+///
+/// - `events`: A vector of BabyEvent objects.
+/// - `tz`: The time zone whose days the events are grouped by.
+///
+/// # Returns
+///
+/// A vector of tuples containing the date and average time between feedings (minutes).
+pub fn calculate_daily_average_time_between_feedings<Tz: TimeZone>(
+    events: Vec<BabyEvent>,
+    tz: &Tz,
+) -> Vec<(NaiveDate, i32)> {
+    let events = without_deleted(events);
+    // Filter out non-feeding events and sort by date
+    let mut feedings: Vec<&BabyEvent> = events
+        .iter()
+        .filter(|e| e.breastfeed > 0 || e.breastmilk > 0 || e.formula > 0)
+        .collect();
+    feedings.sort_by_key(|e| e.dt);
+
+    // Group feedings by date
+    let mut grouped_feedings: HashMap<NaiveDate, Vec<&BabyEvent>> = HashMap::new();
+    for feeding in feedings {
+        let date = zone::to_local(tz, feeding.dt).date();
+        grouped_feedings.entry(date).or_default().push(feeding);
+    }
+
+    // Calculate average time between feedings for each day
+    let mut daily_average_time_between_feedings: Vec<(NaiveDate, i32)> = Vec::new();
+
+    for (date, daily_feedings) in grouped_feedings {
+        let mut total_time = 0;
+        let mut count = 0;
+
+        for windows in daily_feedings.windows(2) {
+            if let [start, end] = windows {
+                total_time += (end.dt - start.dt).num_minutes();
+                count += 1;
+            }
+        }
+
+        // Calculate average time between feedings for the day
+        if count > 0 {
+            daily_average_time_between_feedings.push((date, (total_time / count) as i32));
+        }
+    }
+
+    daily_average_time_between_feedings.sort_by_key(|(d, _)| *d);
+    daily_average_time_between_feedings
+}
+
+/// Truncates a datetime to the start of its hour.
+fn start_of_hour(dt: NaiveDateTime) -> NaiveDateTime {
+    dt.date().and_hms_opt(dt.hour(), 0, 0).unwrap()
+}
+
+/// Calculate total volume of food consumed for each hour.
+///
+/// # Arguments
+///
+/// - `events`: A vector of BabyEvent objects.
+/// - `tz`: The time zone whose hours the events are grouped by.
+///
+/// # Returns
+///
+/// A vector of tuples containing the start of the hour and total volume of food consumed.
+pub fn calculate_hourly_volume<Tz: TimeZone>(
+    events: Vec<BabyEvent>,
+    tz: &Tz,
+) -> Vec<(NaiveDateTime, i32)> {
+    let events = without_deleted(events);
+    let mut hourly_volume: Vec<(NaiveDateTime, i32)> = Vec::new();
+
+    for event in events {
+        let hour = start_of_hour(zone::to_local(tz, event.dt));
+        let volume = event.breastmilk + event.formula;
+
+        if let Some((_, tmp)) = hourly_volume.iter_mut().find(|(h, _)| h == &hour) {
+            *tmp += volume;
+        } else {
+            hourly_volume.push((hour, volume));
+        }
+    }
+
+    hourly_volume
+}
+
+/// Calculate total volume of milk pumped for each hour.
+///
+/// # Arguments
+///
+/// - `events`: A vector of BabyEvent objects.
+/// - `tz`: The time zone whose hours the events are grouped by.
+///
+/// # Returns
+///
+/// A vector of tuples containing the start of the hour and total volume of milk pumped.
+pub fn calculate_hourly_pumped<Tz: TimeZone>(
+    events: Vec<BabyEvent>,
+    tz: &Tz,
+) -> Vec<(NaiveDateTime, i32)> {
+    let events = without_deleted(events);
+    let mut hourly_pumped: Vec<(NaiveDateTime, i32)> = Vec::new();
+
+    for event in events {
+        let hour = start_of_hour(zone::to_local(tz, event.dt));
         let volume = event.pump;
 
-        if let Some((_, tmp)) = daily_pumped.iter_mut().find(|(d, _)| d == &date) {
+        if let Some((_, tmp)) = hourly_pumped.iter_mut().find(|(h, _)| h == &hour) {
             *tmp += volume;
         } else {
-            daily_pumped.push((date, volume));
+            hourly_pumped.push((hour, volume));
+        }
+    }
+
+    hourly_pumped
+}
+
+/// Calculate number of wet diapers for each hour.
+///
+/// # Arguments
+///
+/// - `events`: A vector of BabyEvent objects.
+/// - `tz`: The time zone whose hours the events are grouped by.
+///
+/// # Returns
+///
+/// A vector of tuples containing the start of the hour and number of wet diapers.
+pub fn calculate_hourly_wet_diapers<Tz: TimeZone>(
+    events: Vec<BabyEvent>,
+    tz: &Tz,
+) -> Vec<(NaiveDateTime, i32)> {
+    let events = without_deleted(events);
+    let mut hourly_wet_diapers: Vec<(NaiveDateTime, i32)> = Vec::new();
+
+    for event in events {
+        let hour = start_of_hour(zone::to_local(tz, event.dt));
+        let wet_diapers = if event.urine { 1 } else { 0 };
+
+        if let Some((_, tmp)) = hourly_wet_diapers.iter_mut().find(|(h, _)| h == &hour) {
+            *tmp += wet_diapers;
+        } else {
+            hourly_wet_diapers.push((hour, wet_diapers));
+        }
+    }
+
+    hourly_wet_diapers
+}
+
+/// Calculate number of poopy diapers for each hour.
+///
+/// # Arguments
+///
+/// - `events`: A vector of BabyEvent objects.
+/// - `tz`: The time zone whose hours the events are grouped by.
+///
+/// # Returns
+///
+/// A vector of tuples containing the start of the hour and number of poopy diapers.
+pub fn calculate_hourly_poopy_diapers<Tz: TimeZone>(
+    events: Vec<BabyEvent>,
+    tz: &Tz,
+) -> Vec<(NaiveDateTime, i32)> {
+    let events = without_deleted(events);
+    let mut hourly_poopy_diapers: Vec<(NaiveDateTime, i32)> = Vec::new();
+
+    for event in events {
+        let hour = start_of_hour(zone::to_local(tz, event.dt));
+        let poopy_diapers = if event.stool { 1 } else { 0 };
+
+        if let Some((_, tmp)) = hourly_poopy_diapers.iter_mut().find(|(h, _)| h == &hour) {
+            *tmp += poopy_diapers;
+        } else {
+            hourly_poopy_diapers.push((hour, poopy_diapers));
+        }
+    }
+
+    hourly_poopy_diapers
+}
+
+/// Calculate average time (minutes) since the previous feeding for feedings in each hour.
+///
+/// # Arguments
+///
+/// - `events`: A vector of BabyEvent objects.
+/// - `tz`: The time zone whose hours the events are grouped by.
+///
+/// # Returns
+///
+/// A vector of tuples containing the start of the hour and average time since the previous feeding (minutes).
+pub fn calculate_hourly_average_time_between_feedings<Tz: TimeZone>(
+    events: Vec<BabyEvent>,
+    tz: &Tz,
+) -> Vec<(NaiveDateTime, i32)> {
+    let events = without_deleted(events);
+    // Filter out non-feeding events and sort by date
+    let mut feedings: Vec<&BabyEvent> = events
+        .iter()
+        .filter(|e| e.breastfeed > 0 || e.breastmilk > 0 || e.formula > 0)
+        .collect();
+    feedings.sort_by_key(|e| e.dt);
+
+    // Group intervals by the hour of the feeding that ends them
+    let mut grouped_intervals: HashMap<NaiveDateTime, Vec<i64>> = HashMap::new();
+    for window in feedings.windows(2) {
+        if let [start, end] = window {
+            grouped_intervals
+                .entry(start_of_hour(zone::to_local(tz, end.dt)))
+                .or_default()
+                .push((end.dt - start.dt).num_minutes());
         }
     }
 
-    daily_pumped
+    let mut hourly_average_time_between_feedings: Vec<(NaiveDateTime, i32)> = grouped_intervals
+        .into_iter()
+        .map(|(hour, intervals)| {
+            let average = intervals.iter().sum::<i64>() / intervals.len() as i64;
+            (hour, average as i32)
+        })
+        .collect();
+
+    hourly_average_time_between_feedings.sort_by_key(|(h, _)| *h);
+    hourly_average_time_between_feedings
+}
+
+/// Calculate the time (minutes) between consecutive feedings.
+///
+/// # Arguments
+///
+/// - `events`: A vector of BabyEvent objects.
+///
+/// # Returns
+///
+/// A vector containing the minutes between each feeding and the one before it, in chronological order.
+pub fn calculate_feeding_intervals(events: Vec<BabyEvent>) -> Vec<i64> {
+    let events = without_deleted(events);
+    // Filter out non-feeding events and sort by date
+    let mut feedings: Vec<&BabyEvent> = events
+        .iter()
+        .filter(|e| e.breastfeed > 0 || e.breastmilk > 0 || e.formula > 0)
+        .collect();
+    feedings.sort_by_key(|e| e.dt);
+
+    feedings
+        .windows(2)
+        .map(|window| (window[1].dt - window[0].dt).num_minutes())
+        .collect()
+}
+
+/// Summary statistics for a period of baby events.
+///
+/// # Fields
+///
+/// - `breastmilk`: Total breastmilk consumed.
+/// - `formula`: Total formula consumed.
+/// - `pumped`: Total volume of milk pumped.
+/// - `wet_diapers`: Number of wet diapers.
+/// - `poopy_diapers`: Number of poopy diapers.
+/// - `breastfeed`: Total minutes of breastfeeding.
+/// - `breastfeed_left`: Minutes of breastfeeding on the left side, where the side was recorded.
+/// - `breastfeed_right`: Minutes of breastfeeding on the right side, where the side was recorded.
+/// - `pumped_left`: Volume of milk pumped from the left side, where the side was recorded.
+/// - `pumped_right`: Volume of milk pumped from the right side, where the side was recorded.
+/// - `pump_duration`: Total minutes of pumping.
+/// - `skin2skin`: Total minutes of skin-to-skin contact.
+/// - `mean_feeding_interval`: Mean minutes between feedings, if there were at least two feedings.
+/// - `longest_feeding_interval`: Longest minutes between feedings, if there were at least two feedings.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Statistics {
+    pub breastmilk: i32,
+    pub formula: i32,
+    pub pumped: i32,
+    pub wet_diapers: i32,
+    pub poopy_diapers: i32,
+    pub breastfeed: i32,
+    pub breastfeed_left: i32,
+    pub breastfeed_right: i32,
+    pub pumped_left: i32,
+    pub pumped_right: i32,
+    pub pump_duration: i32,
+    pub skin2skin: i32,
+    pub mean_feeding_interval: Option<i64>,
+    pub longest_feeding_interval: Option<i64>,
+}
+
+impl Statistics {
+    /// Total volume of food consumed.
+    ///
+    /// # Returns
+    ///
+    /// The sum of breastmilk and formula consumed.
+    pub fn intake(&self) -> i32 {
+        self.breastmilk + self.formula
+    }
+}
+
+/// Calculate summary statistics for a period of baby events.
+///
+/// # Arguments
+///
+/// - `events`: A vector of BabyEvent objects, usually the events of a single day, week or month.
+///
+/// # Returns
+///
+/// A `Statistics` object summarizing the events.
+pub fn calculate_statistics(events: Vec<BabyEvent>) -> Statistics {
+    let events = without_deleted(events);
+    let intervals = calculate_feeding_intervals(events.clone());
+
+    Statistics {
+        breastmilk: events.iter().map(|e| e.breastmilk).sum(),
+        formula: events.iter().map(|e| e.formula).sum(),
+        pumped: events.iter().map(|e| e.pump).sum(),
+        wet_diapers: events.iter().filter(|e| e.urine).count() as i32,
+        poopy_diapers: events.iter().filter(|e| e.stool).count() as i32,
+        breastfeed: events.iter().map(|e| e.breastfeed).sum(),
+        breastfeed_left: events.iter().map(|e| e.breastfeed_left).sum(),
+        breastfeed_right: events.iter().map(|e| e.breastfeed_right).sum(),
+        pumped_left: events.iter().map(|e| e.pump_left).sum(),
+        pumped_right: events.iter().map(|e| e.pump_right).sum(),
+        pump_duration: events.iter().map(|e| e.pump_duration).sum(),
+        skin2skin: events.iter().map(|e| e.skin2skin).sum(),
+        mean_feeding_interval: (!intervals.is_empty())
+            .then(|| intervals.iter().sum::<i64>() / intervals.len() as i64),
+        longest_feeding_interval: intervals.iter().max().copied(),
+    }
+}
+
+/// Summary statistics of the events recorded by one caregiver.
+///
+/// # Fields
+///
+/// - `caregiver_id`: The caregiver, `None` for events recorded before caregivers were known.
+/// - `events`: Number of events recorded.
+/// - `feedings`: Number of feedings recorded.
+/// - `night_feedings`: Number of feedings recorded between `NIGHT_START_HOUR` and `NIGHT_END_HOUR`.
+/// - `diapers`: Number of wet or poopy diapers recorded.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct CaregiverStatistics {
+    pub caregiver_id: Option<i32>,
+    pub events: i32,
+    pub feedings: i32,
+    pub night_feedings: i32,
+    pub diapers: i32,
 }
 
-/// Calculate total volume of milk pumped for each week, starting on Mondays.
+/// Calculate summary statistics per caregiver for a period of baby events.
 ///
 /// # Arguments
 ///
-/// - `daily_pumped`: A vector of tuples containing the date and total volume of milk pumped.
+/// - `events`: A vector of BabyEvent objects, usually the events of a single day, week or month.
+/// - `tz`: The time zone whose nights are counted.
 ///
 /// # Returns
 ///
-/// A vector of tuples containing the start date of the week (Monday) and the total volume of milk pumped for that week.
-pub fn calculate_weekly_pumped(daily_pumped: Vec<(NaiveDate, i32)>) -> Vec<(NaiveDate, i32)> {
-    let mut weekly_pumped: Vec<(NaiveDate, i32)> = Vec::new();
+/// A vector of `CaregiverStatistics`, one for every caregiver who recorded an event, ordered by caregiver id with the
+/// unknown caregiver first.
+pub fn calculate_caregiver_statistics<Tz: TimeZone>(
+    events: Vec<BabyEvent>,
+    tz: &Tz,
+) -> Vec<CaregiverStatistics> {
+    let events = without_deleted(events);
+    let mut statistics: BTreeMap<Option<i32>, CaregiverStatistics> = BTreeMap::new();
+
+    for event in &events {
+        let entry = statistics
+            .entry(event.caregiver_id)
+            .or_insert_with(|| CaregiverStatistics {
+                caregiver_id: event.caregiver_id,
+                ..Default::default()
+            });
+        let feeding = event.breastfeed > 0 || event.breastmilk > 0 || event.formula > 0;
+
+        entry.events += 1;
+        entry.feedings += i32::from(feeding);
+        entry.night_feedings += i32::from(feeding && is_night(zone::to_local(tz, event.dt)));
+        entry.diapers += i32::from(event.urine || event.stool);
+    }
 
-    for (date, volume) in daily_pumped {
-        let days_from_monday = date.weekday().num_days_from_monday();
-        let start_date = date - chrono::Duration::days(days_from_monday as i64);
+    statistics.into_values().collect()
+}
 
-        if let Some((_, tmp)) = weekly_pumped.iter_mut().find(|(d, _)| d == &start_date) {
-            *tmp += volume;
-        } else {
-            weekly_pumped.push((start_date, volume));
-        }
-    }
+/// Checks whether a wall-clock time falls in the night, between `NIGHT_START_HOUR` and `NIGHT_END_HOUR`.
+fn is_night(dt: NaiveDateTime) -> bool {
+    dt.hour() >= NIGHT_START_HOUR || dt.hour() < NIGHT_END_HOUR
+}
 
-    weekly_pumped
+/// Returns the date of the evening a night starts on for a wall-clock time, e.g. the 1st for 3 in the morning of the
+/// 2nd.
+fn night_of(dt: NaiveDateTime) -> NaiveDate {
+    (dt - Duration::hours(NIGHT_END_HOUR.into())).date()
 }
 
-/// Calculate total volume of milk pumped for each month.
+/// Splits a sleep session at every midnight, `NIGHT_END_HOUR` and `NIGHT_START_HOUR` of a time zone.
 ///
 /// # Arguments
 ///
-/// - `daily_pumped`: A vector of tuples containing the date and total volume of milk pumped.
+/// - `tz`: The time zone of the midnights and night hours.
+/// - `start`: When the child fell asleep, in UTC.
+/// - `end`: When the child woke up, in UTC.
 ///
 /// # Returns
 ///
-/// A vector of tuples containing the start date of the month and the total volume of milk pumped for that month.
-pub fn calculate_monthly_pumped(daily_pumped: Vec<(NaiveDate, i32)>) -> Vec<(NaiveDate, i32)> {
-    let mut monthly_pumped: Vec<(NaiveDate, i32)> = Vec::new();
-
-    for (date, volume) in daily_pumped {
-        let start_date = NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap();
-
-        if let Some((_, tmp)) = monthly_pumped.iter_mut().find(|(d, _)| d == &start_date) {
-            *tmp += volume;
-        } else {
-            monthly_pumped.push((start_date, volume));
-        }
+/// A vector of consecutive pieces in UTC, each lying within a single calendar day and entirely in the day or the night.
+fn split_sleep<Tz: TimeZone>(
+    tz: &Tz,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let mut pieces = Vec::new();
+    let mut from = start;
+
+    while from < end {
+        let date = zone::to_local(tz, from).date();
+        let boundary = [
+            date.and_hms_opt(NIGHT_END_HOUR, 0, 0).unwrap(),
+            date.and_hms_opt(NIGHT_START_HOUR, 0, 0).unwrap(),
+            date.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap(),
+        ]
+        .into_iter()
+        .map(|boundary| zone::to_utc(tz, boundary))
+        .find(|boundary| boundary > &from)
+        .unwrap();
+        let to = boundary.min(end);
+
+        pieces.push((from, to));
+        from = to;
     }
 
-    monthly_pumped
+    pieces
 }
 
-/// Calculate number of wet diapers for each day.
+/// Finds the night wakings: sessions ending during the night followed by another session starting later in the same
+/// night.
 ///
 /// # Arguments
 ///
-/// - `events`: A vector of BabyEvent objects.
+/// - `sessions`: The sessions, sorted by start.
+/// - `tz`: The time zone of the nights.
 ///
 /// # Returns
 ///
-/// A vector of tuples containing the date and number of wet diapers.
-pub fn calculate_daily_wet_diapers(events: Vec<BabyEvent>) -> Vec<(NaiveDate, i32)> {
-    let mut daily_wet_diapers: Vec<(NaiveDate, i32)> = Vec::new();
-
-    for event in events {
-        let date = event.dt.date();
-        let wet_diapers = if event.urine { 1 } else { 0 };
-
-        if let Some((_, tmp)) = daily_wet_diapers.iter_mut().find(|(d, _)| d == &date) {
-            *tmp += wet_diapers;
-        } else {
-            daily_wet_diapers.push((date, wet_diapers));
-        }
-    }
-
-    daily_wet_diapers
+/// A vector containing the time of each waking, in UTC.
+fn night_wakings<Tz: TimeZone>(sessions: &[SleepSession], tz: &Tz) -> Vec<NaiveDateTime> {
+    sessions
+        .windows(2)
+        .filter_map(|window| {
+            let woke = window[0].end_dt?;
+            let (woke_local, slept) = (
+                zone::to_local(tz, woke),
+                zone::to_local(tz, window[1].start_dt),
+            );
+
+            (is_night(woke_local) && is_night(slept) && night_of(woke_local) == night_of(slept))
+                .then_some(woke)
+        })
+        .collect()
 }
 
-/// Calculate number of wet diapers for each week, starting on Mondays.
+/// Calculate total sleep (minutes) for each day.
+///
+/// Sessions spanning midnight count towards both days; sessions in progress are skipped.
 ///
 /// # Arguments
 ///
-/// - `daily_wet_diapers`: A vector of tuples containing the date and number of wet diapers.
+/// - `sessions`: A vector of SleepSession objects.
+/// - `tz`: The time zone whose days and nights the sleep is split into.
 ///
 /// # Returns
 ///
-/// A vector of tuples containing the start date of the week (Monday) and the number of wet diapers for that week.
-pub fn calculate_weekly_wet_diapers(
-    daily_wet_diapers: Vec<(NaiveDate, i32)>,
+/// A vector of tuples containing the date and total minutes of sleep, sorted by date.
+pub fn calculate_daily_sleep<Tz: TimeZone>(
+    sessions: Vec<SleepSession>,
+    tz: &Tz,
 ) -> Vec<(NaiveDate, i32)> {
-    let mut weekly_wet_diapers: Vec<(NaiveDate, i32)> = Vec::new();
+    let mut daily_sleep: Vec<(NaiveDate, i32)> = Vec::new();
 
-    for (date, wet_diapers) in daily_wet_diapers {
-        let days_from_monday = date.weekday().num_days_from_monday();
-        let start_date = date - chrono::Duration::days(days_from_monday as i64);
+    for session in sessions {
+        let Some(end) = session.end_dt else {
+            continue;
+        };
 
-        if let Some((_, tmp)) = weekly_wet_diapers
-            .iter_mut()
-            .find(|(d, _)| d == &start_date)
-        {
-            *tmp += wet_diapers;
-        } else {
-            weekly_wet_diapers.push((start_date, wet_diapers));
+        for (from, to) in split_sleep(tz, session.start_dt, end) {
+            let date = zone::to_local(tz, from).date();
+            let minutes = (to - from).num_minutes() as i32;
+
+            if let Some((_, tmp)) = daily_sleep.iter_mut().find(|(d, _)| d == &date) {
+                *tmp += minutes;
+            } else {
+                daily_sleep.push((date, minutes));
+            }
         }
     }
 
-    weekly_wet_diapers
+    daily_sleep.sort_by_key(|(d, _)| *d);
+    daily_sleep
 }
 
-/// Calculate number of poopy diapers for each day.
+/// Calculate the longest stretch of sleep (minutes) for each day.
+///
+/// Each session counts towards the day it started on; sessions in progress are skipped.
 ///
 /// # Arguments
 ///
-/// - `events`: A vector of BabyEvent objects.
+/// - `sessions`: A vector of SleepSession objects.
+/// - `tz`: The time zone whose days the sessions are grouped by.
 ///
 /// # Returns
 ///
-/// A vector of tuples containing the date and number of poopy diapers.
-pub fn calculate_daily_poopy_diapers(events: Vec<BabyEvent>) -> Vec<(NaiveDate, i32)> {
-    let mut daily_poopy_diapers: Vec<(NaiveDate, i32)> = Vec::new();
+/// A vector of tuples containing the date and the minutes of its longest session, sorted by date.
+pub fn calculate_daily_longest_sleep<Tz: TimeZone>(
+    sessions: Vec<SleepSession>,
+    tz: &Tz,
+) -> Vec<(NaiveDate, i32)> {
+    let mut daily_longest_sleep: Vec<(NaiveDate, i32)> = Vec::new();
 
-    for event in events {
-        let date = event.dt.date();
-        let poopy_diapers = if event.stool { 1 } else { 0 };
+    for session in sessions {
+        let Some(duration) = session.duration() else {
+            continue;
+        };
+        let date = zone::to_local(tz, session.start_dt).date();
+        let minutes = duration.num_minutes() as i32;
 
-        if let Some((_, tmp)) = daily_poopy_diapers.iter_mut().find(|(d, _)| d == &date) {
-            *tmp += poopy_diapers;
+        if let Some((_, tmp)) = daily_longest_sleep.iter_mut().find(|(d, _)| d == &date) {
+            *tmp = (*tmp).max(minutes);
         } else {
-            daily_poopy_diapers.push((date, poopy_diapers));
+            daily_longest_sleep.push((date, minutes));
         }
     }
 
-    daily_poopy_diapers
+    daily_longest_sleep.sort_by_key(|(d, _)| *d);
+    daily_longest_sleep
 }
 
-/// Calculate number of poopy diapers for each week, starting on Mondays.
+/// Calculate sleep (minutes) during the day and during the night for each day.
+///
+/// A day runs from `NIGHT_END_HOUR` to `NIGHT_END_HOUR` the next morning, so that a night counts towards the evening
+/// it started on. Sessions in progress are skipped.
 ///
 /// # Arguments
 ///
-/// - `daily_poopy_diapers`: A vector of tuples containing the date and number of poopy diapers.
+/// - `sessions`: A vector of SleepSession objects.
+/// - `tz`: The time zone whose days and nights the sleep is split into.
 ///
 /// # Returns
 ///
-/// A vector of tuples containing the start date of the week (Monday) and the number of poopy diapers for that week.
-pub fn calculate_weekly_poopy_diapers(
-    daily_poopy_diapers: Vec<(NaiveDate, i32)>,
-) -> Vec<(NaiveDate, i32)> {
-    let mut weekly_poopy_diapers: Vec<(NaiveDate, i32)> = Vec::new();
-
-    for (date, poopy_diapers) in daily_poopy_diapers {
-        let days_from_monday = date.weekday().num_days_from_monday();
-        let start_date = date - chrono::Duration::days(days_from_monday as i64);
-
-        if let Some((_, tmp)) = weekly_poopy_diapers
-            .iter_mut()
-            .find(|(d, _)| d == &start_date)
-        {
-            *tmp += poopy_diapers;
-        } else {
-            weekly_poopy_diapers.push((start_date, poopy_diapers));
+/// A vector of tuples containing the date, minutes of day sleep and minutes of night sleep, sorted by date.
+pub fn calculate_daily_day_night_sleep<Tz: TimeZone>(
+    sessions: Vec<SleepSession>,
+    tz: &Tz,
+) -> Vec<(NaiveDate, i32, i32)> {
+    let mut daily_day_night_sleep: Vec<(NaiveDate, i32, i32)> = Vec::new();
+
+    for session in sessions {
+        let Some(end) = session.end_dt else {
+            continue;
+        };
+
+        for (from, to) in split_sleep(tz, session.start_dt, end) {
+            let from_local = zone::to_local(tz, from);
+            let date = night_of(from_local);
+            let minutes = (to - from).num_minutes() as i32;
+            let (day, night) = if is_night(from_local) {
+                (0, minutes)
+            } else {
+                (minutes, 0)
+            };
+
+            if let Some((_, d, n)) = daily_day_night_sleep
+                .iter_mut()
+                .find(|(dt, _, _)| dt == &date)
+            {
+                *d += day;
+                *n += night;
+            } else {
+                daily_day_night_sleep.push((date, day, night));
+            }
         }
     }
 
-    weekly_poopy_diapers
+    daily_day_night_sleep.sort_by_key(|(d, _, _)| *d);
+    daily_day_night_sleep
 }
 
-/// Calculate average time (minutes) between feedings for each day.
+/// Calculate the number of night wakings for each night.
+///
+/// A waking is a session ending during the night followed by another session starting later in the same night.
+/// Nights with sleep but without wakings are listed with 0.
 ///
 /// # Arguments
 ///
-/// - `events`: A vector of BabyEvent objects.
+/// - `sessions`: A vector of SleepSession objects.
+/// - `tz`: The time zone whose nights the wakings are counted in.
 ///
 /// # Returns
 ///
-/// A vector of tuples containing the date and average time between feedings (minutes).
-pub fn calculate_daily_average_time_between_feedings(
-    events: Vec<BabyEvent>,
+/// A vector of tuples containing the date of the evening the night started on and its number of wakings, sorted by
+/// date.
+pub fn calculate_daily_night_wakings<Tz: TimeZone>(
+    sessions: Vec<SleepSession>,
+    tz: &Tz,
 ) -> Vec<(NaiveDate, i32)> {
-    // Filter out non-feeding events and sort by date
-    let mut feedings: Vec<&BabyEvent> = events
-        .iter()
-        .filter(|e| e.breastfeed > 0 || e.breastmilk > 0 || e.formula > 0)
-        .collect();
-    feedings.sort_by_key(|e| e.dt);
+    let mut sessions = sessions;
+    sessions.sort_by_key(|s| s.start_dt);
 
-    // Group feedings by date
-    let mut grouped_feedings: HashMap<NaiveDate, Vec<&BabyEvent>> = HashMap::new();
-    for feeding in feedings {
-        let date = feeding.dt.date();
-        grouped_feedings.entry(date).or_default().push(feeding);
-    }
+    let mut daily_night_wakings: Vec<(NaiveDate, i32)> = Vec::new();
 
-    // Calculate average time between feedings for each day
-    let mut daily_average_time_between_feedings: Vec<(NaiveDate, i32)> = Vec::new();
+    for session in &sessions {
+        let Some(end) = session.end_dt else {
+            continue;
+        };
 
-    for (date, daily_feedings) in grouped_feedings {
-        let mut total_time = 0;
-        let mut count = 0;
+        for (from, _) in split_sleep(tz, session.start_dt, end) {
+            let from = zone::to_local(tz, from);
+            let date = night_of(from);
 
-        for windows in daily_feedings.windows(2) {
-            if let [start, end] = windows {
-                total_time += (end.dt - start.dt).num_minutes();
-                count += 1;
+            if is_night(from) && !daily_night_wakings.iter().any(|(d, _)| d == &date) {
+                daily_night_wakings.push((date, 0));
             }
         }
+    }
 
-        // Calculate average time between feedings for the day
-        if count > 0 {
-            daily_average_time_between_feedings.push((date, (total_time / count) as i32));
+    for woke in night_wakings(&sessions, tz) {
+        let date = night_of(zone::to_local(tz, woke));
+
+        if let Some((_, tmp)) = daily_night_wakings.iter_mut().find(|(d, _)| d == &date) {
+            *tmp += 1;
+        } else {
+            daily_night_wakings.push((date, 1));
         }
     }
 
-    daily_average_time_between_feedings.sort_by_key(|(d, _)| *d);
-    daily_average_time_between_feedings
+    daily_night_wakings.sort_by_key(|(d, _)| *d);
+    daily_night_wakings
+}
+
+/// Summary statistics for the sleep within a period.
+///
+/// # Fields
+///
+/// - `day`: Minutes of sleep during the day.
+/// - `night`: Minutes of sleep during the night.
+/// - `longest`: Minutes of the longest session that started in the period, if any ended.
+/// - `night_wakings`: Number of night wakings in the period.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct SleepStatistics {
+    pub day: i32,
+    pub night: i32,
+    pub longest: Option<i32>,
+    pub night_wakings: i32,
+}
+
+impl SleepStatistics {
+    /// Total sleep.
+    ///
+    /// # Returns
+    ///
+    /// The sum of day and night sleep in minutes.
+    pub fn total(&self) -> i32 {
+        self.day + self.night
+    }
+}
+
+/// Calculate summary statistics for the sleep within a period.
+///
+/// Sessions are cut to the period, and sessions in progress are counted up to `now`.
+///
+/// # Arguments
+///
+/// - `sessions`: A vector of SleepSession objects overlapping the period.
+/// - `start`: The inclusive start of the period, in UTC.
+/// - `end`: The exclusive end of the period, in UTC.
+/// - `now`: The time sessions in progress are counted up to, in UTC.
+/// - `tz`: The time zone whose days and nights the sleep is split into.
+///
+/// # Returns
+///
+/// A `SleepStatistics` object summarizing the sleep.
+pub fn calculate_sleep_statistics<Tz: TimeZone>(
+    sessions: Vec<SleepSession>,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    now: NaiveDateTime,
+    tz: &Tz,
+) -> SleepStatistics {
+    let mut statistics = SleepStatistics::default();
+
+    for session in &sessions {
+        let from = session.start_dt.max(start);
+        let to = session.end_dt.unwrap_or(now).min(end);
+
+        for (from, to) in split_sleep(tz, from, to) {
+            let minutes = (to - from).num_minutes() as i32;
+
+            if is_night(zone::to_local(tz, from)) {
+                statistics.night += minutes;
+            } else {
+                statistics.day += minutes;
+            }
+        }
+    }
+
+    statistics.longest = sessions
+        .iter()
+        .filter(|s| s.start_dt >= start && s.start_dt < end)
+        .filter_map(|s| s.duration())
+        .map(|duration| duration.num_minutes() as i32)
+        .max();
+
+    let mut sorted = sessions;
+    sorted.sort_by_key(|s| s.start_dt);
+    statistics.night_wakings = night_wakings(&sorted, tz)
+        .iter()
+        .filter(|woke| **woke >= start && **woke < end)
+        .count() as i32;
+
+    statistics
 }
 
 #[cfg(test)]
 mod tests {
-    use chrono::NaiveDateTime;
-
     use super::*;
 
     fn baby_events(
@@ -526,6 +3496,7 @@ mod tests {
         vec![
             BabyEvent {
                 id: 1,
+                child_id: 1,
                 urine: true,
                 stool: true,
                 skin2skin: 0,
@@ -534,9 +3505,19 @@ mod tests {
                 dt: date_time1,
                 breastmilk: 100,
                 formula: 50,
+                breastfeed_left: 0,
+                breastfeed_right: 0,
+                pump_left: 0,
+                pump_right: 0,
+                pump_duration: 0,
+                caregiver_id: None,
+                utc_offset: 0,
+                deleted_at: None,
+                event_type_id: None,
             },
             BabyEvent {
                 id: 2,
+                child_id: 1,
                 urine: true,
                 stool: false,
                 skin2skin: 0,
@@ -545,9 +3526,19 @@ mod tests {
                 dt: date_time2,
                 breastmilk: 0,
                 formula: 150,
+                breastfeed_left: 0,
+                breastfeed_right: 0,
+                pump_left: 0,
+                pump_right: 0,
+                pump_duration: 0,
+                caregiver_id: None,
+                utc_offset: 0,
+                deleted_at: None,
+                event_type_id: None,
             },
             BabyEvent {
                 id: 3,
+                child_id: 1,
                 urine: false,
                 stool: false,
                 skin2skin: 0,
@@ -556,9 +3547,19 @@ mod tests {
                 dt: date_time3,
                 breastmilk: 50,
                 formula: 50,
+                breastfeed_left: 0,
+                breastfeed_right: 0,
+                pump_left: 0,
+                pump_right: 0,
+                pump_duration: 0,
+                caregiver_id: None,
+                utc_offset: 0,
+                deleted_at: None,
+                event_type_id: None,
             },
             BabyEvent {
                 id: 4,
+                child_id: 1,
                 urine: false,
                 stool: true,
                 skin2skin: 0,
@@ -567,6 +3568,15 @@ mod tests {
                 dt: date_time4,
                 breastmilk: 50,
                 formula: 50,
+                breastfeed_left: 0,
+                breastfeed_right: 0,
+                pump_left: 0,
+                pump_right: 0,
+                pump_duration: 0,
+                caregiver_id: None,
+                utc_offset: 0,
+                deleted_at: None,
+                event_type_id: None,
             },
         ]
     }
@@ -575,7 +3585,26 @@ mod tests {
     #[test]
     fn test_get_database_url() {
         std::env::set_var(*DB_KEY, "sqlite://test.db");
-        assert_eq!(get_database_url(), "sqlite://test.db");
+        assert_eq!(get_database_url().unwrap(), "sqlite://test.db");
+    }
+
+    /// Test to ensure database_path finds the file behind a database URL.
+    #[test]
+    fn test_database_path() {
+        assert_eq!(
+            database_path("babyrs.sqlite3"),
+            Some(PathBuf::from("babyrs.sqlite3"))
+        );
+        assert_eq!(
+            database_path("sqlite://test.db"),
+            Some(PathBuf::from("test.db"))
+        );
+        assert_eq!(
+            database_path("file:data/test.db?mode=rwc"),
+            Some(PathBuf::from("data/test.db"))
+        );
+        assert_eq!(database_path(":memory:"), None);
+        assert_eq!(database_path("file:test?mode=memory&cache=shared"), None);
     }
 
     /// Test to ensure create_event creates the event correctly.
@@ -591,8 +3620,8 @@ mod tests {
             Some(25),
         );
 
-        assert_eq!(new_event.urine, true);
-        assert_eq!(new_event.stool, true);
+        assert!(new_event.urine);
+        assert!(new_event.stool);
         assert_eq!(new_event.skin2skin, 5);
         assert_eq!(new_event.breastfeed, 10);
         assert_eq!(new_event.breastmilk, 15);
@@ -601,8 +3630,8 @@ mod tests {
 
         let another_event = create_event(None, None, None, None, None, None, None);
 
-        assert_eq!(another_event.urine, false);
-        assert_eq!(another_event.stool, false);
+        assert!(!another_event.urine);
+        assert!(!another_event.stool);
         assert_eq!(another_event.skin2skin, 0);
         assert_eq!(another_event.breastfeed, 0);
         assert_eq!(another_event.breastmilk, 0);
@@ -628,7 +3657,7 @@ mod tests {
 
         let events = baby_events(date_time1, date_time2, date_time3, date_time3);
 
-        let result = calculate_daily_volume(events);
+        let result = calculate_daily_volume(events, &Utc);
 
         assert_eq!(result.len(), 2);
         assert_eq!(result[0], (date_time1.date(), 300));
@@ -700,7 +3729,7 @@ mod tests {
 
         let events = baby_events(date_time1, date_time2, date_time3, date_time3);
 
-        let result = calculate_daily_pumped(events);
+        let result = calculate_daily_pumped(events, &Utc);
 
         assert_eq!(result.len(), 2);
         assert_eq!(result[0], (date_time1.date(), 100));
@@ -772,7 +3801,7 @@ mod tests {
 
         let events = baby_events(date_time1, date_time2, date_time3, date_time3);
 
-        let result = calculate_daily_wet_diapers(events);
+        let result = calculate_daily_wet_diapers(events, &Utc);
 
         assert_eq!(result.len(), 2);
         assert_eq!(result[0], (date_time1.date(), 2));
@@ -814,7 +3843,7 @@ mod tests {
 
         let events = baby_events(date_time1, date_time2, date_time3, date_time3);
 
-        let result = calculate_daily_poopy_diapers(events);
+        let result = calculate_daily_poopy_diapers(events, &Utc);
 
         assert_eq!(result.len(), 2);
         assert_eq!(result[0], (date_time1.date(), 1));
@@ -877,12 +3906,339 @@ mod tests {
         let events_1: Vec<BabyEvent> = baby_events(date_time1, date_time2, date_time3, date_time4);
         let events_2: Vec<BabyEvent> = baby_events(date_time5, date_time6, date_time7, date_time8);
 
-        let events: Vec<BabyEvent> = events_1.into_iter().chain(events_2.into_iter()).collect();
+        let events: Vec<BabyEvent> = events_1.into_iter().chain(events_2).collect();
 
-        let result = calculate_daily_average_time_between_feedings(events);
+        let result = calculate_daily_average_time_between_feedings(events, &Utc);
 
         assert_eq!(result.len(), 2); // Expecting 2 consolidated days, no average for 2023-01-04
         assert_eq!(result[0], (date_time1.date(), 93)); // between 0800 and 1415
         assert_eq!(result[1], (date_time6.date(), 210)); // between 0500 and 0830
     }
+
+    /// Test to ensure feeding intervals are calculated correctly.
+    #[test]
+    fn test_calculate_feeding_intervals() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+
+        // out of order, spanning midnight
+        let events = baby_events(
+            date.and_hms_opt(23, 0, 0).unwrap(),
+            date.and_hms_opt(20, 30, 0).unwrap(),
+            date.succ_opt().unwrap().and_hms_opt(1, 0, 0).unwrap(),
+            date.and_hms_opt(18, 0, 0).unwrap(),
+        );
+
+        let result = calculate_feeding_intervals(events);
+
+        assert_eq!(result, vec![150, 150, 120]);
+        assert!(calculate_feeding_intervals(vec![]).is_empty());
+    }
+
+    /// Test to ensure summary statistics are calculated correctly.
+    #[test]
+    fn test_calculate_statistics() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+
+        let mut events = baby_events(
+            date.and_hms_opt(8, 0, 0).unwrap(),
+            date.and_hms_opt(10, 0, 0).unwrap(),
+            date.and_hms_opt(11, 0, 0).unwrap(),
+            date.and_hms_opt(15, 0, 0).unwrap(),
+        );
+        events[0].breastfeed = 15;
+        events[1].skin2skin = 30;
+
+        let result = calculate_statistics(events);
+
+        assert_eq!(result.breastmilk, 200);
+        assert_eq!(result.formula, 300);
+        assert_eq!(result.intake(), 500);
+        assert_eq!(result.pumped, 375);
+        assert_eq!(result.wet_diapers, 2);
+        assert_eq!(result.poopy_diapers, 2);
+        assert_eq!(result.breastfeed, 15);
+        assert_eq!(result.skin2skin, 30);
+        assert_eq!(result.mean_feeding_interval, Some(140)); // (120 + 60 + 240) / 3
+        assert_eq!(result.longest_feeding_interval, Some(240));
+
+        let empty = calculate_statistics(vec![]);
+        assert_eq!(empty, Statistics::default());
+        assert!(empty.mean_feeding_interval.is_none());
+    }
+
+    /// Test to ensure statistics are calculated per caregiver, counting the night feedings.
+    #[test]
+    fn test_calculate_caregiver_statistics() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+
+        let mut events = baby_events(
+            date.and_hms_opt(2, 0, 0).unwrap(),
+            date.and_hms_opt(10, 0, 0).unwrap(),
+            date.and_hms_opt(20, 0, 0).unwrap(),
+            date.and_hms_opt(15, 0, 0).unwrap(),
+        );
+        events[0].caregiver_id = Some(1);
+        events[1].caregiver_id = Some(2);
+        events[2].caregiver_id = Some(1);
+
+        let result = calculate_caregiver_statistics(events, &Utc);
+
+        assert_eq!(
+            result,
+            vec![
+                CaregiverStatistics {
+                    caregiver_id: None,
+                    events: 1,
+                    feedings: 1,
+                    night_feedings: 0,
+                    diapers: 1,
+                },
+                CaregiverStatistics {
+                    caregiver_id: Some(1),
+                    events: 2,
+                    feedings: 2,
+                    night_feedings: 2,
+                    diapers: 1,
+                },
+                CaregiverStatistics {
+                    caregiver_id: Some(2),
+                    events: 1,
+                    feedings: 1,
+                    night_feedings: 0,
+                    diapers: 1,
+                },
+            ]
+        );
+        assert!(calculate_caregiver_statistics(vec![], &Utc).is_empty());
+    }
+
+    /// Test to ensure hourly volume is calculated correctly.
+    #[test]
+    fn test_calculate_hourly_volume() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+
+        let events = baby_events(
+            date.and_hms_opt(8, 0, 0).unwrap(),
+            date.and_hms_opt(8, 45, 0).unwrap(),
+            date.and_hms_opt(10, 15, 0).unwrap(),
+            date.succ_opt().unwrap().and_hms_opt(8, 30, 0).unwrap(),
+        );
+
+        let result = calculate_hourly_volume(events, &Utc);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0], (date.and_hms_opt(8, 0, 0).unwrap(), 300));
+        assert_eq!(result[1], (date.and_hms_opt(10, 0, 0).unwrap(), 100));
+        assert_eq!(
+            result[2],
+            (date.succ_opt().unwrap().and_hms_opt(8, 0, 0).unwrap(), 100)
+        );
+    }
+
+    /// Test to ensure hourly pumped volume is calculated correctly.
+    #[test]
+    fn test_calculate_hourly_pumped() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+
+        let events = baby_events(
+            date.and_hms_opt(8, 0, 0).unwrap(),
+            date.and_hms_opt(8, 45, 0).unwrap(),
+            date.and_hms_opt(10, 15, 0).unwrap(),
+            date.and_hms_opt(10, 30, 0).unwrap(),
+        );
+
+        let result = calculate_hourly_pumped(events, &Utc);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], (date.and_hms_opt(8, 0, 0).unwrap(), 100));
+        assert_eq!(result[1], (date.and_hms_opt(10, 0, 0).unwrap(), 275));
+    }
+
+    /// Test to ensure hourly wet and poopy diapers are calculated correctly.
+    #[test]
+    fn test_calculate_hourly_diapers() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+
+        let events = baby_events(
+            date.and_hms_opt(8, 0, 0).unwrap(),
+            date.and_hms_opt(8, 45, 0).unwrap(),
+            date.and_hms_opt(10, 15, 0).unwrap(),
+            date.and_hms_opt(10, 30, 0).unwrap(),
+        );
+
+        let wet = calculate_hourly_wet_diapers(events.clone(), &Utc);
+        let poopy = calculate_hourly_poopy_diapers(events, &Utc);
+
+        assert_eq!(wet[0], (date.and_hms_opt(8, 0, 0).unwrap(), 2));
+        assert_eq!(wet[1], (date.and_hms_opt(10, 0, 0).unwrap(), 0));
+        assert_eq!(poopy[0], (date.and_hms_opt(8, 0, 0).unwrap(), 1));
+        assert_eq!(poopy[1], (date.and_hms_opt(10, 0, 0).unwrap(), 1));
+    }
+
+    /// Test to ensure hourly average time between feedings is calculated correctly.
+    #[test]
+    fn test_calculate_hourly_average_time_between_feedings() {
+        let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+
+        let events = baby_events(
+            date.and_hms_opt(8, 0, 0).unwrap(),
+            date.and_hms_opt(10, 0, 0).unwrap(),
+            date.and_hms_opt(10, 30, 0).unwrap(),
+            date.and_hms_opt(13, 0, 0).unwrap(),
+        );
+
+        let result = calculate_hourly_average_time_between_feedings(events, &Utc);
+
+        assert_eq!(result.len(), 2); // no interval ends in the first hour
+        assert_eq!(result[0], (date.and_hms_opt(10, 0, 0).unwrap(), 75)); // (120 + 30) / 2
+        assert_eq!(result[1], (date.and_hms_opt(13, 0, 0).unwrap(), 150));
+    }
+
+    /// Test to ensure events and sleep are grouped into the hours and days of the time zone across a change of the
+    /// clocks.
+    #[test]
+    fn test_calculate_in_time_zone() {
+        use chrono_tz::Europe::Berlin;
+
+        // the clocks in Berlin go back from 03:00 to 02:00 on 2023-10-29, at 01:00 UTC
+        let oct28 = NaiveDate::from_ymd_opt(2023, 10, 28).unwrap();
+        let oct29 = oct28.succ_opt().unwrap();
+
+        let events = baby_events(
+            oct28.and_hms_opt(21, 30, 0).unwrap(), // 23:30 local
+            oct28.and_hms_opt(22, 30, 0).unwrap(), // 00:30 local, the next day
+            oct29.and_hms_opt(0, 30, 0).unwrap(),  // 02:30 local, the first time
+            oct29.and_hms_opt(1, 30, 0).unwrap(),  // 02:30 local, the second time
+        );
+
+        assert_eq!(
+            calculate_daily_volume(events.clone(), &Berlin),
+            [(oct28, 150), (oct29, 350)]
+        );
+        assert_eq!(
+            calculate_hourly_volume(events, &Berlin),
+            [
+                (oct28.and_hms_opt(23, 0, 0).unwrap(), 150),
+                (oct29.and_hms_opt(0, 0, 0).unwrap(), 150),
+                (oct29.and_hms_opt(2, 0, 0).unwrap(), 200),
+            ]
+        );
+
+        // from 21:00 to 07:00 local is 11 hours of sleep in the night the clocks go back
+        let night = SleepSession {
+            id: 0,
+            child_id: 1,
+            start_dt: oct28.and_hms_opt(19, 0, 0).unwrap(),
+            end_dt: oct29.and_hms_opt(6, 0, 0),
+        };
+        assert_eq!(
+            calculate_daily_sleep(vec![night], &Berlin),
+            [(oct28, 180), (oct29, 480)]
+        );
+    }
+
+    fn sleep_sessions() -> Vec<SleepSession> {
+        let jan1 = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        let jan2 = jan1.succ_opt().unwrap();
+        let session = |start: NaiveDateTime, end: Option<NaiveDateTime>| SleepSession {
+            id: 0,
+            child_id: 1,
+            start_dt: start,
+            end_dt: end,
+        };
+
+        vec![
+            // a nap
+            session(
+                jan1.and_hms_opt(13, 0, 0).unwrap(),
+                jan1.and_hms_opt(14, 30, 0),
+            ),
+            // a night with two wakings, ending after the night
+            session(
+                jan1.and_hms_opt(20, 0, 0).unwrap(),
+                jan1.and_hms_opt(23, 30, 0),
+            ),
+            session(
+                jan2.and_hms_opt(0, 30, 0).unwrap(),
+                jan2.and_hms_opt(5, 0, 0),
+            ),
+            session(
+                jan2.and_hms_opt(5, 30, 0).unwrap(),
+                jan2.and_hms_opt(8, 0, 0),
+            ),
+            // in progress
+            session(jan2.and_hms_opt(13, 0, 0).unwrap(), None),
+        ]
+    }
+
+    /// Test to ensure daily sleep is split at midnight and skips sessions in progress.
+    #[test]
+    fn test_calculate_daily_sleep() {
+        let jan1 = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+
+        let result = calculate_daily_sleep(sleep_sessions(), &Utc);
+
+        assert_eq!(result, [(jan1, 300), (jan1.succ_opt().unwrap(), 420)]);
+    }
+
+    /// Test to ensure the longest stretch counts towards the day the session started on.
+    #[test]
+    fn test_calculate_daily_longest_sleep() {
+        let jan1 = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+
+        let result = calculate_daily_longest_sleep(sleep_sessions(), &Utc);
+
+        assert_eq!(result, [(jan1, 210), (jan1.succ_opt().unwrap(), 270)]);
+    }
+
+    /// Test to ensure nights count towards the evening they started on.
+    #[test]
+    fn test_calculate_daily_day_night_sleep() {
+        let jan1 = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+
+        let result = calculate_daily_day_night_sleep(sleep_sessions(), &Utc);
+
+        assert_eq!(
+            result,
+            [
+                (jan1, 90, 210 + 270 + 90),
+                (jan1.succ_opt().unwrap(), 60, 0)
+            ]
+        );
+    }
+
+    /// Test to ensure wakings are only counted between sessions of the same night.
+    #[test]
+    fn test_calculate_daily_night_wakings() {
+        let jan1 = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+
+        let result = calculate_daily_night_wakings(sleep_sessions(), &Utc);
+
+        assert_eq!(result, [(jan1, 2)]);
+    }
+
+    /// Test to ensure sleep statistics are cut to the period and count sessions in progress up to now.
+    #[test]
+    fn test_calculate_sleep_statistics() {
+        let jan2 = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+
+        let statistics = calculate_sleep_statistics(
+            sleep_sessions(),
+            jan2.and_hms_opt(0, 0, 0).unwrap(),
+            jan2.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap(),
+            jan2.and_hms_opt(14, 0, 0).unwrap(),
+            &Utc,
+        );
+
+        assert_eq!(
+            statistics,
+            SleepStatistics {
+                day: 60 + 60,
+                night: 270 + 90,
+                longest: Some(270),
+                night_wakings: 1,
+            }
+        );
+        assert_eq!(statistics.total(), 480);
+    }
 }
@@ -1,21 +1,19 @@
-use diesel::prelude::*;
 use log::info;
 
 use babyrs::terminal::{self, app::App};
-use babyrs::{establish_connection, process_csv};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Welcome to babyrs!");
 
+    // bring the database schema up to date, refusing to start on a database from a newer version
+    if let Err(error) = babyrs::open_database() {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+
+    // CSV files are imported from within the UI with <i>
     let app = App::new();
     terminal::start_ui(app)?;
 
-    // Process CSV file
-    info!("Loading events from CSV into the database...");
-
-    // Establish connection to database
-    let connection: &mut SqliteConnection = &mut establish_connection();
-    process_csv(connection, "sample/example.csv").expect("Error processing CSV");
-
     Ok(())
 }
@@ -0,0 +1,334 @@
+/// Module deciding when the next dose of a medication is allowed and when it is due.
+///
+/// A dose is allowed once the minimum interval since the last dose has passed and the daily maxima over the last 24
+/// hours leave room for it. A scheduled medication is due every interval counting from the first scheduled dose, and
+/// a dose counts for the scheduled time closest to it.
+use crate::models::{Dose, Medication, MedicationSchedule};
+use chrono::{Duration, NaiveDateTime};
+use std::fmt::{self, Display};
+
+/// How late a scheduled dose may be given before it is overdue.
+pub const OVERDUE_AFTER_MINUTES: i64 = 60;
+
+/// The limit keeping a dose from being given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    /// The minimum interval since the last dose has not passed.
+    MinInterval,
+    /// The maximum number of doses in 24 hours has been given.
+    DailyDoses,
+    /// Another dose would exceed the maximum amount in 24 hours.
+    DailyAmount,
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MinInterval => write!(f, "minimum interval"),
+            Self::DailyDoses => write!(f, "maximum doses in 24 hours"),
+            Self::DailyAmount => write!(f, "maximum amount in 24 hours"),
+        }
+    }
+}
+
+/// When a medication was last given and may or should be given next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DoseStatus {
+    /// When the last dose was given, if ever.
+    pub last_dose: Option<NaiveDateTime>,
+    /// When the next dose is allowed and the limit deciding it, `None` if it is allowed now.
+    pub next_allowed: Option<(NaiveDateTime, Limit)>,
+    /// When the next scheduled dose is due, `None` if the medication is not scheduled.
+    pub next_due: Option<NaiveDateTime>,
+}
+
+impl DoseStatus {
+    /// Checks whether a dose may be given now.
+    ///
+    /// # Returns
+    ///
+    /// `true` if no limit keeps a dose from being given.
+    pub fn is_allowed(&self) -> bool {
+        self.next_allowed.is_none()
+    }
+
+    /// Checks whether a scheduled dose is overdue.
+    ///
+    /// # Parameters
+    ///
+    /// * `now`: The current time.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the next dose was due more than `OVERDUE_AFTER_MINUTES` ago.
+    pub fn is_overdue(&self, now: NaiveDateTime) -> bool {
+        self.next_due
+            .is_some_and(|due| now - due > Duration::minutes(OVERDUE_AFTER_MINUTES))
+    }
+}
+
+/// Computes the dose status of a medication for a child.
+///
+/// # Parameters
+///
+/// * `medication`: The medication.
+/// * `schedule`: The schedule of the medication for the child, if any.
+/// * `doses`: The doses of the medication given to the child, in any order.
+/// * `now`: The current time.
+///
+/// # Returns
+///
+/// The `DoseStatus` for a dose of the usual amount.
+pub fn dose_status(
+    medication: &Medication,
+    schedule: Option<&MedicationSchedule>,
+    doses: &[Dose],
+    now: NaiveDateTime,
+) -> DoseStatus {
+    let mut doses = doses
+        .iter()
+        .filter(|d| d.medication_id == medication.id && d.dt <= now)
+        .copied()
+        .collect::<Vec<Dose>>();
+    doses.sort_by_key(|d| d.dt);
+
+    DoseStatus {
+        last_dose: doses.last().map(|d| d.dt),
+        next_allowed: next_allowed(medication, &doses, medication.dose, now),
+        next_due: schedule.map(|schedule| next_due(schedule, &doses)),
+    }
+}
+
+/// Finds the earliest time a dose may be given, enforcing the minimum interval and the daily maxima.
+///
+/// # Parameters
+///
+/// * `medication`: The medication, holding the limits.
+/// * `doses`: The doses given before `now`, oldest first.
+/// * `amount`: The amount of the dose to give.
+/// * `now`: The current time.
+///
+/// # Returns
+///
+/// The time and the limit deciding it, `None` if the dose may be given now.
+pub fn next_allowed(
+    medication: &Medication,
+    doses: &[Dose],
+    amount: f64,
+    now: NaiveDateTime,
+) -> Option<(NaiveDateTime, Limit)> {
+    let day = Duration::hours(24);
+    let mut limits = Vec::new();
+
+    if let (Some(minutes), Some(last)) = (medication.min_interval_minutes, doses.last()) {
+        limits.push((
+            last.dt + Duration::minutes(minutes.into()),
+            Limit::MinInterval,
+        ));
+    }
+
+    // the oldest of the last `max` doses has to leave the 24 hours
+    if let Some(max) = medication.max_daily_doses {
+        let max = max.max(0) as usize;
+        if let Some(oldest) = doses.len().checked_sub(max).and_then(|i| doses.get(i)) {
+            limits.push((oldest.dt + day, Limit::DailyDoses));
+        }
+    }
+
+    // enough doses have to leave the 24 hours to make room for the amount
+    if let Some(max) = medication.max_daily_amount {
+        let given = |at: NaiveDateTime| -> f64 {
+            doses
+                .iter()
+                .filter(|d| d.dt > at - day && d.dt <= at)
+                .map(|d| d.amount)
+                .sum()
+        };
+        let allowed = std::iter::once(now)
+            .chain(doses.iter().map(|d| d.dt + day).filter(|at| at > &now))
+            .find(|at| given(*at) + amount <= max + f64::EPSILON);
+
+        // a dose larger than the daily amount is never allowed, show it as allowed a day after the last dose
+        let allowed = allowed.unwrap_or_else(|| doses.last().map_or(now, |d| d.dt) + day);
+        limits.push((allowed, Limit::DailyAmount));
+    }
+
+    limits
+        .into_iter()
+        .filter(|(at, _)| at > &now)
+        .max_by_key(|(at, _)| *at)
+}
+
+/// Finds when the next scheduled dose is due.
+///
+/// A dose counts for the scheduled time closest to it, so a dose given a little early or late still covers its slot.
+///
+/// # Parameters
+///
+/// * `schedule`: The schedule.
+/// * `doses`: The doses given, oldest first.
+///
+/// # Returns
+///
+/// The scheduled time after the one covered by the last dose, or the first scheduled time if no dose has been given
+/// since.
+pub fn next_due(schedule: &MedicationSchedule, doses: &[Dose]) -> NaiveDateTime {
+    let interval = Duration::minutes(schedule.interval_minutes.max(1).into());
+    let half = interval / 2;
+
+    match doses
+        .iter()
+        .rev()
+        .find(|d| d.dt >= schedule.first_dt - half)
+    {
+        Some(last) => {
+            let slot = ((last.dt - schedule.first_dt + half).num_minutes() / interval.num_minutes())
+                as i32;
+            schedule.first_dt + interval * (slot + 1)
+        }
+        None => schedule.first_dt,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 6, 15)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn paracetamol() -> Medication {
+        Medication {
+            id: 1,
+            name: "Paracetamol".to_owned(),
+            unit: "ml".to_owned(),
+            dose: 2.5,
+            min_interval_minutes: Some(4 * 60),
+            max_daily_doses: Some(4),
+            max_daily_amount: None,
+        }
+    }
+
+    fn dose(dt: NaiveDateTime, amount: f64) -> Dose {
+        Dose {
+            id: 0,
+            child_id: 1,
+            medication_id: 1,
+            dt,
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_min_interval() {
+        let doses = [dose(at(8, 0), 2.5)];
+
+        assert_eq!(
+            next_allowed(&paracetamol(), &doses, 2.5, at(10, 0)),
+            Some((at(12, 0), Limit::MinInterval))
+        );
+        assert_eq!(next_allowed(&paracetamol(), &doses, 2.5, at(12, 0)), None);
+        assert_eq!(next_allowed(&paracetamol(), &[], 2.5, at(10, 0)), None);
+    }
+
+    #[test]
+    fn test_max_daily_doses() {
+        let doses = [
+            dose(at(0, 0), 2.5),
+            dose(at(4, 0), 2.5),
+            dose(at(8, 0), 2.5),
+            dose(at(12, 0), 2.5),
+        ];
+
+        // the first dose has to leave the 24 hours
+        assert_eq!(
+            next_allowed(&paracetamol(), &doses, 2.5, at(16, 0)),
+            Some((at(0, 0) + Duration::hours(24), Limit::DailyDoses))
+        );
+    }
+
+    #[test]
+    fn test_max_daily_amount() {
+        let medication = Medication {
+            min_interval_minutes: None,
+            max_daily_doses: None,
+            max_daily_amount: Some(10.0),
+            ..paracetamol()
+        };
+        let doses = [
+            dose(at(0, 0), 5.0),
+            dose(at(4, 0), 2.5),
+            dose(at(8, 0), 2.5),
+        ];
+
+        assert_eq!(
+            next_allowed(&medication, &doses, 2.5, at(9, 0)),
+            Some((at(0, 0) + Duration::hours(24), Limit::DailyAmount))
+        );
+        assert_eq!(next_allowed(&medication, &doses[1..], 2.5, at(9, 0)), None);
+
+        // more than the daily amount is never allowed
+        assert!(next_allowed(&medication, &[], 12.0, at(9, 0)).is_some());
+    }
+
+    #[test]
+    fn test_next_due() {
+        let schedule = MedicationSchedule {
+            id: 1,
+            child_id: 1,
+            medication_id: 1,
+            first_dt: at(9, 0) - Duration::days(1),
+            interval_minutes: 24 * 60,
+        };
+
+        assert_eq!(next_due(&schedule, &[]), schedule.first_dt);
+
+        // a dose given a little early or late covers its slot
+        assert_eq!(
+            next_due(&schedule, &[dose(at(8, 15), 1.0)]),
+            at(9, 0) + Duration::days(1)
+        );
+        assert_eq!(
+            next_due(&schedule, &[dose(at(11, 0), 1.0)]),
+            at(9, 0) + Duration::days(1)
+        );
+
+        // yesterday's dose makes today's due
+        let yesterday = dose(at(9, 30) - Duration::days(1), 1.0);
+        assert_eq!(next_due(&schedule, &[yesterday]), at(9, 0));
+    }
+
+    #[test]
+    fn test_dose_status() {
+        let schedule = MedicationSchedule {
+            id: 1,
+            child_id: 1,
+            medication_id: 1,
+            first_dt: at(6, 0),
+            interval_minutes: 6 * 60,
+        };
+        let doses = [dose(at(6, 10), 2.5)];
+
+        let status = dose_status(&paracetamol(), Some(&schedule), &doses, at(9, 0));
+        assert_eq!(status.last_dose, Some(at(6, 10)));
+        assert!(!status.is_allowed());
+        assert_eq!(status.next_due, Some(at(12, 0)));
+        assert!(!status.is_overdue(at(13, 0)));
+        assert!(status.is_overdue(at(13, 1)));
+
+        // doses of other medications are ignored
+        let other = Medication {
+            id: 2,
+            ..paracetamol()
+        };
+        let status = dose_status(&other, None, &doses, at(9, 0));
+        assert_eq!(status.last_dose, None);
+        assert!(status.is_allowed());
+        assert!(!status.is_overdue(at(9, 0)));
+    }
+}
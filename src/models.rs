@@ -1,28 +1,397 @@
 /// This file contains the models for the database.
-use chrono::NaiveDateTime;
-use diesel::prelude::*;
-use serde::Deserialize;
+use crate::{units::VolumeUnit, zone, BabyrsError, DEFAULT_CHILD_ID};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc};
+use diesel::{
+    deserialize::{self, FromSql, FromSqlRow},
+    expression::AsExpression,
+    prelude::*,
+    serialize::{self, IsNull, Output, ToSql},
+    sql_types::Text,
+    sqlite::Sqlite,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+/// Represents the sex of a child, stored as `F` or `M`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, AsExpression, FromSqlRow)]
+#[diesel(sql_type = Text)]
+pub enum Sex {
+    Female,
+    Male,
+}
+
+impl Sex {
+    /// Returns the code the sex is stored as.
+    ///
+    /// # Returns
+    ///
+    /// `"F"` or `"M"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Female => "F",
+            Self::Male => "M",
+        }
+    }
+
+    /// Parses a sex from its code or name, ignoring case.
+    ///
+    /// # Parameters
+    ///
+    /// * `text`: `F`, `M`, `female` or `male`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Sex)` if the text names a sex.
+    /// - `None` otherwise.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "f" | "female" => Some(Self::Female),
+            "m" | "male" => Some(Self::Male),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Sex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Female => write!(f, "Female"),
+            Self::Male => write!(f, "Male"),
+        }
+    }
+}
+
+impl ToSql<Text, Sqlite> for Sex {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(self.code());
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Sqlite> for Sex {
+    fn from_sql(
+        bytes: <Sqlite as diesel::backend::Backend>::RawValue<'_>,
+    ) -> deserialize::Result<Self> {
+        let code = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+
+        Self::parse(&code).ok_or_else(|| format!("Unknown sex: {}", code).into())
+    }
+}
+
+/// Represents a child as it is stored in the database.
+///
+/// # Fields
+///
+/// - `id`: Unique identifier for the child.
+/// - `name`: The name of the child.
+/// - `birth_date`: The date the child was born, if known.
+/// - `sex`: The sex of the child, if known.
+/// - `birth_weight`: The weight of the child at birth in grams, if known.
+#[derive(Queryable, Selectable, Identifiable, AsChangeset, Debug, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::children)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_null = true)]
+pub struct Child {
+    pub id: i32,
+    pub name: String,
+    pub birth_date: Option<NaiveDate>,
+    pub sex: Option<Sex>,
+    pub birth_weight: Option<i32>,
+}
+
+/// Represents a new child to be inserted into the database.
+///
+/// # Fields
+///
+/// - `name`: The name of the child.
+/// - `birth_date`: The date the child was born, if known.
+/// - `sex`: The sex of the child, if known.
+/// - `birth_weight`: The weight of the child at birth in grams, if known.
+#[derive(Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::children)]
+pub struct NewChild {
+    pub name: String,
+    pub birth_date: Option<NaiveDate>,
+    pub sex: Option<Sex>,
+    pub birth_weight: Option<i32>,
+}
+
+impl Child {
+    /// Checks that the child has a name and that the birth weight is not negative.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the child is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        validate_child(&self.name, self.birth_weight)
+    }
+}
+
+impl NewChild {
+    /// Checks that the child has a name and that the birth weight is not negative.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the child is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        validate_child(&self.name, self.birth_weight)
+    }
+}
+
+/// Checks that a child has a name and that the birth weight is not negative.
+fn validate_child(name: &str, birth_weight: Option<i32>) -> Result<(), BabyrsError> {
+    if name.trim().is_empty() {
+        return Err(BabyrsError::Validation("name must not be empty".to_owned()));
+    }
+
+    validate_quantities([("birth weight", birth_weight.unwrap_or(0))])
+}
+
+/// Represents a person recording events, such as a parent or a nanny.
+///
+/// # Fields
+///
+/// - `id`: Unique identifier for the caregiver.
+/// - `name`: The name of the caregiver, unique among caregivers.
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::caregivers)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Caregiver {
+    pub id: i32,
+    pub name: String,
+}
+
+/// Represents a new caregiver to be inserted into the database.
+///
+/// # Fields
+///
+/// - `name`: The name of the caregiver.
+#[derive(Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::caregivers)]
+pub struct NewCaregiver {
+    pub name: String,
+}
+
+impl NewCaregiver {
+    /// Checks that the caregiver has a name.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the caregiver is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        if self.name.trim().is_empty() {
+            return Err(BabyrsError::Validation("name must not be empty".to_owned()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Represents a side of the body, for breastfeeding and pumping.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Left => write!(f, "Left"),
+            Self::Right => write!(f, "Right"),
+        }
+    }
+}
+
+/// Defines an enum stored as a lowercase text code, with `code`, `parse` and `ALL`, and the diesel conversions.
+macro_rules! text_enum {
+    ($(#[$meta:meta])* $name:ident { $($(#[$variant_meta:meta])* $variant:ident => $code:literal,)+ }) => {
+        $(#[$meta])*
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, AsExpression, FromSqlRow)]
+        #[diesel(sql_type = Text)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+        }
+
+        impl $name {
+            /// Every value, in the order they are offered.
+            pub const ALL: &'static [Self] = &[$(Self::$variant),+];
+
+            /// Returns the code the value is stored as.
+            pub fn code(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $code,)+
+                }
+            }
+
+            /// Parses a value from its code, ignoring case.
+            ///
+            /// # Parameters
+            ///
+            /// * `text`: The code of the value.
+            ///
+            /// # Returns
+            ///
+            /// - `Some` value if the text is a code.
+            /// - `None` otherwise.
+            pub fn parse(text: &str) -> Option<Self> {
+                let text = text.trim().to_lowercase();
+
+                Self::ALL.iter().copied().find(|value| value.code() == text)
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.code())
+            }
+        }
+
+        impl ToSql<Text, Sqlite> for $name {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+                out.set_value(self.code());
+                Ok(IsNull::No)
+            }
+        }
+
+        impl FromSql<Text, Sqlite> for $name {
+            fn from_sql(
+                bytes: <Sqlite as diesel::backend::Backend>::RawValue<'_>,
+            ) -> deserialize::Result<Self> {
+                let code = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+
+                Self::parse(&code).ok_or_else(|| {
+                    format!("Unknown {}: {}", stringify!($name), code).into()
+                })
+            }
+        }
+    };
+}
+
+text_enum! {
+    /// Represents the color of a stool.
+    ///
+    /// Meconium and transitional stools are expected in the first days; red, black after meconium and white stools
+    /// need a doctor.
+    StoolColor {
+        Meconium => "meconium",
+        Transitional => "transitional",
+        Yellow => "yellow",
+        Green => "green",
+        Brown => "brown",
+        Orange => "orange",
+        Red => "red",
+        Black => "black",
+        White => "white",
+    }
+}
+
+impl StoolColor {
+    /// Checks whether the color is medically concerning.
+    ///
+    /// Red may be blood, black after the meconium days digested blood, and white or pale stools a liver problem.
+    ///
+    /// # Returns
+    ///
+    /// `true` for red, black and white stools.
+    pub fn is_concerning(&self) -> bool {
+        matches!(self, Self::Red | Self::Black | Self::White)
+    }
+}
+
+text_enum! {
+    /// Represents the consistency of a stool.
+    StoolConsistency {
+        Watery => "watery",
+        Loose => "loose",
+        Seedy => "seedy",
+        Soft => "soft",
+        Formed => "formed",
+        Hard => "hard",
+    }
+}
+
+text_enum! {
+    /// Represents the size of a stool or the amount of urine in a diaper.
+    Amount {
+        Small => "small",
+        Medium => "medium",
+        Large => "large",
+    }
+}
+
+text_enum! {
+    /// Represents the kind of change recorded in the history of an event.
+    ///
+    /// A deleted event is moved to the trash, and purged when it is removed from the trash for good.
+    HistoryAction {
+        Insert => "insert",
+        Update => "update",
+        Delete => "delete",
+        Purge => "purge",
+    }
+}
+
+text_enum! {
+    /// Represents the kind of value a field of a user-defined event type records.
+    ///
+    /// Durations are stored in minutes and volumes in millilitres, enums as one of the choices of their field.
+    FieldKind {
+        Bool => "bool",
+        Duration => "duration",
+        Volume => "volume",
+        Enum => "enum",
+        Text => "text",
+    }
+}
 
 /// Represents a baby event as it is stored in the database.
 ///
-/// This struct is used for querying existing baby events from the database.
+/// This struct is used for querying existing baby events from the database, and for re-inserting a previously deleted
+/// event with its original id. Updates write every field, clearing those that are `None`.
 ///
 /// # Fields
 ///
 /// - `id`: Unique identifier for the event.
-/// - `dt`: The datetime when the event occurred.
+/// - `child_id`: The child the event belongs to.
+/// - `dt`: The datetime when the event occurred, in UTC.
 /// - `urine`: Indicates if there was a urine event.
 /// - `stool`: Indicates if there was a stool event.
 /// - `skin2skin`: Duration in minutes of skin-to-skin contact.
-/// - `breastfeed`: Duration in minutes of breastfeeding.
+/// - `breastfeed`: Duration in minutes of breastfeeding, the sum of both sides when they are known.
 /// - `breastmilk`: Quantity of breastmilk consumed.
 /// - `formula`: Quantity of formula consumed.
-/// - `pump`: Duration in minutes of pumping.
-#[derive(Queryable, Selectable, Debug, AsChangeset, Copy, Clone)]
+/// - `pump`: Volume in ml pumped, the sum of both sides when they are known.
+/// - `breastfeed_left`: Duration in minutes of breastfeeding on the left side.
+/// - `breastfeed_right`: Duration in minutes of breastfeeding on the right side.
+/// - `pump_left`: Volume in ml pumped from the left side.
+/// - `pump_right`: Volume in ml pumped from the right side.
+/// - `pump_duration`: Duration in minutes of pumping.
+/// - `caregiver_id`: The caregiver who recorded the event, if known.
+/// - `utc_offset`: The offset from UTC in seconds of the time zone the event was recorded in.
+/// - `deleted_at`: When the event was moved to the trash, in UTC, `None` if it has not been deleted.
+/// - `event_type_id`: The user-defined type of the event, `None` for an event of the built-in kinds.
+#[derive(
+    Queryable,
+    Selectable,
+    Insertable,
+    Debug,
+    AsChangeset,
+    Serialize,
+    Deserialize,
+    Copy,
+    Clone,
+    PartialEq,
+)]
 #[diesel(table_name = crate::schema::events)]
+#[diesel(treat_none_as_null = true)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 pub struct BabyEvent {
     pub id: i32,
+    pub child_id: i32,
     pub dt: NaiveDateTime,
     pub urine: bool,
     pub stool: bool,
@@ -31,6 +400,19 @@ pub struct BabyEvent {
     pub breastmilk: i32,
     pub formula: i32,
     pub pump: i32,
+    pub breastfeed_left: i32,
+    pub breastfeed_right: i32,
+    pub pump_left: i32,
+    pub pump_right: i32,
+    pub pump_duration: i32,
+    pub caregiver_id: Option<i32>,
+    pub utc_offset: i32,
+    // missing from the snapshots recorded before the trash existed
+    #[serde(default)]
+    pub deleted_at: Option<NaiveDateTime>,
+    // missing from the snapshots recorded before event types existed
+    #[serde(default)]
+    pub event_type_id: Option<i32>,
 }
 
 /// Represents a new baby event to be inserted into the database.
@@ -39,17 +421,27 @@ pub struct BabyEvent {
 ///
 /// # Fields
 ///
-/// - `dt`: The datetime when the event occurred.
+/// - `child_id`: The child the event belongs to.
+/// - `dt`: The datetime when the event occurred, in UTC.
 /// - `urine`: Indicates if there was a urine event.
 /// - `stool`: Indicates if there was a stool event.
 /// - `skin2skin`: Duration in minutes of skin-to-skin contact.
-/// - `breastfeed`: Duration in minutes of breastfeeding.
+/// - `breastfeed`: Duration in minutes of breastfeeding, the sum of both sides when they are known.
 /// - `breastmilk`: Quantity of breastmilk consumed.
 /// - `formula`: Quantity of formula consumed.
-/// - `pump`: Duration in minutes of pumping.
-#[derive(Insertable, Debug, Deserialize, Copy, Clone)]
+/// - `pump`: Volume in ml pumped, the sum of both sides when they are known.
+/// - `breastfeed_left`: Duration in minutes of breastfeeding on the left side.
+/// - `breastfeed_right`: Duration in minutes of breastfeeding on the right side.
+/// - `pump_left`: Volume in ml pumped from the left side.
+/// - `pump_right`: Volume in ml pumped from the right side.
+/// - `pump_duration`: Duration in minutes of pumping.
+/// - `caregiver_id`: The caregiver who recorded the event, if known.
+/// - `utc_offset`: The offset from UTC in seconds of the time zone the event was recorded in.
+/// - `event_type_id`: The user-defined type of the event, `None` for an event of the built-in kinds.
+#[derive(Insertable, Debug, Copy, Clone, PartialEq)]
 #[diesel(table_name = crate::schema::events)]
 pub struct NewBabyEvent {
+    pub child_id: i32,
     pub dt: NaiveDateTime,
     pub urine: bool,
     pub stool: bool,
@@ -58,4 +450,1531 @@ pub struct NewBabyEvent {
     pub breastmilk: i32,
     pub formula: i32,
     pub pump: i32,
+    pub breastfeed_left: i32,
+    pub breastfeed_right: i32,
+    pub pump_left: i32,
+    pub pump_right: i32,
+    pub pump_duration: i32,
+    pub caregiver_id: Option<i32>,
+    pub utc_offset: i32,
+    pub event_type_id: Option<i32>,
+}
+
+impl BabyEvent {
+    /// Returns the wall-clock time of the event in the time zone it was recorded in.
+    ///
+    /// # Returns
+    ///
+    /// The timestamp shifted by the offset it was recorded with.
+    pub fn recorded_dt(&self) -> NaiveDateTime {
+        self.dt + Duration::seconds(self.utc_offset.into())
+    }
+
+    /// Checks whether the event is in the trash.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the event has been deleted and not yet restored or purged.
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Checks whether a new event records the same as this event.
+    ///
+    /// The child, timestamp, type, diapers, durations and volumes are compared; the caregiver and the offset the
+    /// events were recorded with are not, so the same diaper entered by two caregivers is a duplicate. The values of
+    /// events of a user-defined type are not part of the event and are compared by the caller.
+    ///
+    /// # Parameters
+    ///
+    /// * `new_event`: The event to compare with.
+    ///
+    /// # Returns
+    ///
+    /// `true` if `new_event` is an exact duplicate of this event, otherwise `false`.
+    pub fn is_duplicate(&self, new_event: &NewBabyEvent) -> bool {
+        let recorded = NewBabyEvent {
+            caregiver_id: new_event.caregiver_id,
+            utc_offset: new_event.utc_offset,
+            ..self.to_new_event()
+        };
+
+        recorded == *new_event
+    }
+
+    /// Merges a new event at the same timestamp into this event.
+    ///
+    /// The diaper is wet or dirty if either event says so. Skin-to-skin, breastmilk and formula recorded by the new
+    /// event replace those of this event, and so does a breastfeed or pump together with its sides and duration.
+    /// Anything the new event leaves at 0 is kept, as is the caregiver unless only the new event names one.
+    ///
+    /// # Parameters
+    ///
+    /// * `new_event`: The event to merge into this event.
+    ///
+    /// # Returns
+    ///
+    /// The merged event, keeping the id and timestamp of this event.
+    pub fn merge(&self, new_event: &NewBabyEvent) -> Self {
+        let or = |old: i32, new: i32| if new == 0 { old } else { new };
+        let mut merged = Self {
+            urine: self.urine || new_event.urine,
+            stool: self.stool || new_event.stool,
+            skin2skin: or(self.skin2skin, new_event.skin2skin),
+            breastmilk: or(self.breastmilk, new_event.breastmilk),
+            formula: or(self.formula, new_event.formula),
+            caregiver_id: self.caregiver_id.or(new_event.caregiver_id),
+            ..*self
+        };
+
+        if new_event.breastfeed > 0 {
+            merged.breastfeed = new_event.breastfeed;
+            merged.breastfeed_left = new_event.breastfeed_left;
+            merged.breastfeed_right = new_event.breastfeed_right;
+        }
+        if new_event.pump > 0 || new_event.pump_duration > 0 {
+            merged.pump = new_event.pump;
+            merged.pump_left = new_event.pump_left;
+            merged.pump_right = new_event.pump_right;
+            merged.pump_duration = new_event.pump_duration;
+        }
+
+        merged
+    }
+
+    /// Returns the event without its id, as it would be written anew.
+    ///
+    /// # Returns
+    ///
+    /// A `NewBabyEvent` with the same values.
+    pub fn to_new_event(&self) -> NewBabyEvent {
+        NewBabyEvent {
+            child_id: self.child_id,
+            dt: self.dt,
+            urine: self.urine,
+            stool: self.stool,
+            skin2skin: self.skin2skin,
+            breastfeed: self.breastfeed,
+            breastmilk: self.breastmilk,
+            formula: self.formula,
+            pump: self.pump,
+            breastfeed_left: self.breastfeed_left,
+            breastfeed_right: self.breastfeed_right,
+            pump_left: self.pump_left,
+            pump_right: self.pump_right,
+            pump_duration: self.pump_duration,
+            caregiver_id: self.caregiver_id,
+            utc_offset: self.utc_offset,
+            event_type_id: self.event_type_id,
+        }
+    }
+
+    /// Lists the fields whose values differ from those of another event.
+    ///
+    /// # Parameters
+    ///
+    /// * `other`: The event to compare with.
+    ///
+    /// # Returns
+    ///
+    /// The names of the differing fields, in the order of the table columns.
+    pub fn changed_fields(&self, other: &Self) -> Vec<&'static str> {
+        [
+            ("child", self.child_id != other.child_id),
+            ("dt", self.dt != other.dt),
+            ("urine", self.urine != other.urine),
+            ("stool", self.stool != other.stool),
+            ("skin2skin", self.skin2skin != other.skin2skin),
+            ("breastfeed", self.breastfeed != other.breastfeed),
+            ("breastmilk", self.breastmilk != other.breastmilk),
+            ("formula", self.formula != other.formula),
+            ("pump", self.pump != other.pump),
+            (
+                "breastfeed_left",
+                self.breastfeed_left != other.breastfeed_left,
+            ),
+            (
+                "breastfeed_right",
+                self.breastfeed_right != other.breastfeed_right,
+            ),
+            ("pump_left", self.pump_left != other.pump_left),
+            ("pump_right", self.pump_right != other.pump_right),
+            ("pump_duration", self.pump_duration != other.pump_duration),
+            ("caregiver", self.caregiver_id != other.caregiver_id),
+            ("utc_offset", self.utc_offset != other.utc_offset),
+        ]
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect()
+    }
+
+    /// Encodes the event as a headerless CSV record, the way it is kept in the history.
+    ///
+    /// # Returns
+    ///
+    /// The record without a line break, or a `BabyrsError::Csv` if it cannot be written.
+    pub fn to_snapshot(&self) -> Result<String, BabyrsError> {
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(false)
+            .terminator(csv::Terminator::Any(b'\n'))
+            .from_writer(vec![]);
+
+        writer.serialize(self)?;
+
+        let bytes = writer
+            .into_inner()
+            .map_err(|error| csv::Error::from(error.into_error()))?;
+
+        Ok(String::from_utf8_lossy(&bytes).trim_end().to_owned())
+    }
+
+    /// Decodes an event kept in the history.
+    ///
+    /// # Parameters
+    ///
+    /// * `snapshot`: The record written by `to_snapshot`.
+    ///
+    /// # Returns
+    ///
+    /// The event, or a `BabyrsError::Csv` if the record cannot be parsed.
+    pub fn from_snapshot(snapshot: &str) -> Result<Self, BabyrsError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(snapshot.as_bytes());
+
+        match reader.deserialize().next() {
+            Some(event) => Ok(event?),
+            None => Err(BabyrsError::Validation("empty event snapshot".to_owned())),
+        }
+    }
+
+    /// Suggests the side to start the next breastfeed on.
+    ///
+    /// The side fed for less time was emptied less, so it goes first next time; after feeding on one side only, the
+    /// other side is next.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Side)` to start on.
+    /// - `None` if the sides of this event are unknown or were fed equally long.
+    pub fn next_side(&self) -> Option<Side> {
+        match (self.breastfeed_left, self.breastfeed_right) {
+            (0, 0) => None,
+            (_, 0) => Some(Side::Right),
+            (0, _) => Some(Side::Left),
+            (left, right) if left < right => Some(Side::Left),
+            (left, right) if right < left => Some(Side::Right),
+            _ => None,
+        }
+    }
+
+    /// Checks that the durations and quantities of the event are not negative, and that the sides of a breastfeed or
+    /// pump add up to its total when they are known.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the event is valid, otherwise a `BabyrsError::Validation` naming the first invalid field.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        validate_quantities([
+            ("skin2skin", self.skin2skin),
+            ("breastfeed", self.breastfeed),
+            ("breastmilk", self.breastmilk),
+            ("formula", self.formula),
+            ("pump", self.pump),
+            ("breastfeed_left", self.breastfeed_left),
+            ("breastfeed_right", self.breastfeed_right),
+            ("pump_left", self.pump_left),
+            ("pump_right", self.pump_right),
+            ("pump_duration", self.pump_duration),
+        ])?;
+        validate_sides(
+            "breastfeed",
+            self.breastfeed,
+            self.breastfeed_left,
+            self.breastfeed_right,
+        )?;
+        validate_sides("pump", self.pump, self.pump_left, self.pump_right)
+    }
+}
+
+impl NewBabyEvent {
+    /// Checks that the durations and quantities of the event are not negative, and that the sides of a breastfeed or
+    /// pump add up to its total when they are known.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the event is valid, otherwise a `BabyrsError::Validation` naming the first invalid field.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        validate_quantities([
+            ("skin2skin", self.skin2skin),
+            ("breastfeed", self.breastfeed),
+            ("breastmilk", self.breastmilk),
+            ("formula", self.formula),
+            ("pump", self.pump),
+            ("breastfeed_left", self.breastfeed_left),
+            ("breastfeed_right", self.breastfeed_right),
+            ("pump_left", self.pump_left),
+            ("pump_right", self.pump_right),
+            ("pump_duration", self.pump_duration),
+        ])?;
+        validate_sides(
+            "breastfeed",
+            self.breastfeed,
+            self.breastfeed_left,
+            self.breastfeed_right,
+        )?;
+        validate_sides("pump", self.pump, self.pump_left, self.pump_right)
+    }
+}
+
+/// Represents an event as a row of a CSV file, for importing and exporting events.
+///
+/// Volumes are in the unit of the `unit` column, or in millilitres if the column is missing or empty. The caregiver is
+/// not part of the file. An event of a user-defined type names its type in the `type` column and lists its values in
+/// the `values` column as `name=value` pairs separated by `;`, such as `minutes=15; product=oil`, with a `;` or `\`
+/// within a value escaped by a `\`.
+///
+/// # Fields
+///
+/// - `child_id`: The child the event belongs to, the default child if the column is missing.
+/// - `dt`: The datetime when the event occurred, with its offset from UTC such as `2023-06-15T05:15:00+02:00`, or
+///   without one such as `2023-06-15T05:15:00` for a wall-clock time in the local time zone.
+/// - `urine`: Indicates if there was a urine event.
+/// - `stool`: Indicates if there was a stool event.
+/// - `skin2skin`: Duration in minutes of skin-to-skin contact.
+/// - `breastfeed`: Duration in minutes of breastfeeding, the sum of both sides when they are known.
+/// - `breastmilk`: Volume of breastmilk consumed.
+/// - `formula`: Volume of formula consumed.
+/// - `pump`: Volume pumped, the sum of both sides when they are known.
+/// - `breastfeed_left`: Duration in minutes of breastfeeding on the left side, 0 if the column is missing.
+/// - `breastfeed_right`: Duration in minutes of breastfeeding on the right side, 0 if the column is missing.
+/// - `pump_left`: Volume pumped from the left side, 0 if the column is missing.
+/// - `pump_right`: Volume pumped from the right side, 0 if the column is missing.
+/// - `pump_duration`: Duration in minutes of pumping, 0 if the column is missing.
+/// - `event_type`: The name of the user-defined type of the event, empty for an event of the built-in kinds.
+/// - `values`: The values of the fields of the type, entered as in [`EventField::parse_value`].
+/// - `unit`: The unit of the volumes, such as `ml` or `fl oz`, see [`VolumeUnit::parse`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CsvEvent {
+    #[serde(default = "default_child_id")]
+    pub child_id: i32,
+    pub dt: String,
+    pub urine: bool,
+    pub stool: bool,
+    pub skin2skin: i32,
+    pub breastfeed: i32,
+    pub breastmilk: f64,
+    pub formula: f64,
+    pub pump: f64,
+    #[serde(default)]
+    pub breastfeed_left: i32,
+    #[serde(default)]
+    pub breastfeed_right: i32,
+    #[serde(default)]
+    pub pump_left: f64,
+    #[serde(default)]
+    pub pump_right: f64,
+    #[serde(default)]
+    pub pump_duration: i32,
+    #[serde(default, rename = "type")]
+    pub event_type: String,
+    #[serde(default)]
+    pub values: String,
+    #[serde(default)]
+    pub unit: String,
+}
+
+impl CsvEvent {
+    /// Converts an event into a row with the volumes in the given unit.
+    ///
+    /// The timestamp is written in the time zone the event was recorded in, with its offset. Volumes are rounded with
+    /// [`VolumeUnit::exact_value`], so that they are read back unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The event to convert.
+    /// * `values`: The values of the event's fields; values of other events are left out.
+    /// * `registry`: The event types, naming the type and fields of the event.
+    /// * `unit`: The unit of the volumes.
+    ///
+    /// # Returns
+    ///
+    /// A new `CsvEvent` naming `unit` in its unit column.
+    pub fn from_event(
+        event: &BabyEvent,
+        values: &[EventValue],
+        registry: &EventRegistry,
+        unit: VolumeUnit,
+    ) -> Self {
+        let volume = |ml: i32| unit.exact_value(ml);
+        let fields = event
+            .event_type_id
+            .map_or(vec![], |event_type| registry.fields_of(event_type));
+        let values = fields
+            .iter()
+            .filter_map(|field| {
+                let value = values
+                    .iter()
+                    .find(|v| v.event_id == event.id && v.field_id == field.id)?;
+                let text = field.input_value(&value.value, unit);
+                Some(format!(
+                    "{}={}",
+                    field.name,
+                    text.replace('\\', "\\\\").replace(';', "\\;")
+                ))
+            })
+            .collect::<Vec<String>>();
+
+        Self {
+            child_id: event.child_id,
+            dt: FixedOffset::east_opt(event.utc_offset)
+                .unwrap_or(Utc.fix())
+                .from_utc_datetime(&event.dt)
+                .format("%Y-%m-%dT%H:%M:%S%.f%:z")
+                .to_string(),
+            urine: event.urine,
+            stool: event.stool,
+            skin2skin: event.skin2skin,
+            breastfeed: event.breastfeed,
+            breastmilk: volume(event.breastmilk),
+            formula: volume(event.formula),
+            pump: volume(event.pump),
+            breastfeed_left: event.breastfeed_left,
+            breastfeed_right: event.breastfeed_right,
+            pump_left: volume(event.pump_left),
+            pump_right: volume(event.pump_right),
+            pump_duration: event.pump_duration,
+            event_type: event
+                .event_type_id
+                .and_then(|event_type| registry.get_type(event_type))
+                .map_or(String::new(), |event_type| event_type.name.clone()),
+            values: values.join("; "),
+            unit: unit.code().to_owned(),
+        }
+    }
+
+    /// Converts the row into a new event, with the timestamp in UTC and the volumes rounded to whole millilitres.
+    ///
+    /// A pump total made up of its sides is the sum of the rounded sides, see [`VolumeUnit::total_to_ml`].
+    ///
+    /// # Parameters
+    ///
+    /// * `tz`: The time zone of timestamps without an offset.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(NewBabyEvent)` without a caregiver.
+    /// - `Err(BabyrsError::Validation)` if the timestamp cannot be parsed or the unit is unknown.
+    pub fn to_new_event<Tz: TimeZone>(&self, tz: &Tz) -> Result<NewBabyEvent, BabyrsError> {
+        let text = self.dt.trim();
+        let (dt, utc_offset) = match (
+            text.parse::<DateTime<FixedOffset>>(),
+            text.parse::<NaiveDateTime>(),
+        ) {
+            (Ok(dt), _) => (dt.naive_utc(), dt.offset().local_minus_utc()),
+            (_, Ok(local)) => {
+                let dt = zone::to_utc(tz, local);
+                (dt, zone::offset(tz, dt))
+            }
+            _ => {
+                return Err(BabyrsError::Validation(format!(
+                    "Invalid timestamp {}",
+                    text
+                )))
+            }
+        };
+        let unit = self.unit()?;
+
+        Ok(NewBabyEvent {
+            child_id: self.child_id,
+            dt,
+            urine: self.urine,
+            stool: self.stool,
+            skin2skin: self.skin2skin,
+            breastfeed: self.breastfeed,
+            breastmilk: unit.to_ml(self.breastmilk),
+            formula: unit.to_ml(self.formula),
+            pump: unit.total_to_ml(self.pump, self.pump_left, self.pump_right),
+            breastfeed_left: self.breastfeed_left,
+            breastfeed_right: self.breastfeed_right,
+            pump_left: unit.to_ml(self.pump_left),
+            pump_right: unit.to_ml(self.pump_right),
+            pump_duration: self.pump_duration,
+            caregiver_id: None,
+            utc_offset,
+            event_type_id: None,
+        })
+    }
+
+    /// Looks up the type of the row and parses its values.
+    ///
+    /// # Parameters
+    ///
+    /// * `registry`: The event types the type and its fields are looked up in.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok((Option<i32>, BTreeMap<i32, String>))` with the id of the type, `None` for an event of the built-in
+    ///   kinds, and the stored values by the id of their field.
+    /// - `Err(BabyrsError::Validation)` if the type or a field is unknown or a value is invalid.
+    pub fn to_values(
+        &self,
+        registry: &EventRegistry,
+    ) -> Result<(Option<i32>, BTreeMap<i32, String>), BabyrsError> {
+        let mut values = BTreeMap::new();
+
+        if self.event_type.trim().is_empty() {
+            return match self.values.trim() {
+                "" => Ok((None, values)),
+                _ => Err(BabyrsError::Validation(
+                    "values need an event type".to_owned(),
+                )),
+            };
+        }
+
+        let event_type = registry.find_type(&self.event_type).ok_or_else(|| {
+            BabyrsError::Validation(format!("Unknown event type {}", self.event_type.trim()))
+        })?;
+        let fields = registry.fields_of(event_type.id);
+        let unit = self.unit()?;
+
+        for pair in split_values(&self.values) {
+            let (name, text) = pair.split_once('=').ok_or_else(|| {
+                BabyrsError::Validation(format!("Value {} must look like name=value", pair))
+            })?;
+            let field = fields
+                .iter()
+                .find(|field| field.name.eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| {
+                    BabyrsError::Validation(format!(
+                        "Event type {} has no field {}",
+                        event_type.name,
+                        name.trim()
+                    ))
+                })?;
+
+            if let Some(value) = field.parse_value(text, unit)? {
+                values.insert(field.id, value);
+            }
+        }
+
+        Ok((Some(event_type.id), values))
+    }
+
+    /// Parses the unit of the volumes of the row.
+    fn unit(&self) -> Result<VolumeUnit, BabyrsError> {
+        match self.unit.trim() {
+            "" => Ok(VolumeUnit::Millilitre),
+            unit => VolumeUnit::parse(unit)
+                .ok_or_else(|| BabyrsError::Validation(format!("Unknown unit {}", unit))),
+        }
+    }
+}
+
+/// Splits the values column of a CSV row into its `name=value` pairs, unescaping `\;` and `\\`.
+fn split_values(text: &str) -> Vec<String> {
+    let mut pairs = vec![];
+    let mut pair = String::new();
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => pair.extend(chars.next()),
+            ';' => pairs.push(std::mem::take(&mut pair)),
+            c => pair.push(c),
+        }
+    }
+    pairs.push(pair);
+
+    pairs
+        .into_iter()
+        .filter(|pair| !pair.trim().is_empty())
+        .collect()
+}
+
+/// Represents the optional details of a diaper, linked to the event recording it.
+///
+/// # Fields
+///
+/// - `event_id`: The event the details belong to.
+/// - `stool_color`: The color of the stool, if noted.
+/// - `stool_consistency`: The consistency of the stool, if noted.
+/// - `stool_size`: The size of the stool, if noted.
+/// - `urine_amount`: The amount of urine, if noted.
+/// - `urate_crystals`: Indicates if there were urate crystals, orange or pink powder in the diaper.
+#[derive(
+    Queryable, Selectable, Insertable, Identifiable, AsChangeset, Debug, Copy, Clone, PartialEq, Eq,
+)]
+#[diesel(table_name = crate::schema::diaper_details)]
+#[diesel(primary_key(event_id))]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_null = true)]
+pub struct DiaperDetails {
+    pub event_id: i32,
+    pub stool_color: Option<StoolColor>,
+    pub stool_consistency: Option<StoolConsistency>,
+    pub stool_size: Option<Amount>,
+    pub urine_amount: Option<Amount>,
+    pub urate_crystals: bool,
+}
+
+impl DiaperDetails {
+    /// Checks whether no detail is noted.
+    ///
+    /// # Returns
+    ///
+    /// `true` if every detail is missing.
+    pub fn is_empty(&self) -> bool {
+        !self.has_stool() && !self.has_urine()
+    }
+
+    /// Checks whether the stool color is medically concerning.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the stool is red, black or white.
+    pub fn is_concerning(&self) -> bool {
+        self.stool_color.is_some_and(|color| color.is_concerning())
+    }
+
+    /// Checks that stool details are only noted for a stool and urine details only for urine.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The event the details belong to.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the details are valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self, event: &BabyEvent) -> Result<(), BabyrsError> {
+        if self.has_stool() && !event.stool {
+            return Err(BabyrsError::Validation(
+                "stool details need an event with a stool".to_owned(),
+            ));
+        }
+        if self.has_urine() && !event.urine {
+            return Err(BabyrsError::Validation(
+                "urine details need an event with urine".to_owned(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Drops the details that do not match an event, the stool details of an event without a stool and the urine
+    /// details of an event without urine.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The event the details belong to.
+    ///
+    /// # Returns
+    ///
+    /// The details that are valid for `event`, see [`DiaperDetails::validate`].
+    pub fn matching(&self, event: &BabyEvent) -> Self {
+        let mut details = *self;
+
+        if !event.stool {
+            details.stool_color = None;
+            details.stool_consistency = None;
+            details.stool_size = None;
+        }
+        if !event.urine {
+            details.urine_amount = None;
+            details.urate_crystals = false;
+        }
+
+        details
+    }
+
+    /// Checks whether any stool detail is noted.
+    fn has_stool(&self) -> bool {
+        self.stool_color.is_some() || self.stool_consistency.is_some() || self.stool_size.is_some()
+    }
+
+    /// Checks whether any urine detail is noted.
+    fn has_urine(&self) -> bool {
+        self.urine_amount.is_some() || self.urate_crystals
+    }
+}
+
+/// Represents a free-text note on an event.
+///
+/// The notes are indexed for full-text search in the `event_notes_fts` table, which is kept in sync by triggers.
+///
+/// # Fields
+///
+/// - `event_id`: The event the note belongs to.
+/// - `text`: The text of the note.
+#[derive(
+    Queryable, Selectable, Insertable, Identifiable, AsChangeset, Debug, Clone, PartialEq, Eq,
+)]
+#[diesel(table_name = crate::schema::event_notes)]
+#[diesel(primary_key(event_id))]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct EventNote {
+    pub event_id: i32,
+    pub text: String,
+}
+
+/// Represents a change of an event as it is kept in the append-only history.
+///
+/// # Fields
+///
+/// - `id`: Unique identifier for the entry, increasing with every change.
+/// - `event_id`: The event that was changed, which may since have been deleted.
+/// - `changed_at`: The datetime of the change, in UTC.
+/// - `caregiver_id`: The caregiver who made the change, if known.
+/// - `action`: Whether the event was inserted, updated or deleted.
+/// - `before`: The event before the change as written by `BabyEvent::to_snapshot`, `None` for an insert.
+/// - `after`: The event after the change, `None` for a delete or purge.
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::event_history)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct EventHistory {
+    pub id: i32,
+    pub event_id: i32,
+    pub changed_at: NaiveDateTime,
+    pub caregiver_id: Option<i32>,
+    pub action: HistoryAction,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Represents a new entry to be appended to the history of an event.
+///
+/// # Fields
+///
+/// - `event_id`: The event that was changed.
+/// - `changed_at`: The datetime of the change, in UTC.
+/// - `caregiver_id`: The caregiver who made the change, if known.
+/// - `action`: Whether the event was inserted, updated or deleted.
+/// - `before`: The event before the change, `None` for an insert.
+/// - `after`: The event after the change, `None` for a delete or purge.
+#[derive(Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::event_history)]
+pub struct NewEventHistory {
+    pub event_id: i32,
+    pub changed_at: NaiveDateTime,
+    pub caregiver_id: Option<i32>,
+    pub action: HistoryAction,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl EventHistory {
+    /// Decodes the event as it was before the change.
+    ///
+    /// # Returns
+    ///
+    /// `None` for an insert, the event otherwise, or a `BabyrsError::Csv` if the snapshot cannot be parsed.
+    pub fn before_event(&self) -> Result<Option<BabyEvent>, BabyrsError> {
+        self.before
+            .as_deref()
+            .map(BabyEvent::from_snapshot)
+            .transpose()
+    }
+
+    /// Decodes the event as it was after the change.
+    ///
+    /// # Returns
+    ///
+    /// `None` for a delete or purge, the event otherwise, or a `BabyrsError::Csv` if the snapshot cannot be parsed.
+    pub fn after_event(&self) -> Result<Option<BabyEvent>, BabyrsError> {
+        self.after
+            .as_deref()
+            .map(BabyEvent::from_snapshot)
+            .transpose()
+    }
+}
+
+/// Represents a user-defined event type, such as tummy time or a bath.
+///
+/// # Fields
+///
+/// - `id`: Unique identifier for the type.
+/// - `name`: The name of the type, unique among types ignoring case.
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::event_types)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct EventType {
+    pub id: i32,
+    pub name: String,
+}
+
+/// Represents a new event type to be inserted into the database.
+///
+/// # Fields
+///
+/// - `name`: The name of the type.
+#[derive(Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::event_types)]
+pub struct NewEventType {
+    pub name: String,
+}
+
+impl NewEventType {
+    /// Checks that the type has a name.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the type is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        if self.name.trim().is_empty() {
+            return Err(BabyrsError::Validation("name must not be empty".to_owned()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Represents a field of a user-defined event type.
+///
+/// # Fields
+///
+/// - `id`: Unique identifier for the field.
+/// - `event_type_id`: The type the field belongs to.
+/// - `position`: The position of the field among the fields of its type, from 0.
+/// - `name`: The name of the field, unique within its type.
+/// - `kind`: The kind of value the field records.
+/// - `choices`: The choices of an enum field separated by `|`, `None` for the other kinds.
+#[derive(Queryable, Selectable, Identifiable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::event_fields)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct EventField {
+    pub id: i32,
+    pub event_type_id: i32,
+    pub position: i32,
+    pub name: String,
+    pub kind: FieldKind,
+    pub choices: Option<String>,
+}
+
+/// Represents a new field of an event type to be inserted into the database.
+///
+/// # Fields
+///
+/// - `event_type_id`: The type the field belongs to.
+/// - `position`: The position of the field among the fields of its type, from 0.
+/// - `name`: The name of the field.
+/// - `kind`: The kind of value the field records.
+/// - `choices`: The choices of an enum field separated by `|`, `None` for the other kinds.
+#[derive(Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::event_fields)]
+pub struct NewEventField {
+    pub event_type_id: i32,
+    pub position: i32,
+    pub name: String,
+    pub kind: FieldKind,
+    pub choices: Option<String>,
+}
+
+impl NewEventField {
+    /// Parses the specification of a field, its name and kind separated by a colon.
+    ///
+    /// The type and position of the field are left at 0, to be set when the type is written.
+    ///
+    /// # Parameters
+    ///
+    /// * `spec`: The specification, such as `minutes:duration` or `product:enum(soap|oil|none)`.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(NewEventField)` if the specification names a valid field.
+    /// - `Err(BabyrsError::Validation)` describing the problem otherwise.
+    pub fn parse(spec: &str) -> Result<Self, BabyrsError> {
+        let invalid = || {
+            let kinds = FieldKind::ALL
+                .iter()
+                .map(|kind| match kind {
+                    FieldKind::Enum => "enum(a|b)".to_owned(),
+                    kind => kind.to_string(),
+                })
+                .collect::<Vec<String>>();
+            BabyrsError::Validation(format!(
+                "field {} must look like name:kind, with kind one of {}",
+                spec.trim(),
+                kinds.join(", ")
+            ))
+        };
+
+        let (name, kind) = spec.split_once(':').ok_or_else(invalid)?;
+        let (kind, choices) = match kind.trim().split_once('(') {
+            Some((kind, choices)) => (
+                kind,
+                Some(choices.strip_suffix(')').ok_or_else(invalid)?.to_owned()),
+            ),
+            None => (kind, None),
+        };
+
+        let new_field = Self {
+            event_type_id: 0,
+            position: 0,
+            name: name.trim().to_owned(),
+            kind: FieldKind::parse(kind).ok_or_else(invalid)?,
+            choices,
+        };
+        new_field.validate()?;
+
+        Ok(new_field)
+    }
+
+    /// Checks that the field has a name that can be written to a CSV file, and that an enum field and only an enum
+    /// field has choices.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the field is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        if self.name.trim().is_empty() {
+            return Err(BabyrsError::Validation(
+                "field name must not be empty".to_owned(),
+            ));
+        }
+        // the values column of a CSV file separates the fields by ';' and a name from its value by '='
+        if self.name.contains([';', '=']) {
+            return Err(BabyrsError::Validation(format!(
+                "field name {} must not contain ';' or '='",
+                self.name
+            )));
+        }
+
+        match (self.kind, &self.choices) {
+            (FieldKind::Enum, Some(choices))
+                if !choices.split('|').any(|choice| choice.trim().is_empty()) =>
+            {
+                Ok(())
+            }
+            (FieldKind::Enum, _) => Err(BabyrsError::Validation(format!(
+                "field {} needs choices separated by '|'",
+                self.name
+            ))),
+            (_, Some(_)) => Err(BabyrsError::Validation(format!(
+                "only an enum field has choices, not {}",
+                self.name
+            ))),
+            (_, None) => Ok(()),
+        }
+    }
+}
+
+impl EventField {
+    /// Returns the choices of an enum field.
+    ///
+    /// # Returns
+    ///
+    /// The trimmed choices in the order they were defined, empty for the other kinds.
+    pub fn choices(&self) -> Vec<&str> {
+        self.choices.as_deref().map_or(vec![], |choices| {
+            choices.split('|').map(str::trim).collect()
+        })
+    }
+
+    /// Parses a value entered for the field into the text it is stored as.
+    ///
+    /// A bool is entered as `yes` or `no`, a duration in whole minutes, a volume in the given unit and an enum as one
+    /// of its choices, ignoring case.
+    ///
+    /// # Parameters
+    ///
+    /// * `text`: The value as entered.
+    /// * `unit`: The unit a volume is entered in.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(None)` if the text is empty.
+    /// - `Ok(Some(String))` with the value to store.
+    /// - `Err(BabyrsError::Validation)` describing the problem otherwise.
+    pub fn parse_value(&self, text: &str, unit: VolumeUnit) -> Result<Option<String>, BabyrsError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+
+        let value = match self.kind {
+            FieldKind::Bool => match text.to_lowercase().as_str() {
+                "yes" | "y" | "true" | "x" | "1" => Some("true".to_owned()),
+                "no" | "n" | "false" | "0" => Some("false".to_owned()),
+                _ => None,
+            },
+            FieldKind::Duration => text.parse::<u16>().ok().map(|minutes| minutes.to_string()),
+            FieldKind::Volume => unit.parse_volume(text).map(|ml| ml.to_string()),
+            FieldKind::Enum => self
+                .choices()
+                .into_iter()
+                .find(|choice| choice.eq_ignore_ascii_case(text))
+                .map(str::to_owned),
+            FieldKind::Text => Some(text.to_owned()),
+        };
+
+        value.map(Some).ok_or_else(|| {
+            BabyrsError::Validation(match self.kind {
+                FieldKind::Bool => format!("{} must be yes or no", self.name),
+                FieldKind::Duration => format!("{} must be a whole number of minutes", self.name),
+                FieldKind::Volume => format!("{} must be a volume in {}", self.name, unit.code()),
+                _ => format!("{} must be one of {}", self.name, self.choices().join(", ")),
+            })
+        })
+    }
+
+    /// Formats a stored value the way it is entered, so that [`EventField::parse_value`] reads it back unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// * `value`: The stored value.
+    /// * `unit`: The unit a volume is entered in.
+    ///
+    /// # Returns
+    ///
+    /// The value, with a volume rounded with [`VolumeUnit::exact_value`].
+    pub fn input_value(&self, value: &str, unit: VolumeUnit) -> String {
+        match (self.kind, value.parse::<i32>()) {
+            (FieldKind::Volume, Ok(ml)) => unit.exact_value(ml).to_string(),
+            _ => value.to_owned(),
+        }
+    }
+
+    /// Checks that a value is stored the way [`EventField::parse_value`] stores it.
+    ///
+    /// # Parameters
+    ///
+    /// * `value`: The stored value.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the value is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate_value(&self, value: &str) -> Result<(), BabyrsError> {
+        match self.parse_value(value, VolumeUnit::Millilitre)? {
+            Some(parsed) if parsed == value => Ok(()),
+            _ => Err(BabyrsError::Validation(format!(
+                "{} cannot store {:?}",
+                self.name, value
+            ))),
+        }
+    }
+}
+
+/// Represents the value of a field on an event of a user-defined type.
+///
+/// # Fields
+///
+/// - `event_id`: The event the value belongs to.
+/// - `field_id`: The field of the event's type the value is for.
+/// - `value`: The value as stored, see [`EventField::parse_value`].
+#[derive(Queryable, Selectable, Insertable, Identifiable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::event_values)]
+#[diesel(primary_key(event_id, field_id))]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct EventValue {
+    pub event_id: i32,
+    pub field_id: i32,
+    pub value: String,
+}
+
+/// The user-defined event types with their fields, for looking up the type and fields of an event.
+///
+/// # Fields
+///
+/// - `types`: The event types, in the order they were added.
+/// - `fields`: The fields of every type, ordered by type and position.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EventRegistry {
+    pub types: Vec<EventType>,
+    pub fields: Vec<EventField>,
+}
+
+impl EventRegistry {
+    /// Returns the event type with the given id.
+    ///
+    /// # Parameters
+    ///
+    /// * `id`: The id of the type.
+    ///
+    /// # Returns
+    ///
+    /// The type, or `None` if there is no such type.
+    pub fn get_type(&self, id: i32) -> Option<&EventType> {
+        self.types.iter().find(|event_type| event_type.id == id)
+    }
+
+    /// Finds an event type by its name, ignoring case.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: The name of the type.
+    ///
+    /// # Returns
+    ///
+    /// The type, or `None` if no type has the name.
+    pub fn find_type(&self, name: &str) -> Option<&EventType> {
+        self.types
+            .iter()
+            .find(|event_type| event_type.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Returns the fields of an event type.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_type`: The id of the type.
+    ///
+    /// # Returns
+    ///
+    /// The fields in the order of their position, empty if there is no such type.
+    pub fn fields_of(&self, event_type: i32) -> Vec<&EventField> {
+        self.fields
+            .iter()
+            .filter(|field| field.event_type_id == event_type)
+            .collect()
+    }
+}
+
+/// Represents a growth measurement as it is stored in the database.
+///
+/// A measurement holds any of the weight, length and head circumference taken at the same time.
+///
+/// # Fields
+///
+/// - `id`: Unique identifier for the measurement.
+/// - `child_id`: The child that was measured.
+/// - `dt`: The datetime when the measurement was taken, in UTC.
+/// - `weight`: Weight in grams.
+/// - `length`: Length in millimetres.
+/// - `head_circumference`: Head circumference in millimetres.
+#[derive(Queryable, Selectable, Identifiable, AsChangeset, Debug, Copy, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::measurements)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_null = true)]
+pub struct Measurement {
+    pub id: i32,
+    pub child_id: i32,
+    pub dt: NaiveDateTime,
+    pub weight: Option<i32>,
+    pub length: Option<i32>,
+    pub head_circumference: Option<i32>,
+}
+
+/// Represents a new growth measurement to be inserted into the database.
+///
+/// # Fields
+///
+/// - `child_id`: The child that was measured.
+/// - `dt`: The datetime when the measurement was taken, in UTC.
+/// - `weight`: Weight in grams.
+/// - `length`: Length in millimetres.
+/// - `head_circumference`: Head circumference in millimetres.
+#[derive(Insertable, Debug, Copy, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::measurements)]
+pub struct NewMeasurement {
+    pub child_id: i32,
+    pub dt: NaiveDateTime,
+    pub weight: Option<i32>,
+    pub length: Option<i32>,
+    pub head_circumference: Option<i32>,
+}
+
+impl Measurement {
+    /// Checks that the measurement holds at least one value and that every value is positive.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the measurement is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        validate_measures(self.weight, self.length, self.head_circumference)
+    }
+}
+
+impl NewMeasurement {
+    /// Checks that the measurement holds at least one value and that every value is positive.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the measurement is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        validate_measures(self.weight, self.length, self.head_circumference)
+    }
+}
+
+/// Represents a sleep session as it is stored in the database.
+///
+/// # Fields
+///
+/// - `id`: Unique identifier for the session.
+/// - `child_id`: The child that slept.
+/// - `start_dt`: The datetime when the child fell asleep, in UTC.
+/// - `end_dt`: The datetime when the child woke up in UTC, `None` while the child is asleep.
+#[derive(Queryable, Selectable, Identifiable, AsChangeset, Debug, Copy, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::sleep_sessions)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_null = true)]
+pub struct SleepSession {
+    pub id: i32,
+    pub child_id: i32,
+    pub start_dt: NaiveDateTime,
+    pub end_dt: Option<NaiveDateTime>,
+}
+
+/// Represents a new sleep session to be inserted into the database.
+///
+/// # Fields
+///
+/// - `child_id`: The child that slept.
+/// - `start_dt`: The datetime when the child fell asleep, in UTC.
+/// - `end_dt`: The datetime when the child woke up in UTC, `None` while the child is asleep.
+#[derive(Insertable, Debug, Copy, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::sleep_sessions)]
+pub struct NewSleepSession {
+    pub child_id: i32,
+    pub start_dt: NaiveDateTime,
+    pub end_dt: Option<NaiveDateTime>,
+}
+
+impl SleepSession {
+    /// Checks whether the child is still asleep.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the session has no end yet.
+    pub fn is_open(&self) -> bool {
+        self.end_dt.is_none()
+    }
+
+    /// Returns the length of the session.
+    ///
+    /// # Returns
+    ///
+    /// The time between falling asleep and waking up, `None` while the child is asleep.
+    pub fn duration(&self) -> Option<Duration> {
+        self.end_dt.map(|end_dt| end_dt - self.start_dt)
+    }
+
+    /// Checks that the session ends after it starts.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the session is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        validate_sleep(self.start_dt, self.end_dt)
+    }
+}
+
+impl NewSleepSession {
+    /// Checks that the session ends after it starts.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the session is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        validate_sleep(self.start_dt, self.end_dt)
+    }
+}
+
+/// Rejects sleep sessions that do not end after they start.
+fn validate_sleep(
+    start_dt: NaiveDateTime,
+    end_dt: Option<NaiveDateTime>,
+) -> Result<(), BabyrsError> {
+    match end_dt {
+        Some(end_dt) if end_dt <= start_dt => Err(BabyrsError::Validation(format!(
+            "sleep must end after it starts at {}",
+            start_dt
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Represents a medicine or supplement in the catalogue, shared by all children.
+///
+/// # Fields
+///
+/// - `id`: Unique identifier for the medication.
+/// - `name`: The name of the medication.
+/// - `unit`: The unit doses are measured in, e.g. `ml`, `drops` or `IU`.
+/// - `dose`: The usual amount given at once.
+/// - `min_interval_minutes`: The minimum minutes between two doses, if limited.
+/// - `max_daily_doses`: The maximum number of doses in any 24 hours, if limited.
+/// - `max_daily_amount`: The maximum amount given in any 24 hours, if limited.
+#[derive(Queryable, Selectable, Identifiable, AsChangeset, Debug, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::medications)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(treat_none_as_null = true)]
+pub struct Medication {
+    pub id: i32,
+    pub name: String,
+    pub unit: String,
+    pub dose: f64,
+    pub min_interval_minutes: Option<i32>,
+    pub max_daily_doses: Option<i32>,
+    pub max_daily_amount: Option<f64>,
+}
+
+/// Represents a new medication to be inserted into the catalogue.
+///
+/// # Fields
+///
+/// - `name`: The name of the medication.
+/// - `unit`: The unit doses are measured in, e.g. `ml`, `drops` or `IU`.
+/// - `dose`: The usual amount given at once.
+/// - `min_interval_minutes`: The minimum minutes between two doses, if limited.
+/// - `max_daily_doses`: The maximum number of doses in any 24 hours, if limited.
+/// - `max_daily_amount`: The maximum amount given in any 24 hours, if limited.
+#[derive(Insertable, Debug, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::medications)]
+pub struct NewMedication {
+    pub name: String,
+    pub unit: String,
+    pub dose: f64,
+    pub min_interval_minutes: Option<i32>,
+    pub max_daily_doses: Option<i32>,
+    pub max_daily_amount: Option<f64>,
+}
+
+impl Medication {
+    /// Checks that the medication has a name and unit and that the dose and limits are positive.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the medication is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        validate_medication(
+            &self.name,
+            &self.unit,
+            self.dose,
+            self.min_interval_minutes,
+            self.max_daily_doses,
+            self.max_daily_amount,
+        )
+    }
+}
+
+impl NewMedication {
+    /// Checks that the medication has a name and unit and that the dose and limits are positive.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the medication is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        validate_medication(
+            &self.name,
+            &self.unit,
+            self.dose,
+            self.min_interval_minutes,
+            self.max_daily_doses,
+            self.max_daily_amount,
+        )
+    }
+}
+
+/// Rejects medications without a name or unit, with a dose or limit that is not positive, or with a daily amount
+/// smaller than a single dose.
+fn validate_medication(
+    name: &str,
+    unit: &str,
+    dose: f64,
+    min_interval_minutes: Option<i32>,
+    max_daily_doses: Option<i32>,
+    max_daily_amount: Option<f64>,
+) -> Result<(), BabyrsError> {
+    if name.trim().is_empty() {
+        return Err(BabyrsError::Validation("name must not be empty".to_owned()));
+    }
+    if unit.trim().is_empty() {
+        return Err(BabyrsError::Validation("unit must not be empty".to_owned()));
+    }
+    validate_amount("dose", dose)?;
+
+    let limits = [
+        ("minimum interval", min_interval_minutes),
+        ("maximum daily doses", max_daily_doses),
+    ];
+    if let Some((name, value)) = limits
+        .iter()
+        .find_map(|(name, value)| value.filter(|v| *v <= 0).map(|v| (name, v)))
+    {
+        return Err(BabyrsError::Validation(format!(
+            "{} must be positive, got {}",
+            name, value
+        )));
+    }
+
+    match max_daily_amount {
+        Some(amount) if amount < dose => Err(BabyrsError::Validation(format!(
+            "maximum daily amount must be at least one dose of {}, got {}",
+            dose, amount
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Represents a dose of a medication given to a child, as it is stored in the database.
+///
+/// # Fields
+///
+/// - `id`: Unique identifier for the dose.
+/// - `child_id`: The child that was given the dose.
+/// - `medication_id`: The medication that was given.
+/// - `dt`: The datetime when the dose was given, in UTC.
+/// - `amount`: The amount given, in the unit of the medication.
+#[derive(Queryable, Selectable, Identifiable, AsChangeset, Debug, Copy, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::doses)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Dose {
+    pub id: i32,
+    pub child_id: i32,
+    pub medication_id: i32,
+    pub dt: NaiveDateTime,
+    pub amount: f64,
+}
+
+/// Represents a new dose to be inserted into the database.
+///
+/// # Fields
+///
+/// - `child_id`: The child that was given the dose.
+/// - `medication_id`: The medication that was given.
+/// - `dt`: The datetime when the dose was given, in UTC.
+/// - `amount`: The amount given, in the unit of the medication.
+#[derive(Insertable, Debug, Copy, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::doses)]
+pub struct NewDose {
+    pub child_id: i32,
+    pub medication_id: i32,
+    pub dt: NaiveDateTime,
+    pub amount: f64,
+}
+
+impl NewDose {
+    /// Checks that the amount is positive.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the dose is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        validate_amount("amount", self.amount)
+    }
+}
+
+/// Rejects amounts that are not positive, including NaN.
+fn validate_amount(name: &str, amount: f64) -> Result<(), BabyrsError> {
+    if amount > 0.0 && amount.is_finite() {
+        Ok(())
+    } else {
+        Err(BabyrsError::Validation(format!(
+            "{} must be positive, got {}",
+            name, amount
+        )))
+    }
+}
+
+/// Represents a recurring schedule of a medication for a child, as it is stored in the database.
+///
+/// # Fields
+///
+/// - `id`: Unique identifier for the schedule.
+/// - `child_id`: The child the medication is scheduled for.
+/// - `medication_id`: The scheduled medication.
+/// - `first_dt`: The datetime of the first scheduled dose, in UTC.
+/// - `interval_minutes`: The minutes between two scheduled doses, e.g. 1440 for a daily dose.
+#[derive(Queryable, Selectable, Identifiable, AsChangeset, Debug, Copy, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::medication_schedules)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct MedicationSchedule {
+    pub id: i32,
+    pub child_id: i32,
+    pub medication_id: i32,
+    pub first_dt: NaiveDateTime,
+    pub interval_minutes: i32,
+}
+
+/// Represents a new medication schedule to be inserted into the database.
+///
+/// # Fields
+///
+/// - `child_id`: The child the medication is scheduled for.
+/// - `medication_id`: The scheduled medication.
+/// - `first_dt`: The datetime of the first scheduled dose, in UTC.
+/// - `interval_minutes`: The minutes between two scheduled doses, e.g. 1440 for a daily dose.
+#[derive(Insertable, Debug, Copy, Clone, PartialEq, Eq)]
+#[diesel(table_name = crate::schema::medication_schedules)]
+pub struct NewMedicationSchedule {
+    pub child_id: i32,
+    pub medication_id: i32,
+    pub first_dt: NaiveDateTime,
+    pub interval_minutes: i32,
+}
+
+impl NewMedicationSchedule {
+    /// Checks that the interval is positive.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the schedule is valid, otherwise a `BabyrsError::Validation` describing the problem.
+    pub fn validate(&self) -> Result<(), BabyrsError> {
+        if self.interval_minutes > 0 {
+            Ok(())
+        } else {
+            Err(BabyrsError::Validation(format!(
+                "interval must be positive, got {}",
+                self.interval_minutes
+            )))
+        }
+    }
+}
+
+/// Rejects measurements without any value, or with a value that is not positive.
+fn validate_measures(
+    weight: Option<i32>,
+    length: Option<i32>,
+    head_circumference: Option<i32>,
+) -> Result<(), BabyrsError> {
+    let measures = [
+        ("weight", weight),
+        ("length", length),
+        ("head circumference", head_circumference),
+    ];
+
+    if measures.iter().all(|(_, value)| value.is_none()) {
+        return Err(BabyrsError::Validation(
+            "a measurement needs a weight, length or head circumference".to_owned(),
+        ));
+    }
+
+    match measures
+        .iter()
+        .find_map(|(name, value)| value.filter(|v| *v <= 0).map(|v| (name, v)))
+    {
+        Some((name, value)) => Err(BabyrsError::Validation(format!(
+            "{} must be positive, got {}",
+            name, value
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Returns the id of the default child, for CSV files without a `child_id` column.
+fn default_child_id() -> i32 {
+    DEFAULT_CHILD_ID
+}
+
+/// Rejects sides that do not add up to the total, unless both are 0 because the sides are unknown.
+fn validate_sides(name: &str, total: i32, left: i32, right: i32) -> Result<(), BabyrsError> {
+    if (left == 0 && right == 0) || left + right == total {
+        Ok(())
+    } else {
+        Err(BabyrsError::Validation(format!(
+            "{} must be the sum of the left and right sides, got {} for {} + {}",
+            name, total, left, right
+        )))
+    }
+}
+
+/// Rejects the first negative value among the named durations and quantities.
+fn validate_quantities<const N: usize>(quantities: [(&str, i32); N]) -> Result<(), BabyrsError> {
+    match quantities.iter().find(|(_, value)| *value < 0) {
+        Some((name, value)) => Err(BabyrsError::Validation(format!(
+            "{} must not be negative, got {}",
+            name, value
+        ))),
+        None => Ok(()),
+    }
 }
@@ -1,8 +1,92 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    caregivers (id) {
+        id -> Integer,
+        name -> Text,
+    }
+}
+
+diesel::table! {
+    children (id) {
+        id -> Integer,
+        name -> Text,
+        birth_date -> Nullable<Date>,
+        sex -> Nullable<Text>,
+        birth_weight -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    diaper_details (event_id) {
+        event_id -> Integer,
+        stool_color -> Nullable<Text>,
+        stool_consistency -> Nullable<Text>,
+        stool_size -> Nullable<Text>,
+        urine_amount -> Nullable<Text>,
+        urate_crystals -> Bool,
+    }
+}
+
+diesel::table! {
+    doses (id) {
+        id -> Integer,
+        child_id -> Integer,
+        medication_id -> Integer,
+        dt -> Timestamp,
+        amount -> Double,
+    }
+}
+
+diesel::table! {
+    event_fields (id) {
+        id -> Integer,
+        event_type_id -> Integer,
+        position -> Integer,
+        name -> Text,
+        kind -> Text,
+        choices -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    event_history (id) {
+        id -> Integer,
+        event_id -> Integer,
+        changed_at -> Timestamp,
+        caregiver_id -> Nullable<Integer>,
+        action -> Text,
+        before -> Nullable<Text>,
+        after -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    event_notes (event_id) {
+        event_id -> Integer,
+        text -> Text,
+    }
+}
+
+diesel::table! {
+    event_types (id) {
+        id -> Integer,
+        name -> Text,
+    }
+}
+
+diesel::table! {
+    event_values (event_id, field_id) {
+        event_id -> Integer,
+        field_id -> Integer,
+        value -> Text,
+    }
+}
+
 diesel::table! {
     events (id) {
         id -> Integer,
+        child_id -> Integer,
         dt -> Timestamp,
         urine -> Bool,
         stool -> Bool,
@@ -11,5 +95,89 @@ diesel::table! {
         breastmilk -> Integer,
         formula -> Integer,
         pump -> Integer,
+        breastfeed_left -> Integer,
+        breastfeed_right -> Integer,
+        pump_left -> Integer,
+        pump_right -> Integer,
+        pump_duration -> Integer,
+        caregiver_id -> Nullable<Integer>,
+        utc_offset -> Integer,
+        deleted_at -> Nullable<Timestamp>,
+        event_type_id -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    measurements (id) {
+        id -> Integer,
+        child_id -> Integer,
+        dt -> Timestamp,
+        weight -> Nullable<Integer>,
+        length -> Nullable<Integer>,
+        head_circumference -> Nullable<Integer>,
     }
 }
+
+diesel::table! {
+    medication_schedules (id) {
+        id -> Integer,
+        child_id -> Integer,
+        medication_id -> Integer,
+        first_dt -> Timestamp,
+        interval_minutes -> Integer,
+    }
+}
+
+diesel::table! {
+    medications (id) {
+        id -> Integer,
+        name -> Text,
+        unit -> Text,
+        dose -> Double,
+        min_interval_minutes -> Nullable<Integer>,
+        max_daily_doses -> Nullable<Integer>,
+        max_daily_amount -> Nullable<Double>,
+    }
+}
+
+diesel::table! {
+    sleep_sessions (id) {
+        id -> Integer,
+        child_id -> Integer,
+        start_dt -> Timestamp,
+        end_dt -> Nullable<Timestamp>,
+    }
+}
+
+diesel::joinable!(diaper_details -> events (event_id));
+diesel::joinable!(doses -> children (child_id));
+diesel::joinable!(doses -> medications (medication_id));
+diesel::joinable!(event_fields -> event_types (event_type_id));
+diesel::joinable!(event_history -> caregivers (caregiver_id));
+diesel::joinable!(event_notes -> events (event_id));
+diesel::joinable!(event_values -> event_fields (field_id));
+diesel::joinable!(event_values -> events (event_id));
+diesel::joinable!(events -> caregivers (caregiver_id));
+diesel::joinable!(events -> children (child_id));
+diesel::joinable!(events -> event_types (event_type_id));
+diesel::joinable!(measurements -> children (child_id));
+diesel::joinable!(medication_schedules -> children (child_id));
+diesel::joinable!(medication_schedules -> medications (medication_id));
+diesel::joinable!(sleep_sessions -> children (child_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    caregivers,
+    children,
+    diaper_details,
+    doses,
+    event_fields,
+    event_history,
+    event_notes,
+    event_types,
+    event_values,
+    events,
+    measurements,
+    medication_schedules,
+    medications,
+    sleep_sessions,
+);
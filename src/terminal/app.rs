@@ -1,6 +1,14 @@
+use crate::models::{BabyEvent, EventHistory, EventNote, HistoryAction};
+use crate::terminal::browser::{BrowserAction, FileBrowser};
 use crate::terminal::events::Key;
-use crate::terminal::state::AppState;
-use log::{debug, warn};
+use crate::terminal::form::{
+    CaregiverForm, ChildForm, CustomEventForm, DiaperForm, EventForm, EventTypeForm, FilterPrompt,
+    FormAction, MeasurementForm, MedicationForm, SearchPrompt,
+};
+use crate::terminal::state::{AppState, Filter};
+use crate::{BabyrsError, WriteOutcome};
+use log::{debug, error, warn};
+use ratatui::widgets::ListState;
 use std::{
     collections::HashMap,
     fmt::{self, Display},
@@ -16,10 +24,63 @@ pub enum AppReturn {
     Continue,
 }
 
+/// Enum representing the modal popup currently shown on top of the main screen.
+#[derive(Debug)]
+pub enum Popup {
+    /// A form for entering a new event or editing an existing one.
+    EventForm(EventForm),
+    /// A form for noting the diaper details of an event.
+    DiaperForm(DiaperForm),
+    /// The user-defined event types with their fields, and the selected type.
+    EventTypes(ListState),
+    /// A form for adding a user-defined event type.
+    EventTypeForm(EventTypeForm),
+    /// A form for entering a new event of a user-defined type or editing an existing one.
+    CustomEventForm(CustomEventForm),
+    /// A confirmation prompt for deleting an event.
+    ConfirmDelete(BabyEvent),
+    /// A file browser for picking a CSV file to import.
+    FileBrowser(FileBrowser),
+    /// A prompt for entering a date range or rolling window to filter by.
+    FilterPrompt(FilterPrompt),
+    /// A form for adding a child or editing the current one.
+    ChildForm(ChildForm),
+    /// A form for adding a caregiver.
+    CaregiverForm(CaregiverForm),
+    /// A form for recording a growth measurement.
+    MeasurementForm(MeasurementForm),
+    /// The medication catalogue with the dose status of every medication, and the selected medication.
+    Medications(ListState),
+    /// A form for adding a medication to the catalogue.
+    MedicationForm(MedicationForm),
+    /// A prompt for entering the words to search the notes for.
+    SearchPrompt(SearchPrompt),
+    /// The events whose notes match a search, newest first, and the selected result.
+    SearchResults {
+        results: Vec<(BabyEvent, EventNote)>,
+        selection: ListState,
+    },
+    /// The deleted events of the current child, most recently deleted first, and the selected event.
+    Trash {
+        events: Vec<BabyEvent>,
+        selection: ListState,
+    },
+    /// The changes of an event, newest first, and the selected change.
+    History {
+        entries: Vec<EventHistory>,
+        selection: ListState,
+    },
+    /// A message dismissed with any key.
+    Message { title: String, text: String },
+}
+
 /// The main application struct, holding all actions and state.
 pub struct App {
     actions: Actions,
     pub state: AppState,
+    popup: Option<Popup>,
+    /// Events moved to the trash during this session, most recent last.
+    undo_stack: Vec<BabyEvent>,
 }
 
 impl App {
@@ -37,16 +98,49 @@ impl App {
             Action::AddEvent,
             Action::DeleteEvent,
             Action::UpdateEvent,
+            Action::EditDiaper,
+            Action::History,
+            Action::Trash,
+            Action::EventTypes,
             Action::NextEvent,
             Action::PreviousEvent,
+            Action::PreviousDay,
+            Action::NextDay,
+            Action::PreviousPeriod,
+            Action::NextPeriod,
+            Action::Today,
             Action::SwitchFilter,
+            Action::FilterRange,
+            Action::Search,
+            Action::SwitchChartMetric,
+            Action::SwitchChartView,
+            Action::SwitchUnit,
+            Action::SwitchChild,
+            Action::AddChild,
+            Action::EditChild,
+            Action::SwitchCaregiver,
+            Action::AddCaregiver,
+            Action::FilterCaregiver,
+            Action::AddMeasurement,
+            Action::SwitchGrowth,
+            Action::ToggleSleep,
+            Action::Medications,
             Action::LoadCSV,
+            Action::ExportCSV,
+            Action::Undo,
             Action::Quit,
         ]
         .into();
         let state = AppState::default();
+        let popup = None;
+        let undo_stack = vec![];
 
-        Self { actions, state }
+        Self {
+            actions,
+            state,
+            popup,
+            undo_stack,
+        }
     }
 
     /// Handles a key event by performing the associated action.
@@ -59,17 +153,48 @@ impl App {
     ///
     /// An `AppReturn` indicating whether to exit or continue the application.
     pub fn do_action(&mut self, key: Key) -> AppReturn {
+        // An open popup captures all keys
+        if self.popup.is_some() {
+            return self.do_popup_action(key);
+        }
+
         if let Some(action) = self.actions.find(key) {
             debug!("Action: {:?}", action);
 
             match action {
-                Action::AddEvent => AppReturn::Continue,
-                Action::DeleteEvent => AppReturn::Continue,
+                Action::AddEvent => self.add_event(),
+                Action::DeleteEvent => self.delete_event(),
+                Action::EditDiaper => self.edit_diaper(),
+                Action::History => self.history(),
+                Action::Trash => self.trash(),
+                Action::EventTypes => self.event_types(),
                 Action::NextEvent => self.next_event(),
                 Action::PreviousEvent => self.previous_event(),
+                Action::PreviousDay => self.shift_day(-1),
+                Action::NextDay => self.shift_day(1),
+                Action::PreviousPeriod => self.shift_period(-1),
+                Action::NextPeriod => self.shift_period(1),
+                Action::Today => self.today(),
                 Action::SwitchFilter => self.switch_filter(),
-                Action::LoadCSV => AppReturn::Continue,
-                Action::UpdateEvent => AppReturn::Continue,
+                Action::FilterRange => self.filter_range(),
+                Action::Search => self.search(),
+                Action::SwitchChartMetric => self.switch_chart_metric(),
+                Action::SwitchChartView => self.switch_chart_view(),
+                Action::SwitchUnit => self.switch_unit(),
+                Action::SwitchChild => self.switch_child(),
+                Action::AddChild => self.add_child(),
+                Action::EditChild => self.edit_child(),
+                Action::SwitchCaregiver => self.switch_caregiver(),
+                Action::AddCaregiver => self.add_caregiver(),
+                Action::FilterCaregiver => self.filter_caregiver(),
+                Action::AddMeasurement => self.add_measurement(),
+                Action::SwitchGrowth => self.switch_growth(),
+                Action::ToggleSleep => self.toggle_sleep(),
+                Action::Medications => self.medications(),
+                Action::LoadCSV => self.load_csv(),
+                Action::ExportCSV => self.export_csv(),
+                Action::UpdateEvent => self.update_event(),
+                Action::Undo => self.undo(),
                 Action::Quit => AppReturn::Exit,
             }
         } else {
@@ -78,6 +203,430 @@ impl App {
         }
     }
 
+    /// Handles a key event while a popup is open.
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: The key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    fn do_popup_action(&mut self, key: Key) -> AppReturn {
+        match &mut self.popup {
+            Some(Popup::EventForm(form)) => match form.handle_key(key) {
+                FormAction::Continue => {}
+                FormAction::Cancel => self.popup = None,
+                FormAction::Submit => self.submit_event_form(),
+            },
+            Some(Popup::DiaperForm(form)) => match form.handle_key(key) {
+                FormAction::Continue => {}
+                FormAction::Cancel => self.popup = None,
+                FormAction::Submit => match form
+                    .to_details()
+                    .and_then(|details| self.state.set_diaper_details(details, None))
+                {
+                    Ok(_) => self.popup = None,
+                    Err(error) => form.set_error(error.to_string()),
+                },
+            },
+            Some(Popup::EventTypes(selection)) => {
+                let count = self
+                    .state
+                    .get_event_registry()
+                    .map_or(0, |registry| registry.types.len());
+
+                match key {
+                    Key::Down if count > 0 => {
+                        selection.select(Some(selection.selected().map_or(0, |i| (i + 1) % count)))
+                    }
+                    Key::Up if count > 0 => selection.select(Some(
+                        selection
+                            .selected()
+                            .map_or(count - 1, |i| (i + count - 1) % count),
+                    )),
+                    Key::Enter => self.add_custom_event(),
+                    Key::Char('a') => {
+                        self.popup = Some(Popup::EventTypeForm(EventTypeForm::default()));
+                    }
+                    Key::Esc | Key::Char('q') => self.popup = None,
+                    _ => {}
+                }
+            }
+            Some(Popup::EventTypeForm(form)) => match form.handle_key(key) {
+                FormAction::Continue => {}
+                FormAction::Cancel => {
+                    self.event_types();
+                }
+                FormAction::Submit => match form.to_new_event_type() {
+                    Ok((new_type, fields)) => {
+                        match self.state.add_event_type(new_type, fields, None) {
+                            Ok(_) => {
+                                self.event_types();
+                            }
+                            Err(error) => form.set_error(error.to_string()),
+                        }
+                    }
+                    Err(error) => form.set_error(error.to_string()),
+                },
+            },
+            Some(Popup::CustomEventForm(form)) => match form.handle_key(key) {
+                FormAction::Continue => {}
+                FormAction::Cancel => self.popup = None,
+                FormAction::Submit => self.submit_custom_event_form(),
+            },
+            Some(Popup::ConfirmDelete(event)) => match key {
+                Key::Char('y') | Key::Enter => {
+                    let event = *event;
+                    self.popup = None;
+
+                    match self.state.delete_event(event, None) {
+                        Ok(1) => self.undo_stack.push(event),
+                        Ok(_) => {}
+                        Err(error) => self.show_error(error),
+                    }
+                }
+                Key::Char('n') | Key::Esc => self.popup = None,
+                _ => {}
+            },
+            Some(Popup::FileBrowser(browser)) => match browser.handle_key(key) {
+                BrowserAction::Continue => {}
+                BrowserAction::Cancel => self.popup = None,
+                BrowserAction::Import(path) => self.import_csv(&path.to_string_lossy()),
+            },
+            Some(Popup::FilterPrompt(prompt)) => match prompt.handle_key(key) {
+                FormAction::Continue => {}
+                FormAction::Cancel => self.popup = None,
+                FormAction::Submit => match prompt.to_filter(chrono::Local::now().naive_local()) {
+                    Ok(filter) => {
+                        self.popup = None;
+
+                        let result = self.state.set_filter(filter, None);
+                        self.report_error(result);
+                    }
+                    Err(error) => prompt.set_error(error.to_string()),
+                },
+            },
+            Some(Popup::ChildForm(form)) => match form.handle_key(key) {
+                FormAction::Continue => {}
+                FormAction::Cancel => self.popup = None,
+                FormAction::Submit => self.submit_child_form(),
+            },
+            Some(Popup::CaregiverForm(form)) => match form.handle_key(key) {
+                FormAction::Continue => {}
+                FormAction::Cancel => self.popup = None,
+                FormAction::Submit => match form.to_new_caregiver() {
+                    Ok(new_caregiver) => match self.state.add_caregiver(new_caregiver, None) {
+                        Ok(()) => self.popup = None,
+                        Err(error) => form.set_error(error.to_string()),
+                    },
+                    Err(error) => form.set_error(error.to_string()),
+                },
+            },
+            Some(Popup::MeasurementForm(form)) => match form.handle_key(key) {
+                FormAction::Continue => {}
+                FormAction::Cancel => self.popup = None,
+                FormAction::Submit => match form.to_new_measurement() {
+                    Ok(new_measurement) => {
+                        match self.state.add_measurement(new_measurement, None) {
+                            Ok(_) => self.popup = None,
+                            Err(error) => form.set_error(error.to_string()),
+                        }
+                    }
+                    Err(error) => form.set_error(error.to_string()),
+                },
+            },
+            Some(Popup::Medications(selection)) => {
+                let count = self.state.get_medications().map_or(0, <[_]>::len);
+
+                match key {
+                    Key::Down if count > 0 => {
+                        selection.select(Some(selection.selected().map_or(0, |i| (i + 1) % count)))
+                    }
+                    Key::Up if count > 0 => selection.select(Some(
+                        selection
+                            .selected()
+                            .map_or(count - 1, |i| (i + count - 1) % count),
+                    )),
+                    Key::Enter => self.give_dose(),
+                    Key::Char('a') => {
+                        let now = chrono::Local::now().naive_local();
+                        self.popup = Some(Popup::MedicationForm(MedicationForm::new(now)));
+                    }
+                    Key::Esc | Key::Char('q') => self.popup = None,
+                    _ => {}
+                }
+            }
+            Some(Popup::MedicationForm(form)) => match form.handle_key(key) {
+                FormAction::Continue => {}
+                FormAction::Cancel => {
+                    self.medications();
+                }
+                FormAction::Submit => match form.to_new_medication() {
+                    Ok((new_medication, schedule)) => {
+                        match self.state.add_medication(new_medication, schedule, None) {
+                            Ok(()) => {
+                                self.medications();
+                            }
+                            Err(error) => form.set_error(error.to_string()),
+                        }
+                    }
+                    Err(error) => form.set_error(error.to_string()),
+                },
+            },
+            Some(Popup::SearchPrompt(prompt)) => match prompt.handle_key(key) {
+                FormAction::Continue => {}
+                FormAction::Cancel => self.popup = None,
+                FormAction::Submit => match self.state.search_notes(prompt.input(), None) {
+                    Ok(results) if results.is_empty() => {
+                        prompt.set_error(format!("No notes match {}", prompt.input().trim()))
+                    }
+                    Ok(results) => {
+                        self.popup = Some(Popup::SearchResults {
+                            results,
+                            selection: ListState::default().with_selected(Some(0)),
+                        })
+                    }
+                    Err(error) => prompt.set_error(error.to_string()),
+                },
+            },
+            Some(Popup::SearchResults { results, selection }) => {
+                let count = results.len();
+
+                match key {
+                    Key::Down => selection.select(selection.selected().map(|i| (i + 1) % count)),
+                    Key::Up => {
+                        selection.select(selection.selected().map(|i| (i + count - 1) % count))
+                    }
+                    Key::Enter => {
+                        let event = selection.selected().map(|i| results[i].0);
+                        self.popup = None;
+
+                        if let Some(event) = event {
+                            let result = self.state.show_event(&event, None);
+                            self.report_error(result);
+                        }
+                    }
+                    Key::Esc | Key::Char('q') => self.popup = None,
+                    _ => {}
+                }
+            }
+            Some(Popup::Trash { events, selection }) => {
+                let count = events.len();
+                let event = selection.selected().map(|i| events[i]);
+
+                match (key, event) {
+                    (Key::Down, _) => {
+                        selection.select(selection.selected().map(|i| (i + 1) % count))
+                    }
+                    (Key::Up, _) => {
+                        selection.select(selection.selected().map(|i| (i + count - 1) % count))
+                    }
+                    (Key::Enter | Key::Char('r'), Some(event)) => {
+                        self.popup = None;
+
+                        match self.state.restore_event(event, None) {
+                            Ok(0) => {
+                                self.popup = Some(Popup::Message {
+                                    title: "Trash".to_owned(),
+                                    text: format!("Event {} could not be restored", event.id),
+                                })
+                            }
+                            Ok(_) => self.undo_stack.retain(|e| e.id != event.id),
+                            Err(error) => self.show_error(error),
+                        }
+                    }
+                    (Key::Char('p'), Some(event)) => match self.state.purge_event(event, None) {
+                        Ok(_) => {
+                            self.undo_stack.retain(|e| e.id != event.id);
+                            self.trash();
+                        }
+                        Err(error) => self.show_error(error),
+                    },
+                    (Key::Esc | Key::Char('q'), _) => self.popup = None,
+                    _ => {}
+                }
+            }
+            Some(Popup::History { entries, selection }) => {
+                let count = entries.len();
+
+                match key {
+                    Key::Down => selection.select(selection.selected().map(|i| (i + 1) % count)),
+                    Key::Up => {
+                        selection.select(selection.selected().map(|i| (i + count - 1) % count))
+                    }
+                    Key::Enter => {
+                        let entry = selection
+                            .selected()
+                            .map(|i| entries[i].clone())
+                            .filter(|entry| entry.action != HistoryAction::Purge);
+
+                        if let Some(entry) = entry {
+                            self.popup = None;
+                            let result = self.state.revert_event(&entry, None).map(|_| ());
+                            self.report_error(result);
+                        }
+                    }
+                    Key::Char('v') => {
+                        let entry = selection
+                            .selected()
+                            .map(|i| entries[i].clone())
+                            .filter(|entry| entry.after.is_some());
+
+                        if let Some(entry) = entry {
+                            self.popup = None;
+                            let result =
+                                self.state.revert_event_to_version(&entry, None).map(|_| ());
+                            self.report_error(result);
+                        }
+                    }
+                    Key::Esc | Key::Char('q') => self.popup = None,
+                    _ => {}
+                }
+            }
+            Some(Popup::Message { .. }) if key != Key::Unknown => self.popup = None,
+            Some(Popup::Message { .. }) | None => {}
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Validates the open event form and writes the event and its note to the database, as a new event or as an update
+    /// to the edited event.
+    ///
+    /// A new event at the same timestamp as an existing one is written according to the duplicate policy. The form
+    /// stays open and displays an error if validation fails, the database could not be written or nothing was written.
+    fn submit_event_form(&mut self) {
+        if let Some(Popup::EventForm(form)) = &mut self.popup {
+            let result = match form.id() {
+                None => form.to_new_event().and_then(|new_event| {
+                    match self.state.add_event(new_event, None)? {
+                        Some(WriteOutcome::Inserted(_) | WriteOutcome::Merged(_)) => Ok(None),
+                        _ => Ok(Some("The same event has already been recorded".to_owned())),
+                    }
+                }),
+                Some(id) => form.to_event().and_then(|event| {
+                    match self.state.update_event(event, None)? {
+                        1 => Ok(None),
+                        _ => Ok(Some(format!("Event {} no longer exists", id))),
+                    }
+                }),
+            };
+
+            match result {
+                Ok(None) => {
+                    let note = form.note();
+                    let edited = form.id().is_some();
+                    self.popup = None;
+                    self.write_note(&note, edited);
+                }
+                Ok(Some(error)) => form.set_error(error),
+                Err(error) => form.set_error(error.to_string()),
+            }
+        }
+    }
+
+    /// Validates the open form of an event of a user-defined type and writes the event, its values and its note to
+    /// the database, as a new event or as an update to the edited event.
+    ///
+    /// The form stays open and displays an error if validation fails, the database could not be written or nothing
+    /// was written.
+    fn submit_custom_event_form(&mut self) {
+        if let Some(Popup::CustomEventForm(form)) = &mut self.popup {
+            let result = match form.id() {
+                None => form.to_new_event().and_then(|(new_event, values)| {
+                    match self.state.add_event_with_values(new_event, &values, None)? {
+                        Some(WriteOutcome::Inserted(_) | WriteOutcome::Merged(_)) => Ok(None),
+                        _ => Ok(Some("The same event has already been recorded".to_owned())),
+                    }
+                }),
+                Some(id) => form.to_event().and_then(|(event, values)| {
+                    match self.state.update_event_with_values(event, &values, None)? {
+                        1 => Ok(None),
+                        _ => Ok(Some(format!("Event {} no longer exists", id))),
+                    }
+                }),
+            };
+
+            match result {
+                Ok(None) => {
+                    let note = form.note();
+                    let edited = form.id().is_some();
+                    self.popup = None;
+                    self.write_note(&note, edited);
+                }
+                Ok(Some(error)) => form.set_error(error),
+                Err(error) => form.set_error(error.to_string()),
+            }
+        }
+    }
+
+    /// Writes the note entered in a form to the event the form has just written, which is selected after the write.
+    ///
+    /// A new event only gets a note if one was entered, while the note of an edited event is replaced or deleted.
+    ///
+    /// # Parameters
+    ///
+    /// * `note`: The note entered in the form.
+    /// * `edited`: Whether the form edited an existing event.
+    fn write_note(&mut self, note: &str, edited: bool) {
+        if let Some(event) = self
+            .state
+            .get_selected_event()
+            .filter(|_| edited || !note.is_empty())
+        {
+            let result = self.state.set_note(event.id, note, None).map(|_| ());
+            self.report_error(result);
+        }
+    }
+
+    /// Validates the open child form and writes the child to the database, as a new child or as an update to the
+    /// edited child.
+    ///
+    /// The form stays open and displays an error if validation fails or the database could not be written.
+    fn submit_child_form(&mut self) {
+        if let Some(Popup::ChildForm(form)) = &mut self.popup {
+            let result = match form.id() {
+                None => form
+                    .to_new_child()
+                    .and_then(|new_child| self.state.add_child(new_child, None)),
+                Some(_) => form
+                    .to_child()
+                    .and_then(|child| self.state.update_child(child, None).map(|_| ())),
+            };
+
+            match result {
+                Ok(()) => self.popup = None,
+                Err(error) => form.set_error(error.to_string()),
+            }
+        }
+    }
+
+    /// Shows an error in a popup, replacing any open popup.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error to show.
+    fn show_error(&mut self, error: BabyrsError) {
+        error!("{}", error);
+
+        self.popup = Some(Popup::Message {
+            title: "Error".to_owned(),
+            text: error.to_string(),
+        });
+    }
+
+    /// Shows the error of a failed state update in a popup, if there is one.
+    ///
+    /// # Parameters
+    ///
+    /// * `result`: The result of the state update.
+    fn report_error(&mut self, result: Result<(), BabyrsError>) {
+        if let Err(error) = result {
+            self.show_error(error);
+        }
+    }
+
     /// Initializes the application.
     ///
     /// # Returns
@@ -88,13 +637,14 @@ impl App {
         AppReturn::Continue
     }
 
-    /// Loads the events from the database into the state.
+    /// Loads the events from the database into the state, showing an error if the database cannot be read.
     ///
     /// # Returns
     ///
     /// An `AppReturn` indicating that the application should continue running.
     pub fn load_events(&mut self) -> AppReturn {
-        self.state.load_events(None);
+        let result = self.state.load_events(None);
+        self.report_error(result);
         AppReturn::Continue
     }
 
@@ -116,26 +666,241 @@ impl App {
         &mut self.state
     }
 
-    /// Adds an event to the application.
+    /// Returns a reference to the currently open popup, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&Popup)` if a popup is open.
+    /// - `None` otherwise.
+    pub fn popup(&self) -> Option<&Popup> {
+        self.popup.as_ref()
+    }
+
+    /// Returns a mutable reference to the currently open popup, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&mut Popup)` if a popup is open.
+    /// - `None` otherwise.
+    pub fn popup_mut(&mut self) -> Option<&mut Popup> {
+        self.popup.as_mut()
+    }
+
+    /// Opens the form for adding an event, with the timestamp defaulting to now.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn add_event(&mut self) -> AppReturn {
+        if self.state.is_initialized() {
+            let now = chrono::Local::now().naive_local();
+            self.popup = Some(Popup::EventForm(EventForm::new(
+                now,
+                self.state.get_volume_unit(),
+            )));
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Opens the form for editing the selected event, pre-populated from the event and its note, and the values of an
+    /// event of a user-defined type.
+    ///
+    /// Does nothing if no event is selected.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn update_event(&mut self) -> AppReturn {
+        if let Some(event) = self.state.get_selected_event() {
+            let note = self.state.get_note(event.id);
+            let unit = self.state.get_volume_unit();
+            let registry = self.state.get_event_registry();
+
+            let popup = match (event.event_type_id, registry) {
+                (Some(event_type), Some(registry)) => {
+                    registry.get_type(event_type).map(|event_type| {
+                        Popup::CustomEventForm(CustomEventForm::from_event(
+                            &event,
+                            event_type,
+                            &registry.fields_of(event_type.id),
+                            &self.state.get_event_values(event.id),
+                            note,
+                            unit,
+                        ))
+                    })
+                }
+                _ => Some(Popup::EventForm(EventForm::from_event(&event, note, unit))),
+            };
+            self.popup = popup;
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Asks for confirmation before deleting the selected event.
+    ///
+    /// Does nothing if no event is selected.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn delete_event(&mut self) -> AppReturn {
+        if let Some(event) = self.state.get_selected_event() {
+            self.popup = Some(Popup::ConfirmDelete(event));
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Restores the most recently deleted event of this session from the trash, with its original id and timestamp, its
+    /// diaper details and its note.
     ///
     /// # Returns
     ///
     /// An `AppReturn` indicating that the application should continue running.
-    pub fn add_event(&mut self) {
-        // self.state.add_event();
-        // AppReturn::Continue
-        unimplemented!()
+    pub fn undo(&mut self) -> AppReturn {
+        if let Some(event) = self.undo_stack.pop() {
+            match self.state.restore_event(event, None) {
+                Ok(0) => warn!("Could not restore event: {:?}", event),
+                Ok(_) => {}
+                Err(error) => {
+                    // keep the event so that restoring it can be retried
+                    self.undo_stack.push(event);
+                    self.show_error(error);
+                }
+            }
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Opens the prompt for searching the notes of the current child across all dates.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn search(&mut self) -> AppReturn {
+        if self.state.is_initialized() {
+            self.popup = Some(Popup::SearchPrompt(SearchPrompt::default()));
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Opens the diaper form for the selected event, pre-populated with the details noted before.
+    ///
+    /// Does nothing if no event is selected.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn edit_diaper(&mut self) -> AppReturn {
+        if let Some(event) = self.state.get_selected_event() {
+            let form = DiaperForm::new(event.id, self.state.get_diaper_details(event.id));
+            self.popup = Some(Popup::DiaperForm(form));
+        }
+
+        AppReturn::Continue
     }
 
-    /// Deletes an event from the application.
+    /// Opens the trash of the current child, for restoring deleted events or purging them for good.
+    ///
+    /// The trash is closed once it is empty.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn trash(&mut self) -> AppReturn {
+        match self.state.read_deleted_events(None) {
+            Ok(events) if events.is_empty() => {
+                self.popup = self.state.is_initialized().then(|| Popup::Message {
+                    title: "Trash".to_owned(),
+                    text: "The trash is empty".to_owned(),
+                })
+            }
+            Ok(events) => {
+                self.popup = Some(Popup::Trash {
+                    events,
+                    selection: ListState::default().with_selected(Some(0)),
+                })
+            }
+            Err(error) => self.show_error(error),
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Opens the history of the selected event, for reverting it to the version before any of its changes.
+    ///
+    /// Does nothing if no event is selected.
     ///
     /// # Returns
     ///
     /// An `AppReturn` indicating that the application should continue running.
-    pub fn delete_event(&mut self) {
-        // self.state.delete_event();
-        // AppReturn::Continue
-        unimplemented!()
+    pub fn history(&mut self) -> AppReturn {
+        if let Some(event) = self.state.get_selected_event() {
+            match self.state.read_event_history(event.id, None) {
+                Ok(entries) if entries.is_empty() => {
+                    self.popup = Some(Popup::Message {
+                        title: "History".to_owned(),
+                        text: "No changes have been recorded for this event".to_owned(),
+                    })
+                }
+                Ok(mut entries) => {
+                    entries.reverse();
+                    self.popup = Some(Popup::History {
+                        entries,
+                        selection: ListState::default().with_selected(Some(0)),
+                    });
+                }
+                Err(error) => self.show_error(error),
+            }
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Opens the list of user-defined event types, for recording an event of a type or adding a type.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn event_types(&mut self) -> AppReturn {
+        if let Some(registry) = self.state.get_event_registry() {
+            let mut selection = ListState::default();
+            selection.select((!registry.types.is_empty()).then_some(0));
+
+            self.popup = Some(Popup::EventTypes(selection));
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Opens the form for recording an event of the type selected in the list of event types, with the timestamp
+    /// defaulting to now.
+    ///
+    /// Does nothing if no type is selected.
+    fn add_custom_event(&mut self) {
+        if let Some(Popup::EventTypes(selection)) = &self.popup {
+            let now = chrono::Local::now().naive_local();
+            let unit = self.state.get_volume_unit();
+            let form = selection.selected().and_then(|i| {
+                let registry = self.state.get_event_registry()?;
+                let event_type = registry.types.get(i)?;
+
+                Some(CustomEventForm::new(
+                    event_type,
+                    &registry.fields_of(event_type.id),
+                    now,
+                    unit,
+                ))
+            });
+
+            if let Some(form) = form {
+                self.popup = Some(Popup::CustomEventForm(form));
+            }
+        }
     }
 
     /// Move the event selection to the next event.
@@ -167,13 +932,332 @@ impl App {
     ///
     /// An `AppReturn` indicating that the application should continue running.
     pub fn switch_filter(&mut self) -> AppReturn {
-        self.state.switch_filter();
+        let result = self.state.switch_filter(None);
+        self.report_error(result);
+        AppReturn::Continue
+    }
+
+    /// Moves the filter by a number of days, keeping its variant.
+    ///
+    /// # Parameters
+    ///
+    /// * `days`: The number of days to move by, negative to move back in time.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn shift_day(&mut self, days: i32) -> AppReturn {
+        if let Some(filter) = self.state.get_filter() {
+            let date = Filter::Day(filter.date()).shift(days).date();
+            let result = self.state.move_filter(date, None);
+            self.report_error(result);
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Moves the filter by a number of its own periods: days, weeks or months.
+    ///
+    /// # Parameters
+    ///
+    /// * `periods`: The number of periods to move by, negative to move back in time.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn shift_period(&mut self, periods: i32) -> AppReturn {
+        if let Some(filter) = self.state.get_filter() {
+            let date = filter.shift(periods).date();
+            let result = self.state.move_filter(date, None);
+            self.report_error(result);
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Moves the filter to today, keeping its variant.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn today(&mut self) -> AppReturn {
+        let result = self
+            .state
+            .move_filter(chrono::Local::now().date_naive(), None);
+        self.report_error(result);
+        AppReturn::Continue
+    }
+
+    /// Opens the prompt for filtering by a date range or a rolling window such as the last 24 hours.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn filter_range(&mut self) -> AppReturn {
+        if self.state.is_initialized() {
+            self.popup = Some(Popup::FilterPrompt(FilterPrompt::default()));
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Switches the chart to the next metric.
+    ///
+    /// The order of metrics is: intake, pumped, wet diapers, stools, feeding interval.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn switch_chart_metric(&mut self) -> AppReturn {
+        self.state.switch_chart_metric();
+        AppReturn::Continue
+    }
+
+    /// Switches the chart between the bar and line views.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn switch_chart_view(&mut self) -> AppReturn {
+        self.state.switch_chart_view();
+        AppReturn::Continue
+    }
+
+    /// Switches the unit volumes are displayed, entered and exported in to the next unit.
+    ///
+    /// The order is: millilitres, US fluid ounces, imperial fluid ounces.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn switch_unit(&mut self) -> AppReturn {
+        self.state.switch_volume_unit();
+        AppReturn::Continue
+    }
+
+    /// Switches to the events of the next child.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn switch_child(&mut self) -> AppReturn {
+        let result = self.state.switch_child(None);
+        self.report_error(result);
+        AppReturn::Continue
+    }
+
+    /// Opens the form for adding a child.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn add_child(&mut self) -> AppReturn {
+        if self.state.is_initialized() {
+            self.popup = Some(Popup::ChildForm(ChildForm::default()));
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Opens the form for editing the current child, pre-populated from the child.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn edit_child(&mut self) -> AppReturn {
+        if let Some(child) = self.state.get_child() {
+            self.popup = Some(Popup::ChildForm(ChildForm::from_child(child)));
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Switches the caregiver recording new events to the next caregiver, or to nobody after the last one.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn switch_caregiver(&mut self) -> AppReturn {
+        self.state.switch_caregiver();
+        AppReturn::Continue
+    }
+
+    /// Opens the form for adding a caregiver.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn add_caregiver(&mut self) -> AppReturn {
+        if self.state.is_initialized() {
+            self.popup = Some(Popup::CaregiverForm(CaregiverForm::default()));
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Shows only the events of the next caregiver, or of all caregivers after the last one.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn filter_caregiver(&mut self) -> AppReturn {
+        let result = self.state.switch_caregiver_filter(None);
+        self.report_error(result);
+        AppReturn::Continue
+    }
+
+    /// Opens the form for recording a growth measurement of the current child, with the timestamp defaulting to now.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn add_measurement(&mut self) -> AppReturn {
+        if self.state.is_initialized() {
+            let now = chrono::Local::now().naive_local();
+            self.popup = Some(Popup::MeasurementForm(MeasurementForm::new(now)));
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Switches the growth panel to the next measure.
+    ///
+    /// The order is: weight, length, head circumference, hidden.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn switch_growth(&mut self) -> AppReturn {
+        self.state.switch_growth_measure();
+        AppReturn::Continue
+    }
+
+    /// Records that the current child fell asleep now, or woke up now if it is asleep.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn toggle_sleep(&mut self) -> AppReturn {
+        let result = self
+            .state
+            .toggle_sleep(chrono::Utc::now().naive_utc(), None);
+        self.report_error(result);
+        AppReturn::Continue
+    }
+
+    /// Opens the medication catalogue, listing when every medication was last given and may or should be given next.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn medications(&mut self) -> AppReturn {
+        if let Some(medications) = self.state.get_medications() {
+            let mut selection = ListState::default();
+            selection.select((!medications.is_empty()).then_some(0));
+
+            self.popup = Some(Popup::Medications(selection));
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Logs a dose of the medication selected in the catalogue, given now in its usual amount.
+    ///
+    /// The catalogue stays open, and an error naming the time the dose is allowed is shown if it is given too early.
+    fn give_dose(&mut self) {
+        if let Some(Popup::Medications(selection)) = &self.popup {
+            let medication = selection
+                .selected()
+                .and_then(|i| self.state.get_medications()?.get(i))
+                .map(|medication| medication.id);
+
+            if let Some(medication_id) = medication {
+                let now = chrono::Utc::now().naive_utc();
+
+                if let Err(error) = self.state.add_dose(medication_id, now, None) {
+                    self.show_error(error);
+                }
+            }
+        }
+    }
+
+    /// Opens a file browser for picking a CSV file to import, starting in the current directory.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn load_csv(&mut self) -> AppReturn {
+        if self.state.is_initialized() {
+            let browser = std::env::current_dir().and_then(FileBrowser::new);
+
+            self.popup = Some(match browser {
+                Ok(browser) => Popup::FileBrowser(browser),
+                Err(error) => Popup::Message {
+                    title: "Load CSV".to_owned(),
+                    text: format!("Could not list the current directory: {}", error),
+                },
+            });
+        }
+
+        AppReturn::Continue
+    }
+
+    /// Exports the shown events into a timestamped CSV file in the current directory, with the volumes in the
+    /// current unit, and reports how many events were written.
+    ///
+    /// # Returns
+    ///
+    /// An `AppReturn` indicating that the application should continue running.
+    pub fn export_csv(&mut self) -> AppReturn {
+        if self.state.is_initialized() {
+            let now = chrono::Local::now().naive_local();
+            let file_path = format!("babyrs-export-{}.csv", now.format("%Y%m%d-%H%M%S"));
+
+            let text = match self.state.export_csv(&file_path) {
+                Ok(count) => format!("Exported {} events to {}.", count, file_path),
+                Err(error) => format!("Could not export {}: {}", file_path, error),
+            };
+
+            self.popup = Some(Popup::Message {
+                title: "Export CSV".to_owned(),
+                text,
+            });
+        }
+
         AppReturn::Continue
     }
 
-    /// Loads events from a CSV file.
-    pub fn load_csv(&mut self) {
-        unimplemented!()
+    /// Imports a CSV file and reports how many rows were inserted and merged, and which rows were skipped.
+    ///
+    /// # Parameters
+    ///
+    /// * `file_path`: The path of the CSV file.
+    fn import_csv(&mut self, file_path: &str) {
+        let text = match self.state.import_csv(file_path, None) {
+            Ok(summary) if summary.skipped.is_empty() => format!(
+                "Imported {}: {} rows inserted, {} merged.",
+                file_path, summary.inserted, summary.merged
+            ),
+            Ok(summary) => format!(
+                "Imported {}: {} rows inserted, {} merged, {} skipped as duplicates (rows {}).",
+                file_path,
+                summary.inserted,
+                summary.merged,
+                summary.skipped.len(),
+                summary
+                    .skipped
+                    .iter()
+                    .map(|row| row.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Err(error) => format!("Could not import {}: {}", file_path, error),
+        };
+
+        self.popup = Some(Popup::Message {
+            title: "Load CSV".to_owned(),
+            text,
+        });
     }
 }
 
@@ -184,9 +1268,35 @@ pub enum Action {
     DeleteEvent,
     NextEvent,
     PreviousEvent,
+    PreviousDay,
+    NextDay,
+    PreviousPeriod,
+    NextPeriod,
+    Today,
     SwitchFilter,
+    FilterRange,
+    Search,
+    SwitchChartMetric,
+    SwitchChartView,
+    SwitchUnit,
+    SwitchChild,
+    AddChild,
+    EditChild,
+    SwitchCaregiver,
+    AddCaregiver,
+    FilterCaregiver,
+    AddMeasurement,
+    SwitchGrowth,
+    ToggleSleep,
+    Medications,
     LoadCSV,
+    ExportCSV,
     UpdateEvent,
+    EditDiaper,
+    History,
+    Trash,
+    EventTypes,
+    Undo,
     Quit,
 }
 
@@ -197,14 +1307,40 @@ impl Action {
     ///
     /// An iterator over [`Action`].
     pub fn iterator() -> Iter<'static, Action> {
-        static ACTIONS: [Action; 8] = [
+        static ACTIONS: [Action; 34] = [
             Action::AddEvent,
             Action::DeleteEvent,
             Action::UpdateEvent,
+            Action::EditDiaper,
+            Action::History,
+            Action::Trash,
+            Action::EventTypes,
             Action::NextEvent,
             Action::PreviousEvent,
+            Action::PreviousDay,
+            Action::NextDay,
+            Action::PreviousPeriod,
+            Action::NextPeriod,
+            Action::Today,
             Action::SwitchFilter,
+            Action::FilterRange,
+            Action::Search,
+            Action::SwitchChartMetric,
+            Action::SwitchChartView,
+            Action::SwitchUnit,
+            Action::SwitchChild,
+            Action::AddChild,
+            Action::EditChild,
+            Action::SwitchCaregiver,
+            Action::AddCaregiver,
+            Action::FilterCaregiver,
+            Action::AddMeasurement,
+            Action::SwitchGrowth,
+            Action::ToggleSleep,
+            Action::Medications,
             Action::LoadCSV,
+            Action::ExportCSV,
+            Action::Undo,
             Action::Quit,
         ];
         ACTIONS.iter()
@@ -221,9 +1357,35 @@ impl Action {
             Action::DeleteEvent => &[Key::Char('d')],
             Action::NextEvent => &[Key::Down],
             Action::PreviousEvent => &[Key::Up],
+            Action::PreviousDay => &[Key::Left, Key::Char('h')],
+            Action::NextDay => &[Key::Right, Key::Char('l')],
+            Action::PreviousPeriod => &[Key::Char('[')],
+            Action::NextPeriod => &[Key::Char(']')],
+            Action::Today => &[Key::Char('t')],
             Action::SwitchFilter => &[Key::Char('f')],
-            Action::LoadCSV => &[Key::Char('l')],
+            Action::FilterRange => &[Key::Char('r')],
+            Action::Search => &[Key::Char('/')],
+            Action::SwitchChartMetric => &[Key::Char('c')],
+            Action::SwitchChartView => &[Key::Char('v')],
+            Action::SwitchUnit => &[Key::Char('o')],
+            Action::SwitchChild => &[Key::Char('b')],
+            Action::AddChild => &[Key::Char('B')],
+            Action::EditChild => &[Key::Char('e')],
+            Action::SwitchCaregiver => &[Key::Char('w')],
+            Action::AddCaregiver => &[Key::Char('W')],
+            Action::FilterCaregiver => &[Key::Char('F')],
+            Action::AddMeasurement => &[Key::Char('m')],
+            Action::SwitchGrowth => &[Key::Char('g')],
+            Action::ToggleSleep => &[Key::Char('s')],
+            Action::Medications => &[Key::Char('M')],
+            Action::LoadCSV => &[Key::Char('i')],
+            Action::ExportCSV => &[Key::Char('x')],
             Action::UpdateEvent => &[Key::Char('u')],
+            Action::EditDiaper => &[Key::Char('p')],
+            Action::History => &[Key::Char('H')],
+            Action::Trash => &[Key::Char('T')],
+            Action::EventTypes => &[Key::Char('E')],
+            Action::Undo => &[Key::Ctrl('z')],
             Action::Quit => &[Key::Char('q'), Key::Ctrl('c')],
         }
     }
@@ -237,9 +1399,35 @@ impl Display for Action {
             Action::DeleteEvent => "delete",
             Action::NextEvent => "next",
             Action::PreviousEvent => "prev",
+            Action::PreviousDay => "prev day",
+            Action::NextDay => "next day",
+            Action::PreviousPeriod => "prev period",
+            Action::NextPeriod => "next period",
+            Action::Today => "today",
             Action::SwitchFilter => "switch filter",
+            Action::FilterRange => "range",
+            Action::Search => "search",
+            Action::SwitchChartMetric => "chart",
+            Action::SwitchChartView => "chart view",
+            Action::SwitchUnit => "units",
+            Action::SwitchChild => "child",
+            Action::AddChild => "add child",
+            Action::EditChild => "edit child",
+            Action::SwitchCaregiver => "caregiver",
+            Action::AddCaregiver => "add caregiver",
+            Action::FilterCaregiver => "caregiver filter",
+            Action::AddMeasurement => "measure",
+            Action::SwitchGrowth => "growth",
+            Action::ToggleSleep => "sleep",
+            Action::Medications => "meds",
             Action::LoadCSV => "load csv",
+            Action::ExportCSV => "export csv",
             Action::UpdateEvent => "update",
+            Action::EditDiaper => "diaper",
+            Action::History => "history",
+            Action::Trash => "trash",
+            Action::EventTypes => "event types",
+            Action::Undo => "undo",
             Action::Quit => "quit",
         };
         write!(f, "{}", str)
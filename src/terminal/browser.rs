@@ -0,0 +1,275 @@
+use crate::{models::NewBabyEvent, preview_csv, terminal::events::Key};
+use ratatui::widgets::ListState;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The number of rows parsed when previewing a CSV file.
+const PREVIEW_ROWS: usize = 5;
+
+/// Enum representing the outcome of a key press on the file browser.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BrowserAction {
+    /// The user is still browsing.
+    Continue,
+    /// The user asked to close the browser without importing.
+    Cancel,
+    /// The user confirmed importing the previewed file.
+    Import(PathBuf),
+}
+
+/// A preview of the first rows of a CSV file.
+#[derive(Debug)]
+pub struct CsvPreview {
+    /// The path of the previewed file.
+    pub path: PathBuf,
+    /// The parsed rows, or the error encountered while parsing them.
+    pub rows: Result<Vec<NewBabyEvent>, String>,
+}
+
+/// A popup for picking a CSV file to import.
+///
+/// Lists the sub-directories and CSV files of a directory. Selecting a file shows a preview of its first rows which has
+/// to be confirmed before the file is imported.
+#[derive(Debug)]
+pub struct FileBrowser {
+    /// The directory being listed.
+    dir: PathBuf,
+    /// The entries of the directory, starting with the parent directory if there is one.
+    entries: Vec<PathBuf>,
+    /// The current selection in the entry list.
+    selection: ListState,
+    /// The preview of the selected CSV file, if any.
+    preview: Option<CsvPreview>,
+}
+
+impl FileBrowser {
+    /// Constructs a new `FileBrowser` listing `dir`.
+    ///
+    /// # Parameters
+    ///
+    /// * `dir`: The directory to list.
+    ///
+    /// # Returns
+    ///
+    /// A new `FileBrowser` instance, or an error if the directory cannot be read.
+    pub fn new(dir: PathBuf) -> io::Result<Self> {
+        let entries = read_entries(&dir)?;
+        let mut selection = ListState::default();
+        selection.select(Some(0));
+
+        Ok(Self {
+            dir,
+            entries,
+            selection,
+            preview: None,
+        })
+    }
+
+    /// Returns the directory being listed.
+    ///
+    /// # Returns
+    ///
+    /// The path of the listed directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the entries of the listed directory.
+    ///
+    /// # Returns
+    ///
+    /// A slice of paths, starting with the parent directory if there is one.
+    pub fn entries(&self) -> &[PathBuf] {
+        self.entries.as_slice()
+    }
+
+    /// Returns the selection in the entry list.
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the `ListState` of the entry list.
+    pub fn selection(&mut self) -> &mut ListState {
+        &mut self.selection
+    }
+
+    /// Returns the preview of the selected CSV file, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&CsvPreview)` if a file is being previewed.
+    /// - `None` while browsing.
+    pub fn preview(&self) -> Option<&CsvPreview> {
+        self.preview.as_ref()
+    }
+
+    /// Handles a key press on the file browser.
+    ///
+    /// While browsing, `Up`/`Down` move the selection, `Enter` opens a directory or previews a CSV file, `Backspace`
+    /// goes to the parent directory and `Esc` cancels. While previewing, `Enter`/`y` imports the file and `Esc`/`n`
+    /// goes back to browsing.
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: The key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// A [`BrowserAction`] telling the caller what to do next.
+    pub fn handle_key(&mut self, key: Key) -> BrowserAction {
+        if let Some(preview) = &self.preview {
+            match key {
+                Key::Enter | Key::Char('y') if preview.rows.is_ok() => {
+                    return BrowserAction::Import(preview.path.clone())
+                }
+                Key::Esc | Key::Char('n') => self.preview = None,
+                _ => {}
+            }
+
+            return BrowserAction::Continue;
+        }
+
+        match key {
+            Key::Esc => return BrowserAction::Cancel,
+            Key::Down => self.move_selection(1),
+            Key::Up => self.move_selection(self.entries.len().saturating_sub(1)),
+            Key::Backspace | Key::Left => {
+                if let Some(parent) = self.dir.parent().map(Path::to_path_buf) {
+                    self.open_dir(parent);
+                }
+            }
+            Key::Enter => {
+                if let Some(path) = self.selection.selected().map(|i| self.entries[i].clone()) {
+                    if path.is_dir() {
+                        self.open_dir(path);
+                    } else {
+                        let rows = preview_csv(&path.to_string_lossy(), PREVIEW_ROWS)
+                            .map_err(|e| e.to_string());
+                        self.preview = Some(CsvPreview { path, rows });
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        BrowserAction::Continue
+    }
+
+    /// Moves the selection forward by `step` entries, wrapping around the end of the list.
+    fn move_selection(&mut self, step: usize) {
+        if self.entries.is_empty() {
+            self.selection.select(None);
+        } else {
+            let i = self.selection.selected().unwrap_or(0);
+            self.selection.select(Some((i + step) % self.entries.len()));
+        }
+    }
+
+    /// Lists `dir`, keeping the current directory if it cannot be read.
+    fn open_dir(&mut self, dir: PathBuf) {
+        if let Ok(entries) = read_entries(&dir) {
+            self.dir = dir;
+            self.entries = entries;
+            self.selection = ListState::default();
+            self.selection.select(Some(0));
+        }
+    }
+}
+
+/// Reads the sub-directories and CSV files of `dir`, sorted by name, preceded by the parent directory.
+fn read_entries(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut dirs = vec![];
+    let mut files = vec![];
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            dirs.push(path);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"))
+        {
+            files.push(path);
+        }
+    }
+
+    dirs.sort();
+    files.sort();
+
+    Ok(dir
+        .parent()
+        .map(Path::to_path_buf)
+        .into_iter()
+        .chain(dirs)
+        .chain(files)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("sample")
+    }
+
+    #[test]
+    fn test_new_lists_csv_files() {
+        let browser = FileBrowser::new(sample_dir()).unwrap();
+
+        // the parent directory comes first
+        assert_eq!(
+            browser.entries()[0],
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        );
+        assert!(browser
+            .entries()
+            .contains(&sample_dir().join("example.csv")));
+        assert!(browser.preview().is_none());
+    }
+
+    #[test]
+    fn test_handle_key_preview_and_import() {
+        let mut browser = FileBrowser::new(sample_dir()).unwrap();
+        let csv = sample_dir().join("example.csv");
+
+        let position = browser.entries().iter().position(|e| e == &csv).unwrap();
+        for _ in 0..position {
+            browser.handle_key(Key::Down);
+        }
+
+        // selecting the file previews it
+        assert_eq!(browser.handle_key(Key::Enter), BrowserAction::Continue);
+        let preview = browser.preview().unwrap();
+        assert_eq!(preview.path, csv);
+        assert_eq!(preview.rows.as_ref().unwrap().len(), PREVIEW_ROWS);
+
+        // backing out of the preview returns to browsing
+        browser.handle_key(Key::Esc);
+        assert!(browser.preview().is_none());
+
+        // confirming the preview imports the file
+        browser.handle_key(Key::Enter);
+        assert_eq!(browser.handle_key(Key::Enter), BrowserAction::Import(csv));
+    }
+
+    #[test]
+    fn test_handle_key_navigation() {
+        let mut browser = FileBrowser::new(sample_dir()).unwrap();
+
+        // opening the parent directory
+        browser.handle_key(Key::Enter);
+        assert_eq!(browser.dir(), Path::new(env!("CARGO_MANIFEST_DIR")));
+
+        // going back up again
+        browser.handle_key(Key::Backspace);
+        assert_eq!(
+            browser.dir(),
+            Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap()
+        );
+
+        assert_eq!(browser.handle_key(Key::Esc), BrowserAction::Cancel);
+    }
+}
@@ -0,0 +1,605 @@
+use crate::{
+    calculate_daily_average_time_between_feedings, calculate_daily_poopy_diapers,
+    calculate_daily_pumped, calculate_daily_volume, calculate_daily_wet_diapers,
+    calculate_hourly_average_time_between_feedings, calculate_hourly_poopy_diapers,
+    calculate_hourly_pumped, calculate_hourly_volume, calculate_hourly_wet_diapers,
+    growth::{self, Measure, MAX_AGE_MONTHS, PERCENTILE_CURVES},
+    models::{BabyEvent, Child, Measurement},
+    terminal::state::Filter,
+    units::VolumeUnit,
+};
+use chrono::{Duration, NaiveDateTime, TimeZone, Timelike};
+use std::fmt::{self, Display};
+
+/// Represents the metric displayed in the chart.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ChartMetric {
+    #[default]
+    Intake,
+    Pumped,
+    WetDiapers,
+    Stools,
+    FeedingInterval,
+}
+
+impl ChartMetric {
+    /// Switches to the next metric in the sequence.
+    ///
+    /// # Returns
+    ///
+    /// The next metric in the sequence, wrapping around to `Intake`.
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Intake => Self::Pumped,
+            Self::Pumped => Self::WetDiapers,
+            Self::WetDiapers => Self::Stools,
+            Self::Stools => Self::FeedingInterval,
+            Self::FeedingInterval => Self::Intake,
+        }
+    }
+
+    /// Returns the title of the metric, naming the unit of volumes.
+    ///
+    /// # Parameters
+    ///
+    /// * `unit`: The unit volumes are charted in.
+    ///
+    /// # Returns
+    ///
+    /// The title, e.g. `"Intake (fl oz)"` or `"Wet diapers"`.
+    pub fn title(&self, unit: VolumeUnit) -> String {
+        match self {
+            Self::Intake | Self::Pumped => format!("{} ({})", self, unit),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Aggregates events into per-period values for the metric.
+    ///
+    /// Filters covering up to a day are aggregated per hour, longer filters per day.
+    ///
+    /// # Parameters
+    ///
+    /// * `filter`: The filter deciding the aggregation period.
+    /// * `events`: The events to aggregate.
+    /// * `tz`: The time zone whose hours and days the events are grouped into.
+    ///
+    /// # Returns
+    ///
+    /// A vector of tuples containing the local start of each period and its value.
+    fn aggregate<Tz: TimeZone>(
+        &self,
+        filter: &Filter,
+        events: Vec<BabyEvent>,
+        tz: &Tz,
+    ) -> Vec<(NaiveDateTime, i32)> {
+        if is_hourly(filter) {
+            return match self {
+                Self::Intake => calculate_hourly_volume(events, tz),
+                Self::Pumped => calculate_hourly_pumped(events, tz),
+                Self::WetDiapers => calculate_hourly_wet_diapers(events, tz),
+                Self::Stools => calculate_hourly_poopy_diapers(events, tz),
+                Self::FeedingInterval => calculate_hourly_average_time_between_feedings(events, tz),
+            };
+        }
+
+        let daily = match self {
+            Self::Intake => calculate_daily_volume(events, tz),
+            Self::Pumped => calculate_daily_pumped(events, tz),
+            Self::WetDiapers => calculate_daily_wet_diapers(events, tz),
+            Self::Stools => calculate_daily_poopy_diapers(events, tz),
+            Self::FeedingInterval => calculate_daily_average_time_between_feedings(events, tz),
+        };
+
+        daily
+            .into_iter()
+            .map(|(date, value)| (date.and_hms_opt(0, 0, 0).unwrap(), value))
+            .collect()
+    }
+}
+
+impl Display for ChartMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Intake => write!(f, "Intake"),
+            Self::Pumped => write!(f, "Pumped"),
+            Self::WetDiapers => write!(f, "Wet diapers"),
+            Self::Stools => write!(f, "Stools"),
+            Self::FeedingInterval => write!(f, "Feeding interval (min)"),
+        }
+    }
+}
+
+/// Represents how the chart is drawn.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ChartView {
+    #[default]
+    Bar,
+    /// A line chart, overlaying breastmilk and formula for the intake metric.
+    Line,
+}
+
+impl ChartView {
+    /// Switches between the bar and line views.
+    ///
+    /// # Returns
+    ///
+    /// The other view.
+    pub fn switch(&self) -> Self {
+        match self {
+            Self::Bar => Self::Line,
+            Self::Line => Self::Bar,
+        }
+    }
+}
+
+/// The labelled values plotted by the chart.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ChartData {
+    /// One label per period: the hour for hourly periods, the weekday for up to a week of days and the day of the
+    /// month for longer filters.
+    pub labels: Vec<String>,
+    /// The named series, each holding one value per label.
+    pub series: Vec<(String, Vec<u64>)>,
+}
+
+impl ChartData {
+    /// Computes the chart data for the filtered events.
+    ///
+    /// # Parameters
+    ///
+    /// * `filter`: The active filter, deciding the periods.
+    /// * `events`: The filtered events.
+    /// * `metric`: The metric to chart.
+    /// * `view`: The chart view; the line view splits intake into breastmilk and formula.
+    /// * `unit`: The unit volumes are charted in, rounded to whole units once every period has been added up.
+    /// * `tz`: The time zone the periods are read in.
+    ///
+    /// # Returns
+    ///
+    /// A new `ChartData` instance with a value for every period, zero where there are no events.
+    pub fn new<Tz: TimeZone>(
+        filter: &Filter,
+        events: &[BabyEvent],
+        metric: ChartMetric,
+        view: ChartView,
+        unit: VolumeUnit,
+        tz: &Tz,
+    ) -> Self {
+        let periods = periods(filter);
+        let label_format = match periods.len() {
+            _ if is_hourly(filter) => "%H",
+            0..=7 => "%a",
+            _ => "%d",
+        };
+        let labels = periods
+            .iter()
+            .map(|period| period.format(label_format).to_string())
+            .collect();
+
+        let values = |events: Vec<BabyEvent>| {
+            let aggregated = metric.aggregate(filter, events, tz);
+
+            periods
+                .iter()
+                .map(|period| {
+                    aggregated.iter().find(|(p, _)| p == period).map_or(
+                        0,
+                        |(_, value)| match metric {
+                            ChartMetric::Intake | ChartMetric::Pumped => {
+                                unit.from_ml((*value).max(0)).round() as u64
+                            }
+                            _ => (*value).max(0) as u64,
+                        },
+                    )
+                })
+                .collect::<Vec<u64>>()
+        };
+
+        let series = match (metric, view) {
+            (ChartMetric::Intake, ChartView::Line) => {
+                let breastmilk = events
+                    .iter()
+                    .map(|e| BabyEvent { formula: 0, ..*e })
+                    .collect();
+                let formula = events
+                    .iter()
+                    .map(|e| BabyEvent {
+                        breastmilk: 0,
+                        ..*e
+                    })
+                    .collect();
+
+                vec![
+                    ("Breastmilk".to_owned(), values(breastmilk)),
+                    ("Formula".to_owned(), values(formula)),
+                ]
+            }
+            _ => vec![(metric.title(unit), values(events.to_vec()))],
+        };
+
+        Self { labels, series }
+    }
+
+    /// Returns the largest value of any series.
+    ///
+    /// # Returns
+    ///
+    /// The maximum value, 0 if there is no data.
+    pub fn max(&self) -> u64 {
+        self.series
+            .iter()
+            .flat_map(|(_, values)| values.iter())
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// The growth measurements of a child plotted against the WHO percentile curves, by age in months.
+#[derive(Debug, PartialEq)]
+pub struct GrowthChart {
+    /// The plotted measure.
+    pub measure: Measure,
+    /// The named percentile curves, sampled every week or so.
+    pub curves: Vec<(String, Vec<(f64, f64)>)>,
+    /// The measurements taken within the WHO tables, as age and value.
+    pub points: Vec<(f64, f64)>,
+    /// The age range shown, at least the first six months.
+    pub x_bounds: [f64; 2],
+    /// The value range shown, covering the curves and the points.
+    pub y_bounds: [f64; 2],
+    /// The value, z-score and percentile of the latest measurement, if any.
+    pub latest: Option<(f64, f64, f64)>,
+}
+
+impl GrowthChart {
+    /// The step in months between two samples of the percentile curves.
+    const STEP: f64 = 0.25;
+
+    /// Computes the growth chart of a child.
+    ///
+    /// # Parameters
+    ///
+    /// * `child`: The measured child.
+    /// * `measurements`: The measurements of the child, oldest first.
+    /// * `measure`: The measure to plot.
+    ///
+    /// # Returns
+    ///
+    /// A new `GrowthChart` instance, or `None` if the birth date or sex of the child is unknown.
+    pub fn new(child: &Child, measurements: &[Measurement], measure: Measure) -> Option<Self> {
+        let birth_date = child.birth_date?;
+        let sex = child.sex?;
+
+        let points = measurements
+            .iter()
+            .filter_map(|m| Some((growth::age_in_months(birth_date, m.dt), measure.value(m)?)))
+            .filter(|(age, _)| (0.0..=MAX_AGE_MONTHS).contains(age))
+            .collect::<Vec<(f64, f64)>>();
+        let latest = points.last().and_then(|(age, value)| {
+            let z = growth::z_score(measure, sex, *age, *value)?;
+            Some((*value, z, growth::percentile(z)))
+        });
+
+        let oldest = points.last().map_or(0.0, |(age, _)| *age);
+        let x_max = (oldest.ceil() + 2.0).clamp(6.0, MAX_AGE_MONTHS);
+        let ages = (0..=(x_max / Self::STEP) as usize).map(|i| i as f64 * Self::STEP);
+
+        let curves = PERCENTILE_CURVES
+            .iter()
+            .map(|(percentile, z)| {
+                let curve = ages
+                    .clone()
+                    .filter_map(|age| Some((age, growth::lms(measure, sex, age)?.value(*z))))
+                    .collect();
+                (format!("P{}", percentile), curve)
+            })
+            .collect::<Vec<(String, Vec<(f64, f64)>)>>();
+
+        let values = curves
+            .iter()
+            .flat_map(|(_, curve)| curve.iter())
+            .chain(points.iter())
+            .map(|(_, value)| *value);
+        let y_min = values.clone().fold(f64::INFINITY, f64::min).floor();
+        let y_max = values.fold(f64::NEG_INFINITY, f64::max).ceil();
+
+        Some(Self {
+            measure,
+            curves,
+            points,
+            x_bounds: [0.0, x_max],
+            y_bounds: [y_min, y_max],
+            latest,
+        })
+    }
+
+    /// Returns the title of the chart, describing the latest measurement.
+    ///
+    /// # Returns
+    ///
+    /// The measure, followed by the value, z-score and percentile of the latest measurement if there is one.
+    pub fn title(&self) -> String {
+        match self.latest {
+            Some((value, z, percentile)) => format!(
+                " {} for age: {:.1} {}, z {:+.2}, P{:.0} ",
+                self.measure,
+                value,
+                self.measure.unit(),
+                z,
+                percentile
+            ),
+            None => format!(" {} for age ({}) ", self.measure, self.measure.unit()),
+        }
+    }
+}
+
+/// Checks whether the filter covers at most a day and is therefore charted per hour.
+fn is_hourly(filter: &Filter) -> bool {
+    let (start, end) = filter.bounds();
+
+    end - start <= Duration::days(1)
+}
+
+/// Returns the start of every period charted for the filter: each hour for filters covering up to a day, each day
+/// otherwise.
+fn periods(filter: &Filter) -> Vec<NaiveDateTime> {
+    let (start, end) = filter.bounds();
+
+    let (first, step) = if is_hourly(filter) {
+        let hour = start.date().and_hms_opt(start.hour(), 0, 0).unwrap();
+        (hour, Duration::hours(1))
+    } else {
+        (
+            start.date().and_hms_opt(0, 0, 0).unwrap(),
+            Duration::days(1),
+        )
+    };
+
+    std::iter::successors(Some(first), |period| Some(*period + step))
+        .take_while(|period| period < &end)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, Utc};
+
+    fn event(dt: NaiveDateTime, breastmilk: i32, formula: i32) -> BabyEvent {
+        BabyEvent {
+            id: 0,
+            child_id: 1,
+            dt,
+            urine: true,
+            stool: false,
+            skin2skin: 0,
+            breastfeed: 0,
+            breastmilk,
+            formula,
+            breastfeed_left: 0,
+            breastfeed_right: 0,
+            pump_left: 0,
+            pump_right: 0,
+            pump_duration: 0,
+            pump: 0,
+            caregiver_id: None,
+            utc_offset: 0,
+            deleted_at: None,
+            event_type_id: None,
+        }
+    }
+
+    fn child() -> Child {
+        Child {
+            id: 1,
+            name: "Ada".to_owned(),
+            birth_date: NaiveDate::from_ymd_opt(2023, 6, 1),
+            sex: Some(crate::models::Sex::Male),
+            birth_weight: None,
+        }
+    }
+
+    fn measurement(date: NaiveDate, weight: i32) -> Measurement {
+        Measurement {
+            id: 0,
+            child_id: 1,
+            dt: date.and_hms_opt(9, 0, 0).unwrap(),
+            weight: Some(weight),
+            length: None,
+            head_circumference: None,
+        }
+    }
+
+    #[test]
+    fn test_metric_next() {
+        let mut metric = ChartMetric::default();
+
+        for _ in 0..5 {
+            metric = metric.next();
+        }
+
+        assert_eq!(metric, ChartMetric::Intake);
+        assert_eq!(ChartMetric::Intake.next(), ChartMetric::Pumped);
+    }
+
+    #[test]
+    fn test_view_switch() {
+        assert_eq!(ChartView::Bar.switch(), ChartView::Line);
+        assert_eq!(ChartView::Line.switch(), ChartView::Bar);
+    }
+
+    #[test]
+    fn test_chart_data_day() {
+        let date = NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+        let events = vec![
+            event(date.and_hms_opt(5, 15, 0).unwrap(), 60, 0),
+            event(date.and_hms_opt(5, 45, 0).unwrap(), 0, 30),
+            event(date.and_hms_opt(9, 0, 0).unwrap(), 90, 0),
+        ];
+
+        let data = ChartData::new(
+            &Filter::Day(date),
+            &events,
+            ChartMetric::Intake,
+            ChartView::Bar,
+            VolumeUnit::Millilitre,
+            &Utc,
+        );
+
+        assert_eq!(data.labels.len(), 24);
+        assert_eq!(data.labels[5], "05");
+        assert_eq!(data.series.len(), 1);
+        assert_eq!(data.series[0].1[5], 90);
+        assert_eq!(data.series[0].1[9], 90);
+        assert_eq!(data.series[0].1[10], 0);
+
+        // the line view splits intake into breastmilk and formula
+        let data = ChartData::new(
+            &Filter::Day(date),
+            &events,
+            ChartMetric::Intake,
+            ChartView::Line,
+            VolumeUnit::Millilitre,
+            &Utc,
+        );
+
+        assert_eq!(data.series.len(), 2);
+        assert_eq!(data.series[0].1[5], 60);
+        assert_eq!(data.series[1].1[5], 30);
+        assert_eq!(data.max(), 90);
+
+        // volumes are converted once the hour has been added up
+        let data = ChartData::new(
+            &Filter::Day(date),
+            &events,
+            ChartMetric::Intake,
+            ChartView::Bar,
+            VolumeUnit::UsFluidOunce,
+            &Utc,
+        );
+
+        assert_eq!(data.series[0].0, "Intake (fl oz)");
+        assert_eq!(data.series[0].1[5], 3);
+        assert_eq!(data.series[0].1[9], 3);
+    }
+
+    #[test]
+    fn test_chart_data_week_and_month() {
+        // a Thursday
+        let date = NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+        let events = vec![
+            event(date.and_hms_opt(5, 15, 0).unwrap(), 60, 0),
+            event(date.and_hms_opt(9, 0, 0).unwrap(), 90, 0),
+        ];
+
+        let data = ChartData::new(
+            &Filter::Week(date),
+            &events,
+            ChartMetric::WetDiapers,
+            ChartView::Bar,
+            VolumeUnit::Millilitre,
+            &Utc,
+        );
+
+        assert_eq!(
+            data.labels,
+            ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+        );
+        assert_eq!(data.series[0].1, [0, 0, 0, 2, 0, 0, 0]);
+
+        let data = ChartData::new(
+            &Filter::Month(date),
+            &events,
+            ChartMetric::FeedingInterval,
+            ChartView::Bar,
+            VolumeUnit::Millilitre,
+            &Utc,
+        );
+
+        assert_eq!(data.labels.len(), 30);
+        assert_eq!(data.labels[14], "15");
+        assert_eq!(data.series[0].1[14], 225);
+    }
+
+    #[test]
+    fn test_chart_data_range_and_rolling() {
+        let date = NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+        let events = vec![
+            event(date.and_hms_opt(5, 15, 0).unwrap(), 60, 0),
+            event(date.and_hms_opt(9, 0, 0).unwrap(), 90, 0),
+        ];
+
+        // a range of more than a week is charted per day of the month
+        let data = ChartData::new(
+            &Filter::Range(date - Duration::days(9), date),
+            &events,
+            ChartMetric::Intake,
+            ChartView::Bar,
+            VolumeUnit::Millilitre,
+            &Utc,
+        );
+
+        assert_eq!(data.labels.len(), 10);
+        assert_eq!(data.labels[9], "15");
+        assert_eq!(data.series[0].1[9], 150);
+
+        // the last 24 hours are charted per hour, starting with the hour the window starts in
+        let end = date.and_hms_opt(10, 30, 0).unwrap();
+        let data = ChartData::new(
+            &Filter::Rolling(end, Duration::hours(24)),
+            &events,
+            ChartMetric::Intake,
+            ChartView::Bar,
+            VolumeUnit::Millilitre,
+            &Utc,
+        );
+
+        assert_eq!(data.labels.len(), 25);
+        assert_eq!(data.labels[0], "10");
+        assert_eq!(data.series[0].1[19], 60);
+        assert_eq!(data.series[0].1[23], 90);
+    }
+
+    #[test]
+    fn test_growth_chart() {
+        let measurements = vec![
+            measurement(NaiveDate::from_ymd_opt(2023, 6, 1).unwrap(), 3300),
+            // the median weight of a boy at 12 months, 366 days later
+            measurement(NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(), 9648),
+        ];
+
+        let chart = GrowthChart::new(&child(), &measurements, Measure::Weight).unwrap();
+
+        assert_eq!(chart.points.len(), 2);
+        assert_eq!(chart.points[0], (0.0, 3.3));
+        assert_eq!(chart.x_bounds, [0.0, 15.0]);
+        assert_eq!(chart.curves.len(), 3);
+        assert_eq!(chart.curves[1].0, "P50");
+        assert_eq!(chart.curves[1].1.len(), 61);
+        assert!(chart.y_bounds[0] <= 2.0 && chart.y_bounds[1] >= 12.0);
+
+        let (value, z, percentile) = chart.latest.unwrap();
+        assert_eq!(value, 9.648);
+        assert!(z.abs() < 0.1, "{}", z);
+        assert!((percentile - 50.0).abs() < 4.0, "{}", percentile);
+
+        // measures that were not taken are not plotted
+        let chart = GrowthChart::new(&child(), &measurements, Measure::Length).unwrap();
+        assert!(chart.points.is_empty());
+        assert_eq!(chart.latest, None);
+        assert_eq!(chart.x_bounds, [0.0, 6.0]);
+        assert_eq!(chart.title(), " Length for age (cm) ");
+
+        // the birth date and sex are needed
+        let unknown = Child {
+            sex: None,
+            ..child()
+        };
+        assert_eq!(
+            GrowthChart::new(&unknown, &measurements, Measure::Weight),
+            None
+        );
+    }
+}
@@ -0,0 +1,2435 @@
+use crate::{
+    create_event,
+    models::{
+        Amount, BabyEvent, Child, DiaperDetails, EventField, EventType, FieldKind, NewBabyEvent,
+        NewCaregiver, NewChild, NewEventField, NewEventType, NewMeasurement, NewMedication,
+        NewMedicationSchedule, Sex, StoolColor, StoolConsistency,
+    },
+    terminal::{events::Key, state::Filter},
+    units::VolumeUnit,
+    zone, BabyrsError, DEFAULT_CHILD_ID,
+};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+/// The format used to display and parse the event timestamp, in the local time zone.
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Alternative timestamp format accepted when the seconds are omitted.
+const TIMESTAMP_FORMAT_SHORT: &str = "%Y-%m-%d %H:%M";
+
+/// Represents the value held by a single form field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValue {
+    /// A free-form timestamp, parsed on submission.
+    Timestamp(String),
+    /// Free-form text.
+    Text(String),
+    /// A boolean toggle.
+    Toggle(bool),
+    /// A non-negative whole number, parsed on submission.
+    Number(String),
+    /// A non-negative number with a `.` or `,` as decimal separator, parsed on submission.
+    Decimal(String),
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timestamp(text) | Self::Text(text) | Self::Number(text) | Self::Decimal(text) => {
+                write!(f, "{}", text)
+            }
+            Self::Toggle(true) => write!(f, "[x]"),
+            Self::Toggle(false) => write!(f, "[ ]"),
+        }
+    }
+}
+
+/// A labelled field of the event form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormField {
+    /// The label displayed next to the value.
+    pub label: String,
+    /// The current value of the field.
+    pub value: FieldValue,
+}
+
+impl FormField {
+    /// Creates a new `FormField`.
+    ///
+    /// # Parameters
+    ///
+    /// * `label`: The label displayed next to the value.
+    /// * `value`: The initial value of the field.
+    ///
+    /// # Returns
+    ///
+    /// A new `FormField` instance.
+    fn new(label: impl Into<String>, value: FieldValue) -> Self {
+        Self {
+            label: label.into(),
+            value,
+        }
+    }
+}
+
+/// Enum representing the outcome of a key press on the form.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FormAction {
+    /// The form is still being edited.
+    Continue,
+    /// The user asked to save the form.
+    Submit,
+    /// The user asked to close the form without saving.
+    Cancel,
+}
+
+/// A modal form holding one field for every `NewBabyEvent` column.
+///
+/// The same form is used for adding new events and editing existing ones; an edit form carries the id of the event
+/// being edited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventForm {
+    /// The id of the event being edited, `None` for a new event.
+    id: Option<i32>,
+    /// The child the event belongs to.
+    child_id: i32,
+    /// The caregiver who recorded the event, kept unchanged when editing.
+    caregiver_id: Option<i32>,
+    /// The unit the volumes are entered in.
+    unit: VolumeUnit,
+    /// The fields of the form, in display order.
+    fields: Vec<FormField>,
+    /// The index of the field that currently has focus.
+    focus: usize,
+    /// The validation error from the last submission, if any.
+    error: Option<String>,
+}
+
+impl EventForm {
+    /// Constructs a new, empty `EventForm` with the timestamp set to `now`.
+    ///
+    /// # Parameters
+    ///
+    /// * `now`: The timestamp the form should default to, in the local time zone.
+    /// * `unit`: The unit the volumes are entered in.
+    ///
+    /// # Returns
+    ///
+    /// A new `EventForm` instance.
+    pub fn new(now: NaiveDateTime, unit: VolumeUnit) -> Self {
+        let [breastmilk, formula, pump, pump_left, pump_right] = volume_labels(unit);
+        let volume = || match unit {
+            VolumeUnit::Millilitre => FieldValue::Number("0".to_owned()),
+            _ => FieldValue::Decimal("0".to_owned()),
+        };
+
+        let fields = vec![
+            FormField::new(
+                "Timestamp",
+                FieldValue::Timestamp(now.format(TIMESTAMP_FORMAT).to_string()),
+            ),
+            FormField::new("Urine", FieldValue::Toggle(false)),
+            FormField::new("Stool", FieldValue::Toggle(false)),
+            FormField::new("Skin-to-Skin(min)", FieldValue::Number("0".to_owned())),
+            FormField::new("Breastfeed(min)", FieldValue::Number("0".to_owned())),
+            FormField::new("Breastfeed L(min)", FieldValue::Number("0".to_owned())),
+            FormField::new("Breastfeed R(min)", FieldValue::Number("0".to_owned())),
+            FormField::new(breastmilk, volume()),
+            FormField::new(formula, volume()),
+            FormField::new(pump, volume()),
+            FormField::new(pump_left, volume()),
+            FormField::new(pump_right, volume()),
+            FormField::new("Pump(min)", FieldValue::Number("0".to_owned())),
+            FormField::new("Notes", FieldValue::Text(String::new())),
+        ];
+
+        Self {
+            id: None,
+            child_id: DEFAULT_CHILD_ID,
+            caregiver_id: None,
+            unit,
+            fields,
+            focus: 0,
+            error: None,
+        }
+    }
+
+    /// Constructs an `EventForm` pre-populated from an existing event and its note, for editing.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The event to edit.
+    /// * `note`: The note of the event, if any.
+    /// * `unit`: The unit the volumes are entered in.
+    ///
+    /// # Returns
+    ///
+    /// A new `EventForm` instance carrying the id of `event`.
+    pub fn from_event(event: &BabyEvent, note: Option<&str>, unit: VolumeUnit) -> Self {
+        let mut form = Self::new(zone::to_local(&Local, event.dt), unit);
+        let volume = |ml: i32| match unit {
+            VolumeUnit::Millilitre => FieldValue::Number(ml.to_string()),
+            _ => FieldValue::Decimal(unit.exact_value(ml).to_string()),
+        };
+
+        form.id = Some(event.id);
+        form.child_id = event.child_id;
+        form.caregiver_id = event.caregiver_id;
+        form.fields[1].value = FieldValue::Toggle(event.urine);
+        form.fields[2].value = FieldValue::Toggle(event.stool);
+        form.fields[3].value = FieldValue::Number(event.skin2skin.to_string());
+        form.fields[4].value = FieldValue::Number(event.breastfeed.to_string());
+        form.fields[5].value = FieldValue::Number(event.breastfeed_left.to_string());
+        form.fields[6].value = FieldValue::Number(event.breastfeed_right.to_string());
+        form.fields[7].value = volume(event.breastmilk);
+        form.fields[8].value = volume(event.formula);
+        form.fields[9].value = volume(event.pump);
+        form.fields[10].value = volume(event.pump_left);
+        form.fields[11].value = volume(event.pump_right);
+        form.fields[12].value = FieldValue::Number(event.pump_duration.to_string());
+        form.fields[13].value = FieldValue::Text(note.unwrap_or_default().to_owned());
+
+        form
+    }
+
+    /// Returns the id of the event being edited.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(i32)` if the form edits an existing event.
+    /// - `None` if the form creates a new event.
+    pub fn id(&self) -> Option<i32> {
+        self.id
+    }
+
+    /// Returns the fields of the form.
+    ///
+    /// # Returns
+    ///
+    /// A slice of [`FormField`].
+    pub fn fields(&self) -> &[FormField] {
+        self.fields.as_slice()
+    }
+
+    /// Returns the index of the focused field.
+    ///
+    /// # Returns
+    ///
+    /// The index of the focused field.
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    /// Returns the validation error from the last submission, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&str)` containing the error message.
+    /// - `None` if the form is valid or has not been submitted yet.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Sets the error message displayed by the form.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error message to display.
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Handles a key press on the form.
+    ///
+    /// `Tab`/`Down` and `Up` move the focus, `Space` flips toggles, printable characters and `Backspace` edit text
+    /// fields, `Enter` submits and `Esc` cancels.
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: The key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// A [`FormAction`] telling the caller what to do next.
+    pub fn handle_key(&mut self, key: Key) -> FormAction {
+        handle_field_key(&mut self.fields, &mut self.focus, key)
+    }
+
+    /// Validates the form and converts it into a `NewBabyEvent`.
+    ///
+    /// A breastfeed or pump total left at 0 is the sum of its sides. Volumes are converted into whole millilitres, a
+    /// pump total made up of its sides as the sum of the rounded sides, see [`VolumeUnit::total_to_ml`].
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(NewBabyEvent)` if every field holds a valid value.
+    /// - `Err(BabyrsError::Validation)` describing the first invalid field otherwise.
+    pub fn to_new_event(&self) -> Result<NewBabyEvent, BabyrsError> {
+        let dt = parse_timestamp(&self.fields[0])?;
+
+        let mut new_event = create_event(
+            Some(self.toggle(1)),
+            Some(self.toggle(2)),
+            Some(self.number(3)?),
+            Some(self.number(4)?),
+            Some(self.volume(7)?),
+            Some(self.volume(8)?),
+            Some(self.volume(9)?),
+        );
+        new_event.child_id = self.child_id;
+        new_event.caregiver_id = self.caregiver_id;
+        new_event.dt = dt;
+        new_event.utc_offset = zone::offset(&Local, dt);
+        new_event.breastfeed_left = self.number(5)?.into();
+        new_event.breastfeed_right = self.number(6)?.into();
+        new_event.pump_left = self.volume(10)?.into();
+        new_event.pump_right = self.volume(11)?.into();
+        new_event.pump_duration = self.number(12)?.into();
+
+        if new_event.breastfeed == 0 {
+            new_event.breastfeed = new_event.breastfeed_left + new_event.breastfeed_right;
+        }
+        if new_event.pump == 0 {
+            new_event.pump = new_event.pump_left + new_event.pump_right;
+        } else {
+            new_event.pump = self.unit.total_to_ml(
+                self.entered_volume(9),
+                self.entered_volume(10),
+                self.entered_volume(11),
+            );
+        }
+
+        Ok(new_event)
+    }
+
+    /// Validates the form and converts it into a `BabyEvent` carrying the id of the edited event.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(BabyEvent)` if the form edits an existing event and every field holds a valid value.
+    /// - `Err(BabyrsError::Validation)` describing the problem otherwise.
+    pub fn to_event(&self) -> Result<BabyEvent, BabyrsError> {
+        let id = self.id.ok_or_else(|| {
+            BabyrsError::Validation("The form is not editing an event".to_owned())
+        })?;
+        let new_event = self.to_new_event()?;
+
+        Ok(BabyEvent {
+            id,
+            child_id: new_event.child_id,
+            dt: new_event.dt,
+            urine: new_event.urine,
+            stool: new_event.stool,
+            skin2skin: new_event.skin2skin,
+            breastfeed: new_event.breastfeed,
+            breastmilk: new_event.breastmilk,
+            formula: new_event.formula,
+            pump: new_event.pump,
+            breastfeed_left: new_event.breastfeed_left,
+            breastfeed_right: new_event.breastfeed_right,
+            pump_left: new_event.pump_left,
+            pump_right: new_event.pump_right,
+            pump_duration: new_event.pump_duration,
+            caregiver_id: new_event.caregiver_id,
+            utc_offset: new_event.utc_offset,
+            deleted_at: None,
+            event_type_id: new_event.event_type_id,
+        })
+    }
+
+    /// Returns the note entered in the form.
+    ///
+    /// # Returns
+    ///
+    /// The trimmed note, empty if there is none.
+    pub fn note(&self) -> String {
+        self.fields[13].value.to_string().trim().to_owned()
+    }
+
+    /// Returns the value of the toggle held by the field at `index`.
+    fn toggle(&self, index: usize) -> bool {
+        matches!(self.fields[index].value, FieldValue::Toggle(true))
+    }
+
+    /// Parses the number held by the field at `index`.
+    fn number(&self, index: usize) -> Result<u16, BabyrsError> {
+        let field = &self.fields[index];
+
+        match &field.value {
+            FieldValue::Number(text) if text.is_empty() => Ok(0),
+            FieldValue::Number(text) => text.parse::<u16>().map_err(|_| {
+                BabyrsError::Validation(format!(
+                    "{} must be between 0 and {}",
+                    field.label,
+                    u16::MAX
+                ))
+            }),
+            _ => Err(BabyrsError::Validation(format!(
+                "{} is not a number",
+                field.label
+            ))),
+        }
+    }
+
+    /// Parses the volume held by the field at `index` into whole millilitres.
+    fn volume(&self, index: usize) -> Result<u16, BabyrsError> {
+        let field = &self.fields[index];
+
+        match &field.value {
+            FieldValue::Number(text) | FieldValue::Decimal(text) if text.trim().is_empty() => Ok(0),
+            FieldValue::Number(text) | FieldValue::Decimal(text) => self
+                .unit
+                .parse_volume(text)
+                .and_then(|ml| u16::try_from(ml).ok())
+                .ok_or_else(|| {
+                    BabyrsError::Validation(format!(
+                        "{} must be between 0 and {}",
+                        field.label,
+                        self.unit.format_value(u16::MAX.into())
+                    ))
+                }),
+            _ => Err(BabyrsError::Validation(format!(
+                "{} is not a number",
+                field.label
+            ))),
+        }
+    }
+
+    /// Reads the volume held by the field at `index` as entered in the unit of the form, 0 if it is empty or invalid.
+    fn entered_volume(&self, index: usize) -> f64 {
+        self.fields[index]
+            .value
+            .to_string()
+            .trim()
+            .replace(',', ".")
+            .parse()
+            .unwrap_or_default()
+    }
+}
+
+/// Returns the labels of the breastmilk, formula, pump, left pump and right pump volume fields.
+fn volume_labels(unit: VolumeUnit) -> [&'static str; 5] {
+    match unit {
+        VolumeUnit::Millilitre => [
+            "Breastmilk(ml)",
+            "Formula(ml)",
+            "Pump(ml)",
+            "Pump L(ml)",
+            "Pump R(ml)",
+        ],
+        VolumeUnit::UsFluidOunce => [
+            "Breastmilk(fl oz)",
+            "Formula(fl oz)",
+            "Pump(fl oz)",
+            "Pump L(fl oz)",
+            "Pump R(fl oz)",
+        ],
+        VolumeUnit::ImperialFluidOunce => [
+            "Breastmilk(imp fl oz)",
+            "Formula(imp fl oz)",
+            "Pump(imp fl oz)",
+            "Pump L(imp fl oz)",
+            "Pump R(imp fl oz)",
+        ],
+    }
+}
+
+/// Parses the local timestamp held by a field, with or without seconds, into UTC.
+fn parse_timestamp(field: &FormField) -> Result<NaiveDateTime, BabyrsError> {
+    match &field.value {
+        FieldValue::Timestamp(text) => NaiveDateTime::parse_from_str(text.trim(), TIMESTAMP_FORMAT)
+            .or_else(|_| NaiveDateTime::parse_from_str(text.trim(), TIMESTAMP_FORMAT_SHORT))
+            .map(|dt| zone::to_utc(&Local, dt))
+            .map_err(|_| {
+                BabyrsError::Validation(format!(
+                    "{} must look like YYYY-MM-DD HH:MM:SS",
+                    field.label
+                ))
+            }),
+        _ => Err(BabyrsError::Validation(format!(
+            "{} is not a timestamp",
+            field.label
+        ))),
+    }
+}
+
+/// Moves the focus between `fields` or edits the focused field.
+///
+/// `Tab`/`Down` and `Up` move the focus, `Space` flips toggles, printable characters and `Backspace` edit text
+/// fields, `Enter` submits and `Esc` cancels.
+fn handle_field_key(fields: &mut [FormField], focus: &mut usize, key: Key) -> FormAction {
+    match key {
+        Key::Enter => return FormAction::Submit,
+        Key::Esc => return FormAction::Cancel,
+        Key::Tab | Key::Down => *focus = (*focus + 1) % fields.len(),
+        Key::Up => *focus = (*focus + fields.len() - 1) % fields.len(),
+        Key::Backspace => match &mut fields[*focus].value {
+            FieldValue::Timestamp(text)
+            | FieldValue::Text(text)
+            | FieldValue::Number(text)
+            | FieldValue::Decimal(text) => {
+                text.pop();
+            }
+            FieldValue::Toggle(_) => {}
+        },
+        Key::Char(c) => match &mut fields[*focus].value {
+            FieldValue::Toggle(value) if c == ' ' => *value = !*value,
+            FieldValue::Timestamp(text) | FieldValue::Text(text) if !c.is_control() => text.push(c),
+            FieldValue::Number(text) if c.is_ascii_digit() => text.push(c),
+            FieldValue::Decimal(text) if c.is_ascii_digit() || c == '.' || c == ',' => text.push(c),
+            _ => {}
+        },
+        _ => {}
+    }
+
+    FormAction::Continue
+}
+
+/// A modal form for adding or editing a child.
+///
+/// Only the name is required; the birth date, sex and birth weight may be left empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChildForm {
+    /// The id of the child being edited, `None` for a new child.
+    id: Option<i32>,
+    /// The fields of the form, in display order.
+    fields: Vec<FormField>,
+    /// The index of the field that currently has focus.
+    focus: usize,
+    /// The validation error from the last submission, if any.
+    error: Option<String>,
+}
+
+impl Default for ChildForm {
+    fn default() -> Self {
+        Self {
+            id: None,
+            fields: vec![
+                FormField::new("Name", FieldValue::Text(String::new())),
+                FormField::new("Birth date", FieldValue::Text(String::new())),
+                FormField::new("Sex (F/M)", FieldValue::Text(String::new())),
+                FormField::new("Birth weight(g)", FieldValue::Number(String::new())),
+            ],
+            focus: 0,
+            error: None,
+        }
+    }
+}
+
+impl ChildForm {
+    /// Constructs a `ChildForm` pre-populated from an existing child, for editing.
+    ///
+    /// # Parameters
+    ///
+    /// * `child`: The child to edit.
+    ///
+    /// # Returns
+    ///
+    /// A new `ChildForm` instance carrying the id of `child`.
+    pub fn from_child(child: &Child) -> Self {
+        let mut form = Self {
+            id: Some(child.id),
+            ..Self::default()
+        };
+
+        form.fields[0].value = FieldValue::Text(child.name.clone());
+        form.fields[1].value = FieldValue::Text(
+            child
+                .birth_date
+                .map(|date| date.format(DATE_FORMAT).to_string())
+                .unwrap_or_default(),
+        );
+        form.fields[2].value = FieldValue::Text(
+            child
+                .sex
+                .map(|sex| sex.code().to_owned())
+                .unwrap_or_default(),
+        );
+        form.fields[3].value = FieldValue::Number(
+            child
+                .birth_weight
+                .map(|weight| weight.to_string())
+                .unwrap_or_default(),
+        );
+
+        form
+    }
+
+    /// Returns the id of the child being edited.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(i32)` if the form edits an existing child.
+    /// - `None` if the form creates a new child.
+    pub fn id(&self) -> Option<i32> {
+        self.id
+    }
+
+    /// Returns the fields of the form.
+    ///
+    /// # Returns
+    ///
+    /// A slice of [`FormField`].
+    pub fn fields(&self) -> &[FormField] {
+        self.fields.as_slice()
+    }
+
+    /// Returns the index of the focused field.
+    ///
+    /// # Returns
+    ///
+    /// The index of the focused field.
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    /// Returns the validation error from the last submission, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&str)` containing the error message.
+    /// - `None` if the form is valid or has not been submitted yet.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Sets the error message displayed by the form.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error message to display.
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Handles a key press on the form, see [`EventForm::handle_key`].
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: The key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// A [`FormAction`] telling the caller what to do next.
+    pub fn handle_key(&mut self, key: Key) -> FormAction {
+        handle_field_key(&mut self.fields, &mut self.focus, key)
+    }
+
+    /// Validates the form and converts it into a `NewChild`.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(NewChild)` if every field holds a valid value.
+    /// - `Err(BabyrsError::Validation)` describing the first invalid field otherwise.
+    pub fn to_new_child(&self) -> Result<NewChild, BabyrsError> {
+        let text = |index: usize| self.fields[index].value.to_string().trim().to_owned();
+
+        let name = text(0);
+        let birth_date = match text(1) {
+            date if date.is_empty() => None,
+            date => Some(NaiveDate::parse_from_str(&date, DATE_FORMAT).map_err(|_| {
+                BabyrsError::Validation("Birth date must look like YYYY-MM-DD".to_owned())
+            })?),
+        };
+        let sex = match text(2) {
+            sex if sex.is_empty() => None,
+            sex => Some(
+                Sex::parse(&sex)
+                    .ok_or_else(|| BabyrsError::Validation("Sex must be F or M".to_owned()))?,
+            ),
+        };
+        let birth_weight = match text(3) {
+            weight if weight.is_empty() => None,
+            weight => Some(weight.parse::<i32>().map_err(|_| {
+                BabyrsError::Validation("Birth weight must be a number of grams".to_owned())
+            })?),
+        };
+
+        let new_child = NewChild {
+            name,
+            birth_date,
+            sex,
+            birth_weight,
+        };
+        new_child.validate()?;
+
+        Ok(new_child)
+    }
+
+    /// Validates the form and converts it into a `Child` carrying the id of the edited child.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Child)` if the form edits an existing child and every field holds a valid value.
+    /// - `Err(BabyrsError::Validation)` describing the problem otherwise.
+    pub fn to_child(&self) -> Result<Child, BabyrsError> {
+        let id = self
+            .id
+            .ok_or_else(|| BabyrsError::Validation("The form is not editing a child".to_owned()))?;
+        let new_child = self.to_new_child()?;
+
+        Ok(Child {
+            id,
+            name: new_child.name,
+            birth_date: new_child.birth_date,
+            sex: new_child.sex,
+            birth_weight: new_child.birth_weight,
+        })
+    }
+}
+
+/// A modal form for adding a caregiver, who then records the events of the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaregiverForm {
+    /// The fields of the form, in display order.
+    fields: Vec<FormField>,
+    /// The index of the field that currently has focus.
+    focus: usize,
+    /// The validation error from the last submission, if any.
+    error: Option<String>,
+}
+
+impl Default for CaregiverForm {
+    fn default() -> Self {
+        Self {
+            fields: vec![FormField::new("Name", FieldValue::Text(String::new()))],
+            focus: 0,
+            error: None,
+        }
+    }
+}
+
+impl CaregiverForm {
+    /// Returns the fields of the form.
+    ///
+    /// # Returns
+    ///
+    /// A slice of [`FormField`].
+    pub fn fields(&self) -> &[FormField] {
+        self.fields.as_slice()
+    }
+
+    /// Returns the index of the focused field.
+    ///
+    /// # Returns
+    ///
+    /// The index of the focused field.
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    /// Returns the validation error from the last submission, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&str)` containing the error message.
+    /// - `None` if the form is valid or has not been submitted yet.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Sets the error message displayed by the form.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error message to display.
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Handles a key press on the form, see [`EventForm::handle_key`].
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: The key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// A [`FormAction`] telling the caller what to do next.
+    pub fn handle_key(&mut self, key: Key) -> FormAction {
+        handle_field_key(&mut self.fields, &mut self.focus, key)
+    }
+
+    /// Validates the form and converts it into a `NewCaregiver`.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(NewCaregiver)` if the name is not empty.
+    /// - `Err(BabyrsError::Validation)` otherwise.
+    pub fn to_new_caregiver(&self) -> Result<NewCaregiver, BabyrsError> {
+        let new_caregiver = NewCaregiver {
+            name: self.fields[0].value.to_string().trim().to_owned(),
+        };
+        new_caregiver.validate()?;
+
+        Ok(new_caregiver)
+    }
+}
+
+/// A modal form for recording a growth measurement.
+///
+/// Any of the weight, length and head circumference may be left empty, but not all of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeasurementForm {
+    /// The fields of the form, in display order.
+    fields: Vec<FormField>,
+    /// The index of the field that currently has focus.
+    focus: usize,
+    /// The validation error from the last submission, if any.
+    error: Option<String>,
+}
+
+impl MeasurementForm {
+    /// Constructs a new, empty `MeasurementForm` with the timestamp set to `now`.
+    ///
+    /// # Parameters
+    ///
+    /// * `now`: The timestamp the form should default to, in the local time zone.
+    ///
+    /// # Returns
+    ///
+    /// A new `MeasurementForm` instance.
+    pub fn new(now: NaiveDateTime) -> Self {
+        let fields = vec![
+            FormField::new(
+                "Timestamp",
+                FieldValue::Timestamp(now.format(TIMESTAMP_FORMAT).to_string()),
+            ),
+            FormField::new("Weight(g)", FieldValue::Number(String::new())),
+            FormField::new("Length(mm)", FieldValue::Number(String::new())),
+            FormField::new("Head circumference(mm)", FieldValue::Number(String::new())),
+        ];
+
+        Self {
+            fields,
+            focus: 0,
+            error: None,
+        }
+    }
+
+    /// Returns the fields of the form.
+    ///
+    /// # Returns
+    ///
+    /// A slice of [`FormField`].
+    pub fn fields(&self) -> &[FormField] {
+        self.fields.as_slice()
+    }
+
+    /// Returns the index of the focused field.
+    ///
+    /// # Returns
+    ///
+    /// The index of the focused field.
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    /// Returns the validation error from the last submission, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&str)` containing the error message.
+    /// - `None` if the form is valid or has not been submitted yet.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Sets the error message displayed by the form.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error message to display.
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Handles a key press on the form, see [`EventForm::handle_key`].
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: The key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// A [`FormAction`] telling the caller what to do next.
+    pub fn handle_key(&mut self, key: Key) -> FormAction {
+        handle_field_key(&mut self.fields, &mut self.focus, key)
+    }
+
+    /// Validates the form and converts it into a `NewMeasurement` for the default child.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(NewMeasurement)` if every field holds a valid value.
+    /// - `Err(BabyrsError::Validation)` describing the first invalid field otherwise.
+    pub fn to_new_measurement(&self) -> Result<NewMeasurement, BabyrsError> {
+        let dt = parse_timestamp(&self.fields[0])?;
+        let number = |index: usize| {
+            let field = &self.fields[index];
+
+            match field.value.to_string().trim() {
+                "" => Ok(None),
+                text => text.parse::<i32>().map(Some).map_err(|_| {
+                    BabyrsError::Validation(format!("{} must be a whole number", field.label))
+                }),
+            }
+        };
+
+        let new_measurement = NewMeasurement {
+            child_id: DEFAULT_CHILD_ID,
+            dt,
+            weight: number(1)?,
+            length: number(2)?,
+            head_circumference: number(3)?,
+        };
+        new_measurement.validate()?;
+
+        Ok(new_measurement)
+    }
+}
+
+/// A modal form for adding a medication to the catalogue, optionally scheduled for the current child.
+///
+/// The limits may be left empty when the medication has none, and leaving the interval empty gives the medication as
+/// needed rather than on a schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MedicationForm {
+    /// The fields of the form, in display order.
+    fields: Vec<FormField>,
+    /// The index of the field that currently has focus.
+    focus: usize,
+    /// The validation error from the last submission, if any.
+    error: Option<String>,
+}
+
+impl MedicationForm {
+    /// Constructs a new, empty `MedicationForm` with the first scheduled dose set to `now`.
+    ///
+    /// # Parameters
+    ///
+    /// * `now`: The timestamp the first scheduled dose should default to, in the local time zone.
+    ///
+    /// # Returns
+    ///
+    /// A new `MedicationForm` instance.
+    pub fn new(now: NaiveDateTime) -> Self {
+        let fields = vec![
+            FormField::new("Name", FieldValue::Text(String::new())),
+            FormField::new("Unit", FieldValue::Text("ml".to_owned())),
+            FormField::new("Dose", FieldValue::Text(String::new())),
+            FormField::new("Min interval(min)", FieldValue::Number(String::new())),
+            FormField::new("Max doses/24h", FieldValue::Number(String::new())),
+            FormField::new("Max amount/24h", FieldValue::Text(String::new())),
+            FormField::new("Every(h)", FieldValue::Number(String::new())),
+            FormField::new(
+                "First dose",
+                FieldValue::Timestamp(now.format(TIMESTAMP_FORMAT).to_string()),
+            ),
+        ];
+
+        Self {
+            fields,
+            focus: 0,
+            error: None,
+        }
+    }
+
+    /// Returns the fields of the form.
+    ///
+    /// # Returns
+    ///
+    /// A slice of [`FormField`].
+    pub fn fields(&self) -> &[FormField] {
+        self.fields.as_slice()
+    }
+
+    /// Returns the index of the focused field.
+    ///
+    /// # Returns
+    ///
+    /// The index of the focused field.
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    /// Returns the validation error from the last submission, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&str)` containing the error message.
+    /// - `None` if the form is valid or has not been submitted yet.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Sets the error message displayed by the form.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error message to display.
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Handles a key press on the form, see [`EventForm::handle_key`].
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: The key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// A [`FormAction`] telling the caller what to do next.
+    pub fn handle_key(&mut self, key: Key) -> FormAction {
+        handle_field_key(&mut self.fields, &mut self.focus, key)
+    }
+
+    /// Validates the form and converts it into a `NewMedication` and, if an interval is given, a
+    /// `NewMedicationSchedule` for the default child.
+    ///
+    /// The schedule refers to no medication yet; its `medication_id` is set once the medication has been written.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok((NewMedication, Option<NewMedicationSchedule>))` if every field holds a valid value.
+    /// - `Err(BabyrsError::Validation)` describing the first invalid field otherwise.
+    pub fn to_new_medication(
+        &self,
+    ) -> Result<(NewMedication, Option<NewMedicationSchedule>), BabyrsError> {
+        let text = |index: usize| self.fields[index].value.to_string().trim().to_owned();
+        let number = |index: usize| match text(index) {
+            text if text.is_empty() => Ok(None),
+            text => text.parse::<i32>().map(Some).map_err(|_| {
+                BabyrsError::Validation(format!(
+                    "{} must be a whole number",
+                    self.fields[index].label
+                ))
+            }),
+        };
+        let amount = |index: usize| match text(index) {
+            text if text.is_empty() => Ok(None),
+            text => text.parse::<f64>().map(Some).map_err(|_| {
+                BabyrsError::Validation(format!("{} must be a number", self.fields[index].label))
+            }),
+        };
+
+        let new_medication = NewMedication {
+            name: text(0),
+            unit: text(1),
+            dose: amount(2)?
+                .ok_or_else(|| BabyrsError::Validation("Dose must not be empty".to_owned()))?,
+            min_interval_minutes: number(3)?,
+            max_daily_doses: number(4)?,
+            max_daily_amount: amount(5)?,
+        };
+        new_medication.validate()?;
+
+        let schedule = match number(6)? {
+            Some(hours) => {
+                let new_schedule = NewMedicationSchedule {
+                    child_id: DEFAULT_CHILD_ID,
+                    medication_id: 0,
+                    first_dt: parse_timestamp(&self.fields[7])?,
+                    interval_minutes: hours.saturating_mul(60),
+                };
+                new_schedule.validate()?;
+                Some(new_schedule)
+            }
+            None => None,
+        };
+
+        Ok((new_medication, schedule))
+    }
+}
+
+/// A modal form for noting the details of a diaper on an existing event.
+///
+/// Every detail may be left empty; the stool details need an event with a stool and the urine details an event with
+/// urine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiaperForm {
+    /// The id of the event the details belong to.
+    event_id: i32,
+    /// The fields of the form, in display order.
+    fields: Vec<FormField>,
+    /// The index of the field that currently has focus.
+    focus: usize,
+    /// The validation error from the last submission, if any.
+    error: Option<String>,
+}
+
+impl DiaperForm {
+    /// Constructs a `DiaperForm` for an event, pre-populated with the details noted before.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_id`: The id of the event.
+    /// * `details`: The details noted before, if any.
+    ///
+    /// # Returns
+    ///
+    /// A new `DiaperForm` instance.
+    pub fn new(event_id: i32, details: Option<&DiaperDetails>) -> Self {
+        let text = |code: Option<&str>| FieldValue::Text(code.unwrap_or_default().to_owned());
+        let fields = vec![
+            FormField::new(
+                "Stool color",
+                text(details.and_then(|d| d.stool_color).map(|c| c.code())),
+            ),
+            FormField::new(
+                "Consistency",
+                text(details.and_then(|d| d.stool_consistency).map(|c| c.code())),
+            ),
+            FormField::new(
+                "Stool size",
+                text(details.and_then(|d| d.stool_size).map(|a| a.code())),
+            ),
+            FormField::new(
+                "Urine amount",
+                text(details.and_then(|d| d.urine_amount).map(|a| a.code())),
+            ),
+            FormField::new(
+                "Urate crystals",
+                FieldValue::Toggle(details.is_some_and(|d| d.urate_crystals)),
+            ),
+        ];
+
+        Self {
+            event_id,
+            fields,
+            focus: 0,
+            error: None,
+        }
+    }
+
+    /// Returns the fields of the form.
+    ///
+    /// # Returns
+    ///
+    /// A slice of [`FormField`].
+    pub fn fields(&self) -> &[FormField] {
+        self.fields.as_slice()
+    }
+
+    /// Returns the index of the focused field.
+    ///
+    /// # Returns
+    ///
+    /// The index of the focused field.
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    /// Returns the validation error from the last submission, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&str)` containing the error message.
+    /// - `None` if the form is valid or has not been submitted yet.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Sets the error message displayed by the form.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error message to display.
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Handles a key press on the form, see [`EventForm::handle_key`].
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: The key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// A [`FormAction`] telling the caller what to do next.
+    pub fn handle_key(&mut self, key: Key) -> FormAction {
+        handle_field_key(&mut self.fields, &mut self.focus, key)
+    }
+
+    /// Validates the form and converts it into the `DiaperDetails` of the event.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(DiaperDetails)` if every field is empty or holds one of its codes.
+    /// - `Err(BabyrsError::Validation)` listing the codes of the first invalid field otherwise.
+    pub fn to_details(&self) -> Result<DiaperDetails, BabyrsError> {
+        Ok(DiaperDetails {
+            event_id: self.event_id,
+            stool_color: parse_choice(&self.fields[0], StoolColor::parse, StoolColor::ALL)?,
+            stool_consistency: parse_choice(
+                &self.fields[1],
+                StoolConsistency::parse,
+                StoolConsistency::ALL,
+            )?,
+            stool_size: parse_choice(&self.fields[2], Amount::parse, Amount::ALL)?,
+            urine_amount: parse_choice(&self.fields[3], Amount::parse, Amount::ALL)?,
+            urate_crystals: self.fields[4].value == FieldValue::Toggle(true),
+        })
+    }
+}
+
+/// Parses the value of a text field into one of a fixed set of choices.
+///
+/// # Parameters
+///
+/// * `field`: The field to parse.
+/// * `parse`: Parses a choice from its code.
+/// * `all`: Every choice, listed in the error message.
+///
+/// # Returns
+///
+/// - `Ok(None)` if the field is empty.
+/// - `Ok(Some(T))` if the field holds the code of a choice.
+/// - `Err(BabyrsError::Validation)` listing the choices otherwise.
+fn parse_choice<T: Display>(
+    field: &FormField,
+    parse: fn(&str) -> Option<T>,
+    all: &[T],
+) -> Result<Option<T>, BabyrsError> {
+    let text = field.value.to_string();
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+
+    parse(&text).map(Some).ok_or_else(|| {
+        let choices = all.iter().map(|c| c.to_string()).collect::<Vec<String>>();
+        BabyrsError::Validation(format!(
+            "{} must be one of {}",
+            field.label,
+            choices.join(", ")
+        ))
+    })
+}
+
+/// A modal form for adding a user-defined event type to the registry.
+///
+/// The fields of the type are entered on one line as `name:kind` specifications separated by commas, such as
+/// `minutes:duration, product:enum(soap|oil)`, see [`NewEventField::parse`]; a type may have no fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventTypeForm {
+    /// The fields of the form, in display order.
+    fields: Vec<FormField>,
+    /// The index of the field that currently has focus.
+    focus: usize,
+    /// The validation error from the last submission, if any.
+    error: Option<String>,
+}
+
+impl Default for EventTypeForm {
+    fn default() -> Self {
+        Self {
+            fields: vec![
+                FormField::new("Name", FieldValue::Text(String::new())),
+                FormField::new("Fields", FieldValue::Text(String::new())),
+            ],
+            focus: 0,
+            error: None,
+        }
+    }
+}
+
+impl EventTypeForm {
+    /// Returns the fields of the form.
+    ///
+    /// # Returns
+    ///
+    /// A slice of [`FormField`].
+    pub fn fields(&self) -> &[FormField] {
+        self.fields.as_slice()
+    }
+
+    /// Returns the index of the focused field.
+    ///
+    /// # Returns
+    ///
+    /// The index of the focused field.
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    /// Returns the validation error from the last submission, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&str)` containing the error message.
+    /// - `None` if the form is valid or has not been submitted yet.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Sets the error message displayed by the form.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error message to display.
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Handles a key press on the form, see [`EventForm::handle_key`].
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: The key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// A [`FormAction`] telling the caller what to do next.
+    pub fn handle_key(&mut self, key: Key) -> FormAction {
+        handle_field_key(&mut self.fields, &mut self.focus, key)
+    }
+
+    /// Validates the form and converts it into a `NewEventType` and its fields.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok((NewEventType, Vec<NewEventField>))` if the name is not empty and every field specification is valid.
+    /// - `Err(BabyrsError::Validation)` describing the first problem otherwise.
+    pub fn to_new_event_type(&self) -> Result<(NewEventType, Vec<NewEventField>), BabyrsError> {
+        let new_type = NewEventType {
+            name: self.fields[0].value.to_string().trim().to_owned(),
+        };
+        new_type.validate()?;
+
+        let fields = self.fields[1]
+            .value
+            .to_string()
+            .split(',')
+            .filter(|spec| !spec.trim().is_empty())
+            .map(NewEventField::parse)
+            .collect::<Result<Vec<NewEventField>, BabyrsError>>()?;
+
+        Ok((new_type, fields))
+    }
+}
+
+/// A modal form for recording an event of a user-defined type, with one field for every field of the type.
+///
+/// Bool fields are toggles, durations and volumes numbers in minutes and the entered unit, and enum and text fields
+/// free text. The same form is used for adding new events and editing existing ones; an edit form carries the id of
+/// the event being edited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomEventForm {
+    /// The id of the event being edited, `None` for a new event.
+    id: Option<i32>,
+    /// The child the event belongs to.
+    child_id: i32,
+    /// The caregiver who recorded the event, kept unchanged when editing.
+    caregiver_id: Option<i32>,
+    /// The type of the event.
+    event_type: EventType,
+    /// The fields of the type, in the order of the form fields after the timestamp.
+    type_fields: Vec<EventField>,
+    /// The unit the volumes are entered in.
+    unit: VolumeUnit,
+    /// The fields of the form, in display order.
+    fields: Vec<FormField>,
+    /// The index of the field that currently has focus.
+    focus: usize,
+    /// The validation error from the last submission, if any.
+    error: Option<String>,
+}
+
+impl CustomEventForm {
+    /// Constructs a new, empty `CustomEventForm` for an event type with the timestamp set to `now`.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_type`: The type of the event.
+    /// * `type_fields`: The fields of the type, in the order of their position.
+    /// * `now`: The timestamp the form should default to, in the local time zone.
+    /// * `unit`: The unit the volumes are entered in.
+    ///
+    /// # Returns
+    ///
+    /// A new `CustomEventForm` instance.
+    pub fn new(
+        event_type: &EventType,
+        type_fields: &[&EventField],
+        now: NaiveDateTime,
+        unit: VolumeUnit,
+    ) -> Self {
+        let mut fields = vec![FormField::new(
+            "Timestamp",
+            FieldValue::Timestamp(now.format(TIMESTAMP_FORMAT).to_string()),
+        )];
+        fields.extend(type_fields.iter().map(|field| {
+            let (label, value) = match field.kind {
+                FieldKind::Bool => (field.name.clone(), FieldValue::Toggle(false)),
+                FieldKind::Duration => (
+                    format!("{}(min)", field.name),
+                    FieldValue::Number(String::new()),
+                ),
+                FieldKind::Volume => (
+                    format!("{}({})", field.name, unit.code()),
+                    FieldValue::Decimal(String::new()),
+                ),
+                FieldKind::Enum => (
+                    format!("{}({})", field.name, field.choices().join("|")),
+                    FieldValue::Text(String::new()),
+                ),
+                FieldKind::Text => (field.name.clone(), FieldValue::Text(String::new())),
+            };
+            FormField::new(label, value)
+        }));
+        fields.push(FormField::new("Notes", FieldValue::Text(String::new())));
+
+        Self {
+            id: None,
+            child_id: DEFAULT_CHILD_ID,
+            caregiver_id: None,
+            event_type: event_type.clone(),
+            type_fields: type_fields.iter().map(|&field| field.clone()).collect(),
+            unit,
+            fields,
+            focus: 0,
+            error: None,
+        }
+    }
+
+    /// Constructs a `CustomEventForm` pre-populated from an existing event, its values and its note, for editing.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The event to edit.
+    /// * `event_type`: The type of the event.
+    /// * `type_fields`: The fields of the type, in the order of their position.
+    /// * `values`: The stored values of the event by the id of their field.
+    /// * `note`: The note of the event, if any.
+    /// * `unit`: The unit the volumes are entered in.
+    ///
+    /// # Returns
+    ///
+    /// A new `CustomEventForm` instance carrying the id of `event`.
+    pub fn from_event(
+        event: &BabyEvent,
+        event_type: &EventType,
+        type_fields: &[&EventField],
+        values: &BTreeMap<i32, String>,
+        note: Option<&str>,
+        unit: VolumeUnit,
+    ) -> Self {
+        let mut form = Self::new(
+            event_type,
+            type_fields,
+            zone::to_local(&Local, event.dt),
+            unit,
+        );
+
+        form.id = Some(event.id);
+        form.child_id = event.child_id;
+        form.caregiver_id = event.caregiver_id;
+        for (index, field) in type_fields.iter().enumerate() {
+            let Some(value) = values.get(&field.id) else {
+                continue;
+            };
+
+            form.fields[index + 1].value = match &form.fields[index + 1].value {
+                FieldValue::Toggle(_) => FieldValue::Toggle(value == "true"),
+                FieldValue::Number(_) => FieldValue::Number(field.input_value(value, unit)),
+                FieldValue::Decimal(_) => FieldValue::Decimal(field.input_value(value, unit)),
+                _ => FieldValue::Text(field.input_value(value, unit)),
+            };
+        }
+        let notes = form.fields.len() - 1;
+        form.fields[notes].value = FieldValue::Text(note.unwrap_or_default().to_owned());
+
+        form
+    }
+
+    /// Returns the id of the event being edited.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(i32)` if the form edits an existing event.
+    /// - `None` if the form creates a new event.
+    pub fn id(&self) -> Option<i32> {
+        self.id
+    }
+
+    /// Returns the type of the event.
+    ///
+    /// # Returns
+    ///
+    /// The [`EventType`] the form records.
+    pub fn event_type(&self) -> &EventType {
+        &self.event_type
+    }
+
+    /// Returns the fields of the form.
+    ///
+    /// # Returns
+    ///
+    /// A slice of [`FormField`].
+    pub fn fields(&self) -> &[FormField] {
+        self.fields.as_slice()
+    }
+
+    /// Returns the index of the focused field.
+    ///
+    /// # Returns
+    ///
+    /// The index of the focused field.
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    /// Returns the validation error from the last submission, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&str)` containing the error message.
+    /// - `None` if the form is valid or has not been submitted yet.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Sets the error message displayed by the form.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error message to display.
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Handles a key press on the form, see [`EventForm::handle_key`].
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: The key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// A [`FormAction`] telling the caller what to do next.
+    pub fn handle_key(&mut self, key: Key) -> FormAction {
+        handle_field_key(&mut self.fields, &mut self.focus, key)
+    }
+
+    /// Validates the form and converts it into a `NewBabyEvent` of the form's type and the values of its fields.
+    ///
+    /// Fields left empty have no value; a toggle always has one.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok((NewBabyEvent, BTreeMap<i32, String>))` with the stored values by the id of their field, if every field
+    ///   holds a valid value.
+    /// - `Err(BabyrsError::Validation)` describing the first invalid field otherwise.
+    pub fn to_new_event(&self) -> Result<(NewBabyEvent, BTreeMap<i32, String>), BabyrsError> {
+        let dt = parse_timestamp(&self.fields[0])?;
+
+        let mut new_event = create_event(None, None, None, None, None, None, None);
+        new_event.child_id = self.child_id;
+        new_event.caregiver_id = self.caregiver_id;
+        new_event.dt = dt;
+        new_event.utc_offset = zone::offset(&Local, dt);
+        new_event.event_type_id = Some(self.event_type.id);
+
+        let mut values = BTreeMap::new();
+        for (field, form_field) in self.type_fields.iter().zip(&self.fields[1..]) {
+            let text = match &form_field.value {
+                FieldValue::Toggle(value) => value.to_string(),
+                value => value.to_string(),
+            };
+
+            if let Some(value) = field.parse_value(&text, self.unit)? {
+                values.insert(field.id, value);
+            }
+        }
+
+        Ok((new_event, values))
+    }
+
+    /// Validates the form and converts it into a `BabyEvent` carrying the id of the edited event and the values of
+    /// its fields.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok((BabyEvent, BTreeMap<i32, String>))` if the form edits an existing event and every field holds a valid
+    ///   value.
+    /// - `Err(BabyrsError::Validation)` describing the problem otherwise.
+    pub fn to_event(&self) -> Result<(BabyEvent, BTreeMap<i32, String>), BabyrsError> {
+        let id = self.id.ok_or_else(|| {
+            BabyrsError::Validation("The form is not editing an event".to_owned())
+        })?;
+        let (new_event, values) = self.to_new_event()?;
+
+        Ok((
+            BabyEvent {
+                id,
+                child_id: new_event.child_id,
+                dt: new_event.dt,
+                urine: new_event.urine,
+                stool: new_event.stool,
+                skin2skin: new_event.skin2skin,
+                breastfeed: new_event.breastfeed,
+                breastmilk: new_event.breastmilk,
+                formula: new_event.formula,
+                pump: new_event.pump,
+                breastfeed_left: new_event.breastfeed_left,
+                breastfeed_right: new_event.breastfeed_right,
+                pump_left: new_event.pump_left,
+                pump_right: new_event.pump_right,
+                pump_duration: new_event.pump_duration,
+                caregiver_id: new_event.caregiver_id,
+                utc_offset: new_event.utc_offset,
+                deleted_at: None,
+                event_type_id: new_event.event_type_id,
+            },
+            values,
+        ))
+    }
+
+    /// Returns the note entered in the form.
+    ///
+    /// # Returns
+    ///
+    /// The trimmed note, empty if there is none.
+    pub fn note(&self) -> String {
+        self.fields[self.fields.len() - 1]
+            .value
+            .to_string()
+            .trim()
+            .to_owned()
+    }
+}
+
+/// The format used to parse dates.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// A single-line prompt for entering a date range or a rolling window to filter the events by.
+///
+/// Accepts `YYYY-MM-DD..YYYY-MM-DD` for a range of dates, a single `YYYY-MM-DD` for one day, and `<n>h` or `<n>d`
+/// for the last `n` hours or days.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FilterPrompt {
+    /// The text entered so far.
+    input: String,
+    /// The validation error from the last submission, if any.
+    error: Option<String>,
+}
+
+impl FilterPrompt {
+    /// Returns the text entered so far.
+    ///
+    /// # Returns
+    ///
+    /// The entered text.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Returns the validation error from the last submission, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&str)` containing the error message.
+    /// - `None` if the input is valid or has not been submitted yet.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Sets the error message displayed by the prompt.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error message to display.
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Handles a key press on the prompt.
+    ///
+    /// Printable characters and `Backspace` edit the input, `Enter` submits and `Esc` cancels.
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: The key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// A [`FormAction`] telling the caller what to do next.
+    pub fn handle_key(&mut self, key: Key) -> FormAction {
+        match key {
+            Key::Enter => return FormAction::Submit,
+            Key::Esc => return FormAction::Cancel,
+            Key::Backspace => {
+                self.input.pop();
+            }
+            Key::Char(c) if !c.is_control() => self.input.push(c),
+            _ => {}
+        }
+
+        FormAction::Continue
+    }
+
+    /// Parses the input into a filter.
+    ///
+    /// # Parameters
+    ///
+    /// * `now`: The time rolling windows end at.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Filter)` holding a `Range` or `Rolling` filter.
+    /// - `Err(BabyrsError::Validation)` describing why the input is invalid otherwise.
+    pub fn to_filter(&self, now: NaiveDateTime) -> Result<Filter, BabyrsError> {
+        let input = self.input.trim();
+        let date = |text: &str| {
+            NaiveDate::parse_from_str(text.trim(), DATE_FORMAT).map_err(|_| {
+                BabyrsError::Validation(format!("{} must look like YYYY-MM-DD", text.trim()))
+            })
+        };
+
+        if let Some(count) = input.strip_suffix(['h', 'd']) {
+            let count = count
+                .trim()
+                .parse::<u16>()
+                .ok()
+                .filter(|count| *count > 0)
+                .ok_or_else(|| {
+                    BabyrsError::Validation(format!("{} must be a positive number", count.trim()))
+                })?;
+            let duration = if input.ends_with('h') {
+                Duration::hours(count.into())
+            } else {
+                Duration::days(count.into())
+            };
+
+            return Ok(Filter::Rolling(now, duration));
+        }
+
+        let (start, end) = match input.split_once("..") {
+            Some((start, end)) => (date(start)?, date(end)?),
+            None => (date(input)?, date(input)?),
+        };
+
+        if start > end {
+            return Err(BabyrsError::Validation(format!(
+                "{} is after {}",
+                start, end
+            )));
+        }
+
+        Ok(Filter::Range(start, end))
+    }
+}
+
+/// A single-line prompt for entering the words to search the notes for.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SearchPrompt {
+    /// The text entered so far.
+    input: String,
+    /// The error from the last search, if any.
+    error: Option<String>,
+}
+
+impl SearchPrompt {
+    /// Returns the text entered so far.
+    ///
+    /// # Returns
+    ///
+    /// The entered text.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Returns the error from the last search, if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&str)` containing the error message.
+    /// - `None` if nothing went wrong or nothing has been searched yet.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Sets the error message displayed by the prompt.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error message to display.
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Handles a key press on the prompt, see [`FilterPrompt::handle_key`].
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: The key that was pressed.
+    ///
+    /// # Returns
+    ///
+    /// A [`FormAction`] telling the caller what to do next.
+    pub fn handle_key(&mut self, key: Key) -> FormAction {
+        match key {
+            Key::Enter => return FormAction::Submit,
+            Key::Esc => return FormAction::Cancel,
+            Key::Backspace => {
+                self.input.pop();
+            }
+            Key::Char(c) if !c.is_control() => self.input.push(c),
+            _ => {}
+        }
+
+        FormAction::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 6, 15)
+            .unwrap()
+            .and_hms_opt(5, 15, 0)
+            .unwrap()
+    }
+
+    /// The local time `now` in UTC, as stored by the forms.
+    fn utc() -> NaiveDateTime {
+        zone::to_utc(&Local, now())
+    }
+
+    #[test]
+    fn test_new_form_defaults() {
+        let form = EventForm::new(now(), VolumeUnit::Millilitre);
+        let new_event = form.to_new_event().unwrap();
+
+        assert_eq!(form.focus(), 0);
+        assert!(form.error().is_none());
+        assert_eq!(new_event.dt, utc());
+        assert_eq!(new_event.utc_offset, zone::offset(&Local, utc()));
+        assert!(!new_event.urine);
+        assert!(!new_event.stool);
+        assert_eq!(new_event.skin2skin, 0);
+        assert_eq!(new_event.pump, 0);
+    }
+
+    #[test]
+    fn test_from_event() {
+        let event = BabyEvent {
+            id: 7,
+            child_id: 1,
+            dt: now(),
+            urine: true,
+            stool: false,
+            skin2skin: 10,
+            breastfeed: 20,
+            breastmilk: 30,
+            formula: 40,
+            pump: 50,
+            breastfeed_left: 5,
+            breastfeed_right: 15,
+            pump_left: 0,
+            pump_right: 0,
+            pump_duration: 25,
+            caregiver_id: Some(2),
+            utc_offset: 0,
+            deleted_at: None,
+            event_type_id: None,
+        };
+
+        let mut form = EventForm::from_event(&event, Some("spat up"), VolumeUnit::Millilitre);
+        assert_eq!(form.id(), Some(7));
+        assert_eq!(form.note(), "spat up");
+
+        let edited = form.to_event().unwrap();
+        assert_eq!(edited.id, 7);
+        assert_eq!(edited.dt, now());
+        assert!(edited.urine);
+        assert!(!edited.stool);
+        assert_eq!(edited.skin2skin, 10);
+        assert_eq!(edited.breastfeed, 20);
+        assert_eq!(edited.breastmilk, 30);
+        assert_eq!(edited.formula, 40);
+        assert_eq!(edited.pump, 50);
+        assert_eq!(edited.breastfeed_left, 5);
+        assert_eq!(edited.breastfeed_right, 15);
+        assert_eq!(edited.pump_duration, 25);
+        assert_eq!(edited.caregiver_id, Some(2));
+
+        // correcting a mistyped volume
+        for _ in 0..7 {
+            form.handle_key(Key::Tab);
+        }
+        form.handle_key(Key::Backspace);
+        form.handle_key(Key::Backspace);
+        form.handle_key(Key::Char('6'));
+        form.handle_key(Key::Char('0'));
+        assert_eq!(form.to_event().unwrap().breastmilk, 60);
+
+        // a new event form cannot produce an existing event
+        assert!(EventForm::new(now(), VolumeUnit::Millilitre)
+            .to_event()
+            .is_err());
+    }
+
+    #[test]
+    fn test_handle_key_navigation() {
+        let mut form = EventForm::new(now(), VolumeUnit::Millilitre);
+
+        assert_eq!(form.handle_key(Key::Up), FormAction::Continue);
+        assert_eq!(form.focus(), form.fields().len() - 1);
+
+        form.handle_key(Key::Tab);
+        form.handle_key(Key::Down);
+        assert_eq!(form.focus(), 1);
+
+        assert_eq!(form.handle_key(Key::Enter), FormAction::Submit);
+        assert_eq!(form.handle_key(Key::Esc), FormAction::Cancel);
+    }
+
+    #[test]
+    fn test_handle_key_editing() {
+        let mut form = EventForm::new(now(), VolumeUnit::Millilitre);
+
+        // toggle urine
+        form.handle_key(Key::Down);
+        form.handle_key(Key::Char(' '));
+
+        // type a breastmilk volume, ignoring non-digits
+        for _ in 0..6 {
+            form.handle_key(Key::Down);
+        }
+        form.handle_key(Key::Backspace);
+        form.handle_key(Key::Char('9'));
+        form.handle_key(Key::Char('x'));
+        form.handle_key(Key::Char('0'));
+
+        let new_event = form.to_new_event().unwrap();
+
+        assert!(new_event.urine);
+        assert!(!new_event.stool);
+        assert_eq!(new_event.breastmilk, 90);
+    }
+
+    #[test]
+    fn test_to_new_event_invalid() {
+        let mut form = EventForm::new(now(), VolumeUnit::Millilitre);
+
+        // an unparseable timestamp is rejected
+        form.handle_key(Key::Char('x'));
+        assert!(form.to_new_event().is_err());
+
+        // the short timestamp format is accepted
+        form.fields[0].value = FieldValue::Timestamp("2023-06-15 05:15".to_owned());
+        assert_eq!(form.to_new_event().unwrap().dt, utc());
+
+        // an out of range number is rejected
+        form.fields[3].value = FieldValue::Number("70000".to_owned());
+        assert!(form.to_new_event().is_err());
+    }
+
+    #[test]
+    fn test_event_form_sides() {
+        let mut form = EventForm::new(now(), VolumeUnit::Millilitre);
+
+        // the totals default to the sum of the sides
+        form.fields[5].value = FieldValue::Number("10".to_owned());
+        form.fields[6].value = FieldValue::Number("5".to_owned());
+        form.fields[11].value = FieldValue::Number("60".to_owned());
+        let new_event = form.to_new_event().unwrap();
+        assert_eq!(new_event.breastfeed, 15);
+        assert_eq!(new_event.pump, 60);
+        assert_eq!(new_event.pump_right, 60);
+        assert!(new_event.validate().is_ok());
+
+        // a total that does not match the sides is rejected
+        form.fields[4].value = FieldValue::Number("20".to_owned());
+        assert!(form.to_new_event().unwrap().validate().is_err());
+    }
+
+    #[test]
+    fn test_event_form_ounces() {
+        let oz = VolumeUnit::UsFluidOunce;
+        let mut form = EventForm::new(now(), oz);
+        assert_eq!(form.fields()[7].label, "Breastmilk(fl oz)");
+
+        // type 4,5 ounces of formula
+        for _ in 0..8 {
+            form.handle_key(Key::Tab);
+        }
+        form.handle_key(Key::Backspace);
+        for c in ['4', ',', 'x', '5'] {
+            form.handle_key(Key::Char(c));
+        }
+        assert_eq!(form.fields()[8].value.to_string(), "4,5");
+        assert_eq!(form.to_new_event().unwrap().formula, 133);
+
+        // an edited event keeps its volumes to the millilitre
+        let event = BabyEvent {
+            id: 7,
+            child_id: 1,
+            dt: now(),
+            urine: false,
+            stool: false,
+            skin2skin: 0,
+            breastfeed: 0,
+            breastmilk: 121,
+            formula: 0,
+            pump: 0,
+            breastfeed_left: 0,
+            breastfeed_right: 0,
+            pump_left: 0,
+            pump_right: 0,
+            pump_duration: 0,
+            caregiver_id: None,
+            utc_offset: 0,
+            deleted_at: None,
+            event_type_id: None,
+        };
+        let form = EventForm::from_event(&event, None, oz);
+        assert_eq!(form.fields()[7].value.to_string(), "4.09");
+        assert_eq!(form.to_event().unwrap().breastmilk, 121);
+
+        // halves of an ounce add up to the total once rounded to millilitres
+        let mut form = EventForm::new(now(), oz);
+        form.fields[9].value = FieldValue::Decimal("3".to_owned());
+        form.fields[10].value = FieldValue::Decimal("1,5".to_owned());
+        form.fields[11].value = FieldValue::Decimal("1.5".to_owned());
+        let new_event = form.to_new_event().unwrap();
+        assert_eq!(
+            (new_event.pump, new_event.pump_left, new_event.pump_right),
+            (88, 44, 44)
+        );
+        assert!(new_event.validate().is_ok());
+
+        // sides that do not add up are still rejected
+        form.fields[11].value = FieldValue::Decimal("1.6".to_owned());
+        assert!(form.to_new_event().unwrap().validate().is_err());
+
+        // a volume beyond the range of the database is rejected
+        let mut form = EventForm::new(now(), oz);
+        form.fields[7].value = FieldValue::Decimal("3000".to_owned());
+        assert!(form.to_new_event().is_err());
+    }
+
+    #[test]
+    fn test_filter_prompt_rolling() {
+        let mut prompt = FilterPrompt::default();
+
+        for c in "24h".chars() {
+            assert_eq!(prompt.handle_key(Key::Char(c)), FormAction::Continue);
+        }
+
+        assert_eq!(prompt.input(), "24h");
+        assert_eq!(prompt.handle_key(Key::Enter), FormAction::Submit);
+        assert_eq!(
+            prompt.to_filter(now()).unwrap(),
+            Filter::Rolling(now(), Duration::hours(24))
+        );
+
+        prompt.handle_key(Key::Backspace);
+        prompt.handle_key(Key::Char('d'));
+        assert_eq!(
+            prompt.to_filter(now()).unwrap(),
+            Filter::Rolling(now(), Duration::days(24))
+        );
+    }
+
+    #[test]
+    fn test_filter_prompt_range() {
+        let mut prompt = FilterPrompt::default();
+
+        for c in "2023-06-01..2023-06-15".chars() {
+            prompt.handle_key(Key::Char(c));
+        }
+
+        assert_eq!(
+            prompt.to_filter(now()).unwrap(),
+            Filter::Range(
+                NaiveDate::from_ymd_opt(2023, 6, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 6, 15).unwrap()
+            )
+        );
+
+        // a single date is a range of one day
+        let prompt = FilterPrompt {
+            input: "2023-06-15".to_owned(),
+            error: None,
+        };
+        assert_eq!(
+            prompt.to_filter(now()).unwrap(),
+            Filter::Range(now().date(), now().date())
+        );
+    }
+
+    #[test]
+    fn test_filter_prompt_invalid() {
+        for input in ["", "0h", "xd", "2023-06-15..2023-06-01", "2023-13-01"] {
+            let prompt = FilterPrompt {
+                input: input.to_owned(),
+                error: None,
+            };
+
+            assert!(prompt.to_filter(now()).is_err(), "{}", input);
+        }
+
+        assert_eq!(
+            FilterPrompt::default().handle_key(Key::Esc),
+            FormAction::Cancel
+        );
+    }
+
+    #[test]
+    fn test_child_form() {
+        let mut form = ChildForm::default();
+
+        // only the name is required
+        assert!(form.to_new_child().is_err());
+        for c in "Ada".chars() {
+            form.handle_key(Key::Char(c));
+        }
+        assert_eq!(
+            form.to_new_child().unwrap(),
+            NewChild {
+                name: "Ada".to_owned(),
+                birth_date: None,
+                sex: None,
+                birth_weight: None,
+            }
+        );
+
+        for text in ["2023-06-01", "f", "3250"] {
+            form.handle_key(Key::Tab);
+            for c in text.chars() {
+                form.handle_key(Key::Char(c));
+            }
+        }
+        assert_eq!(form.focus(), 3);
+        assert_eq!(
+            form.to_new_child().unwrap(),
+            NewChild {
+                name: "Ada".to_owned(),
+                birth_date: NaiveDate::from_ymd_opt(2023, 6, 1),
+                sex: Some(Sex::Female),
+                birth_weight: Some(3250),
+            }
+        );
+
+        // the sex must be F or M
+        form.handle_key(Key::Up);
+        form.handle_key(Key::Backspace);
+        form.handle_key(Key::Char('x'));
+        assert!(form.to_new_child().is_err());
+    }
+
+    #[test]
+    fn test_caregiver_form() {
+        let mut form = CaregiverForm::default();
+
+        assert!(form.to_new_caregiver().is_err());
+        for c in " Sam ".chars() {
+            form.handle_key(Key::Char(c));
+        }
+        assert_eq!(
+            form.to_new_caregiver().unwrap(),
+            NewCaregiver {
+                name: "Sam".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_child_form_editing() {
+        let child = Child {
+            id: 2,
+            name: "Ada".to_owned(),
+            birth_date: NaiveDate::from_ymd_opt(2023, 6, 1),
+            sex: Some(Sex::Female),
+            birth_weight: None,
+        };
+        let mut form = ChildForm::from_child(&child);
+
+        assert_eq!(form.id(), Some(2));
+        assert_eq!(form.fields()[1].value.to_string(), "2023-06-01");
+        assert_eq!(form.fields()[2].value.to_string(), "F");
+        assert_eq!(form.to_child().unwrap(), child);
+
+        form.handle_key(Key::Tab);
+        for _ in 0..10 {
+            form.handle_key(Key::Backspace);
+        }
+        assert_eq!(form.to_child().unwrap().birth_date, None);
+
+        // a new child cannot be converted into an existing one
+        assert!(ChildForm::default().to_child().is_err());
+    }
+
+    #[test]
+    fn test_measurement_form() {
+        let mut form = MeasurementForm::new(now());
+
+        // at least one measure is required
+        assert!(form.to_new_measurement().is_err());
+
+        form.handle_key(Key::Tab);
+        for c in "4200".chars() {
+            form.handle_key(Key::Char(c));
+        }
+        form.handle_key(Key::Tab);
+        form.handle_key(Key::Tab);
+        for c in "372".chars() {
+            form.handle_key(Key::Char(c));
+        }
+
+        assert_eq!(
+            form.to_new_measurement().unwrap(),
+            NewMeasurement {
+                child_id: DEFAULT_CHILD_ID,
+                dt: utc(),
+                weight: Some(4200),
+                length: None,
+                head_circumference: Some(372),
+            }
+        );
+    }
+
+    #[test]
+    fn test_medication_form() {
+        let mut form = MedicationForm::new(now());
+        fn type_into(form: &mut MedicationForm, text: &str) {
+            for c in text.chars() {
+                form.handle_key(Key::Char(c));
+            }
+            form.handle_key(Key::Tab);
+        }
+
+        // a name and dose are required
+        assert!(form.to_new_medication().is_err());
+
+        type_into(&mut form, "Paracetamol");
+        form.handle_key(Key::Tab);
+        type_into(&mut form, "2.5");
+        type_into(&mut form, "240");
+        type_into(&mut form, "4");
+        form.handle_key(Key::Tab);
+
+        let (new_medication, schedule) = form.to_new_medication().unwrap();
+        assert_eq!(
+            new_medication,
+            NewMedication {
+                name: "Paracetamol".to_owned(),
+                unit: "ml".to_owned(),
+                dose: 2.5,
+                min_interval_minutes: Some(240),
+                max_daily_doses: Some(4),
+                max_daily_amount: None,
+            }
+        );
+        assert_eq!(schedule, None);
+
+        type_into(&mut form, "6");
+        let (_, schedule) = form.to_new_medication().unwrap();
+        assert_eq!(
+            schedule,
+            Some(NewMedicationSchedule {
+                child_id: DEFAULT_CHILD_ID,
+                medication_id: 0,
+                first_dt: utc(),
+                interval_minutes: 360,
+            })
+        );
+
+        // the daily amount must hold at least one dose
+        form.focus = 5;
+        type_into(&mut form, "1");
+        assert!(form.to_new_medication().is_err());
+    }
+
+    #[test]
+    fn test_diaper_form() {
+        let mut form = DiaperForm::new(3, None);
+        let empty = form.to_details().unwrap();
+        assert_eq!(empty.event_id, 3);
+        assert!(empty.is_empty());
+
+        for c in "Green".chars() {
+            form.handle_key(Key::Char(c));
+        }
+        form.handle_key(Key::Tab);
+        for c in "seedy".chars() {
+            form.handle_key(Key::Char(c));
+        }
+        form.handle_key(Key::Up);
+        form.handle_key(Key::Up);
+        form.handle_key(Key::Char(' '));
+        assert_eq!(form.focus(), 4);
+
+        let details = form.to_details().unwrap();
+        assert_eq!(
+            details,
+            DiaperDetails {
+                event_id: 3,
+                stool_color: Some(StoolColor::Green),
+                stool_consistency: Some(StoolConsistency::Seedy),
+                stool_size: None,
+                urine_amount: None,
+                urate_crystals: true,
+            }
+        );
+        assert_eq!(
+            DiaperForm::new(3, Some(&details)).to_details().unwrap(),
+            details
+        );
+
+        // only the listed choices are accepted
+        form.handle_key(Key::Tab);
+        form.handle_key(Key::Char('x'));
+        let error = form.to_details().unwrap_err().to_string();
+        assert!(error.contains("meconium, transitional"), "{}", error);
+    }
+
+    #[test]
+    fn test_event_type_form() {
+        let mut form = EventTypeForm::default();
+        fn type_into(form: &mut EventTypeForm, text: &str) {
+            for c in text.chars() {
+                form.handle_key(Key::Char(c));
+            }
+            form.handle_key(Key::Tab);
+        }
+
+        // a name is required, fields are not
+        assert!(form.to_new_event_type().is_err());
+        type_into(&mut form, "Bath");
+        let (new_type, fields) = form.to_new_event_type().unwrap();
+        assert_eq!(new_type.name, "Bath");
+        assert!(fields.is_empty());
+
+        type_into(&mut form, "minutes:duration, product:enum(soap|oil),");
+        let (_, fields) = form.to_new_event_type().unwrap();
+        assert_eq!(
+            fields
+                .iter()
+                .map(|field| (field.name.as_str(), field.kind, field.choices.as_deref()))
+                .collect::<Vec<(&str, FieldKind, Option<&str>)>>(),
+            [
+                ("minutes", FieldKind::Duration, None),
+                ("product", FieldKind::Enum, Some("soap|oil")),
+            ]
+        );
+
+        // every field needs a known kind
+        form.focus = 1;
+        type_into(&mut form, ", remark:string");
+        let error = form.to_new_event_type().unwrap_err().to_string();
+        assert!(error.contains("remark:string"), "{}", error);
+    }
+
+    #[test]
+    fn test_custom_event_form() {
+        let event_type = EventType {
+            id: 2,
+            name: "Bath".to_owned(),
+        };
+        let field = |id: i32, name: &str, kind: FieldKind, choices: Option<&str>| EventField {
+            id,
+            event_type_id: 2,
+            position: id,
+            name: name.to_owned(),
+            kind,
+            choices: choices.map(str::to_owned),
+        };
+        let type_fields = [
+            field(0, "minutes", FieldKind::Duration, None),
+            field(1, "outside", FieldKind::Bool, None),
+            field(2, "water", FieldKind::Volume, None),
+            field(3, "product", FieldKind::Enum, Some("soap|oil")),
+        ];
+        let type_fields = type_fields.iter().collect::<Vec<&EventField>>();
+
+        let mut form =
+            CustomEventForm::new(&event_type, &type_fields, now(), VolumeUnit::UsFluidOunce);
+        assert_eq!(
+            form.fields()
+                .iter()
+                .map(|field| field.label.as_str())
+                .collect::<Vec<&str>>(),
+            [
+                "Timestamp",
+                "minutes(min)",
+                "outside",
+                "water(fl oz)",
+                "product(soap|oil)",
+                "Notes"
+            ]
+        );
+
+        // empty fields have no value, a toggle always has one
+        let (new_event, values) = form.to_new_event().unwrap();
+        assert_eq!(new_event.event_type_id, Some(2));
+        assert_eq!(new_event.dt, utc());
+        assert_eq!(values, BTreeMap::from([(1, "false".to_owned())]));
+
+        form.focus = 3;
+        for c in "2".chars() {
+            form.handle_key(Key::Char(c));
+        }
+        form.handle_key(Key::Tab);
+        for c in "OIL".chars() {
+            form.handle_key(Key::Char(c));
+        }
+        let (new_event, values) = form.to_new_event().unwrap();
+        let expected = BTreeMap::from([
+            (1, "false".to_owned()),
+            (2, "59".to_owned()),
+            (3, "oil".to_owned()),
+        ]);
+        assert_eq!(values, expected);
+
+        // an edit form shows the stored values and keeps the event's id
+        let event = BabyEvent {
+            id: 7,
+            child_id: 1,
+            dt: new_event.dt,
+            urine: false,
+            stool: false,
+            skin2skin: 0,
+            breastfeed: 0,
+            breastmilk: 0,
+            formula: 0,
+            pump: 0,
+            breastfeed_left: 0,
+            breastfeed_right: 0,
+            pump_left: 0,
+            pump_right: 0,
+            pump_duration: 0,
+            caregiver_id: Some(3),
+            utc_offset: new_event.utc_offset,
+            deleted_at: None,
+            event_type_id: Some(2),
+        };
+        let edit = CustomEventForm::from_event(
+            &event,
+            &event_type,
+            &type_fields,
+            &expected,
+            Some("warm"),
+            VolumeUnit::UsFluidOunce,
+        );
+        let (edited, values) = edit.to_event().unwrap();
+        assert_eq!(edited, event);
+        assert_eq!(values, expected);
+        assert_eq!(edit.note(), "warm");
+
+        // only the listed choices are accepted
+        form.handle_key(Key::Char('s'));
+        let error = form.to_new_event().unwrap_err().to_string();
+        assert!(error.contains("soap, oil"), "{}", error);
+    }
+}
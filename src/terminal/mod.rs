@@ -1,5 +1,8 @@
 pub mod app;
+pub mod browser;
+pub mod chart;
 mod events;
+pub mod form;
 pub mod state;
 mod ui;
 
@@ -1,26 +1,60 @@
-use crate::{establish_connection, models::BabyEvent, read_events};
-use chrono::{Datelike, NaiveDate};
-use diesel::sqlite::SqliteConnection;
+use crate::{
+    delete_event, establish_connection, export_csv, get_default_caregiver,
+    get_default_duplicate_policy, get_default_trash_retention, get_default_volume_unit,
+    growth::Measure,
+    medication::{dose_status, DoseStatus},
+    models::{
+        BabyEvent, Caregiver, Child, DiaperDetails, Dose, EventHistory, EventNote, EventRegistry,
+        EventType, EventValue, Measurement, Medication, MedicationSchedule, NewBabyEvent,
+        NewCaregiver, NewChild, NewDose, NewEventField, NewEventType, NewMeasurement,
+        NewMedication, NewMedicationSchedule, NewSleepSession, Side, SleepSession, StoolColor,
+    },
+    process_child_csv, purge_deleted_events, purge_event, read_caregivers, read_child_doses,
+    read_child_measurements, read_children, read_deleted_events, read_diaper_details_between,
+    read_event_history, read_event_registry, read_event_values_between, read_events_between,
+    read_last_breastfeed, read_last_event, read_medication_schedules, read_medications,
+    read_notes_between, read_open_sleep_session, read_sleep_sessions_between, restore_event,
+    revert_event, revert_event_to_version, search_notes,
+    terminal::chart::{ChartMetric, ChartView},
+    units::VolumeUnit,
+    update_child, update_event, update_sleep_session, write_caregiver, write_child,
+    write_diaper_details, write_dose, write_event_type, write_event_values,
+    write_event_with_values, write_measurement, write_medication, write_medication_schedule,
+    write_note, write_sleep_session, zone, BabyrsError, DuplicatePolicy, ImportSummary,
+    WriteOutcome, DEFAULT_CHILD_ID, DEFAULT_TRASH_RETENTION_DAYS,
+};
+use chrono::{
+    Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc,
+};
+use diesel::{sqlite::SqliteConnection, Connection};
 use log::info;
 use ratatui::widgets::ListState;
 use std::{
+    collections::BTreeMap,
     fmt::{self, Display},
     vec,
 };
 
 /// Represents the filter for the event list.
 ///
-/// The filter can be `Day`, `Week`, or `Month`.
-#[derive(Debug, PartialEq)]
+/// The filter can be a calendar `Day`, `Week` or `Month`, an inclusive `Range` of dates, or a `Rolling` window of a
+/// fixed duration ending at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Filter {
     Day(NaiveDate),
     Week(NaiveDate),
     Month(NaiveDate),
+    /// All days from the first date up to and including the second date.
+    Range(NaiveDate, NaiveDate),
+    /// The window of the given duration ending (exclusively) at the given time, e.g. the last 24 hours.
+    Rolling(NaiveDateTime, Duration),
 }
 
 impl Filter {
     /// Switches to the next filter in the sequence.
     ///
+    /// Ranges and rolling windows switch back to the day they end on.
+    ///
     /// # Parameters
     ///
     /// * `self`: The current filter.
@@ -33,8 +67,164 @@ impl Filter {
             Self::Day(date) => Self::Week(*date),
             Self::Week(week) => Self::Month(*week),
             Self::Month(month) => Self::Day(*month),
+            Self::Range(..) | Self::Rolling(..) => Self::Day(self.date()),
+        }
+    }
+
+    /// Returns the date the filter is anchored to.
+    ///
+    /// # Returns
+    ///
+    /// The `NaiveDate` held by the filter variant, the last date for a `Range` and the date of the end of a
+    /// `Rolling` window.
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            Self::Day(date) | Self::Week(date) | Self::Month(date) | Self::Range(_, date) => *date,
+            Self::Rolling(end, _) => end.date(),
+        }
+    }
+
+    /// Moves the filter to a new date while keeping the current variant.
+    ///
+    /// A `Range` keeps its length and ends on `date`, a `Rolling` window keeps its duration and the time of day it
+    /// ends at.
+    ///
+    /// # Parameters
+    ///
+    /// * `date`: The date the new filter should be anchored to.
+    ///
+    /// # Returns
+    ///
+    /// A filter of the same variant anchored to `date`.
+    pub fn with_date(&self, date: NaiveDate) -> Self {
+        match self {
+            Self::Day(_) => Self::Day(date),
+            Self::Week(_) => Self::Week(date),
+            Self::Month(_) => Self::Month(date),
+            Self::Range(start, end) => Self::Range(date - (*end - *start), date),
+            Self::Rolling(end, duration) => Self::Rolling(date.and_time(end.time()), *duration),
+        }
+    }
+
+    /// Moves the filter so that it includes `dt`, keeping the current variant.
+    ///
+    /// The filter is returned unchanged if it already includes `dt`. A `Rolling` window is moved to end on the
+    /// minute following `dt`, all other filters are moved to the date of `dt`.
+    ///
+    /// # Parameters
+    ///
+    /// * `dt`: The point in time the filter should include.
+    ///
+    /// # Returns
+    ///
+    /// A filter of the same variant including `dt`.
+    pub fn including(&self, dt: NaiveDateTime) -> Self {
+        match self {
+            _ if self.matches(&dt) => *self,
+            Self::Rolling(_, duration) => {
+                let minute = dt.date().and_hms_opt(dt.hour(), dt.minute(), 0).unwrap();
+                Self::Rolling(minute + Duration::minutes(1), *duration)
+            }
+            _ => self.with_date(dt.date()),
+        }
+    }
+
+    /// Moves the filter by a number of its own periods: days for `Day`, weeks for `Week`, months for `Month`, the
+    /// number of days in the range for `Range` and the window duration for `Rolling`.
+    ///
+    /// # Parameters
+    ///
+    /// * `periods`: The number of periods to move by, negative to move back in time.
+    ///
+    /// # Returns
+    ///
+    /// A filter of the same variant anchored to the shifted date. Moving by months clamps the day to the end of
+    /// shorter months.
+    pub fn shift(&self, periods: i32) -> Self {
+        let date = self.date();
+        let date = match self {
+            Self::Day(_) => date.checked_add_signed(Duration::days(periods.into())),
+            Self::Week(_) => date.checked_add_signed(Duration::weeks(periods.into())),
+            Self::Month(_) if periods < 0 => {
+                date.checked_sub_months(Months::new(periods.unsigned_abs()))
+            }
+            Self::Month(_) => date.checked_add_months(Months::new(periods.unsigned_abs())),
+            Self::Range(start, end) => {
+                let days = (*end - *start).num_days() + 1;
+                date.checked_add_signed(Duration::days(days * i64::from(periods)))
+            }
+            Self::Rolling(end, duration) => {
+                return Self::Rolling(*end + *duration * periods, *duration)
+            }
+        };
+
+        self.with_date(date.unwrap_or(self.date()))
+    }
+
+    /// Returns the period covered by the filter.
+    ///
+    /// Calendar weeks start on Monday.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of the first point in time within the filter and the first point in time after it.
+    pub fn bounds(&self) -> (NaiveDateTime, NaiveDateTime) {
+        let midnight = |date: NaiveDate| date.and_hms_opt(0, 0, 0).unwrap();
+
+        match self {
+            Self::Day(day) => (midnight(*day), midnight(*day) + Duration::days(1)),
+            Self::Week(week) => {
+                let monday = midnight(week.week(chrono::Weekday::Mon).first_day());
+                (monday, monday + Duration::weeks(1))
+            }
+            Self::Month(month) => {
+                let first = midnight(month.with_day(1).unwrap());
+                (first, first + Months::new(1))
+            }
+            Self::Range(start, end) => (midnight(*start), midnight(*end) + Duration::days(1)),
+            Self::Rolling(end, duration) => (*end - *duration, *end),
+        }
+    }
+
+    /// Returns the period covered by the filter in UTC.
+    ///
+    /// The filter bounds are wall-clock times of `tz`, so a day may be 23 or 25 hours long when the clocks change.
+    /// A `Rolling` window keeps its duration and ends at the UTC equivalent of its end.
+    ///
+    /// # Parameters
+    ///
+    /// * `tz`: The time zone the filter is read in.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of the first point in time within the filter and the first point in time after it, both in UTC.
+    pub fn utc_bounds<Tz: TimeZone>(&self, tz: &Tz) -> (NaiveDateTime, NaiveDateTime) {
+        match self {
+            Self::Rolling(end, duration) => {
+                let end = zone::to_utc(tz, *end);
+                (end - *duration, end)
+            }
+            _ => {
+                let (start, end) = self.bounds();
+                (zone::to_utc(tz, start), zone::to_utc(tz, end))
+            }
         }
     }
+
+    /// Checks whether a point in time falls within the filter.
+    ///
+    /// # Parameters
+    ///
+    /// * `dt`: The point in time to check.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the point in time is part of the filtered period, otherwise `false`.
+    pub fn matches(&self, dt: &NaiveDateTime) -> bool {
+        let (start, end) = self.bounds();
+
+        &start <= dt && dt < &end
+    }
 }
 
 impl Default for Filter {
@@ -49,6 +239,13 @@ impl Display for Filter {
             Self::Day(_) => write!(f, "Day"),
             Self::Week(_) => write!(f, "Week"),
             Self::Month(_) => write!(f, "Month"),
+            Self::Range(start, end) => write!(f, "{} to {}", start, end),
+            Self::Rolling(_, duration)
+                if duration.num_hours() > 24 && duration.num_hours() % 24 == 0 =>
+            {
+                write!(f, "Last {} days", duration.num_days())
+            }
+            Self::Rolling(_, duration) => write!(f, "Last {} hours", duration.num_hours()),
         }
     }
 }
@@ -57,111 +254,1977 @@ impl Display for Filter {
 ///
 /// The state can either be `Init` for the initial state,
 /// or `Initialized` when the application is running and has data.
+// there is a single state for the lifetime of the application, boxing it would only add indirection
+#[allow(clippy::large_enum_variant)]
 pub enum AppState {
     /// Initial state of the application.
     Init,
     /// State of the application when it is running and has data.
     Initialized {
-        /// The events that have been added to the application.
-        baby_events: Vec<BabyEvent>,
+        /// The children in the database.
+        children: Vec<Child>,
+        /// The id of the child whose events are shown.
+        child_id: i32,
+        /// The caregivers in the database.
+        caregivers: Vec<Caregiver>,
+        /// The id of the caregiver recording new events in this session, if known.
+        caregiver_id: Option<i32>,
+        /// The id of the caregiver whose events are shown, all events are shown if `None`.
+        caregiver_filter: Option<i32>,
         /// The filter for the event list.
         filter: Filter,
-        /// filtered event list.
-        /// TODO: filtered_events should be a vector of references into baby_events
+        /// The events within the filter, queried from the database.
         filtered_events: Vec<BabyEvent>,
+        /// The diaper details of the filtered events, in the same order.
+        diaper_details: Vec<DiaperDetails>,
+        /// The notes of the filtered events, in the same order.
+        notes: Vec<EventNote>,
+        /// The user-defined event types and their fields.
+        event_registry: EventRegistry,
+        /// The values of the filtered events of a user-defined type, in the same order.
+        event_values: Vec<EventValue>,
+        /// The sleep sessions overlapping the filter, queried from the database.
+        sleep_sessions: Vec<SleepSession>,
+        /// The sleep session the current child is in, if it is asleep.
+        open_sleep: Option<SleepSession>,
+        /// The side to start the next breastfeed of the current child on, if known.
+        next_side: Option<Side>,
         /// The current selection offset for the filtered event list.
         selection: ListState,
+        /// The metric displayed in the chart.
+        chart_metric: ChartMetric,
+        /// How the chart is drawn.
+        chart_view: ChartView,
+        /// The unit volumes are displayed, entered and exported in.
+        volume_unit: VolumeUnit,
+        /// How new events at the same timestamp as an existing event are written.
+        duplicate_policy: DuplicatePolicy,
+        /// The growth measurements of the current child, oldest first.
+        measurements: Vec<Measurement>,
+        /// The measure plotted against the WHO percentiles in place of the chart, if any.
+        growth_measure: Option<Measure>,
+        /// The medication catalogue, ordered by name.
+        medications: Vec<Medication>,
+        /// The medication schedules of the current child.
+        medication_schedules: Vec<MedicationSchedule>,
+        /// The doses given to the current child, oldest first.
+        doses: Vec<Dose>,
     },
 }
 
-impl AppState {
-    /// Creates a new `Initialized` state with default values.
+impl AppState {
+    /// Creates a new `Initialized` state with default values.
+    ///
+    /// # Returns
+    ///
+    /// An `AppState::Initialized` variant for the default child with an empty vector of events and the default filter.
+    pub fn initialized() -> Self {
+        let children = vec![];
+        let child_id = DEFAULT_CHILD_ID;
+        let caregivers = vec![];
+        let caregiver_id = None;
+        let caregiver_filter = None;
+        let filter = Filter::default();
+        let filtered_events = vec![];
+        let diaper_details = vec![];
+        let notes = vec![];
+        let event_registry = EventRegistry::default();
+        let event_values = vec![];
+        let sleep_sessions = vec![];
+        let open_sleep = None;
+        let next_side = None;
+        let selection = ListState::default();
+        let chart_metric = ChartMetric::default();
+        let chart_view = ChartView::default();
+        let volume_unit = VolumeUnit::default();
+        let duplicate_policy = DuplicatePolicy::default();
+        let measurements = vec![];
+        let growth_measure = None;
+        let medications = vec![];
+        let medication_schedules = vec![];
+        let doses = vec![];
+
+        Self::Initialized {
+            children,
+            child_id,
+            caregivers,
+            caregiver_id,
+            caregiver_filter,
+            filter,
+            filtered_events,
+            diaper_details,
+            notes,
+            event_registry,
+            event_values,
+            sleep_sessions,
+            open_sleep,
+            next_side,
+            selection,
+            chart_metric,
+            chart_view,
+            volume_unit,
+            duplicate_policy,
+            measurements,
+            growth_measure,
+            medications,
+            medication_schedules,
+            doses,
+        }
+    }
+
+    /// Checks if the current state is `Initialized`.
+    ///
+    /// # Returns
+    ///
+    /// - `true` if the state is `Initialized`.
+    /// - `false` otherwise.
+    pub fn is_initialized(&self) -> bool {
+        matches!(self, &Self::Initialized { .. })
+    }
+
+    /// Loads the children, the caregivers, the event types, the measurements and medications of the current child and
+    /// the events of the day of its latest event from the database into the state.
+    ///
+    /// The first child is shown if the current child no longer exists, and the filter starts on today if the child
+    /// has no events. The caregiver named by `BABYRS_CAREGIVER` records the events of the session, and is added if
+    /// they do not exist yet, volumes are shown in the unit named by `BABYRS_VOLUME_UNIT` and events sharing a
+    /// timestamp are written as set by `BABYRS_DUPLICATES`. Events deleted longer ago than
+    /// `BABYRS_TRASH_RETENTION_DAYS` are purged from the trash. Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the database could not be read.
+    pub fn load_events(
+        &mut self,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<(), BabyrsError> {
+        if !self.is_initialized() {
+            return Ok(());
+        }
+
+        info!("Loading events from database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        self.load_children(conn)?;
+        self.load_caregivers(conn)?;
+        self.load_event_registry(conn)?;
+        self.refresh_measurements(conn)?;
+        self.refresh_medications(conn)?;
+
+        if let Some(name) = get_default_caregiver() {
+            self.select_caregiver(&name, conn)?;
+        }
+        if let (Some(unit), Self::Initialized { volume_unit, .. }) =
+            (get_default_volume_unit(), &mut *self)
+        {
+            *volume_unit = unit;
+        }
+        if let Some(policy) = get_default_duplicate_policy() {
+            self.set_duplicate_policy(policy);
+        }
+
+        let retention = get_default_trash_retention()
+            .unwrap_or_else(|| Duration::days(DEFAULT_TRASH_RETENTION_DAYS));
+        purge_deleted_events(conn, Utc::now().naive_utc() - retention)?;
+
+        // initialize the filter to the latest event (day)
+        let date = read_last_event(conn, self.child_id())?.map_or(Local::now().date_naive(), |e| {
+            zone::to_local(&Local, e.dt).date()
+        });
+
+        self.refresh_events(conn, Filter::Day(date), |_| false)
+    }
+
+    /// Writes a new event for the current child, recorded by the current caregiver, to the database and reloads the
+    /// events.
+    ///
+    /// An event at the same timestamp as an existing event is written according to the duplicate policy. The filter
+    /// keeps its variant but is moved to the date of the new event, and the event holding its values is selected.
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `new_event`: The event to write.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `WriteOutcome`, `None` if the state is not `Initialized`, or an error if the event is
+    /// invalid or the database could not be written.
+    pub fn add_event(
+        &mut self,
+        new_event: NewBabyEvent,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<Option<WriteOutcome>, BabyrsError> {
+        self.add_event_with_values(new_event, &BTreeMap::new(), connection)
+    }
+
+    /// Writes a new event of a user-defined type with the values of its fields for the current child, recorded by the
+    /// current caregiver, to the database and reloads the events, see [`AppState::add_event`].
+    ///
+    /// # Parameters
+    ///
+    /// * `new_event`: The event to write.
+    /// * `values`: The stored values by the id of their field.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `WriteOutcome`, `None` if the state is not `Initialized`, or an error if the event or
+    /// a value is invalid or the database could not be written.
+    pub fn add_event_with_values(
+        &mut self,
+        mut new_event: NewBabyEvent,
+        values: &BTreeMap<i32, String>,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<Option<WriteOutcome>, BabyrsError> {
+        let Some(filter) = self
+            .get_filter()
+            .map(|f| f.including(zone::to_local(&Local, new_event.dt)))
+        else {
+            return Ok(None);
+        };
+
+        info!("Adding event to database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        new_event.child_id = self.child_id();
+        new_event.caregiver_id = self.get_caregiver().map(|c| c.id);
+        let outcome =
+            write_event_with_values(conn, new_event, values, self.get_duplicate_policy())?;
+
+        self.refresh_events(conn, filter, |e| e.id == outcome.id())?;
+
+        Ok(Some(outcome))
+    }
+
+    /// Writes the changes to an existing event to the database and reloads the events.
+    ///
+    /// The filter keeps its variant but is moved to the date of the edited event, and the edited event stays
+    /// selected. Diaper details that no longer match the event are deleted, see [`crate::update_event`]. Does nothing
+    /// if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The event to update.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of rows updated, or an error if the event is invalid or the database could
+    /// not be written.
+    pub fn update_event(
+        &mut self,
+        event: BabyEvent,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<usize, BabyrsError> {
+        let Some(filter) = self
+            .get_filter()
+            .map(|f| f.including(zone::to_local(&Local, event.dt)))
+        else {
+            return Ok(0);
+        };
+
+        info!("Updating event in database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let updated = update_event(conn, event, self.get_caregiver().map(|c| c.id))?;
+
+        self.refresh_events(conn, filter, |e| e.id == event.id)?;
+
+        Ok(updated)
+    }
+
+    /// Writes the changes to an existing event of a user-defined type and the values of its fields to the database and
+    /// reloads the events, see [`AppState::update_event`].
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The event to update.
+    /// * `values`: The stored values by the id of their field, replacing those written before.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of events updated, or an error if the event or a value is invalid or the
+    /// database could not be written.
+    pub fn update_event_with_values(
+        &mut self,
+        event: BabyEvent,
+        values: &BTreeMap<i32, String>,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<usize, BabyrsError> {
+        let Some(filter) = self
+            .get_filter()
+            .map(|f| f.including(zone::to_local(&Local, event.dt)))
+        else {
+            return Ok(0);
+        };
+
+        info!("Updating event and its values in database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let result = conn.transaction(|conn| {
+            let updated = update_event(conn, event, self.get_caregiver().map(|c| c.id))?;
+            if updated > 0 {
+                write_event_values(conn, event.id, values)?;
+            }
+            Ok(updated)
+        });
+
+        self.refresh_events(conn, filter, |e| e.id == event.id)?;
+
+        result
+    }
+
+    /// Moves an event to the trash on behalf of the current caregiver and reloads the events.
+    ///
+    /// The filter is kept and the selection moves to the neighbouring event. Does nothing if the state is not
+    /// `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The event to delete.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of rows deleted, or an error if the database could not be written.
+    pub fn delete_event(
+        &mut self,
+        event: BabyEvent,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<usize, BabyrsError> {
+        let Self::Initialized {
+            filter,
+            filtered_events,
+            ..
+        } = self
+        else {
+            return Ok(0);
+        };
+        let filter = *filter;
+
+        // select the following event, or the preceding one when deleting the last event
+        let position = filtered_events.iter().position(|e| e.id == event.id);
+        let neighbour = position
+            .and_then(|i| {
+                filtered_events
+                    .get(i + 1)
+                    .or(i.checked_sub(1).and_then(|i| filtered_events.get(i)))
+            })
+            .map(|e| e.id);
+
+        info!("Deleting event from database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let deleted = delete_event(conn, event, self.get_caregiver().map(|c| c.id))?;
+
+        self.refresh_events(conn, filter, |e| Some(e.id) == neighbour)?;
+
+        Ok(deleted)
+    }
+
+    /// Restores a previously deleted event from the trash with its original id and timestamp, on behalf of the current
+    /// caregiver, and reloads the events.
+    ///
+    /// The filter keeps its variant but is moved to the date of the restored event, and the restored event is
+    /// selected. Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The event to restore.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of rows inserted, or an error if the database could not be written.
+    pub fn restore_event(
+        &mut self,
+        event: BabyEvent,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<usize, BabyrsError> {
+        let Some(filter) = self
+            .get_filter()
+            .map(|f| f.including(zone::to_local(&Local, event.dt)))
+        else {
+            return Ok(0);
+        };
+
+        info!("Restoring event to database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let restored = restore_event(conn, event, self.get_caregiver().map(|c| c.id))?;
+
+        self.refresh_events(conn, filter, |e| e.id == event.id)?;
+
+        Ok(restored)
+    }
+
+    /// Writes the diaper details of an event to the database and reloads the events, keeping the event selected.
+    ///
+    /// Empty details delete the details noted before. Does nothing if the state is not `Initialized` or the event is
+    /// not within the filter.
+    ///
+    /// # Parameters
+    ///
+    /// * `details`: The details to write.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of rows written, or an error if the details do not match the event or the
+    /// database could not be written.
+    pub fn set_diaper_details(
+        &mut self,
+        details: DiaperDetails,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<usize, BabyrsError> {
+        let Self::Initialized {
+            filter,
+            filtered_events,
+            ..
+        } = self
+        else {
+            return Ok(0);
+        };
+        if !filtered_events.iter().any(|e| e.id == details.event_id) {
+            return Ok(0);
+        }
+        let filter = *filter;
+
+        info!("Writing diaper details to database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let written = write_diaper_details(conn, details)?;
+
+        self.refresh_events(conn, filter, |e| e.id == details.event_id)?;
+
+        Ok(written)
+    }
+
+    /// Returns the diaper details of a filtered event.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_id`: The id of the event.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&DiaperDetails)` if the state is `Initialized` and details are noted for the event.
+    /// - `None` otherwise.
+    pub fn get_diaper_details(&self, event_id: i32) -> Option<&DiaperDetails> {
+        if let Self::Initialized { diaper_details, .. } = self {
+            diaper_details.iter().find(|d| d.event_id == event_id)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the filtered events with a medically concerning stool color, oldest first.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Vec<(&BabyEvent, StoolColor)>)` if the state is `Initialized`.
+    /// - `None` otherwise.
+    pub fn get_concerning_stools(&self) -> Option<Vec<(&BabyEvent, StoolColor)>> {
+        if let Self::Initialized {
+            filtered_events,
+            diaper_details,
+            ..
+        } = self
+        {
+            Some(
+                filtered_events
+                    .iter()
+                    .filter_map(|event| {
+                        let details = diaper_details.iter().find(|d| d.event_id == event.id)?;
+                        let color = details.stool_color.filter(|c| c.is_concerning())?;
+                        Some((event, color))
+                    })
+                    .collect(),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Writes the note of an event to the database and reloads the events, keeping the event selected.
+    ///
+    /// An empty note deletes the note written before. Does nothing if the state is not `Initialized` or the event is
+    /// not within the filter.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_id`: The id of the event.
+    /// * `text`: The text of the note.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of rows written, or an error if the database could not be written.
+    pub fn set_note(
+        &mut self,
+        event_id: i32,
+        text: &str,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<usize, BabyrsError> {
+        let Self::Initialized {
+            filter,
+            filtered_events,
+            ..
+        } = self
+        else {
+            return Ok(0);
+        };
+        if !filtered_events.iter().any(|e| e.id == event_id) {
+            return Ok(0);
+        }
+        let filter = *filter;
+
+        info!("Writing note to database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let written = write_note(conn, event_id, text)?;
+
+        self.refresh_events(conn, filter, |e| e.id == event_id)?;
+
+        Ok(written)
+    }
+
+    /// Returns the note of a filtered event.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_id`: The id of the event.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&str)` if the state is `Initialized` and the event has a note.
+    /// - `None` otherwise.
+    pub fn get_note(&self, event_id: i32) -> Option<&str> {
+        if let Self::Initialized { notes, .. } = self {
+            notes
+                .iter()
+                .find(|n| n.event_id == event_id)
+                .map(|n| n.text.as_str())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the values of a filtered event of a user-defined type.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_id`: The id of the event.
+    ///
+    /// # Returns
+    ///
+    /// The stored values by the id of their field, empty if the state is not `Initialized` or the event has none.
+    pub fn get_event_values(&self, event_id: i32) -> BTreeMap<i32, String> {
+        if let Self::Initialized { event_values, .. } = self {
+            event_values
+                .iter()
+                .filter(|v| v.event_id == event_id)
+                .map(|v| (v.field_id, v.value.clone()))
+                .collect()
+        } else {
+            BTreeMap::new()
+        }
+    }
+
+    /// Returns the user-defined event types and their fields.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&EventRegistry)` if the state is `Initialized`, empty until the events are loaded.
+    /// - `None` otherwise.
+    pub fn get_event_registry(&self) -> Option<&EventRegistry> {
+        if let Self::Initialized { event_registry, .. } = self {
+            Some(event_registry)
+        } else {
+            None
+        }
+    }
+
+    /// Writes a new user-defined event type with its fields to the database and reloads the event types.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `new_type`: The type to write.
+    /// * `fields`: The fields of the type, in the order they are entered.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the stored type, `None` if the state is not `Initialized`, or an error if the type is
+    /// invalid, its name is taken or the database could not be written.
+    pub fn add_event_type(
+        &mut self,
+        new_type: NewEventType,
+        fields: Vec<NewEventField>,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<Option<EventType>, BabyrsError> {
+        if !self.is_initialized() {
+            return Ok(None);
+        }
+
+        info!("Adding event type to database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let event_type = write_event_type(conn, new_type, fields)?;
+
+        self.load_event_registry(conn)?;
+
+        Ok(Some(event_type))
+    }
+
+    /// Searches the notes of the current child's events across all dates.
+    ///
+    /// # Parameters
+    ///
+    /// * `query`: The words to search for.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the matching events and their notes, newest first, and empty if the state is not
+    /// `Initialized`, or an error if the database could not be queried.
+    pub fn search_notes(
+        &self,
+        query: &str,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<Vec<(BabyEvent, EventNote)>, BabyrsError> {
+        if !self.is_initialized() {
+            return Ok(vec![]);
+        }
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        search_notes(conn, self.child_id(), query)
+    }
+
+    /// Reads the events of the current child in the trash from the database, most recently deleted first.
+    ///
+    /// Returns no events if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the deleted events, or an error if the query fails.
+    pub fn read_deleted_events(
+        &self,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<Vec<BabyEvent>, BabyrsError> {
+        if !self.is_initialized() {
+            return Ok(vec![]);
+        }
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        read_deleted_events(conn, self.child_id())
+    }
+
+    /// Removes an event from the trash for good, on behalf of the current caregiver.
+    ///
+    /// The events shown are not affected. Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The deleted event to purge.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of events purged, or an error if the database could not be written.
+    pub fn purge_event(
+        &self,
+        event: BabyEvent,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<usize, BabyrsError> {
+        if !self.is_initialized() {
+            return Ok(0);
+        }
+
+        info!("Purging event from database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        purge_event(conn, event, self.get_caregiver().map(|c| c.id))
+    }
+
+    /// Reads the history of an event from the database, which leaves out its diaper details, note and values.
+    ///
+    /// Returns no changes if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The id of the event.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the changes from the oldest to the newest, or an error if the query fails.
+    pub fn read_event_history(
+        &self,
+        event: i32,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<Vec<EventHistory>, BabyrsError> {
+        if !self.is_initialized() {
+            return Ok(vec![]);
+        }
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        read_event_history(conn, event)
+    }
+
+    /// Reverts an event to the version it had before a change, on behalf of the current caregiver, and reloads the
+    /// events.
+    ///
+    /// The filter keeps its variant but is moved to the date of the reverted version, which is selected. Does nothing
+    /// if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `entry`: The change to undo.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of events changed, or an error if the event has changed since, the earlier
+    /// version is invalid or the database could not be written.
+    pub fn revert_event(
+        &mut self,
+        entry: &EventHistory,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<usize, BabyrsError> {
+        let version = entry.before_event()?;
+        let Some(filter) = self.get_filter().map(|f| match version {
+            Some(event) => f.including(zone::to_local(&Local, event.dt)),
+            None => *f,
+        }) else {
+            return Ok(0);
+        };
+
+        info!("Reverting event in database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let reverted = revert_event(conn, entry, self.get_caregiver().map(|c| c.id))?;
+
+        self.refresh_events(conn, filter, |e| e.id == entry.event_id)?;
+
+        Ok(reverted)
+    }
+
+    /// Brings an event back to the version a change left it in, on behalf of the current caregiver, and reloads the
+    /// events.
+    ///
+    /// The filter keeps its variant but is moved to the date of the version, which is selected. Does nothing if the
+    /// state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `entry`: The change that left the event in the version to go back to.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of events updated, or an error if the change left no version, the event is in
+    /// the trash or purged, the version is invalid or the database could not be written.
+    pub fn revert_event_to_version(
+        &mut self,
+        entry: &EventHistory,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<usize, BabyrsError> {
+        let version = entry.after_event()?;
+        let Some(filter) = self.get_filter().map(|f| match version {
+            Some(event) => f.including(zone::to_local(&Local, event.dt)),
+            None => *f,
+        }) else {
+            return Ok(0);
+        };
+
+        info!("Reverting event to an earlier version in database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let reverted = revert_event_to_version(conn, entry, self.get_caregiver().map(|c| c.id))?;
+
+        self.refresh_events(conn, filter, |e| e.id == entry.event_id)?;
+
+        Ok(reverted)
+    }
+
+    /// Moves the filter to the date of an event and selects it.
+    ///
+    /// The filter keeps its variant. Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The event to show.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the events could not be queried.
+    pub fn show_event(
+        &mut self,
+        event: &BabyEvent,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<(), BabyrsError> {
+        let Some(filter) = self
+            .get_filter()
+            .map(|f| f.including(zone::to_local(&Local, event.dt)))
+        else {
+            return Ok(());
+        };
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        self.refresh_events(conn, filter, |e| e.id == event.id)
+    }
+
+    /// Imports a CSV file into the database for the current child and reloads the events, keeping the current filter.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `file_path`: The path of the CSV file.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(ImportSummary)` with the inserted, merged and skipped rows.
+    /// - `Err(BabyrsError)` describing why the file could not be imported.
+    pub fn import_csv(
+        &mut self,
+        file_path: &str,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<ImportSummary, BabyrsError> {
+        let Some(filter) = self.get_filter().copied() else {
+            return Ok(ImportSummary::default());
+        };
+
+        info!("Importing CSV file into database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let summary = process_child_csv(
+            conn,
+            file_path,
+            self.child_id(),
+            self.get_duplicate_policy(),
+        );
+
+        // rows written before a parse error are kept, so refresh either way
+        self.refresh_events(conn, filter, |_| false)?;
+
+        summary
+    }
+
+    /// Exports the filtered events into a CSV file, with the volumes in the current unit.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `file_path`: The path of the CSV file, replaced if it exists.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of exported events, or an error if the file could not be written.
+    pub fn export_csv(&self, file_path: &str) -> Result<usize, BabyrsError> {
+        let Self::Initialized {
+            filtered_events,
+            event_registry,
+            event_values,
+            volume_unit,
+            ..
+        } = self
+        else {
+            return Ok(0);
+        };
+
+        info!("Exporting events to CSV file...");
+
+        export_csv(
+            filtered_events,
+            event_values,
+            event_registry,
+            file_path,
+            *volume_unit,
+        )
+    }
+
+    /// Applies `new_filter`, queries the events, their diaper details, notes and values and the sleep sessions within
+    /// it from the database and selects the first event matching `selected`. Only the events of the caregiver filter
+    /// are kept, and the side to start the next breastfeed on is read along with them.
+    ///
+    /// # Parameters
+    ///
+    /// * `connection`: The database connection.
+    /// * `new_filter`: The filter to apply.
+    /// * `selected`: Predicate identifying the event to select.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the events could not be queried.
+    fn refresh_events<P>(
+        &mut self,
+        connection: &mut SqliteConnection,
+        new_filter: Filter,
+        selected: P,
+    ) -> Result<(), BabyrsError>
+    where
+        P: Fn(&BabyEvent) -> bool,
+    {
+        if let Self::Initialized {
+            child_id,
+            caregiver_filter,
+            filter,
+            filtered_events,
+            diaper_details,
+            notes,
+            event_values,
+            sleep_sessions,
+            open_sleep,
+            next_side,
+            selection,
+            ..
+        } = self
+        {
+            let (start, end) = new_filter.utc_bounds(&Local);
+
+            *filter = new_filter;
+            *filtered_events = read_events_between(connection, *child_id, start, end)?;
+            if let Some(caregiver) = caregiver_filter {
+                filtered_events.retain(|e| e.caregiver_id == Some(*caregiver));
+            }
+            *diaper_details = read_diaper_details_between(connection, *child_id, start, end)?;
+            *notes = read_notes_between(connection, *child_id, start, end)?;
+            *event_values = read_event_values_between(connection, *child_id, start, end)?;
+            *sleep_sessions = read_sleep_sessions_between(connection, *child_id, start, end)?;
+            *open_sleep = read_open_sleep_session(connection, *child_id)?;
+            *next_side = read_last_breastfeed(connection, *child_id)?.and_then(|e| e.next_side());
+
+            selection.select(filtered_events.iter().position(selected));
+        }
+
+        Ok(())
+    }
+
+    /// Reads the children from the database, falling back to the first child if the current one no longer exists.
+    ///
+    /// # Parameters
+    ///
+    /// * `connection`: The database connection.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the children could not be queried.
+    fn load_children(&mut self, connection: &mut SqliteConnection) -> Result<(), BabyrsError> {
+        if let Self::Initialized {
+            children, child_id, ..
+        } = self
+        {
+            *children = read_children(connection)?;
+
+            if !children.iter().any(|c| c.id == *child_id) {
+                *child_id = children.first().map_or(DEFAULT_CHILD_ID, |c| c.id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the caregivers from the database, forgetting the current caregiver and the caregiver filter if they no
+    /// longer exist.
+    ///
+    /// # Parameters
+    ///
+    /// * `connection`: The database connection.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the caregivers could not be queried.
+    fn load_caregivers(&mut self, connection: &mut SqliteConnection) -> Result<(), BabyrsError> {
+        if let Self::Initialized {
+            caregivers,
+            caregiver_id,
+            caregiver_filter,
+            ..
+        } = self
+        {
+            *caregivers = read_caregivers(connection)?;
+
+            let exists = |id: &i32| caregivers.iter().any(|c| c.id == *id);
+            *caregiver_id = caregiver_id.filter(exists);
+            *caregiver_filter = caregiver_filter.filter(exists);
+        }
+
+        Ok(())
+    }
+
+    /// Reads the user-defined event types and their fields from the database.
+    ///
+    /// # Parameters
+    ///
+    /// * `connection`: The database connection.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the event types could not be queried.
+    fn load_event_registry(
+        &mut self,
+        connection: &mut SqliteConnection,
+    ) -> Result<(), BabyrsError> {
+        if let Self::Initialized { event_registry, .. } = self {
+            *event_registry = read_event_registry(connection)?;
+        }
+
+        Ok(())
+    }
+
+    /// Makes the caregiver with the given name, ignoring case, record the events of the session, adding them if they
+    /// do not exist yet.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: The name of the caregiver.
+    /// * `connection`: The database connection.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the caregiver could not be written.
+    fn select_caregiver(
+        &mut self,
+        name: &str,
+        connection: &mut SqliteConnection,
+    ) -> Result<(), BabyrsError> {
+        let existing = self
+            .get_caregivers()
+            .and_then(|caregivers| {
+                caregivers
+                    .iter()
+                    .find(|c| c.name.eq_ignore_ascii_case(name))
+            })
+            .map(|c| c.id);
+
+        let id = match existing {
+            Some(id) => id,
+            None => {
+                let new_caregiver = NewCaregiver {
+                    name: name.to_owned(),
+                };
+                let id = write_caregiver(connection, new_caregiver)?.id;
+                self.load_caregivers(connection)?;
+                id
+            }
+        };
+
+        if let Self::Initialized { caregiver_id, .. } = self {
+            *caregiver_id = Some(id);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the caregivers if the state is `Initialized`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&[Caregiver])` containing the caregivers, empty until the events are loaded.
+    /// - `None` otherwise.
+    pub fn get_caregivers(&self) -> Option<&[Caregiver]> {
+        if let Self::Initialized { caregivers, .. } = self {
+            Some(caregivers.as_slice())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the caregiver recording new events in this session.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&Caregiver)` containing the current caregiver.
+    /// - `None` if the state is not `Initialized` or no caregiver is recording.
+    pub fn get_caregiver(&self) -> Option<&Caregiver> {
+        if let Self::Initialized {
+            caregivers,
+            caregiver_id,
+            ..
+        } = self
+        {
+            caregivers.iter().find(|c| Some(c.id) == *caregiver_id)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the caregiver whose events are shown.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&Caregiver)` containing the caregiver of the filter.
+    /// - `None` if the state is not `Initialized` or the events of all caregivers are shown.
+    pub fn get_caregiver_filter(&self) -> Option<&Caregiver> {
+        if let Self::Initialized {
+            caregivers,
+            caregiver_filter,
+            ..
+        } = self
+        {
+            caregivers.iter().find(|c| Some(c.id) == *caregiver_filter)
+        } else {
+            None
+        }
+    }
+
+    /// Switches the caregiver recording new events to the next caregiver, then to nobody before wrapping around to the
+    /// first caregiver.
+    ///
+    /// Does nothing if the state is not `Initialized` or there are no caregivers.
+    pub fn switch_caregiver(&mut self) {
+        if let Self::Initialized {
+            caregivers,
+            caregiver_id,
+            ..
+        } = self
+        {
+            *caregiver_id = next_caregiver(caregivers, *caregiver_id);
+        }
+    }
+
+    /// Writes a new caregiver to the database and makes them record the events of the session.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `new_caregiver`: The caregiver to write.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the caregiver is invalid, the name is taken or the database could not be
+    /// written.
+    pub fn add_caregiver(
+        &mut self,
+        new_caregiver: NewCaregiver,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<(), BabyrsError> {
+        if !self.is_initialized() {
+            return Ok(());
+        }
+
+        info!("Adding caregiver to database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let caregiver = write_caregiver(conn, new_caregiver)?;
+
+        self.load_caregivers(conn)?;
+        if let Self::Initialized { caregiver_id, .. } = self {
+            *caregiver_id = Some(caregiver.id);
+        }
+
+        Ok(())
+    }
+
+    /// Shows only the events of the next caregiver, then the events of all caregivers before wrapping around to the
+    /// first caregiver. The filter is kept and the selection is cleared.
+    ///
+    /// Does nothing if the state is not `Initialized` or there are no caregivers.
+    ///
+    /// # Parameters
+    ///
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the events could not be queried.
+    pub fn switch_caregiver_filter(
+        &mut self,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<(), BabyrsError> {
+        let Self::Initialized {
+            caregivers,
+            caregiver_filter,
+            filter,
+            ..
+        } = self
+        else {
+            return Ok(());
+        };
+        if caregivers.is_empty() {
+            return Ok(());
+        }
+
+        *caregiver_filter = next_caregiver(caregivers, *caregiver_filter);
+        let filter = *filter;
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        self.refresh_events(conn, filter, |_| false)
+    }
+
+    /// Returns the id of the child whose events are shown.
+    ///
+    /// # Returns
+    ///
+    /// The id of the current child, or the default child if the state is not `Initialized`.
+    fn child_id(&self) -> i32 {
+        match self {
+            Self::Initialized { child_id, .. } => *child_id,
+            Self::Init => DEFAULT_CHILD_ID,
+        }
+    }
+
+    /// Returns the children if the state is `Initialized`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&[Child])` containing the children, empty until the events are loaded.
+    /// - `None` otherwise.
+    pub fn get_children(&self) -> Option<&[Child]> {
+        if let Self::Initialized { children, .. } = self {
+            Some(children.as_slice())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the child whose events are shown if the state is `Initialized`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&Child)` containing the current child.
+    /// - `None` if the state is not `Initialized` or the children have not been loaded.
+    pub fn get_child(&self) -> Option<&Child> {
+        if let Self::Initialized {
+            children, child_id, ..
+        } = self
+        {
+            children.iter().find(|c| c.id == *child_id)
+        } else {
+            None
+        }
+    }
+
+    /// Shows the events of the next child, wrapping around to the first child. The filter is kept and the selection
+    /// is cleared.
+    ///
+    /// Does nothing if the state is not `Initialized` or there is only one child.
+    ///
+    /// # Parameters
+    ///
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the events could not be queried.
+    pub fn switch_child(
+        &mut self,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<(), BabyrsError> {
+        let Self::Initialized {
+            children,
+            child_id,
+            filter,
+            ..
+        } = self
+        else {
+            return Ok(());
+        };
+        if children.len() < 2 {
+            return Ok(());
+        }
+
+        let position = children.iter().position(|c| c.id == *child_id);
+        *child_id = children[position.map_or(0, |i| (i + 1) % children.len())].id;
+        let filter = *filter;
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        self.refresh_measurements(conn)?;
+        self.refresh_medications(conn)?;
+        self.refresh_events(conn, filter, |_| false)
+    }
+
+    /// Writes a new child to the database and shows its events, keeping the filter.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `new_child`: The child to write.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the child is invalid or the database could not be written.
+    pub fn add_child(
+        &mut self,
+        new_child: NewChild,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<(), BabyrsError> {
+        let Some(filter) = self.get_filter().copied() else {
+            return Ok(());
+        };
+
+        info!("Adding child to database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let child = write_child(conn, new_child)?;
+
+        if let Self::Initialized { child_id, .. } = self {
+            *child_id = child.id;
+        }
+        self.load_children(conn)?;
+        self.refresh_measurements(conn)?;
+        self.refresh_medications(conn)?;
+
+        self.refresh_events(conn, filter, |_| false)
+    }
+
+    /// Updates the current child in the database and reloads the children.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `child`: The child to update.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of rows updated, or an error if the child is invalid or the database could
+    /// not be written.
+    pub fn update_child(
+        &mut self,
+        child: Child,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<usize, BabyrsError> {
+        if !self.is_initialized() {
+            return Ok(0);
+        }
+
+        info!("Updating child in database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let updated = update_child(conn, child)?;
+        self.load_children(conn)?;
+
+        Ok(updated)
+    }
+
+    /// Writes a new growth measurement for the current child to the database and reloads the measurements.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `new_measurement`: The measurement to write.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of rows inserted, or an error if the measurement is invalid or the database
+    /// could not be written.
+    pub fn add_measurement(
+        &mut self,
+        mut new_measurement: NewMeasurement,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<usize, BabyrsError> {
+        if !self.is_initialized() {
+            return Ok(0);
+        }
+
+        info!("Adding measurement to database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        new_measurement.child_id = self.child_id();
+        let inserted = write_measurement(conn, new_measurement)?;
+        self.refresh_measurements(conn)?;
+
+        Ok(inserted)
+    }
+
+    /// Reads the growth measurements of the current child from the database.
+    ///
+    /// # Parameters
+    ///
+    /// * `connection`: The database connection.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the measurements could not be queried.
+    fn refresh_measurements(
+        &mut self,
+        connection: &mut SqliteConnection,
+    ) -> Result<(), BabyrsError> {
+        if let Self::Initialized {
+            child_id,
+            measurements,
+            ..
+        } = self
+        {
+            *measurements = read_child_measurements(connection, *child_id)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the growth measurements of the current child if the state is `Initialized`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&[Measurement])` containing the measurements, oldest first.
+    /// - `None` otherwise.
+    pub fn get_measurements(&self) -> Option<&[Measurement]> {
+        if let Self::Initialized { measurements, .. } = self {
+            Some(measurements.as_slice())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the measure plotted in the growth panel.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Measure)` if the state is `Initialized` and the growth panel is shown.
+    /// - `None` otherwise.
+    pub fn get_growth_measure(&self) -> Option<Measure> {
+        if let Self::Initialized { growth_measure, .. } = self {
+            *growth_measure
+        } else {
+            None
+        }
+    }
+
+    /// Switches the growth panel to the next measure, hiding it after the head circumference and showing the weight
+    /// when it is hidden.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    pub fn switch_growth_measure(&mut self) {
+        if let Self::Initialized { growth_measure, .. } = self {
+            *growth_measure = match growth_measure {
+                Some(measure) => measure.next(),
+                None => Some(Measure::Weight),
+            };
+        }
+    }
+
+    /// Starts a sleep session for the current child, or ends the session it is in, and reloads the sleep sessions.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `now`: The time the child fell asleep or woke up, in UTC.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the session is invalid or the database could not be written.
+    pub fn toggle_sleep(
+        &mut self,
+        now: NaiveDateTime,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<(), BabyrsError> {
+        let Self::Initialized {
+            child_id,
+            filter,
+            open_sleep,
+            ..
+        } = self
+        else {
+            return Ok(());
+        };
+        let (child_id, filter, open_sleep) = (*child_id, *filter, *open_sleep);
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        match open_sleep {
+            Some(session) => {
+                info!("Ending sleep session...");
+                update_sleep_session(
+                    conn,
+                    SleepSession {
+                        end_dt: Some(now),
+                        ..session
+                    },
+                )?;
+            }
+            None => {
+                info!("Starting sleep session...");
+                write_sleep_session(
+                    conn,
+                    NewSleepSession {
+                        child_id,
+                        start_dt: now,
+                        end_dt: None,
+                    },
+                )?;
+            }
+        }
+
+        let selected = self.get_selected_event().map(|e| e.id);
+        self.refresh_events(conn, filter, |e| Some(e.id) == selected)
+    }
+
+    /// Returns the sleep sessions overlapping the filter if the state is `Initialized`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&[SleepSession])` containing the sessions, oldest first.
+    /// - `None` otherwise.
+    pub fn get_sleep_sessions(&self) -> Option<&[SleepSession]> {
+        if let Self::Initialized { sleep_sessions, .. } = self {
+            Some(sleep_sessions.as_slice())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the sleep session the current child is in.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&SleepSession)` if the state is `Initialized` and the child is asleep.
+    /// - `None` otherwise.
+    pub fn get_open_sleep(&self) -> Option<&SleepSession> {
+        if let Self::Initialized { open_sleep, .. } = self {
+            open_sleep.as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Writes a new medication to the catalogue and, if a schedule is given, schedules it for the current child.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `new_medication`: The medication to write.
+    /// * `schedule`: The schedule of the medication, its child and medication are filled in.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
     ///
     /// # Returns
     ///
-    /// An `AppState::Initialized` variant with an empty vector of events and the default filter.
-    pub fn initialized() -> Self {
-        let baby_events = vec![];
-        let filter = Filter::default();
-        let filtered_events = vec![];
-        let selection = ListState::default();
-
-        Self::Initialized {
-            baby_events,
-            filter,
-            filtered_events,
-            selection,
+    /// An empty `Result`, or an error if the medication or schedule is invalid or the database could not be written.
+    pub fn add_medication(
+        &mut self,
+        new_medication: NewMedication,
+        schedule: Option<NewMedicationSchedule>,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<(), BabyrsError> {
+        if !self.is_initialized() {
+            return Ok(());
         }
+
+        info!("Adding medication to database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let child_id = self.child_id();
+        conn.transaction(|conn| {
+            let medication = write_medication(conn, new_medication)?;
+
+            if let Some(schedule) = schedule {
+                write_medication_schedule(
+                    conn,
+                    NewMedicationSchedule {
+                        child_id,
+                        medication_id: medication.id,
+                        ..schedule
+                    },
+                )?;
+            }
+
+            Ok::<_, BabyrsError>(())
+        })?;
+
+        self.refresh_medications(conn)
     }
 
-    /// Checks if the current state is `Initialized`.
+    /// Writes a dose of a medication in its usual amount for the current child and reloads the doses.
+    ///
+    /// Does nothing if the state is not `Initialized` or the medication is not in the catalogue.
+    ///
+    /// # Parameters
+    ///
+    /// * `medication_id`: The id of the medication given.
+    /// * `now`: The time the dose was given, in UTC.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
     ///
     /// # Returns
     ///
-    /// - `true` if the state is `Initialized`.
-    /// - `false` otherwise.
-    pub fn is_initialized(&self) -> bool {
-        matches!(self, &Self::Initialized { .. })
+    /// A `Result` containing the number of rows inserted, or a `BabyrsError::Validation` naming the time and limit if
+    /// the dose is given too early, or an error if the database could not be written.
+    pub fn add_dose(
+        &mut self,
+        medication_id: i32,
+        now: NaiveDateTime,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<usize, BabyrsError> {
+        let Some((medication, status)) = self
+            .get_dose_statuses(now)
+            .and_then(|statuses| statuses.into_iter().find(|(m, _)| m.id == medication_id))
+        else {
+            return Ok(0);
+        };
+        let medication = medication.clone();
+
+        if let Some((allowed, limit)) = status.next_allowed {
+            return Err(BabyrsError::Validation(format!(
+                "{} is not allowed before {} ({})",
+                medication.name,
+                allowed.format("%Y-%m-%d %H:%M"),
+                limit
+            )));
+        }
+
+        info!("Adding dose to database...");
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        let inserted = write_dose(
+            conn,
+            NewDose {
+                child_id: self.child_id(),
+                medication_id,
+                dt: now,
+                amount: medication.dose,
+            },
+        )?;
+        self.refresh_medications(conn)?;
+
+        Ok(inserted)
     }
 
-    /// Loads the events from the database into the state.
+    /// Reads the medication catalogue and the schedules and doses of the current child from the database.
     ///
-    /// Does nothing if the state is not `Initialized`.
-    pub fn load_events(&mut self, connection: Option<&mut SqliteConnection>) {
+    /// # Parameters
+    ///
+    /// * `connection`: The database connection.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the medications could not be queried.
+    fn refresh_medications(
+        &mut self,
+        connection: &mut SqliteConnection,
+    ) -> Result<(), BabyrsError> {
         if let Self::Initialized {
-            baby_events,
-            filter,
-            filtered_events,
-            selection,
+            child_id,
+            medications,
+            medication_schedules,
+            doses,
+            ..
         } = self
         {
-            info!("Loading events from database...");
-
-            // Establish connection to database
-            let mut local_connection;
-            let conn = match connection {
-                Some(c) => c,
-                None => {
-                    local_connection = establish_connection();
-                    &mut local_connection
-                }
-            };
-
-            // let connection: &mut SqliteConnection = &mut establish_connection();
-            *baby_events = read_events(conn);
+            *medications = read_medications(connection)?;
+            *medication_schedules = read_medication_schedules(connection, *child_id)?;
+            *doses = read_child_doses(connection, *child_id)?;
+        }
 
-            // initialize the filter to the latest event (day)
-            *filter = Filter::Day(baby_events.last().unwrap().dt.date());
+        Ok(())
+    }
 
-            // initialize the filtered events to the last day
-            // TODO: simplify the match to just be the day filter (we know it won't ever be week or month at this point)
-            *filtered_events = baby_events
-                .clone()
-                .into_iter()
-                .filter(|e| match filter {
-                    Filter::Day(date) => &e.dt.date() == date,
-                    Filter::Week(week) => week
-                        .week(chrono::Weekday::Mon)
-                        .days()
-                        .contains(&e.dt.date()),
-                    Filter::Month(month) => e.dt.date().month() == month.month(),
-                })
-                .collect::<Vec<BabyEvent>>();
+    /// Returns the medication catalogue if the state is `Initialized`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(&[Medication])` containing the medications, ordered by name.
+    /// - `None` otherwise.
+    pub fn get_medications(&self) -> Option<&[Medication]> {
+        if let Self::Initialized { medications, .. } = self {
+            Some(medications.as_slice())
+        } else {
+            None
+        }
+    }
 
-            // reset the selection offset
-            *selection = ListState::default();
+    /// Returns the dose status of every medication for the current child if the state is `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `now`: The current time.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Vec<(&Medication, DoseStatus)>)` in the order of the catalogue.
+    /// - `None` otherwise.
+    pub fn get_dose_statuses(&self, now: NaiveDateTime) -> Option<Vec<(&Medication, DoseStatus)>> {
+        if let Self::Initialized {
+            medications,
+            medication_schedules,
+            doses,
+            ..
+        } = self
+        {
+            Some(
+                medications
+                    .iter()
+                    .map(|medication| {
+                        let schedule = medication_schedules
+                            .iter()
+                            .find(|s| s.medication_id == medication.id);
+                        (medication, dose_status(medication, schedule, doses, now))
+                    })
+                    .collect(),
+            )
+        } else {
+            None
         }
     }
 
-    /// Returns the current value of `baby_events` if the state is `Initialized`.
+    /// Returns the side to start the next breastfeed of the current child on.
     ///
     /// # Returns
     ///
-    /// - `Some(Vec<BabyEvent>)` containing the events if the state is `Initialized`.
+    /// - `Some(Side)` if the state is `Initialized` and the last breastfeed with the sides recorded suggests a side.
     /// - `None` otherwise.
-    pub fn get_events(&self) -> Option<&Vec<BabyEvent>> {
-        if let Self::Initialized { baby_events, .. } = self {
-            Some(baby_events)
+    pub fn get_next_side(&self) -> Option<Side> {
+        if let Self::Initialized { next_side, .. } = self {
+            *next_side
         } else {
             None
         }
@@ -182,45 +2245,175 @@ impl AppState {
     }
 
     /// Switches the filter to the next filter in the sequence. Switching the filter resets the selection offset to 0.
-    /// It also recalculates the filtered events based on the new filter.
+    /// It also queries the events within the new filter.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `connection`: An optional database connection, a new one is established if `None`.
     ///
     /// # Returns
     ///
-    /// - `Some(Filter)` containing the filter if the state is `Initialized`.
+    /// An empty `Result`, or an error if the events could not be queried.
+    pub fn switch_filter(
+        &mut self,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<(), BabyrsError> {
+        match self.get_filter() {
+            Some(filter) => self.set_filter(filter.switch(), connection),
+            None => Ok(()),
+        }
+    }
+
+    /// Moves the filter to a new date, keeping its variant. Moving the filter resets the selection offset to 0.
+    /// It also queries the events within the moved filter.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `date`: The date the filter should be anchored to.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the events could not be queried.
+    pub fn move_filter(
+        &mut self,
+        date: NaiveDate,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<(), BabyrsError> {
+        match self.get_filter() {
+            Some(filter) => self.set_filter(filter.with_date(date), connection),
+            None => Ok(()),
+        }
+    }
+
+    /// Replaces the filter, e.g. with a date range or a rolling window. Setting the filter resets the selection
+    /// offset to 0. It also queries the events within the new filter.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `filter`: The filter to apply.
+    /// * `connection`: An optional database connection, a new one is established if `None`.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or an error if the events could not be queried.
+    pub fn set_filter(
+        &mut self,
+        filter: Filter,
+        connection: Option<&mut SqliteConnection>,
+    ) -> Result<(), BabyrsError> {
+        if !self.is_initialized() {
+            return Ok(());
+        }
+
+        // Establish connection to database
+        let mut local_connection;
+        let conn = match connection {
+            Some(c) => c,
+            None => {
+                local_connection = establish_connection()?;
+                &mut local_connection
+            }
+        };
+
+        self.refresh_events(conn, filter, |_| false)
+    }
+
+    /// Returns the current chart metric and view if the state is `Initialized`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some((ChartMetric, ChartView))` if the state is `Initialized`.
     /// - `None` otherwise.
-    pub fn switch_filter(&mut self) {
+    pub fn get_chart(&self) -> Option<(ChartMetric, ChartView)> {
         if let Self::Initialized {
-            baby_events,
-            filter,
-            filtered_events,
-            selection,
+            chart_metric,
+            chart_view,
             ..
         } = self
         {
-            // switch the filter
-            *filter = filter.switch();
+            Some((*chart_metric, *chart_view))
+        } else {
+            None
+        }
+    }
+
+    /// Switches the chart to the next metric in the sequence.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    pub fn switch_chart_metric(&mut self) {
+        if let Self::Initialized { chart_metric, .. } = self {
+            *chart_metric = chart_metric.next();
+        }
+    }
+
+    /// Returns the unit volumes are displayed, entered and exported in.
+    ///
+    /// # Returns
+    ///
+    /// The current unit, millilitres if the state is not `Initialized`.
+    pub fn get_volume_unit(&self) -> VolumeUnit {
+        match self {
+            Self::Initialized { volume_unit, .. } => *volume_unit,
+            Self::Init => VolumeUnit::default(),
+        }
+    }
+
+    /// Returns how new events at the same timestamp as an existing event are written.
+    ///
+    /// # Returns
+    ///
+    /// The current policy, the default policy if the state is not `Initialized`.
+    pub fn get_duplicate_policy(&self) -> DuplicatePolicy {
+        match self {
+            Self::Initialized {
+                duplicate_policy, ..
+            } => *duplicate_policy,
+            Self::Init => DuplicatePolicy::default(),
+        }
+    }
+
+    /// Sets how new events at the same timestamp as an existing event are written.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    ///
+    /// # Parameters
+    ///
+    /// * `policy`: The policy to use.
+    pub fn set_duplicate_policy(&mut self, policy: DuplicatePolicy) {
+        if let Self::Initialized {
+            duplicate_policy, ..
+        } = self
+        {
+            *duplicate_policy = policy;
+        }
+    }
 
-            // recalculate the filtered events
-            *filtered_events = baby_events
-                .clone()
-                .into_iter()
-                .filter(|e| match filter {
-                    Filter::Day(date) => &e.dt.date() == date,
-                    Filter::Week(week) => week
-                        .week(chrono::Weekday::Mon)
-                        .days()
-                        .contains(&e.dt.date()),
-                    Filter::Month(month) => e.dt.date().month() == month.month(),
-                })
-                .collect::<Vec<BabyEvent>>();
+    /// Switches the unit of volumes to the next unit in the sequence.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    pub fn switch_volume_unit(&mut self) {
+        if let Self::Initialized { volume_unit, .. } = self {
+            *volume_unit = volume_unit.next();
+        }
+    }
 
-            // reset the selection offset
-            *selection = ListState::default();
+    /// Switches the chart between the bar and line views.
+    ///
+    /// Does nothing if the state is not `Initialized`.
+    pub fn switch_chart_view(&mut self) {
+        if let Self::Initialized { chart_view, .. } = self {
+            *chart_view = chart_view.switch();
         }
     }
 
     /// Returns the current value of `filtered_events` if the state is `Initialized`.
-    /// TODO: filtered_events should be a vector of references into baby_events
     ///
     /// # Returns
     ///
@@ -237,6 +2430,28 @@ impl AppState {
         }
     }
 
+    /// Returns the currently selected filtered event if the state is `Initialized`.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(BabyEvent)` containing the selected event.
+    /// - `None` if nothing is selected or the state is not `Initialized`.
+    pub fn get_selected_event(&self) -> Option<BabyEvent> {
+        if let Self::Initialized {
+            filtered_events,
+            selection,
+            ..
+        } = self
+        {
+            selection
+                .selected()
+                .and_then(|i| filtered_events.get(i))
+                .copied()
+        } else {
+            None
+        }
+    }
+
     /// Returns the current value of `selection` if the state is `Initialized`.
     ///
     /// # Returns
@@ -340,16 +2555,42 @@ impl Default for AppState {
     }
 }
 
+/// Finds the caregiver following `current`, with nobody between the last and the first caregiver.
+///
+/// # Parameters
+///
+/// * `caregivers`: The caregivers, in order.
+/// * `current`: The id of the current caregiver, if any.
+///
+/// # Returns
+///
+/// The id of the next caregiver, `None` after the last one or if there are no caregivers.
+fn next_caregiver(caregivers: &[Caregiver], current: Option<i32>) -> Option<i32> {
+    match current.and_then(|id| caregivers.iter().position(|c| c.id == id)) {
+        Some(i) => caregivers.get(i + 1).map(|c| c.id),
+        None => caregivers.first().map(|c| c.id),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::migrate_database;
+    use diesel::Connection;
+
+    /// Opens an empty in-memory database.
+    fn connection() -> SqliteConnection {
+        let mut connection = SqliteConnection::establish(":memory:").unwrap();
+        migrate_database(&mut connection, ":memory:").unwrap();
+
+        connection
+    }
 
     #[test]
     fn test_initialized() {
         let state = AppState::initialized();
 
         assert!(state.is_initialized());
-        assert!(state.get_events().unwrap().is_empty());
         assert!(state.get_filtered_events().unwrap().is_empty());
         assert_eq!(
             state.get_filter().unwrap(),
@@ -358,17 +2599,17 @@ mod tests {
     }
 
     #[test]
-    fn test_get_events_not_initialized() {
+    fn test_get_filtered_events_not_initialized() {
         let state = AppState::default();
 
-        assert!(state.get_events().is_none());
+        assert!(state.get_filtered_events().is_none());
     }
 
     #[test]
     fn test_load_events_not_initialized() {
         let mut state = AppState::default();
 
-        state.load_events(None);
+        assert!(state.load_events(None).is_ok());
     }
 
     #[test]
@@ -382,27 +2623,28 @@ mod tests {
     fn test_switch_filter_not_initialized() {
         let mut state = AppState::default();
 
-        assert_eq!(state.switch_filter(), ());
+        assert!(state.switch_filter(None).is_ok());
     }
 
     #[test]
     fn test_switch_filter() {
         let mut state = AppState::initialized();
+        let conn = &mut connection();
         let test_date = NaiveDate::default();
 
         // test that the initialized state has the default filter
         assert_eq!(state.get_filter().unwrap(), &Filter::Day(test_date));
 
         // test switching from day to week
-        state.switch_filter();
+        state.switch_filter(Some(conn)).unwrap();
         assert_eq!(state.get_filter().unwrap(), &Filter::Week(test_date));
 
         // test switching from week to month
-        state.switch_filter();
+        state.switch_filter(Some(conn)).unwrap();
         assert_eq!(state.get_filter().unwrap(), &Filter::Month(test_date));
 
         // test switching from month to day
-        state.switch_filter();
+        state.switch_filter(Some(conn)).unwrap();
         assert_eq!(state.get_filter().unwrap(), &Filter::Day(test_date));
     }
 
@@ -415,6 +2657,138 @@ mod tests {
         assert_eq!(format!("{}", Filter::Month(test_date)), "Month");
     }
 
+    #[test]
+    fn test_shift_filter() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+
+        assert_eq!(
+            Filter::Day(date).shift(1),
+            Filter::Day(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap())
+        );
+        assert_eq!(
+            Filter::Week(date).shift(-1),
+            Filter::Week(NaiveDate::from_ymd_opt(2024, 1, 24).unwrap())
+        );
+
+        // months clamp to the last day of shorter months
+        assert_eq!(
+            Filter::Month(date).shift(1),
+            Filter::Month(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap())
+        );
+        assert_eq!(
+            Filter::Month(date).shift(-2),
+            Filter::Month(NaiveDate::from_ymd_opt(2023, 11, 30).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let date = NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+        let dt = date.and_hms_opt(5, 15, 0).unwrap();
+        let last_year = NaiveDate::from_ymd_opt(2022, 6, 15)
+            .unwrap()
+            .and_hms_opt(5, 15, 0)
+            .unwrap();
+
+        // months match the year as well
+        assert!(Filter::Month(date).matches(&dt));
+        assert!(!Filter::Month(date).matches(&last_year));
+        assert!(!Filter::Week(date).matches(&last_year));
+
+        // ranges include both ends
+        let range = Filter::Range(date - Duration::days(1), date);
+        assert!(range.matches(&(dt - Duration::days(1))));
+        assert!(range.matches(&date.and_hms_opt(23, 59, 59).unwrap()));
+        assert!(!range.matches(&(dt + Duration::days(1))));
+
+        // rolling windows are measured in time rather than calendar days
+        let rolling = Filter::Rolling(dt, Duration::hours(24));
+        assert!(rolling.matches(&(dt - Duration::hours(24))));
+        assert!(rolling.matches(&(dt - Duration::minutes(1))));
+        assert!(!rolling.matches(&dt));
+        assert!(!rolling.matches(&(dt - Duration::hours(25))));
+    }
+
+    #[test]
+    fn test_filter_utc_bounds() {
+        use chrono_tz::Europe::Berlin;
+
+        let at = |date: NaiveDate, hour: u32| date.and_hms_opt(hour, 0, 0).unwrap();
+
+        // the day the clocks go back is 25 hours long
+        let date = NaiveDate::from_ymd_opt(2023, 10, 29).unwrap();
+        assert_eq!(
+            Filter::Day(date).utc_bounds(&Berlin),
+            (at(date.pred_opt().unwrap(), 22), at(date, 23))
+        );
+
+        // rolling windows keep their duration
+        let rolling = Filter::Rolling(at(date, 12), Duration::hours(24));
+        assert_eq!(
+            rolling.utc_bounds(&Berlin),
+            (at(date.pred_opt().unwrap(), 11), at(date, 11))
+        );
+    }
+
+    #[test]
+    fn test_range_and_rolling_filters() {
+        let date = NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+        let dt = date.and_hms_opt(5, 15, 30).unwrap();
+        let range = Filter::Range(date - Duration::days(2), date);
+        let rolling = Filter::Rolling(dt, Duration::days(7));
+
+        // ranges move by their own length
+        assert_eq!(
+            range.shift(-1),
+            Filter::Range(date - Duration::days(5), date - Duration::days(3))
+        );
+        assert_eq!(
+            range.with_date(date + Duration::days(1)),
+            Filter::Range(date - Duration::days(1), date + Duration::days(1))
+        );
+        assert_eq!(
+            rolling.shift(1),
+            Filter::Rolling(dt + Duration::days(7), Duration::days(7))
+        );
+
+        // including a later event moves a rolling window to end just after it
+        let later = dt + Duration::hours(1);
+        assert_eq!(
+            rolling.including(later),
+            Filter::Rolling(date.and_hms_opt(6, 16, 0).unwrap(), Duration::days(7))
+        );
+        assert_eq!(rolling.including(dt - Duration::hours(1)), rolling);
+
+        // switching leaves the range for the day it ends on
+        assert_eq!(range.switch(), Filter::Day(date));
+        assert_eq!(rolling.switch(), Filter::Day(date));
+
+        assert_eq!(range.to_string(), "2023-06-13 to 2023-06-15");
+        assert_eq!(rolling.to_string(), "Last 7 days");
+        assert_eq!(
+            Filter::Rolling(dt, Duration::hours(24)).to_string(),
+            "Last 24 hours"
+        );
+    }
+
+    #[test]
+    fn test_move_filter() {
+        let mut state = AppState::initialized();
+        let conn = &mut connection();
+        let date = NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+
+        state.switch_filter(Some(conn)).unwrap();
+        state.move_filter(date, Some(conn)).unwrap();
+
+        assert_eq!(state.get_filter().unwrap(), &Filter::Week(date));
+        assert!(state.get_selection().unwrap().selected().is_none());
+
+        // moving an uninitialized state does nothing
+        let mut state = AppState::default();
+        assert!(state.move_filter(date, None).is_ok());
+        assert!(state.get_filter().is_none());
+    }
+
     #[test]
     fn test_unselect() {
         let mut state = AppState::initialized();
@@ -432,4 +2806,32 @@ mod tests {
 
         assert!(state.get_selection().is_none());
     }
+
+    #[test]
+    fn test_switch_chart() {
+        let mut state = AppState::initialized();
+
+        assert_eq!(
+            state.get_chart(),
+            Some((ChartMetric::Intake, ChartView::Bar))
+        );
+
+        state.switch_chart_metric();
+        state.switch_chart_view();
+
+        assert_eq!(
+            state.get_chart(),
+            Some((ChartMetric::Pumped, ChartView::Line))
+        );
+    }
+
+    #[test]
+    fn test_switch_chart_not_initialized() {
+        let mut state = AppState::default();
+
+        state.switch_chart_metric();
+        state.switch_chart_view();
+
+        assert!(state.get_chart().is_none());
+    }
 }
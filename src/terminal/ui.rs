@@ -1,14 +1,32 @@
-use chrono::{Datelike, NaiveDateTime};
+use chrono::{Datelike, Local, NaiveDateTime};
 use ratatui::{
     prelude::*,
-    widgets::{calendar::*, *},
+    widgets::{
+        block::{Position, Title},
+        calendar::*,
+        *,
+    },
     Frame,
 };
-use std::vec;
+use std::{collections::BTreeMap, vec};
 use time::{Date, Month};
 
-use crate::terminal::app::{Actions, App};
-use crate::terminal::state::{AppState, Filter};
+use crate::medication::DoseStatus;
+use crate::models::{
+    BabyEvent, Caregiver, Child, DiaperDetails, EventField, EventHistory, EventNote, EventRegistry,
+    FieldKind, HistoryAction, Medication,
+};
+use crate::terminal::app::{Actions, App, Popup};
+use crate::terminal::browser::{CsvPreview, FileBrowser};
+use crate::terminal::chart::{ChartData, ChartView, GrowthChart};
+use crate::terminal::form::{
+    CaregiverForm, ChildForm, CustomEventForm, DiaperForm, EventForm, EventTypeForm, FieldValue,
+    FilterPrompt, FormField, MeasurementForm, MedicationForm, SearchPrompt,
+};
+use crate::terminal::state::AppState;
+use crate::units::VolumeUnit;
+use crate::zone;
+use crate::{calculate_caregiver_statistics, calculate_sleep_statistics, calculate_statistics};
 
 /// Renders the user interface.
 ///
@@ -28,15 +46,48 @@ pub fn draw_ui(rect: &mut Frame, app: &mut App) {
     let size = rect.size();
     check_size(&size);
 
+    // The menu wraps onto as many lines as it needs inside the borders
+    let menu = menu_lines(app.actions(), size.width - 2);
+
     // Vertical layout
     let vertical_chunks = Layout::new(
         Direction::Vertical,
-        [Constraint::Length(3), Constraint::Min(0)],
+        [
+            Constraint::Length(menu.len() as u16 + 2),
+            Constraint::Min(0),
+        ],
     )
     .split(size);
 
-    // Title and menu
-    let title_and_menu = draw_title_and_menu(app.actions());
+    // Title with the child switcher, the current caregiver and warnings, and menu
+    let now = chrono::Utc::now().naive_utc();
+    let statuses = app
+        .state
+        .get_dose_statuses(now)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(medication, status)| (medication.clone(), status))
+        .collect::<Vec<(Medication, DoseStatus)>>();
+    let overdue = statuses
+        .iter()
+        .filter(|(_, status)| status.is_overdue(now))
+        .map(|(medication, _)| medication.name.as_str())
+        .collect::<Vec<&str>>();
+    let mut warnings = vec![];
+    if !overdue.is_empty() {
+        warnings.push(format!("{} overdue", overdue.join(", ")));
+    }
+    for (event, color) in app.state.get_concerning_stools().unwrap_or_default() {
+        let time = zone::to_local(&Local, event.dt).format("%H:%M");
+        warnings.push(format!("{} stool at {}", color, time));
+    }
+    let title_and_menu = draw_title_and_menu(
+        menu,
+        app.state.get_children().unwrap_or_default(),
+        app.state.get_child(),
+        app.state.get_caregiver(),
+        &warnings,
+    );
     rect.render_widget(title_and_menu, vertical_chunks[0]);
 
     // Horizontal layout for body
@@ -49,7 +100,7 @@ pub fn draw_ui(rect: &mut Frame, app: &mut App) {
     // Vertical layout for calendar and events
     let side_chunks = Layout::new(
         Direction::Vertical,
-        Constraint::from_mins([9, vertical_chunks[1].height - 9]),
+        Constraint::from_mins([9, vertical_chunks[1].height.saturating_sub(9)]),
     )
     .split(horizontal_chunks[0]);
 
@@ -68,7 +119,7 @@ pub fn draw_ui(rect: &mut Frame, app: &mut App) {
     // Vertical layout for details and graphing
     let data_chunks = Layout::new(
         Direction::Vertical,
-        Constraint::from_mins([12, vertical_chunks[1].height - 12]),
+        Constraint::from_mins([13, vertical_chunks[1].height.saturating_sub(13)]),
     )
     .split(horizontal_chunks[1]);
 
@@ -87,42 +138,281 @@ pub fn draw_ui(rect: &mut Frame, app: &mut App) {
     let statistics = draw_statistics(app.state());
     rect.render_widget(statistics, detail_chunks[1]);
 
-    // Chart
-    let chart = draw_chart(app.state());
-    rect.render_widget(chart, data_chunks[1]);
+    // Growth chart, in place of the chart
+    if let Some(measure) = app.state.get_growth_measure() {
+        let child = app.state.get_child();
+        let measurements = app.state.get_measurements().unwrap();
+
+        match child.and_then(|child| GrowthChart::new(child, measurements, measure)) {
+            Some(growth) => rect.render_widget(draw_growth_chart(&growth), data_chunks[1]),
+            None => {
+                let name = child.map_or("the child".to_owned(), |child| child.name.clone());
+                rect.render_widget(draw_growth_placeholder(&name), data_chunks[1]);
+            }
+        }
+    } else {
+        draw_chart(rect, &app.state, data_chunks[1]);
+    }
+
+    // Popup
+    let unit = app.state().get_volume_unit();
+    let caregivers = app.state().get_caregivers().unwrap_or_default().to_vec();
+    let registry = app
+        .state()
+        .get_event_registry()
+        .cloned()
+        .unwrap_or_default();
+    match app.popup_mut() {
+        Some(Popup::EventForm(form)) => {
+            let area = centered_rect(56, form.fields().len() as u16 + 6, size);
+            rect.render_widget(Clear, area);
+            rect.render_widget(draw_event_form(form), area);
+        }
+        Some(Popup::ChildForm(form)) => {
+            let area = centered_rect(56, form.fields().len() as u16 + 6, size);
+            rect.render_widget(Clear, area);
+            rect.render_widget(draw_child_form(form), area);
+        }
+        Some(Popup::CaregiverForm(form)) => {
+            let area = centered_rect(56, form.fields().len() as u16 + 6, size);
+            rect.render_widget(Clear, area);
+            rect.render_widget(draw_caregiver_form(form), area);
+        }
+        Some(Popup::MeasurementForm(form)) => {
+            let area = centered_rect(56, form.fields().len() as u16 + 6, size);
+            rect.render_widget(Clear, area);
+            rect.render_widget(draw_measurement_form(form), area);
+        }
+        Some(Popup::Medications(selection)) => {
+            let area = centered_rect(76, statuses.len().max(1) as u16 + 4, size);
+            rect.render_widget(Clear, area);
+            rect.render_stateful_widget(draw_medications(&statuses, now), area, selection);
+        }
+        Some(Popup::MedicationForm(form)) => {
+            let area = centered_rect(56, form.fields().len() as u16 + 6, size);
+            rect.render_widget(Clear, area);
+            rect.render_widget(draw_medication_form(form), area);
+        }
+        Some(Popup::DiaperForm(form)) => {
+            // the error lists every choice and may wrap onto a second line
+            let area = centered_rect(76, form.fields().len() as u16 + 7, size);
+            rect.render_widget(Clear, area);
+            rect.render_widget(draw_diaper_form(form), area);
+        }
+        Some(Popup::EventTypes(selection)) => {
+            let area = centered_rect(76, registry.types.len().max(1) as u16 + 4, size);
+            rect.render_widget(Clear, area);
+            rect.render_stateful_widget(draw_event_types(&registry), area, selection);
+        }
+        Some(Popup::EventTypeForm(form)) => {
+            // the error lists every kind and may wrap onto a second line
+            let area = centered_rect(76, form.fields().len() as u16 + 7, size);
+            rect.render_widget(Clear, area);
+            rect.render_widget(draw_event_type_form(form), area);
+        }
+        Some(Popup::CustomEventForm(form)) => {
+            let area = centered_rect(56, form.fields().len() as u16 + 6, size);
+            rect.render_widget(Clear, area);
+            rect.render_widget(draw_custom_event_form(form), area);
+        }
+        Some(Popup::ConfirmDelete(event)) => {
+            let area = centered_rect(50, 8, size);
+            rect.render_widget(Clear, area);
+            rect.render_widget(draw_delete_confirmation(event, &registry, unit), area);
+        }
+        Some(Popup::FileBrowser(browser)) => {
+            let area = centered_rect(76, 20, size);
+            rect.render_widget(Clear, area);
+
+            match browser.preview() {
+                Some(preview) => rect.render_widget(draw_csv_preview(preview, unit), area),
+                None => {
+                    let file_list = draw_file_browser(browser);
+                    rect.render_stateful_widget(file_list, area, browser.selection());
+                }
+            }
+        }
+        Some(Popup::FilterPrompt(prompt)) => {
+            let area = centered_rect(56, 7, size);
+            rect.render_widget(Clear, area);
+            rect.render_widget(draw_filter_prompt(prompt), area);
+        }
+        Some(Popup::SearchPrompt(prompt)) => {
+            let area = centered_rect(56, 7, size);
+            rect.render_widget(Clear, area);
+            rect.render_widget(draw_search_prompt(prompt), area);
+        }
+        Some(Popup::SearchResults { results, selection }) => {
+            let area = centered_rect(76, (results.len() as u16 + 2).min(20), size);
+            rect.render_widget(Clear, area);
+            rect.render_stateful_widget(draw_search_results(results), area, selection);
+        }
+        Some(Popup::Trash { events, selection }) => {
+            let area = centered_rect(76, (events.len() as u16 + 2).min(20), size);
+            rect.render_widget(Clear, area);
+            rect.render_stateful_widget(draw_trash(events, &registry, unit), area, selection);
+        }
+        Some(Popup::History { entries, selection }) => {
+            let area = centered_rect(76, (entries.len() as u16 + 2).min(20), size);
+            rect.render_widget(Clear, area);
+            let selected = selection.selected().and_then(|i| entries.get(i));
+            rect.render_stateful_widget(
+                draw_history(entries, selected, &caregivers),
+                area,
+                selection,
+            );
+        }
+        Some(Popup::Message { title, text }) => {
+            let area = centered_rect(60, 7, size);
+            rect.render_widget(Clear, area);
+            rect.render_widget(draw_message(title, text), area);
+        }
+        None => {}
+    }
+}
+
+/// Draws the chart of the current metric of the filtered events.
+///
+/// # Arguments
+///
+/// - `rect`: The frame on which to draw the chart.
+/// - `state`: Current `AppState` to chart.
+/// - `area`: The area the chart is drawn in.
+fn draw_chart(rect: &mut Frame, state: &AppState, area: Rect) {
+    let (chart_metric, chart_view) = state.get_chart().unwrap();
+    let chart_data = ChartData::new(
+        state.get_filter().unwrap(),
+        state.get_filtered_events().unwrap(),
+        chart_metric,
+        chart_view,
+        state.get_volume_unit(),
+        &Local,
+    );
+    let chart_title = format!(" {} ", chart_metric.title(state.get_volume_unit()));
+    match chart_view {
+        ChartView::Bar => {
+            let chart = draw_bar_chart(&chart_data, chart_title, area.width);
+            rect.render_widget(chart, area);
+        }
+        ChartView::Line => {
+            let points = chart_data
+                .series
+                .iter()
+                .map(|(_, values)| {
+                    values
+                        .iter()
+                        .enumerate()
+                        .map(|(x, y)| (x as f64, *y as f64))
+                        .collect::<Vec<(f64, f64)>>()
+                })
+                .collect::<Vec<Vec<(f64, f64)>>>();
+            let chart = draw_line_chart(&chart_data, &points, chart_title);
+            rect.render_widget(chart, area);
+        }
+    }
 }
 
-/// Creates a `Table` widget for the title and menu.
+/// Creates a `Paragraph` widget for the title and menu.
+///
+/// The title lists the children when there is more than one, highlighting the child whose events are shown, followed by
+/// the warnings, such as medications whose scheduled dose is overdue or stools with a concerning color.
+///
+/// # Arguments
+///
+/// - `menu`: The lines of the menu, as returned by [`menu_lines`].
+/// - `children`: The children in the database.
+/// - `child`: The child whose events are shown.
+/// - `warnings`: The warnings to show in red.
 ///
 /// # Returns
 ///
-/// Returns a `Table` widget configured to display the title and application menu.
-fn draw_title_and_menu<'a>(actions: &Actions) -> Table<'a> {
-    let mut menu_items = vec![];
+/// Returns a `Paragraph` widget configured to display the title and application menu.
+fn draw_title_and_menu<'a>(
+    menu: Vec<Line<'a>>,
+    children: &[Child],
+    child: Option<&Child>,
+    caregiver: Option<&Caregiver>,
+    warnings: &[String],
+) -> Paragraph<'a> {
+    let mut title = vec![Span::styled(" Babyrs ", Style::new().blue().bold())];
 
-    for action in actions.actions().iter() {
-        menu_items.push(Cell::from(Line::from(vec![
-            Span::styled(
-                format!("<{}> ", action.keys()[0]),
-                Style::default().fg(Color::Yellow),
-            ),
-            Span::styled(action.to_string(), Style::default().fg(Color::White)),
-        ])));
+    if children.len() > 1 {
+        for c in children {
+            let style = if Some(c.id) == child.map(|child| child.id) {
+                Style::new().yellow().bold().reversed()
+            } else {
+                Style::new().dark_gray()
+            };
+
+            title.push(Span::raw(" "));
+            title.push(Span::styled(format!(" {} ", c.name), style));
+        }
+        title.push(Span::raw(" "));
     }
 
-    // A single row with the menu items
-    Table::new(
-        vec![Row::new(menu_items)],
-        &Constraint::from_mins([9, 12, 12, 13, 11, 19, 14, 10]),
-    )
-    .block(
+    if let Some(caregiver) = caregiver {
+        title.push(Span::raw(" "));
+        title.push(Span::styled(
+            format!(" by {} ", caregiver.name),
+            Style::new().green().bold().reversed(),
+        ));
+        title.push(Span::raw(" "));
+    }
+
+    if !warnings.is_empty() {
+        title.push(Span::raw(" "));
+        title.push(Span::styled(
+            format!(" {} ", warnings.join(", ")),
+            Style::new().red().bold().reversed(),
+        ));
+        title.push(Span::raw(" "));
+    }
+
+    Paragraph::new(menu).block(
         Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Plain)
-            .title(" Babyrs ")
-            .title_style(Style::new().blue().bold()),
+            .title(Line::from(title)),
     )
-    .column_spacing(1)
+}
+
+/// Lays out the menu items, wrapping them onto as many lines as needed to fit `width`.
+///
+/// # Arguments
+///
+/// - `actions`: The actions to list in the menu.
+/// - `width`: The width available for the menu.
+///
+/// # Returns
+///
+/// Returns the lines of the menu, at least one.
+fn menu_lines<'a>(actions: &Actions, width: u16) -> Vec<Line<'a>> {
+    let mut lines = vec![Line::default()];
+    let mut line_width = 0;
+
+    for action in actions.actions().iter() {
+        // modified keys already render their own brackets
+        let key = match action.keys()[0].to_string() {
+            key if key.starts_with('<') => format!("{} ", key),
+            key => format!("<{}> ", key),
+        };
+        let label = format!("{} ", action);
+        let item_width = key.len() + label.len();
+
+        if line_width > 0 && line_width + item_width > width as usize {
+            lines.push(Line::default());
+            line_width = 0;
+        }
+
+        let line = lines.last_mut().unwrap();
+        line.spans
+            .push(Span::styled(key, Style::default().fg(Color::Yellow)));
+        line.spans
+            .push(Span::styled(label, Style::default().fg(Color::White)));
+        line_width += item_width;
+    }
+
+    lines
 }
 
 /// Creates a `Calendar` widget.
@@ -140,20 +430,12 @@ fn draw_calendar<'a>(
     let filter = state.get_filter().unwrap();
     let mut calendar_dates: CalendarEventStore = CalendarEventStore::default();
 
-    // Get events based on the current filter enum
+    // Get the events within the current filter
     let items = state
-        .get_events()
+        .get_filtered_events()
         .unwrap()
         .iter()
-        .filter(|e| match filter {
-            Filter::Day(date) => &e.dt.date() == date,
-            Filter::Week(week) => week
-                .week(chrono::Weekday::Mon)
-                .days()
-                .contains(&e.dt.date()),
-            Filter::Month(month) => e.dt.date().month() == month.month(),
-        })
-        .map(|e| e.dt)
+        .map(|e| zone::to_local(&Local, e.dt))
         .collect::<Vec<NaiveDateTime>>();
 
     // add events to the calendar based on the filter and highlight them
@@ -162,13 +444,7 @@ fn draw_calendar<'a>(
     }
 
     // get the current filter selection date
-    let calendar_selection_date = match filter {
-        Filter::Day(date) => *date,
-        Filter::Week(week) => *week,
-        Filter::Month(month) => *month,
-    }
-    .and_hms_opt(0, 0, 0)
-    .unwrap();
+    let calendar_selection_date = filter.date().and_hms_opt(0, 0, 0).unwrap();
 
     // add the current filter selection to the calendar and highlight it
     calendar_dates.add(
@@ -185,6 +461,9 @@ fn draw_calendar<'a>(
 
 /// Creates a `List` widget containing baby_event datetime values.
 ///
+/// Once there are caregivers, the seconds and year make way for the caregiver who recorded the event. Events with a
+/// stool of a concerning color are shown in red.
+///
 /// # Arguments
 ///
 /// - `state`: Current `AppState` to display baby_events.
@@ -193,12 +472,44 @@ fn draw_calendar<'a>(
 ///
 /// Returns a `List` widget configured to display the body content.
 fn draw_event_list<'a>(state: &AppState) -> List<'a> {
+    let caregivers = state.get_caregivers().unwrap_or_default();
+    let registry = state.get_event_registry();
+
     // gather pre-filtered events
     let items = state
         .get_filtered_events()
         .unwrap()
         .iter()
-        .map(|e| ListItem::new(format!("{}", e.dt)))
+        .map(|e| {
+            let dt = zone::to_local(&Local, e.dt);
+            let event_type = e
+                .event_type_id
+                .and_then(|id| registry.and_then(|registry| registry.get_type(id)));
+
+            // the name of a user-defined type and the caregiver need the short timestamp to fit
+            let mut text = if caregivers.is_empty() && event_type.is_none() {
+                format!("{}", dt)
+            } else {
+                dt.format("%m-%d %H:%M").to_string()
+            };
+            if !caregivers.is_empty() {
+                let caregiver = caregivers.iter().find(|c| Some(c.id) == e.caregiver_id);
+                text = format!("{} {}", text, caregiver.map_or("-", |c| c.name.as_str()));
+            }
+            if let Some(event_type) = event_type {
+                text = format!("{} {}", text, event_type.name);
+            }
+            let item = ListItem::new(text);
+
+            if state
+                .get_diaper_details(e.id)
+                .is_some_and(|d| d.is_concerning())
+            {
+                item.red()
+            } else {
+                item
+            }
+        })
         .collect::<Vec<ListItem>>();
 
     // construct the list widget
@@ -232,25 +543,46 @@ fn draw_event_list<'a>(state: &AppState) -> List<'a> {
 /// Returns a `Paragraph` widget configured to display the details.
 fn draw_event_details<'a>(state: &AppState, selection: Option<usize>) -> Paragraph<'a> {
     let event = selection.map(|i| state.get_filtered_events().unwrap()[i]);
+    let unit = state.get_volume_unit();
+    let registry = state.get_event_registry();
+    let event_type = event
+        .and_then(|e| e.event_type_id)
+        .and_then(|id| registry.and_then(|registry| registry.get_type(id)));
 
     let text = match state {
         AppState::Init => "Welcome to babyrs! Press <q> to quit.".to_owned(),
-        AppState::Initialized { .. } => match event {
+        AppState::Initialized { .. } => match (event, event_type, registry) {
+            (Some(e), Some(event_type), Some(registry)) => format!(
+                "ID: {0} \n\rDate: {1} \n\rTime: {2} \n\rType: {3}{4} \n\rNotes: {5}",
+                e.id,
+                zone::to_local(&Local, e.dt).date(),
+                format_time(&e),
+                event_type.name,
+                format_values(
+                    &registry.fields_of(event_type.id),
+                    &state.get_event_values(e.id),
+                    unit
+                ),
+                state.get_note(e.id).unwrap_or("-"),
+            ),
             // TODO: is there a better way to construct a string that doesn't allocate to the heap? Also that isn't this ugly?
-            Some(e) => format!("ID: {0} \n\rDate: {1} \n\rTime: {2} \n\rStool: {3} \n\rUrine: {4} \n\rSkin-to-Skin(min): {5} \n\rBreastfeed(min): {6} \n\rBreastmilk(ml): {7} \n\rFormula(ml): {8} \n\rPump(ml): {9}",
+            (Some(e), _, _) => format!("ID: {0} \n\rDate: {1} \n\rTime: {2} \n\rStool: {3} \n\rUrine: {4} \n\rSkin-to-Skin(min): {5} \n\rBreastfeed(min): {6}{7} \n\rBreastmilk: {8} \n\rFormula: {9} \n\rPump: {10}{11} \n\rNotes: {12}",
                 e.id,
-                e.dt.date(),
-                e.dt.time(),
-                e.stool,
-                e.urine,
+                zone::to_local(&Local, e.dt).date(),
+                format_time(&e),
+                format_stool(e.stool, state.get_diaper_details(e.id)),
+                format_urine(e.urine, state.get_diaper_details(e.id)),
                 e.skin2skin,
                 e.breastfeed,
-                e.breastmilk,
-                e.formula,
-                e.pump,
+                format_sides(e.breastfeed_left, e.breastfeed_right),
+                unit.format(e.breastmilk),
+                unit.format(e.formula),
+                unit.format(e.pump),
+                format_volume_sides(unit, e.pump_left, e.pump_right),
+                state.get_note(e.id).unwrap_or("-"),
             )
             .to_owned(),
-            None => "No event selected.".to_owned(),
+            (None, _, _) => "No event selected.".to_owned(),
         },
     };
 
@@ -267,7 +599,7 @@ fn draw_event_details<'a>(state: &AppState, selection: Option<usize>) -> Paragra
         .alignment(Alignment::Left)
 }
 
-/// Creates a `Paragraph` widget containing statistics
+/// Creates a `Paragraph` widget containing statistics for the filtered events.
 ///
 /// # Arguments
 ///
@@ -278,8 +610,107 @@ fn draw_event_details<'a>(state: &AppState, selection: Option<usize>) -> Paragra
 /// Returns a `Paragraph` widget configured to display the statistics.
 fn draw_statistics<'a>(state: &AppState) -> Paragraph<'a> {
     let text = match state {
-        AppState::Init => "Not implemented...".to_owned(),
-        AppState::Initialized { .. } => "Not Implemented...".to_owned(),
+        AppState::Init => vec![Line::from("No statistics available.")],
+        AppState::Initialized {
+            caregivers,
+            filter,
+            filtered_events,
+            sleep_sessions,
+            open_sleep,
+            next_side,
+            volume_unit: unit,
+            ..
+        } => {
+            let stats = calculate_statistics(filtered_events.clone());
+            let (start, end) = filter.utc_bounds(&Local);
+            let now = chrono::Utc::now().naive_utc();
+            let sleep = calculate_sleep_statistics(sleep_sessions.clone(), start, end, now, &Local);
+            let interval = |minutes: Option<i64>| match minutes {
+                Some(minutes) => format_minutes(minutes),
+                None => "-".to_owned(),
+            };
+
+            let mut lines = vec![
+                Line::from(format!(
+                    "Intake: {} (breastmilk {}, formula {})",
+                    unit.format(stats.intake()),
+                    unit.format_value(stats.breastmilk),
+                    unit.format_value(stats.formula)
+                )),
+                Line::from(format!(
+                    "Pumped: {}{}{}",
+                    unit.format(stats.pumped),
+                    format_volume_sides(*unit, stats.pumped_left, stats.pumped_right),
+                    match stats.pump_duration {
+                        0 => String::new(),
+                        minutes => format!(" in {}", format_minutes(minutes as i64)),
+                    }
+                )),
+                Line::from(format!(
+                    "Diapers: {} wet, {} dirty",
+                    stats.wet_diapers, stats.poopy_diapers
+                )),
+                Line::from(format!(
+                    "Breastfeeding: {}{}, next side {}",
+                    format_minutes(stats.breastfeed as i64),
+                    if stats.breastfeed_left == 0 && stats.breastfeed_right == 0 {
+                        String::new()
+                    } else {
+                        format!(
+                            " (L {}, R {})",
+                            format_minutes(stats.breastfeed_left as i64),
+                            format_minutes(stats.breastfeed_right as i64)
+                        )
+                    },
+                    next_side.map_or("-".to_owned(), |side| side.to_string())
+                )),
+                Line::from(format!(
+                    "Skin-to-skin: {}",
+                    format_minutes(stats.skin2skin as i64)
+                )),
+                Line::from(format!(
+                    "Feeding interval: mean {}, longest {}",
+                    interval(stats.mean_feeding_interval),
+                    interval(stats.longest_feeding_interval)
+                )),
+                Line::from(format!(
+                    "Sleep: {} (day {}, night {}), longest {}, {} night wakings",
+                    format_minutes(sleep.total() as i64),
+                    format_minutes(sleep.day as i64),
+                    format_minutes(sleep.night as i64),
+                    interval(sleep.longest.map(i64::from)),
+                    sleep.night_wakings
+                )),
+                Line::from(match open_sleep {
+                    Some(session) => {
+                        let start = zone::to_local(&Local, session.start_dt);
+                        format!("Asleep since {}", start.format("%Y-%m-%d %H:%M"))
+                    }
+                    None => "Awake".to_owned(),
+                }),
+            ];
+
+            // who did the feeds, and the night feeds
+            if !caregivers.is_empty() {
+                let feedings = calculate_caregiver_statistics(filtered_events.clone(), &Local)
+                    .iter()
+                    .filter(|c| c.feedings > 0)
+                    .map(|c| {
+                        let name = caregivers
+                            .iter()
+                            .find(|caregiver| Some(caregiver.id) == c.caregiver_id)
+                            .map_or("unknown", |caregiver| caregiver.name.as_str());
+                        format!("{} {} ({} at night)", name, c.feedings, c.night_feedings)
+                    })
+                    .collect::<Vec<String>>();
+
+                if !feedings.is_empty() {
+                    lines.push(Line::from(format!("Feedings: {}", feedings.join(", "))));
+                }
+            }
+
+            lines
+        }
     };
 
     // construct the paragraph widget
@@ -288,36 +719,1282 @@ fn draw_statistics<'a>(state: &AppState) -> Paragraph<'a> {
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Plain)
-                .title(format!(" {} Statistics ", state.get_filter().unwrap()))
+                .title(match state.get_caregiver_filter() {
+                    Some(caregiver) => format!(
+                        " {} Statistics by {} ",
+                        state.get_filter().unwrap(),
+                        caregiver.name
+                    ),
+                    None => format!(" {} Statistics ", state.get_filter().unwrap()),
+                })
                 .title_style(Style::new().blue().bold()),
         )
         .style(Style::default().fg(Color::White))
         .alignment(Alignment::Left)
+        .wrap(Wrap { trim: true })
 }
 
-/// Creates a `BarChart` widget containing statistics
+/// Creates a `BarChart` widget containing the charted metric.
 ///
 /// # Arguments
 ///
-/// - `state`: Current `AppState` to display statistics.
+/// - `data`: The `ChartData` to display, only the first series is drawn.
+/// - `title`: The title of the chart.
+/// - `width`: The width of the area the chart is drawn in, used to size the bars.
 ///
 /// # Returns
 ///
-/// Returns a `BarChart` widget configured to display the statistics.
-fn draw_chart<'a>(_state: &AppState) -> BarChart<'a> {
+/// Returns a `BarChart` widget with one labelled bar per period.
+fn draw_bar_chart<'a>(data: &ChartData, title: String, width: u16) -> BarChart<'a> {
+    let bars = match data.series.first() {
+        Some((_, values)) => data
+            .labels
+            .iter()
+            .zip(values)
+            .map(|(label, value)| {
+                Bar::default()
+                    .value(*value)
+                    .label(Line::from(label.clone()))
+            })
+            .collect::<Vec<Bar>>(),
+        None => vec![],
+    };
+
+    // fit every bar, separated by a gap of 1, inside the borders
+    let count = bars.len().max(1) as u16;
+    let bar_width = ((width.saturating_sub(2) + 1) / count)
+        .saturating_sub(1)
+        .clamp(1, 6);
+
     // construct the BarChart widget
     BarChart::default()
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_type(BorderType::Plain),
+                .border_type(BorderType::Plain)
+                .title(title)
+                .title_style(Style::new().blue().bold()),
         )
         .style(Style::default().fg(Color::White))
-        .bar_width(2)
-        .data(&[("B0", 0), ("B1", 2), ("B2", 4), ("B3", 3)])
+        .bar_width(bar_width)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Yellow))
+        .data(BarGroup::default().bars(&bars))
 }
 
-/// Validates the terminal size to ensure it meets minimum requirements.
+/// Creates a `Chart` widget drawing each series of the charted metric as a line.
+///
+/// # Arguments
+///
+/// - `data`: The `ChartData` to display.
+/// - `points`: The points of each series, indexed by period.
+/// - `title`: The title of the chart.
+///
+/// # Returns
+///
+/// Returns a `Chart` widget with one dataset per series.
+fn draw_line_chart<'a>(
+    data: &'a ChartData,
+    points: &'a [Vec<(f64, f64)>],
+    title: String,
+) -> Chart<'a> {
+    let colors = [Color::Yellow, Color::Cyan];
+
+    let datasets = data
+        .series
+        .iter()
+        .zip(points)
+        .zip(colors.iter().cycle())
+        .map(|(((name, _), points), color)| {
+            Dataset::default()
+                .name(name.clone())
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(*color))
+                .data(points)
+        })
+        .collect::<Vec<Dataset>>();
+
+    // label the first, middle and last period
+    let last = data.labels.len().saturating_sub(1);
+    let x_labels = [0, last / 2, last]
+        .iter()
+        .filter_map(|i| data.labels.get(*i))
+        .map(|label| Span::raw(label.clone()))
+        .collect::<Vec<Span>>();
+
+    let max = data.max().max(1);
+    let y_labels = vec![
+        Span::raw("0"),
+        Span::raw((max / 2).to_string()),
+        Span::raw(max.to_string()),
+    ];
+
+    // construct the Chart widget
+    Chart::new(datasets)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(title)
+                .title_style(Style::new().blue().bold()),
+        )
+        .style(Style::default().fg(Color::White))
+        .x_axis(Axis::default().bounds([0.0, last as f64]).labels(x_labels))
+        .y_axis(Axis::default().bounds([0.0, max as f64]).labels(y_labels))
+}
+
+/// Creates a `Chart` widget plotting growth measurements against the WHO percentile curves.
+///
+/// # Arguments
+///
+/// - `growth`: The `GrowthChart` to display.
+///
+/// # Returns
+///
+/// Returns a `Chart` widget with a gray line per percentile curve and the measurements as points.
+fn draw_growth_chart(growth: &GrowthChart) -> Chart<'_> {
+    let mut datasets = growth
+        .curves
+        .iter()
+        .map(|(name, curve)| {
+            Dataset::default()
+                .name(name.clone())
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::DarkGray))
+                .data(curve)
+        })
+        .collect::<Vec<Dataset>>();
+    datasets.push(
+        Dataset::default()
+            .name(growth.measure.unit())
+            .marker(symbols::Marker::Dot)
+            .graph_type(GraphType::Scatter)
+            .style(Style::default().fg(Color::Yellow))
+            .data(&growth.points),
+    );
+
+    let [x_min, x_max] = growth.x_bounds;
+    let x_labels = vec![
+        Span::raw(format!("{:.0}", x_min)),
+        Span::raw(format!("{:.0} months", (x_min + x_max) / 2.0)),
+        Span::raw(format!("{:.0}", x_max)),
+    ];
+    let [y_min, y_max] = growth.y_bounds;
+    let y_labels = vec![
+        Span::raw(format!("{:.0}", y_min)),
+        Span::raw(format!("{:.1}", (y_min + y_max) / 2.0)),
+        Span::raw(format!("{:.0}", y_max)),
+    ];
+
+    // construct the Chart widget
+    Chart::new(datasets)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(growth.title())
+                .title_style(Style::new().blue().bold()),
+        )
+        .style(Style::default().fg(Color::White))
+        .x_axis(Axis::default().bounds(growth.x_bounds).labels(x_labels))
+        .y_axis(Axis::default().bounds(growth.y_bounds).labels(y_labels))
+}
+
+/// Creates a `Paragraph` widget explaining why the growth chart cannot be drawn.
+///
+/// # Arguments
+///
+/// - `name`: The name of the child.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget asking for the birth date and sex of the child.
+fn draw_growth_placeholder<'a>(name: &str) -> Paragraph<'a> {
+    Paragraph::new(format!(
+        "Set the birth date and sex of {} with <e> to compare their growth with the WHO standards.",
+        name
+    ))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain)
+            .title(" Growth ")
+            .title_style(Style::new().blue().bold()),
+    )
+    .style(Style::default().fg(Color::White))
+    .wrap(Wrap { trim: true })
+}
+
+/// Creates a `Paragraph` widget containing the event form.
+///
+/// # Arguments
+///
+/// - `form`: The `EventForm` to display.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget configured to display the form fields, validation error and key help.
+fn draw_event_form<'a>(form: &EventForm) -> Paragraph<'a> {
+    let title = match form.id() {
+        Some(_) => " Edit Event ",
+        None => " Add Event ",
+    };
+
+    draw_form(title, form.fields(), form.focus(), form.error())
+}
+
+/// Creates a `Paragraph` widget containing the child form.
+///
+/// # Arguments
+///
+/// - `form`: The `ChildForm` to display.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget configured to display the form fields, validation error and key help.
+fn draw_child_form<'a>(form: &ChildForm) -> Paragraph<'a> {
+    let title = match form.id() {
+        Some(_) => " Edit Child ",
+        None => " Add Child ",
+    };
+
+    draw_form(title, form.fields(), form.focus(), form.error())
+}
+
+/// Creates a `Paragraph` widget containing the caregiver form.
+///
+/// # Arguments
+///
+/// - `form`: The `CaregiverForm` to display.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget configured to display the form fields, validation error and key help.
+fn draw_caregiver_form<'a>(form: &CaregiverForm) -> Paragraph<'a> {
+    draw_form(" Add Caregiver ", form.fields(), form.focus(), form.error())
+}
+
+/// Creates a `Paragraph` widget containing the diaper form.
+///
+/// # Arguments
+///
+/// - `form`: The `DiaperForm` to display.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget configured to display the form fields, validation error and key help.
+fn draw_diaper_form<'a>(form: &DiaperForm) -> Paragraph<'a> {
+    draw_form(
+        " Diaper Details ",
+        form.fields(),
+        form.focus(),
+        form.error(),
+    )
+}
+
+/// Creates a `Paragraph` widget containing the measurement form.
+///
+/// # Arguments
+///
+/// - `form`: The `MeasurementForm` to display.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget configured to display the form fields, validation error and key help.
+fn draw_measurement_form<'a>(form: &MeasurementForm) -> Paragraph<'a> {
+    draw_form(
+        " Add Measurement ",
+        form.fields(),
+        form.focus(),
+        form.error(),
+    )
+}
+
+/// Creates a `Paragraph` widget containing the medication form.
+///
+/// # Arguments
+///
+/// - `form`: The `MedicationForm` to display.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget configured to display the form fields, validation error and key help.
+fn draw_medication_form<'a>(form: &MedicationForm) -> Paragraph<'a> {
+    draw_form(
+        " Add Medication ",
+        form.fields(),
+        form.focus(),
+        form.error(),
+    )
+}
+
+/// Creates a `Paragraph` widget containing the event type form.
+///
+/// # Arguments
+///
+/// - `form`: The `EventTypeForm` to display.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget configured to display the form fields, validation error and key help.
+fn draw_event_type_form<'a>(form: &EventTypeForm) -> Paragraph<'a> {
+    draw_form(
+        " Add Event Type ",
+        form.fields(),
+        form.focus(),
+        form.error(),
+    )
+}
+
+/// Creates a `Paragraph` widget containing the form for an event of a user-defined type.
+///
+/// # Arguments
+///
+/// - `form`: The `CustomEventForm` to display.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget titled with the event type, configured to display the form fields, validation error
+/// and key help.
+fn draw_custom_event_form<'a>(form: &CustomEventForm) -> Paragraph<'a> {
+    let title = match form.id() {
+        Some(_) => format!(" Edit {} ", form.event_type().name),
+        None => format!(" Add {} ", form.event_type().name),
+    };
+
+    draw_form(title, form.fields(), form.focus(), form.error())
+}
+
+/// Creates a `List` widget containing the user-defined event types and their fields.
+///
+/// # Arguments
+///
+/// - `registry`: The event types and their fields.
+///
+/// # Returns
+///
+/// Returns a `List` widget with one line per type, listing the kind of every field and the choices of enum fields.
+fn draw_event_types<'a>(registry: &EventRegistry) -> List<'a> {
+    let mut items = registry
+        .types
+        .iter()
+        .map(|event_type| {
+            let fields = registry
+                .fields_of(event_type.id)
+                .iter()
+                .map(|field| match field.kind {
+                    FieldKind::Enum => format!("{} ({})", field.name, field.choices().join("|")),
+                    kind => format!("{} ({})", field.name, kind),
+                })
+                .collect::<Vec<String>>();
+
+            ListItem::new(if fields.is_empty() {
+                event_type.name.clone()
+            } else {
+                format!("{}: {}", event_type.name, fields.join(", "))
+            })
+        })
+        .collect::<Vec<ListItem>>();
+
+    if items.is_empty() {
+        items.push(ListItem::new("No event types, press <a> to add one").dark_gray());
+    }
+
+    // construct the list widget
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(" Event Types ")
+                .title_style(Style::new().blue().bold())
+                .title(
+                    Title::from(Line::styled(
+                        " <Enter> record now <a> add <Esc> close ",
+                        Style::default().fg(Color::DarkGray),
+                    ))
+                    .position(Position::Bottom),
+                ),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED))
+        .highlight_symbol(">> ")
+        .highlight_spacing(HighlightSpacing::Always)
+}
+
+/// Creates a `List` widget containing the medication catalogue and the dose status of every medication.
+///
+/// Overdue medications are shown in red, medications that may not be given yet in gray with the time they are
+/// allowed, and medications that may be given in green.
+///
+/// # Arguments
+///
+/// - `statuses`: The medications and their dose status, as returned by `AppState::get_dose_statuses`.
+/// - `now`: The current time, in UTC.
+///
+/// # Returns
+///
+/// Returns a `List` widget with one line per medication, with times shown in the local time zone.
+fn draw_medications<'a>(statuses: &[(Medication, DoseStatus)], now: NaiveDateTime) -> List<'a> {
+    let time = |dt: NaiveDateTime| {
+        let dt = zone::to_local(&Local, dt);
+        if dt.date() == zone::to_local(&Local, now).date() {
+            dt.format("%H:%M").to_string()
+        } else {
+            dt.format("%m-%d %H:%M").to_string()
+        }
+    };
+
+    let mut items = statuses
+        .iter()
+        .map(|(medication, status)| {
+            let last = status
+                .last_dose
+                .map_or("never given".to_owned(), |dt| format!("last {}", time(dt)));
+            let (next, color) = match (status.next_allowed, status.next_due) {
+                (Some((allowed, limit)), _) => (
+                    format!("not before {} ({})", time(allowed), limit),
+                    Color::DarkGray,
+                ),
+                (None, Some(due)) if status.is_overdue(now) => {
+                    (format!("overdue since {}", time(due)), Color::Red)
+                }
+                (None, Some(due)) if due > now => (format!("due {}", time(due)), Color::Green),
+                (None, _) => ("allowed now".to_owned(), Color::Green),
+            };
+
+            ListItem::new(format!(
+                "{} {} {}: {}, {}",
+                medication.name, medication.dose, medication.unit, last, next
+            ))
+            .style(Style::default().fg(color))
+        })
+        .collect::<Vec<ListItem>>();
+
+    if items.is_empty() {
+        items.push(ListItem::new("No medications, press <a> to add one").dark_gray());
+    }
+
+    // construct the list widget
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(" Medications ")
+                .title_style(Style::new().blue().bold())
+                .title(
+                    Title::from(Line::styled(
+                        " <Enter> give dose now <a> add <Esc> close ",
+                        Style::default().fg(Color::DarkGray),
+                    ))
+                    .position(Position::Bottom),
+                ),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED))
+        .highlight_symbol(">> ")
+        .highlight_spacing(HighlightSpacing::Always)
+}
+
+/// Creates a `Paragraph` widget containing the fields of a form.
+///
+/// # Arguments
+///
+/// - `title`: The title of the form.
+/// - `fields`: The fields of the form.
+/// - `focus`: The index of the focused field.
+/// - `error`: The validation error from the last submission, if any.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget configured to display the form fields, validation error and key help.
+fn draw_form<'a>(
+    title: impl Into<Line<'a>>,
+    fields: &[FormField],
+    focus: usize,
+    error: Option<&str>,
+) -> Paragraph<'a> {
+    // labels longer than the usual column, such as volumes in imperial ounces, push the values along
+    let label_width = fields
+        .iter()
+        .map(|field| field.label.len() + 1)
+        .fold(18, usize::max);
+
+    let mut lines = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let style = if i == focus {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+
+            Line::from(vec![
+                Span::styled(format!("{:<1$}", field.label, label_width), style),
+                Span::styled(field.value.to_string(), style),
+            ])
+        })
+        .collect::<Vec<Line>>();
+
+    let help = if fields
+        .iter()
+        .any(|field| matches!(field.value, FieldValue::Toggle(_)))
+    {
+        "<Tab> next <Space> toggle <Enter> save <Esc> cancel"
+    } else {
+        "<Tab> next <Enter> save <Esc> cancel"
+    };
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        error.unwrap_or_default().to_owned(),
+        Style::default().fg(Color::Red),
+    )));
+    lines.push(Line::from(Span::styled(
+        help,
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    // construct the paragraph widget
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(title.into())
+                .title_style(Style::new().blue().bold()),
+        )
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: true })
+}
+
+/// Creates a `Paragraph` widget containing the filter prompt.
+///
+/// # Arguments
+///
+/// - `prompt`: The `FilterPrompt` to display.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget configured to display the input, the accepted formats, validation error and key help.
+fn draw_filter_prompt<'a>(prompt: &FilterPrompt) -> Paragraph<'a> {
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::White)),
+            Span::styled(
+                format!("{}_", prompt.input()),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "e.g. 2023-06-01..2023-06-15, 2023-06-15, 24h, 7d",
+            Style::default().fg(Color::White),
+        )),
+        Line::from(Span::styled(
+            prompt.error().unwrap_or_default().to_owned(),
+            Style::default().fg(Color::Red),
+        )),
+        Line::from(Span::styled(
+            "<Enter> apply <Esc> cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    // construct the paragraph widget
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(" Filter by Range ")
+                .title_style(Style::new().blue().bold()),
+        )
+        .alignment(Alignment::Left)
+}
+
+/// Creates a `Paragraph` widget containing the search prompt.
+///
+/// # Arguments
+///
+/// - `prompt`: The `SearchPrompt` to display.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget configured to display the input, a hint, the error and key help.
+fn draw_search_prompt<'a>(prompt: &SearchPrompt) -> Paragraph<'a> {
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("/ ", Style::default().fg(Color::White)),
+            Span::styled(
+                format!("{}_", prompt.input()),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Notes containing every word, across all dates",
+            Style::default().fg(Color::White),
+        )),
+        Line::from(Span::styled(
+            prompt.error().unwrap_or_default().to_owned(),
+            Style::default().fg(Color::Red),
+        )),
+        Line::from(Span::styled(
+            "<Enter> search <Esc> cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    // construct the paragraph widget
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(" Search Notes ")
+                .title_style(Style::new().blue().bold()),
+        )
+        .alignment(Alignment::Left)
+}
+
+/// Creates a `List` widget containing the events whose notes match a search.
+///
+/// # Arguments
+///
+/// - `results`: The matching events and their notes, as returned by `AppState::search_notes`.
+///
+/// # Returns
+///
+/// Returns a `List` widget with one line per event, showing its time and note.
+fn draw_search_results<'a>(results: &[(BabyEvent, EventNote)]) -> List<'a> {
+    let items = results
+        .iter()
+        .map(|(event, note)| {
+            ListItem::new(format!(
+                "{}  {}",
+                zone::to_local(&Local, event.dt).format("%Y-%m-%d %H:%M"),
+                note.text
+            ))
+        })
+        .collect::<Vec<ListItem>>();
+
+    // construct the list widget
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(format!(" {} Matching Notes ", results.len()))
+                .title_style(Style::new().blue().bold())
+                .title(
+                    Title::from(Line::styled(
+                        " <Enter> show event <Esc> close ",
+                        Style::default().fg(Color::DarkGray),
+                    ))
+                    .position(Position::Bottom),
+                ),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED))
+        .highlight_symbol(">> ")
+        .highlight_spacing(HighlightSpacing::Always)
+}
+
+/// Creates a `List` widget containing the changes of an event.
+///
+/// # Arguments
+///
+/// - `entries`: The changes of the event, newest first.
+/// - `selected`: The selected change, which is offered to be undone unless it is a purge, or gone back to unless it
+///   left no version of the event.
+/// - `caregivers`: The caregivers, to name who made each change.
+///
+/// # Returns
+///
+/// Returns a `List` widget with one line per change, showing its time, action, caregiver and changed fields, and noting
+/// that changes to the diaper details, note and values of the event are not tracked.
+fn draw_history<'a>(
+    entries: &[EventHistory],
+    selected: Option<&EventHistory>,
+    caregivers: &[Caregiver],
+) -> List<'a> {
+    let keys = match selected.map(|entry| entry.action) {
+        Some(HistoryAction::Purge) => " a purge is final <Esc> close ",
+        Some(HistoryAction::Delete) => " <Enter> undo the change <Esc> close ",
+        _ => " <Enter> undo the change <v> go back to this version <Esc> close ",
+    };
+
+    let items = entries
+        .iter()
+        .map(|entry| {
+            let caregiver = caregivers
+                .iter()
+                .find(|c| Some(c.id) == entry.caregiver_id)
+                .map_or("-", |c| c.name.as_str());
+            let fields = match (entry.before_event(), entry.after_event()) {
+                (Ok(Some(before)), Ok(Some(after))) => before.changed_fields(&after).join(", "),
+                _ => String::new(),
+            };
+
+            ListItem::new(format!(
+                "{}  {:<6}  {:<12}  {}",
+                zone::to_local(&Local, entry.changed_at).format("%Y-%m-%d %H:%M:%S"),
+                entry.action,
+                caregiver,
+                fields
+            ))
+        })
+        .collect::<Vec<ListItem>>();
+
+    // construct the list widget
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(format!(
+                    " History of Event {} ",
+                    entries.first().map_or(0, |e| e.event_id)
+                ))
+                .title_style(Style::new().blue().bold())
+                .title(
+                    Title::from(Line::styled(
+                        " notes, diaper details and values are not tracked ",
+                        Style::default().fg(Color::DarkGray),
+                    ))
+                    .alignment(Alignment::Right),
+                )
+                .title(
+                    Title::from(Line::styled(keys, Style::default().fg(Color::DarkGray)))
+                        .position(Position::Bottom),
+                ),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED))
+        .highlight_symbol(">> ")
+        .highlight_spacing(HighlightSpacing::Always)
+}
+
+/// Creates a `Paragraph` widget asking for confirmation before deleting an event.
+///
+/// # Arguments
+///
+/// - `event`: The `BabyEvent` about to be deleted.
+/// - `registry`: The event types, for naming the type of the event.
+/// - `unit`: The unit volumes are shown in.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget summarizing the event and the confirmation keys.
+fn draw_delete_confirmation<'a>(
+    event: &BabyEvent,
+    registry: &EventRegistry,
+    unit: VolumeUnit,
+) -> Paragraph<'a> {
+    let lines = vec![
+        Line::from(format!(
+            "Delete event {} at {}?",
+            event.id,
+            zone::to_local(&Local, event.dt)
+        )),
+        Line::from(""),
+        Line::from(summarize_event(event, registry, unit)),
+        Line::from(""),
+        Line::from(Span::styled(
+            "<y> move to trash <n> cancel, <Ctrl+z> undoes a deletion",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    // construct the paragraph widget
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(" Delete Event ")
+                .title_style(Style::new().red().bold()),
+        )
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: true })
+}
+
+/// Creates a `List` widget containing the deleted events of the current child.
+///
+/// # Arguments
+///
+/// - `events`: The deleted events, most recently deleted first.
+/// - `registry`: The event types, for naming the type of an event.
+/// - `unit`: The unit volumes are shown in.
+///
+/// # Returns
+///
+/// Returns a `List` widget with one line per event, showing when it was deleted, its time and what it recorded.
+fn draw_trash<'a>(events: &[BabyEvent], registry: &EventRegistry, unit: VolumeUnit) -> List<'a> {
+    let items = events
+        .iter()
+        .map(|event| {
+            let deleted = event.deleted_at.map(|dt| zone::to_local(&Local, dt));
+
+            ListItem::new(format!(
+                "{}  {}  {}",
+                deleted.map_or("-".to_owned(), |dt| dt.format("%m-%d %H:%M").to_string()),
+                zone::to_local(&Local, event.dt).format("%Y-%m-%d %H:%M"),
+                summarize_event(event, registry, unit)
+            ))
+        })
+        .collect::<Vec<ListItem>>();
+
+    // construct the list widget
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(format!(" Trash ({} events) ", events.len()))
+                .title_style(Style::new().blue().bold())
+                .title(
+                    Title::from(Line::styled(
+                        " <Enter> restore <p> purge for good <Esc> close ",
+                        Style::default().fg(Color::DarkGray),
+                    ))
+                    .position(Position::Bottom),
+                ),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED))
+        .highlight_symbol(">> ")
+        .highlight_spacing(HighlightSpacing::Always)
+}
+
+/// Summarizes what an event recorded on a single line.
+///
+/// # Arguments
+///
+/// - `event`: The `BabyEvent` to summarize.
+/// - `registry`: The event types, for naming the type of the event.
+/// - `unit`: The unit volumes are shown in.
+///
+/// # Returns
+///
+/// The type of an event of a user-defined type, then the diapers, durations and volumes of the event separated by
+/// commas, or a note that nothing was recorded.
+fn summarize_event(event: &BabyEvent, registry: &EventRegistry, unit: VolumeUnit) -> String {
+    let mut summary = vec![];
+
+    if let Some(event_type) = event.event_type_id.and_then(|id| registry.get_type(id)) {
+        summary.push(event_type.name.clone());
+    }
+    if event.urine {
+        summary.push("urine".to_owned());
+    }
+    if event.stool {
+        summary.push("stool".to_owned());
+    }
+    for (label, value) in [
+        ("skin-to-skin", event.skin2skin),
+        ("breastfeed", event.breastfeed),
+        ("pumping", event.pump_duration),
+    ] {
+        if value > 0 {
+            summary.push(format!("{} {}min", label, value));
+        }
+    }
+    for (label, value) in [
+        ("breastmilk", event.breastmilk),
+        ("formula", event.formula),
+        ("pump", event.pump),
+    ] {
+        if value > 0 {
+            summary.push(format!("{} {}", label, unit.format(value)));
+        }
+    }
+
+    if summary.is_empty() {
+        "No details recorded.".to_owned()
+    } else {
+        summary.join(", ")
+    }
+}
+
+/// Creates a `List` widget containing the entries of the file browser.
+///
+/// # Arguments
+///
+/// - `browser`: The `FileBrowser` to display.
+///
+/// # Returns
+///
+/// Returns a `List` widget configured to display the directory entries.
+fn draw_file_browser<'a>(browser: &FileBrowser) -> List<'a> {
+    let parent = browser.dir().parent();
+
+    let items = browser
+        .entries()
+        .iter()
+        .map(|path| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            if Some(path.as_path()) == parent {
+                ListItem::new("../")
+            } else if path.is_dir() {
+                ListItem::new(format!("{}/", name)).style(Style::default().fg(Color::Blue))
+            } else {
+                ListItem::new(name)
+            }
+        })
+        .collect::<Vec<ListItem>>();
+
+    // construct the list widget
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(format!(" Load CSV: {} ", browser.dir().display()))
+                .title_style(Style::new().blue().bold())
+                .title(
+                    Title::from(Line::styled(
+                        " <Enter> open <Backspace> parent <Esc> cancel ",
+                        Style::default().fg(Color::DarkGray),
+                    ))
+                    .position(Position::Bottom),
+                ),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ")
+        .highlight_spacing(HighlightSpacing::Always)
+        .style(Style::default().fg(Color::White))
+}
+
+/// Creates a `Table` widget containing the preview of a CSV file.
+///
+/// # Arguments
+///
+/// - `preview`: The `CsvPreview` to display.
+/// - `unit`: The unit volumes are shown in, whatever unit the file uses.
+///
+/// # Returns
+///
+/// Returns a `Table` widget with one row per parsed event, or the parse error.
+fn draw_csv_preview<'a>(preview: &CsvPreview, unit: VolumeUnit) -> Table<'a> {
+    let header = Row::new(vec![
+        "Timestamp",
+        "Urine",
+        "Stool",
+        "S2S",
+        "Feed",
+        "Milk",
+        "Form",
+        "Pump",
+    ])
+    .style(Style::new().bold());
+
+    let rows = match &preview.rows {
+        Ok(events) => events
+            .iter()
+            .map(|e| {
+                Row::new(vec![
+                    zone::to_local(&Local, e.dt).to_string(),
+                    e.urine.to_string(),
+                    e.stool.to_string(),
+                    e.skin2skin.to_string(),
+                    e.breastfeed.to_string(),
+                    unit.format_value(e.breastmilk),
+                    unit.format_value(e.formula),
+                    unit.format_value(e.pump),
+                ])
+            })
+            .collect::<Vec<Row>>(),
+        Err(error) => vec![Row::new(vec![error.clone()]).style(Style::default().fg(Color::Red))],
+    };
+
+    let help = match &preview.rows {
+        Ok(_) => " <y> import <n> back ",
+        Err(_) => " <n> back ",
+    };
+
+    // construct the table widget
+    Table::new(
+        rows,
+        [
+            Constraint::Length(19),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Min(4),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain)
+            .title(format!(" Preview: {} ({}) ", preview.path.display(), unit))
+            .title_style(Style::new().blue().bold())
+            .title(
+                Title::from(Line::styled(help, Style::default().fg(Color::DarkGray)))
+                    .position(Position::Bottom),
+            ),
+    )
+    .style(Style::default().fg(Color::White))
+    .column_spacing(1)
+}
+
+/// Creates a `Paragraph` widget containing a message.
+///
+/// # Arguments
+///
+/// - `title`: The title of the message.
+/// - `text`: The message to display.
+///
+/// # Returns
+///
+/// Returns a `Paragraph` widget configured to display the message.
+fn draw_message<'a>(title: &str, text: &str) -> Paragraph<'a> {
+    // construct the paragraph widget
+    Paragraph::new(text.to_owned())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title(format!(" {} ", title))
+                .title_style(Style::new().blue().bold())
+                .title(
+                    Title::from(Line::styled(
+                        " press any key ",
+                        Style::default().fg(Color::DarkGray),
+                    ))
+                    .position(Position::Bottom),
+                ),
+        )
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: true })
+}
+
+/// Computes a rectangle of the given size centered within `area`.
+///
+/// # Arguments
+///
+/// - `width`: The desired width, clamped to the width of `area`.
+/// - `height`: The desired height, clamped to the height of `area`.
+/// - `area`: The area to center the rectangle in.
+///
+/// # Returns
+///
+/// Returns the centered `Rect`.
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Formats a number of minutes as hours and minutes.
+///
+/// # Arguments
+///
+/// - `minutes`: The number of minutes to format.
+///
+/// # Returns
+///
+/// Returns a string such as `2h 05m`, or `45m` for less than an hour.
+fn format_minutes(minutes: i64) -> String {
+    if minutes < 60 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// Formats the values of an event of a user-defined type, one field per line.
+///
+/// # Arguments
+///
+/// - `fields`: The fields of the event's type, in the order of their position.
+/// - `values`: The stored values of the event by the id of their field.
+/// - `unit`: The unit volumes are shown in.
+///
+/// # Returns
+///
+/// Returns a string with a line such as ` \n\rminutes: 15m` for every field, with `-` for a field without a value.
+fn format_values(
+    fields: &[&EventField],
+    values: &BTreeMap<i32, String>,
+    unit: VolumeUnit,
+) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            let value = match (field.kind, values.get(&field.id)) {
+                (_, None) => "-".to_owned(),
+                (FieldKind::Bool, Some(value)) if value == "true" => "yes".to_owned(),
+                (FieldKind::Bool, Some(_)) => "no".to_owned(),
+                (FieldKind::Duration, Some(value)) => {
+                    value.parse::<i64>().map_or(value.clone(), format_minutes)
+                }
+                (FieldKind::Volume, Some(value)) => value
+                    .parse::<i32>()
+                    .map_or(value.clone(), |ml| unit.format(ml)),
+                (_, Some(value)) => value.clone(),
+            };
+
+            format!(" \n\r{}: {}", field.name, value)
+        })
+        .collect()
+}
+
+/// Formats the local time of an event, adding the wall-clock time and offset it was recorded at if they differ.
+///
+/// # Arguments
+///
+/// - `event`: The event to format the time of.
+///
+/// # Returns
+///
+/// Returns a string such as `05:15:00`, or `05:15:00 (recorded 04:15:00 UTC+01:00)` for an event recorded in
+/// another time zone or before the clocks changed.
+fn format_time(event: &BabyEvent) -> String {
+    let time = zone::to_local(&Local, event.dt).time();
+
+    if zone::offset(&Local, event.dt) == event.utc_offset {
+        time.to_string()
+    } else {
+        format!(
+            "{} (recorded {} {})",
+            time,
+            event.recorded_dt().time(),
+            zone::format_offset(event.utc_offset)
+        )
+    }
+}
+
+/// Formats the left and right parts of a total, if either is known.
+///
+/// # Arguments
+///
+/// - `left`: The part of the left side.
+/// - `right`: The part of the right side.
+///
+/// # Returns
+///
+/// Returns a string such as ` (L 10, R 5)`, or an empty string if neither side is known.
+fn format_sides(left: i32, right: i32) -> String {
+    if left == 0 && right == 0 {
+        String::new()
+    } else {
+        format!(" (L {}, R {})", left, right)
+    }
+}
+
+/// Formats the left and right parts of a total volume, if either is known.
+///
+/// # Arguments
+///
+/// - `unit`: The unit the volumes are shown in.
+/// - `left`: The volume of the left side, in millilitres.
+/// - `right`: The volume of the right side, in millilitres.
+///
+/// # Returns
+///
+/// Returns a string such as ` (L 2.0, R 2.1)`, or an empty string if neither side is known.
+fn format_volume_sides(unit: VolumeUnit, left: i32, right: i32) -> String {
+    if left == 0 && right == 0 {
+        String::new()
+    } else {
+        format!(
+            " (L {}, R {})",
+            unit.format_value(left),
+            unit.format_value(right)
+        )
+    }
+}
+
+/// Formats the stool of an event, with its details if any are noted.
+///
+/// # Arguments
+///
+/// - `stool`: Whether there was a stool.
+/// - `details`: The details of the diaper.
+///
+/// # Returns
+///
+/// Returns the noted details such as `green, seedy, small`, most important first, or `true` or `false`.
+fn format_stool(stool: bool, details: Option<&DiaperDetails>) -> String {
+    let parts = details.map_or(vec![], |d| {
+        [
+            d.stool_color.map(|c| c.to_string()),
+            d.stool_consistency.map(|c| c.to_string()),
+            d.stool_size.map(|a| a.to_string()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    });
+
+    format_details(stool, parts)
+}
+
+/// Formats the urine of an event, with its details if any are noted.
+///
+/// # Arguments
+///
+/// - `urine`: Whether there was urine.
+/// - `details`: The details of the diaper.
+///
+/// # Returns
+///
+/// Returns the noted details such as `large, urates`, or `true` or `false`.
+fn format_urine(urine: bool, details: Option<&DiaperDetails>) -> String {
+    let parts = details.map_or(vec![], |d| {
+        [
+            d.urine_amount.map(|a| a.to_string()),
+            d.urate_crystals.then(|| "urates".to_owned()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    });
+
+    format_details(urine, parts)
+}
+
+/// Joins the noted details, or formats the flag if there are none.
+fn format_details(flag: bool, parts: Vec<String>) -> String {
+    if parts.is_empty() {
+        flag.to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Validates the terminal size to ensure it meets minimum requirements.
+///
+/// At the minimum width the menu wraps onto seven lines, and the remaining rows still leave room for the event list
+/// and the chart below the calendar and the details.
 ///
 /// # Arguments
 ///
@@ -329,14 +2006,14 @@ fn draw_chart<'a>(_state: &AppState) -> BarChart<'a> {
 fn check_size(rect: &Rect) {
     if rect.width < 80 {
         panic!(
-            "Terminal width too small, got {}; Please resize to at least 52 columns.",
+            "Terminal width too small, got {}; Please resize to at least 80 columns.",
             rect.width
         );
     }
 
-    if rect.height < 24 {
+    if rect.height < 30 {
         panic!(
-            "Terminal height too small, got {}; Please resize to at least 28 rows.",
+            "Terminal height too small, got {}; Please resize to at least 30 rows.",
             rect.height
         );
     }
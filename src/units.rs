@@ -0,0 +1,279 @@
+/// Module converting volumes between millilitres, in which they are stored, and the unit the user prefers.
+///
+/// Volumes are stored as whole millilitres. They are converted for display only after they have been added up, so that
+/// totals are rounded once rather than accumulating the rounding of every event.
+use std::fmt::{self, Display};
+
+/// The millilitres in a US fluid ounce.
+const ML_PER_US_FL_OZ: f64 = 29.573_529_562_5;
+
+/// The millilitres in an imperial fluid ounce.
+const ML_PER_IMPERIAL_FL_OZ: f64 = 28.413_062_5;
+
+/// Represents a unit volumes are displayed, entered and exported in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeUnit {
+    #[default]
+    Millilitre,
+    UsFluidOunce,
+    ImperialFluidOunce,
+}
+
+impl VolumeUnit {
+    /// Every unit, in the order they are switched through.
+    pub const ALL: [Self; 3] = [
+        Self::Millilitre,
+        Self::UsFluidOunce,
+        Self::ImperialFluidOunce,
+    ];
+
+    /// Switches to the next unit in the sequence.
+    ///
+    /// # Returns
+    ///
+    /// The next unit, wrapping around to millilitres.
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Millilitre => Self::UsFluidOunce,
+            Self::UsFluidOunce => Self::ImperialFluidOunce,
+            Self::ImperialFluidOunce => Self::Millilitre,
+        }
+    }
+
+    /// Returns the code of the unit, as shown after a volume and written to the unit column of a CSV file.
+    ///
+    /// # Returns
+    ///
+    /// `"ml"`, `"fl oz"` or `"imp fl oz"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Millilitre => "ml",
+            Self::UsFluidOunce => "fl oz",
+            Self::ImperialFluidOunce => "imp fl oz",
+        }
+    }
+
+    /// Parses a unit, ignoring case and spaces.
+    ///
+    /// A plain ounce is the US fluid ounce, as on formula tins and bottles.
+    ///
+    /// # Parameters
+    ///
+    /// * `text`: The text to parse, e.g. `"ml"`, `"fl oz"`, `"oz"` or `"imp fl oz"`.
+    ///
+    /// # Returns
+    ///
+    /// The unit, or `None` if the text names no known unit.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase();
+
+        match text.as_str() {
+            "ml" => Some(Self::Millilitre),
+            "floz" | "oz" | "usfloz" | "usoz" => Some(Self::UsFluidOunce),
+            "impfloz" | "impoz" => Some(Self::ImperialFluidOunce),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of decimals volumes are displayed with.
+    ///
+    /// # Returns
+    ///
+    /// 0 for millilitres and 1 for fluid ounces, both finer than a feed is measured.
+    pub fn decimals(&self) -> usize {
+        match self {
+            Self::Millilitre => 0,
+            Self::UsFluidOunce | Self::ImperialFluidOunce => 1,
+        }
+    }
+
+    /// Converts millilitres into the unit.
+    ///
+    /// # Parameters
+    ///
+    /// * `ml`: The volume in millilitres.
+    ///
+    /// # Returns
+    ///
+    /// The unrounded volume in the unit.
+    pub fn from_ml(&self, ml: i32) -> f64 {
+        f64::from(ml) / self.ml_per_unit()
+    }
+
+    /// Converts a volume in the unit into whole millilitres.
+    ///
+    /// # Parameters
+    ///
+    /// * `value`: The volume in the unit.
+    ///
+    /// # Returns
+    ///
+    /// The volume rounded to the nearest millilitre.
+    pub fn to_ml(&self, value: f64) -> i32 {
+        (value * self.ml_per_unit()).round() as i32
+    }
+
+    /// Converts a total with a left and right side in the unit into whole millilitres.
+    ///
+    /// Sides that add up to the total as entered give a total that is the sum of the rounded sides, which may differ
+    /// from the rounded total by a millilitre. Other sides leave the total rounded on its own, to be rejected when
+    /// the event is validated.
+    ///
+    /// # Parameters
+    ///
+    /// * `total`: The total volume in the unit.
+    /// * `left`: The volume of the left side in the unit.
+    /// * `right`: The volume of the right side in the unit.
+    ///
+    /// # Returns
+    ///
+    /// The total in whole millilitres, e.g. 88 for 3 fl oz made up of 1.5 fl oz a side rather than the 89 of 3 fl oz.
+    pub fn total_to_ml(&self, total: f64, left: f64, right: f64) -> i32 {
+        if total > 0.0 && (left + right - total).abs() < 1e-9 * total {
+            self.to_ml(left) + self.to_ml(right)
+        } else {
+            self.to_ml(total)
+        }
+    }
+
+    /// Converts millilitres into the unit, rounded to two decimals.
+    ///
+    /// Two decimals of an ounce are finer than a millilitre, so the rounded volume converts back to the same
+    /// millilitres; it is used wherever a volume is written to be read back, such as forms and CSV files.
+    ///
+    /// # Parameters
+    ///
+    /// * `ml`: The volume in millilitres.
+    ///
+    /// # Returns
+    ///
+    /// The volume in the unit, e.g. `4.06`.
+    pub fn exact_value(&self, ml: i32) -> f64 {
+        (self.from_ml(ml) * 100.0).round() / 100.0
+    }
+
+    /// Formats a volume in the unit, without the unit.
+    ///
+    /// # Parameters
+    ///
+    /// * `ml`: The volume in millilitres.
+    ///
+    /// # Returns
+    ///
+    /// The volume rounded to [`VolumeUnit::decimals`], e.g. `"4.1"`.
+    pub fn format_value(&self, ml: i32) -> String {
+        format!("{:.*}", self.decimals(), self.from_ml(ml))
+    }
+
+    /// Formats a volume in the unit, followed by the unit.
+    ///
+    /// # Parameters
+    ///
+    /// * `ml`: The volume in millilitres.
+    ///
+    /// # Returns
+    ///
+    /// The volume and the unit, e.g. `"120 ml"` or `"4.1 fl oz"`.
+    pub fn format(&self, ml: i32) -> String {
+        format!("{} {}", self.format_value(ml), self.code())
+    }
+
+    /// Parses a volume entered in the unit.
+    ///
+    /// # Parameters
+    ///
+    /// * `text`: The text to parse, a non-negative number with a `.` or `,` as decimal separator.
+    ///
+    /// # Returns
+    ///
+    /// The volume in whole millilitres, or `None` if the text is not a non-negative number.
+    pub fn parse_volume(&self, text: &str) -> Option<i32> {
+        let value = text.trim().replace(',', ".").parse::<f64>().ok()?;
+
+        (value.is_finite() && value >= 0.0 && value <= f64::from(i32::MAX) / self.ml_per_unit())
+            .then(|| self.to_ml(value))
+    }
+
+    /// Returns the millilitres in one unit.
+    fn ml_per_unit(&self) -> f64 {
+        match self {
+            Self::Millilitre => 1.0,
+            Self::UsFluidOunce => ML_PER_US_FL_OZ,
+            Self::ImperialFluidOunce => ML_PER_IMPERIAL_FL_OZ,
+        }
+    }
+}
+
+impl Display for VolumeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        for unit in VolumeUnit::ALL {
+            assert_eq!(VolumeUnit::parse(unit.code()), Some(unit));
+        }
+        assert_eq!(VolumeUnit::parse(" FL OZ "), Some(VolumeUnit::UsFluidOunce));
+        assert_eq!(VolumeUnit::parse("oz"), Some(VolumeUnit::UsFluidOunce));
+        assert_eq!(
+            VolumeUnit::parse("Imp oz"),
+            Some(VolumeUnit::ImperialFluidOunce)
+        );
+        assert_eq!(VolumeUnit::parse("cups"), None);
+    }
+
+    #[test]
+    fn test_convert() {
+        let oz = VolumeUnit::UsFluidOunce;
+        assert_eq!(oz.format(120), "4.1 fl oz");
+        assert_eq!(oz.to_ml(4.0), 118);
+        assert_eq!(VolumeUnit::ImperialFluidOunce.to_ml(4.0), 114);
+        assert_eq!(VolumeUnit::Millilitre.format(120), "120 ml");
+
+        // totals are rounded once: 3 feeds of 1.04 fl oz are 3.1 fl oz, not 3 times 1.0
+        assert_eq!(oz.format_value(31), "1.0");
+        assert_eq!(oz.format_value(3 * 31), "3.1");
+
+        // every whole millilitre survives a round trip through two decimals of an ounce
+        for unit in VolumeUnit::ALL {
+            for ml in 0..1000 {
+                assert_eq!(unit.to_ml(unit.exact_value(ml)), ml);
+            }
+        }
+        assert_eq!(oz.exact_value(120), 4.06);
+    }
+
+    #[test]
+    fn test_total_to_ml() {
+        let oz = VolumeUnit::UsFluidOunce;
+        assert_eq!(oz.to_ml(3.0), 89);
+        assert_eq!(oz.total_to_ml(3.0, 1.5, 1.5), 88);
+        assert_eq!(oz.total_to_ml(0.3, 0.1, 0.2), 9);
+
+        // sides that do not add up, or are unknown, leave the total as entered
+        assert_eq!(oz.total_to_ml(3.0, 1.5, 1.6), 89);
+        assert_eq!(oz.total_to_ml(3.0, 0.0, 0.0), 89);
+        assert_eq!(oz.total_to_ml(0.0, 0.0, 0.0), 0);
+    }
+
+    #[test]
+    fn test_parse_volume() {
+        let oz = VolumeUnit::UsFluidOunce;
+        assert_eq!(oz.parse_volume("4"), Some(118));
+        assert_eq!(oz.parse_volume(" 4,5 "), Some(133));
+        assert_eq!(VolumeUnit::Millilitre.parse_volume("120"), Some(120));
+        assert_eq!(oz.parse_volume("-1"), None);
+        assert_eq!(oz.parse_volume("four"), None);
+        assert_eq!(oz.parse_volume("1e300"), None);
+    }
+}
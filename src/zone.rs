@@ -0,0 +1,126 @@
+/// Module converting between timestamps stored in UTC and the wall-clock time of a time zone.
+///
+/// Timestamps are stored in UTC so that the timeline stays in order across changes to and from summer time and across
+/// time zones. They are converted to the wall-clock time of the local time zone only to be shown, entered or grouped
+/// into hours and days.
+use chrono::{Duration, LocalResult, NaiveDateTime, Offset, TimeZone};
+
+/// Converts a UTC timestamp into the wall-clock time of a time zone.
+///
+/// # Parameters
+///
+/// * `tz`: The time zone.
+/// * `utc`: The timestamp in UTC.
+///
+/// # Returns
+///
+/// The wall-clock time in `tz`.
+pub fn to_local<Tz: TimeZone>(tz: &Tz, utc: NaiveDateTime) -> NaiveDateTime {
+    tz.from_utc_datetime(&utc).naive_local()
+}
+
+/// Converts a wall-clock time of a time zone into UTC.
+///
+/// A time that occurs twice when the clocks go back is read as the first of the two. A time skipped when the clocks go
+/// forward is read with the offset from before the change, so that it is moved forward by the gap.
+///
+/// # Parameters
+///
+/// * `tz`: The time zone.
+/// * `local`: The wall-clock time in `tz`.
+///
+/// # Returns
+///
+/// The timestamp in UTC.
+pub fn to_utc<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> NaiveDateTime {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => dt.naive_utc(),
+        LocalResult::None => {
+            let before = tz.offset_from_utc_datetime(&(local - Duration::days(1)));
+            local - Duration::seconds(before.fix().local_minus_utc().into())
+        }
+    }
+}
+
+/// Returns the offset from UTC of a time zone at a point in time.
+///
+/// # Parameters
+///
+/// * `tz`: The time zone.
+/// * `utc`: The point in time, in UTC.
+///
+/// # Returns
+///
+/// The offset in seconds, positive east of UTC.
+pub fn offset<Tz: TimeZone>(tz: &Tz, utc: NaiveDateTime) -> i32 {
+    tz.offset_from_utc_datetime(&utc).fix().local_minus_utc()
+}
+
+/// Formats an offset from UTC.
+///
+/// # Parameters
+///
+/// * `seconds`: The offset in seconds, positive east of UTC.
+///
+/// # Returns
+///
+/// The offset such as `"UTC+02:00"` or `"UTC-03:30"`, or `"UTC"` for no offset.
+pub fn format_offset(seconds: i32) -> String {
+    if seconds == 0 {
+        return "UTC".to_owned();
+    }
+
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.abs() / 60;
+
+    format!("UTC{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, Utc};
+    use chrono_tz::Europe::Berlin;
+
+    fn at(date: (i32, u32, u32), time: (u32, u32)) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+            .unwrap()
+            .and_hms_opt(time.0, time.1, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let summer = at((2023, 6, 15), (5, 15));
+        assert_eq!(to_utc(&Berlin, summer), at((2023, 6, 15), (3, 15)));
+        assert_eq!(to_local(&Berlin, at((2023, 6, 15), (3, 15))), summer);
+        assert_eq!(offset(&Berlin, at((2023, 6, 15), (3, 15))), 7200);
+        assert_eq!(offset(&Berlin, at((2023, 1, 15), (3, 15))), 3600);
+        assert_eq!(to_utc(&Utc, summer), summer);
+    }
+
+    #[test]
+    fn test_summer_time_changes() {
+        // the clocks go back from 03:00 to 02:00 on 2023-10-29: 02:30 happens twice, the first time is taken
+        assert_eq!(
+            to_utc(&Berlin, at((2023, 10, 29), (2, 30))),
+            at((2023, 10, 29), (0, 30))
+        );
+        assert_eq!(
+            to_local(&Berlin, at((2023, 10, 29), (1, 30))),
+            at((2023, 10, 29), (2, 30))
+        );
+
+        // the clocks go forward from 02:00 to 03:00 on 2023-03-26: 02:30 never happens and is moved to 03:30
+        let utc = to_utc(&Berlin, at((2023, 3, 26), (2, 30)));
+        assert_eq!(utc, at((2023, 3, 26), (1, 30)));
+        assert_eq!(to_local(&Berlin, utc), at((2023, 3, 26), (3, 30)));
+    }
+
+    #[test]
+    fn test_format_offset() {
+        assert_eq!(format_offset(0), "UTC");
+        assert_eq!(format_offset(7200), "UTC+02:00");
+        assert_eq!(format_offset(-12600), "UTC-03:30");
+    }
+}
@@ -1,9 +1,7 @@
-use diesel::sqlite::Sqlite;
-use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
-pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
-use std::error::Error;
+use babyrs::{migrate_database, BabyrsError};
+use diesel::sqlite::SqliteConnection;
 
-/// Run pending database migrations.
+/// Run pending database migrations on an in-memory database.
 ///
 /// # Arguments
 ///
@@ -12,10 +10,8 @@ use std::error::Error;
 /// # Errors
 ///
 /// Returns an error if the migration fails.
-pub fn run_migrations(
-    connection: &mut impl MigrationHarness<Sqlite>,
-) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
-    connection.run_pending_migrations(MIGRATIONS)?;
+pub fn run_migrations(connection: &mut SqliteConnection) -> Result<(), BabyrsError> {
+    migrate_database(connection, ":memory:")?;
 
     Ok(())
 }
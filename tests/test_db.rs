@@ -1,9 +1,33 @@
 //! Integration tests for baby-related event handling and database interactions.
 mod common;
 
-use babyrs::models::{BabyEvent, NewBabyEvent};
-use babyrs::{create_event, establish_connection, read_events, write_event};
+use babyrs::models::{
+    Amount, BabyEvent, DiaperDetails, EventRegistry, HistoryAction, NewBabyEvent, NewCaregiver,
+    NewChild, NewDose, NewEventField, NewEventType, NewMeasurement, NewMedication,
+    NewMedicationSchedule, NewSleepSession, Sex, Side, StoolColor, StoolConsistency,
+};
+use babyrs::units::VolumeUnit;
+use babyrs::{
+    count_events_between, create_event, delete_diaper_details, delete_dose, delete_event,
+    delete_measurement, delete_medication_schedule, delete_note, delete_sleep_session,
+    establish_connection, migrate_database, process_child_csv, purge_deleted_events, purge_event,
+    read_caregivers, read_child_doses, read_child_events, read_child_measurements,
+    read_child_sleep_sessions, read_children, read_deleted_events, read_diaper_details_between,
+    read_event_history, read_event_registry, read_event_values, read_event_values_between,
+    read_events, read_events_between, read_last_breastfeed, read_last_event, read_measurements,
+    read_medication_schedules, read_medications, read_notes_between, read_open_sleep_session,
+    read_sleep_sessions_between, restore_event, revert_event, revert_event_to_version,
+    search_notes, update_child, update_event, update_measurement, update_medication,
+    update_sleep_session, write_caregiver, write_child, write_diaper_details, write_dose,
+    write_event, write_event_type, write_event_values, write_event_with_policy,
+    write_event_with_values, write_measurement, write_medication, write_medication_schedule,
+    write_note, write_sleep_session, MigrationSummary, DEFAULT_CHILD_ID,
+};
+use babyrs::{BabyrsError, DuplicatePolicy, WriteOutcome};
 use diesel::prelude::*;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
 
 /// Test database connection establishment.
 ///
@@ -12,7 +36,7 @@ use diesel::prelude::*;
 fn test_establish_connection() {
     std::env::set_var("DATABASE_URL", ":memory:");
 
-    let connection: &mut SqliteConnection = &mut establish_connection();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
 
     assert!(diesel::sql_query("SELECT 1").execute(connection).is_ok());
 }
@@ -26,7 +50,7 @@ fn test_write_event() {
 
     std::env::set_var("DATABASE_URL", ":memory:");
 
-    let connection: &mut SqliteConnection = &mut establish_connection();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
 
     common::run_migrations(connection).expect("Error running migrations");
 
@@ -40,7 +64,10 @@ fn test_write_event() {
         Some(25),
     );
 
-    assert_eq!(write_event(connection, new_event), 1);
+    assert!(matches!(
+        write_event(connection, new_event).unwrap(),
+        WriteOutcome::Inserted(_)
+    ));
 
     let results: Vec<BabyEvent> = events
         .load::<BabyEvent>(connection)
@@ -50,8 +77,8 @@ fn test_write_event() {
 
     let saved_event = &results[0];
 
-    assert_eq!(saved_event.urine, true);
-    assert_eq!(saved_event.stool, true);
+    assert!(saved_event.urine);
+    assert!(saved_event.stool);
     assert_eq!(saved_event.skin2skin, 5);
     assert_eq!(saved_event.breastfeed, 10);
     assert_eq!(saved_event.breastmilk, 15);
@@ -59,6 +86,29 @@ fn test_write_event() {
     assert_eq!(saved_event.pump, 25);
 }
 
+/// Test writing an invalid event.
+///
+/// This test checks that an event with a negative quantity is rejected with a validation error and not written.
+#[test]
+fn test_write_event_invalid() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let mut new_event: NewBabyEvent = create_event(None, None, None, None, None, None, None);
+    new_event.formula = -20;
+
+    match write_event(connection, new_event) {
+        Err(BabyrsError::Validation(message)) => {
+            assert_eq!(message, "formula must not be negative, got -20")
+        }
+        result => panic!("Expected a validation error, got {:?}", result),
+    }
+    assert!(read_events(connection).unwrap().is_empty());
+}
+
 /// Test reading events from the database.
 ///
 /// This test writes multiple events to the database and then verifies their presence.
@@ -66,7 +116,7 @@ fn test_write_event() {
 fn test_read_events() {
     std::env::set_var("DATABASE_URL", ":memory:");
 
-    let connection: &mut SqliteConnection = &mut establish_connection();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
 
     common::run_migrations(connection).expect("Error running migrations");
 
@@ -80,10 +130,10 @@ fn test_read_events() {
             Some(i + 3),
             Some(i + 4),
         );
-        write_event(connection, new_event);
+        write_event(connection, new_event).unwrap();
     }
 
-    let results: Vec<BabyEvent> = read_events(connection);
+    let results: Vec<BabyEvent> = read_events(connection).unwrap();
 
     assert_eq!(results.len(), 7);
 }
@@ -97,7 +147,7 @@ fn test_update_event() {
 
     std::env::set_var("DATABASE_URL", ":memory:");
 
-    let connection: &mut SqliteConnection = &mut establish_connection();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
 
     common::run_migrations(connection).expect("Error running migrations");
 
@@ -111,7 +161,7 @@ fn test_update_event() {
         Some(25),
     );
 
-    write_event(connection, new_event);
+    write_event(connection, new_event).unwrap();
 
     let results: Vec<BabyEvent> = events
         .load::<BabyEvent>(connection)
@@ -129,7 +179,10 @@ fn test_update_event() {
     saved_event.formula = 0;
     saved_event.pump = 0;
 
-    assert_eq!(babyrs::update_event(connection, saved_event), 1);
+    assert_eq!(
+        babyrs::update_event(connection, saved_event, None).unwrap(),
+        1
+    );
 
     let results: Vec<BabyEvent> = events
         .load::<BabyEvent>(connection)
@@ -139,8 +192,8 @@ fn test_update_event() {
 
     let updated_event = &results[0];
 
-    assert_eq!(updated_event.urine, false);
-    assert_eq!(updated_event.stool, false);
+    assert!(!updated_event.urine);
+    assert!(!updated_event.stool);
     assert_eq!(updated_event.skin2skin, 0);
     assert_eq!(updated_event.breastfeed, 0);
     assert_eq!(updated_event.breastmilk, 0);
@@ -152,14 +205,14 @@ fn test_update_event() {
 
 /// Test deleting an event from the database.
 ///
-/// This test writes an event to the database, deletes it, and then verifies that it was deleted.
+/// This test writes an event to the database, deletes it into the trash, and then verifies that it is gone once purged.
 #[test]
 fn test_delete_event() {
     use babyrs::schema::events::dsl::*;
 
     std::env::set_var("DATABASE_URL", ":memory:");
 
-    let connection: &mut SqliteConnection = &mut establish_connection();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
 
     common::run_migrations(connection).expect("Error running migrations");
 
@@ -173,7 +226,7 @@ fn test_delete_event() {
         Some(25),
     );
 
-    write_event(connection, new_event);
+    write_event(connection, new_event).unwrap();
 
     let results: Vec<BabyEvent> = events
         .load::<BabyEvent>(connection)
@@ -183,7 +236,20 @@ fn test_delete_event() {
 
     let saved_event = results[0];
 
-    assert_eq!(babyrs::delete_event(connection, saved_event), 1);
+    assert_eq!(
+        babyrs::delete_event(connection, saved_event, None).unwrap(),
+        1
+    );
+    assert!(read_events(connection).unwrap().is_empty());
+
+    let results: Vec<BabyEvent> = events
+        .load::<BabyEvent>(connection)
+        .expect("Error loading events");
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_deleted());
+
+    assert_eq!(purge_event(connection, saved_event, None).unwrap(), 1);
 
     let results: Vec<BabyEvent> = events
         .load::<BabyEvent>(connection)
@@ -201,7 +267,7 @@ fn test_process_csv() {
 
     std::env::set_var("DATABASE_URL", ":memory:");
 
-    let connection: &mut SqliteConnection = &mut establish_connection();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
 
     common::run_migrations(connection).expect("Error running migrations");
 
@@ -215,11 +281,1761 @@ fn test_process_csv() {
 
     let saved_event = &results[0];
 
-    assert_eq!(saved_event.urine, false);
-    assert_eq!(saved_event.stool, false);
+    assert!(!saved_event.urine);
+    assert!(!saved_event.stool);
     assert_eq!(saved_event.skin2skin, 60);
     assert_eq!(saved_event.breastfeed, 0);
     assert_eq!(saved_event.breastmilk, 0);
     assert_eq!(saved_event.formula, 0);
     assert_eq!(saved_event.pump, 0);
 }
+
+/// Test restoring a deleted event.
+///
+/// This test deletes an event and restores it, verifying that the original id and timestamp are kept.
+#[test]
+fn test_restore_event() {
+    use babyrs::schema::events::dsl::*;
+
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    for i in 0..2 {
+        let new_event: NewBabyEvent = create_event(
+            Some(true),
+            Some(true),
+            Some(i),
+            Some(i + 1),
+            Some(i + 2),
+            Some(i + 3),
+            Some(i + 4),
+        );
+        write_event(connection, new_event).unwrap();
+    }
+
+    let deleted_event = read_events(connection).unwrap()[0];
+
+    assert_eq!(
+        babyrs::delete_event(connection, deleted_event, None).unwrap(),
+        1
+    );
+    assert_eq!(
+        babyrs::restore_event(connection, deleted_event, None).unwrap(),
+        1
+    );
+
+    let results: Vec<BabyEvent> = events
+        .filter(id.eq(deleted_event.id))
+        .load::<BabyEvent>(connection)
+        .expect("Error loading events");
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].dt, deleted_event.dt);
+    assert_eq!(results[0].skin2skin, deleted_event.skin2skin);
+
+    // restoring an event that still exists is ignored
+    assert_eq!(
+        babyrs::restore_event(connection, deleted_event, None).unwrap(),
+        0
+    );
+    assert_eq!(read_events(connection).unwrap().len(), 2);
+}
+
+/// Test the summary returned when processing the same CSV file twice.
+///
+/// This test imports the sample CSV file twice and verifies that the second import skips every row.
+#[test]
+fn test_process_csv_summary() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let summary =
+        babyrs::process_csv(connection, "sample/example.csv").expect("Error processing CSV");
+
+    assert_eq!(summary.inserted, 38);
+    assert!(summary.skipped.is_empty());
+
+    let summary =
+        babyrs::process_csv(connection, "sample/example.csv").expect("Error processing CSV");
+
+    assert_eq!(summary.inserted, 0);
+    assert_eq!(summary.skipped.len(), 38);
+    assert_eq!(summary.skipped[..3], [1, 2, 3]);
+    assert_eq!(read_events(connection).unwrap().len(), 38);
+}
+
+/// Test previewing a CSV file.
+///
+/// This test parses the first rows of the sample CSV file without touching the database.
+#[test]
+fn test_preview_csv() {
+    let records = babyrs::preview_csv("sample/example.csv", 5).expect("Error previewing CSV");
+
+    assert_eq!(records.len(), 5);
+    assert_eq!(records[0].skin2skin, 60);
+    assert_eq!(records[2].breastfeed, 20);
+
+    assert!(babyrs::preview_csv("sample/missing.csv", 5).is_err());
+}
+
+/// Test reading and counting the events of a period.
+///
+/// This test writes events on consecutive days and checks that only the events of the queried period are returned,
+/// in chronological order.
+#[test]
+fn test_read_events_between() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    assert!(read_last_event(connection, DEFAULT_CHILD_ID)
+        .unwrap()
+        .is_none());
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    for day in [3, 1, 2, 0] {
+        let mut new_event: NewBabyEvent = create_event(None, None, None, None, None, None, None);
+        new_event.dt = (date + chrono::Duration::days(day))
+            .and_hms_opt(5, 15, 0)
+            .unwrap();
+        write_event(connection, new_event).unwrap();
+    }
+
+    let start = date.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let end = start + chrono::Duration::days(2);
+    let results: Vec<BabyEvent> =
+        read_events_between(connection, DEFAULT_CHILD_ID, start, end).unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].dt < results[1].dt);
+    assert!(results.iter().all(|e| start <= e.dt && e.dt < end));
+    assert_eq!(
+        count_events_between(connection, DEFAULT_CHILD_ID, start, end).unwrap(),
+        2
+    );
+
+    // the end of the period is exclusive
+    assert_eq!(
+        count_events_between(connection, DEFAULT_CHILD_ID, results[0].dt, results[1].dt).unwrap(),
+        1
+    );
+
+    let last = read_last_event(connection, DEFAULT_CHILD_ID)
+        .unwrap()
+        .expect("Expected a last event");
+    assert_eq!(last.dt.date(), date + chrono::Duration::days(3));
+}
+
+/// Opens a new database file in the temporary directory.
+fn temporary_database(name: &str) -> (std::path::PathBuf, SqliteConnection) {
+    let path = std::env::temp_dir().join(format!("babyrs-{}-{}.sqlite3", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let connection =
+        SqliteConnection::establish(path.to_str().unwrap()).expect("Error creating database");
+
+    (path, connection)
+}
+
+/// Test migrating a database.
+///
+/// This test migrates a database that is one migration behind and checks that it was backed up first.
+#[test]
+fn test_migrate_database() {
+    let (path, mut connection) = temporary_database("migrate");
+    let url = path.to_str().unwrap();
+
+    // a new database is not backed up
+    let summary = migrate_database(&mut connection, url).unwrap();
+    assert!(!summary.applied.is_empty());
+    assert!(summary.backup.is_none());
+
+    // up to date databases are left alone
+    assert_eq!(
+        migrate_database(&mut connection, url).unwrap(),
+        MigrationSummary::default()
+    );
+
+    // a database with pending migrations is copied before migrating
+    write_event(
+        &mut connection,
+        create_event(None, None, None, None, Some(90), None, None),
+    )
+    .unwrap();
+    let reverted = connection
+        .revert_last_migration(MIGRATIONS)
+        .expect("Error reverting migration");
+
+    let summary = migrate_database(&mut connection, url).unwrap();
+    assert_eq!(summary.applied, vec![reverted.to_string()]);
+    assert_eq!(read_events(&mut connection).unwrap().len(), 1);
+
+    let backup = summary.backup.expect("Expected a backup");
+    let mut backup_connection = SqliteConnection::establish(backup.to_str().unwrap()).unwrap();
+    assert_eq!(
+        babyrs::schema::events::table
+            .count()
+            .get_result::<i64>(&mut backup_connection)
+            .unwrap(),
+        1
+    );
+
+    std::fs::remove_file(&backup).unwrap();
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Test migrating a database from a newer version.
+///
+/// This test checks that a database with an unknown migration is refused.
+#[test]
+fn test_migrate_database_too_new() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    diesel::sql_query("INSERT INTO __diesel_schema_migrations (version) VALUES ('99991231000000')")
+        .execute(connection)
+        .unwrap();
+
+    match migrate_database(connection, ":memory:") {
+        Err(BabyrsError::SchemaTooNew(version)) => assert_eq!(version, "99991231000000"),
+        result => panic!("Expected a schema error, got {:?}", result),
+    }
+}
+
+/// Test keeping the events of several children apart.
+///
+/// This test adds a second child and checks that events are read, counted and imported per child.
+#[test]
+fn test_children() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    // the migration creates the default child
+    let children = read_children(connection).unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].id, DEFAULT_CHILD_ID);
+
+    let child = write_child(
+        connection,
+        NewChild {
+            name: "Ada".to_owned(),
+            birth_date: chrono::NaiveDate::from_ymd_opt(2023, 6, 1),
+            sex: Some(Sex::Female),
+            birth_weight: Some(3250),
+        },
+    )
+    .unwrap();
+    assert_eq!(read_children(connection).unwrap()[1], child);
+
+    // both children may have an event at the same time
+    let mut new_event: NewBabyEvent = create_event(None, None, None, None, Some(90), None, None);
+    assert_eq!(
+        write_event(connection, new_event).unwrap(),
+        WriteOutcome::Inserted(1)
+    );
+    new_event.child_id = child.id;
+    assert_eq!(
+        write_event(connection, new_event).unwrap(),
+        WriteOutcome::Inserted(2)
+    );
+    assert_eq!(
+        write_event(connection, new_event).unwrap(),
+        WriteOutcome::Skipped(2)
+    );
+
+    assert_eq!(read_events(connection).unwrap().len(), 2);
+    assert_eq!(read_child_events(connection, child.id).unwrap().len(), 1);
+
+    let summary = process_child_csv(
+        connection,
+        "sample/example.csv",
+        child.id,
+        DuplicatePolicy::SkipExact,
+    )
+    .unwrap();
+    assert_eq!(summary.inserted, 38);
+    assert_eq!(read_child_events(connection, child.id).unwrap().len(), 39);
+    assert_eq!(
+        read_child_events(connection, DEFAULT_CHILD_ID)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // a child needs a name
+    let invalid = NewChild {
+        name: " ".to_owned(),
+        birth_date: None,
+        sex: None,
+        birth_weight: None,
+    };
+    assert!(matches!(
+        write_child(connection, invalid),
+        Err(BabyrsError::Validation(_))
+    ));
+}
+
+/// Test the CRUD operations on growth measurements.
+///
+/// Measurements are read per child, oldest first, and need at least one positive value.
+#[test]
+fn test_measurements() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 1).unwrap();
+    let later = NewMeasurement {
+        child_id: DEFAULT_CHILD_ID,
+        dt: date.and_hms_opt(9, 0, 0).unwrap() + chrono::Duration::days(30),
+        weight: Some(4200),
+        length: Some(545),
+        head_circumference: None,
+    };
+    let birth = NewMeasurement {
+        dt: date.and_hms_opt(9, 0, 0).unwrap(),
+        weight: Some(3250),
+        ..later
+    };
+
+    assert_eq!(write_measurement(connection, later).unwrap(), 1);
+    assert_eq!(write_measurement(connection, birth).unwrap(), 1);
+    assert_eq!(read_measurements(connection).unwrap().len(), 2);
+
+    let mut measurements = read_child_measurements(connection, DEFAULT_CHILD_ID).unwrap();
+    assert_eq!(measurements[0].weight, Some(3250));
+    assert_eq!(measurements[1].weight, Some(4200));
+    assert!(read_child_measurements(connection, 2).unwrap().is_empty());
+
+    // clearing a value stores NULL
+    measurements[1].length = None;
+    measurements[1].head_circumference = Some(372);
+    assert_eq!(update_measurement(connection, measurements[1]).unwrap(), 1);
+    let updated = read_child_measurements(connection, DEFAULT_CHILD_ID).unwrap();
+    assert_eq!(updated[1], measurements[1]);
+
+    assert_eq!(delete_measurement(connection, measurements[0]).unwrap(), 1);
+    assert_eq!(read_measurements(connection).unwrap().len(), 1);
+
+    // a measurement needs a positive value
+    let empty = NewMeasurement {
+        weight: None,
+        length: None,
+        ..later
+    };
+    assert!(matches!(
+        write_measurement(connection, empty),
+        Err(BabyrsError::Validation(_))
+    ));
+    let negative = NewMeasurement {
+        weight: Some(-1),
+        ..later
+    };
+    assert!(matches!(
+        write_measurement(connection, negative),
+        Err(BabyrsError::Validation(_))
+    ));
+
+    // the birth date and sex of a child can be set later
+    let mut child = read_children(connection).unwrap().remove(0);
+    child.birth_date = Some(date);
+    child.sex = Some(Sex::Male);
+    assert_eq!(update_child(connection, child.clone()).unwrap(), 1);
+    assert_eq!(read_children(connection).unwrap()[0], child);
+}
+
+/// Test the CRUD operations on sleep sessions.
+///
+/// A child can have a single session in progress, and sessions overlapping a period are read whole.
+#[test]
+fn test_sleep_sessions() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    let night = NewSleepSession {
+        child_id: DEFAULT_CHILD_ID,
+        start_dt: (date - chrono::Duration::days(1))
+            .and_hms_opt(20, 0, 0)
+            .unwrap(),
+        end_dt: date.and_hms_opt(2, 0, 0),
+    };
+    let nap = NewSleepSession {
+        child_id: DEFAULT_CHILD_ID,
+        start_dt: date.and_hms_opt(13, 0, 0).unwrap(),
+        end_dt: None,
+    };
+
+    assert_eq!(write_sleep_session(connection, nap).unwrap(), 1);
+    assert_eq!(write_sleep_session(connection, night).unwrap(), 1);
+
+    // only one session can be in progress
+    assert!(matches!(
+        write_sleep_session(connection, nap),
+        Err(BabyrsError::Query(_))
+    ));
+
+    let sessions = read_child_sleep_sessions(connection, DEFAULT_CHILD_ID).unwrap();
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[0].end_dt, night.end_dt);
+
+    // the night overlaps the day, the nap in progress overlaps the next day
+    let (start, end) = (
+        date.and_hms_opt(0, 0, 0).unwrap(),
+        date.and_hms_opt(0, 0, 0).unwrap() + chrono::Duration::days(1),
+    );
+    assert_eq!(
+        read_sleep_sessions_between(connection, DEFAULT_CHILD_ID, start, end)
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        read_sleep_sessions_between(
+            connection,
+            DEFAULT_CHILD_ID,
+            start + chrono::Duration::days(1),
+            end + chrono::Duration::days(1)
+        )
+        .unwrap(),
+        [sessions[1]]
+    );
+
+    // ending the nap
+    let mut open = read_open_sleep_session(connection, DEFAULT_CHILD_ID)
+        .unwrap()
+        .unwrap();
+    assert_eq!(open, sessions[1]);
+    open.end_dt = date.and_hms_opt(12, 0, 0);
+    assert!(matches!(
+        update_sleep_session(connection, open),
+        Err(BabyrsError::Validation(_))
+    ));
+    open.end_dt = date.and_hms_opt(14, 0, 0);
+    assert_eq!(update_sleep_session(connection, open).unwrap(), 1);
+    assert_eq!(
+        read_open_sleep_session(connection, DEFAULT_CHILD_ID).unwrap(),
+        None
+    );
+
+    assert_eq!(delete_sleep_session(connection, open).unwrap(), 1);
+    assert_eq!(
+        read_child_sleep_sessions(connection, DEFAULT_CHILD_ID)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+/// Test the medication catalogue, doses and schedules.
+///
+/// Medication names are unique, and a medication has at most one schedule per child.
+#[test]
+fn test_medications() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let new_medication = NewMedication {
+        name: "Vitamin D".to_owned(),
+        unit: "drops".to_owned(),
+        dose: 1.0,
+        min_interval_minutes: None,
+        max_daily_doses: Some(1),
+        max_daily_amount: None,
+    };
+    let vitamin_d = write_medication(connection, new_medication.clone()).unwrap();
+    assert_eq!(vitamin_d.name, "Vitamin D");
+
+    // names are unique, doses must be positive
+    assert!(matches!(
+        write_medication(connection, new_medication.clone()),
+        Err(BabyrsError::Query(_))
+    ));
+    assert!(matches!(
+        write_medication(
+            connection,
+            NewMedication {
+                name: "Paracetamol".to_owned(),
+                dose: 0.0,
+                ..new_medication.clone()
+            }
+        ),
+        Err(BabyrsError::Validation(_))
+    ));
+
+    let mut paracetamol = write_medication(
+        connection,
+        NewMedication {
+            name: "Paracetamol".to_owned(),
+            unit: "ml".to_owned(),
+            dose: 2.5,
+            min_interval_minutes: Some(240),
+            max_daily_doses: Some(4),
+            max_daily_amount: None,
+        },
+    )
+    .unwrap();
+    paracetamol.max_daily_amount = Some(10.0);
+    assert_eq!(
+        update_medication(connection, paracetamol.clone()).unwrap(),
+        1
+    );
+    assert_eq!(
+        read_medications(connection).unwrap(),
+        [paracetamol.clone(), vitamin_d.clone()]
+    );
+
+    // doses
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    let new_dose = NewDose {
+        child_id: DEFAULT_CHILD_ID,
+        medication_id: paracetamol.id,
+        dt: date.and_hms_opt(8, 0, 0).unwrap(),
+        amount: 2.5,
+    };
+    assert_eq!(write_dose(connection, new_dose).unwrap(), 1);
+    assert!(matches!(
+        write_dose(
+            connection,
+            NewDose {
+                amount: -1.0,
+                ..new_dose
+            }
+        ),
+        Err(BabyrsError::Validation(_))
+    ));
+
+    let doses = read_child_doses(connection, DEFAULT_CHILD_ID).unwrap();
+    assert_eq!(doses.len(), 1);
+    assert_eq!(doses[0].medication_id, paracetamol.id);
+
+    // schedules replace the previous schedule of the medication
+    let new_schedule = NewMedicationSchedule {
+        child_id: DEFAULT_CHILD_ID,
+        medication_id: vitamin_d.id,
+        first_dt: date.and_hms_opt(9, 0, 0).unwrap(),
+        interval_minutes: 24 * 60,
+    };
+    assert_eq!(
+        write_medication_schedule(connection, new_schedule).unwrap(),
+        1
+    );
+    write_medication_schedule(
+        connection,
+        NewMedicationSchedule {
+            interval_minutes: 12 * 60,
+            ..new_schedule
+        },
+    )
+    .unwrap();
+
+    let schedules = read_medication_schedules(connection, DEFAULT_CHILD_ID).unwrap();
+    assert_eq!(schedules.len(), 1);
+    assert_eq!(schedules[0].interval_minutes, 12 * 60);
+
+    assert_eq!(
+        delete_medication_schedule(connection, schedules[0]).unwrap(),
+        1
+    );
+    assert_eq!(delete_dose(connection, doses[0]).unwrap(), 1);
+    assert!(read_medication_schedules(connection, DEFAULT_CHILD_ID)
+        .unwrap()
+        .is_empty());
+    assert!(read_child_doses(connection, DEFAULT_CHILD_ID)
+        .unwrap()
+        .is_empty());
+}
+
+/// Test recording the sides of breastfeeds and pumps.
+///
+/// The sides must add up to the total, and the last breastfeed with the sides recorded suggests the next side.
+#[test]
+fn test_event_sides() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    let mut new_event = create_event(None, None, None, Some(25), None, None, Some(120));
+    new_event.dt = date.and_hms_opt(8, 0, 0).unwrap();
+    new_event.breastfeed_left = 15;
+    new_event.breastfeed_right = 10;
+    new_event.pump_left = 70;
+    new_event.pump_right = 50;
+    new_event.pump_duration = 20;
+    assert!(matches!(
+        write_event(connection, new_event).unwrap(),
+        WriteOutcome::Inserted(_)
+    ));
+
+    // a feed without the sides recorded does not hide the last one with them
+    let mut unknown = create_event(None, None, None, Some(30), None, None, None);
+    unknown.dt = date.and_hms_opt(11, 0, 0).unwrap();
+    assert!(matches!(
+        write_event(connection, unknown).unwrap(),
+        WriteOutcome::Inserted(_)
+    ));
+
+    let last = read_last_breastfeed(connection, DEFAULT_CHILD_ID)
+        .unwrap()
+        .unwrap();
+    assert_eq!(last.dt, new_event.dt);
+    assert_eq!(
+        (last.pump_left, last.pump_right, last.pump_duration),
+        (70, 50, 20)
+    );
+    assert_eq!(last.next_side(), Some(Side::Right));
+
+    // sides that do not add up to the total are rejected
+    let mut mismatched = new_event;
+    mismatched.dt = date.and_hms_opt(14, 0, 0).unwrap();
+    mismatched.pump = 100;
+    assert!(matches!(
+        write_event(connection, mismatched),
+        Err(BabyrsError::Validation(_))
+    ));
+}
+
+/// Test noting the details of diapers.
+///
+/// Details belong to an event with a matching stool or urine, and are deleted along with the event.
+#[test]
+fn test_diaper_details() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    let mut new_event = create_event(Some(true), Some(true), None, None, None, None, None);
+    new_event.dt = date.and_hms_opt(8, 0, 0).unwrap();
+    assert!(matches!(
+        write_event(connection, new_event).unwrap(),
+        WriteOutcome::Inserted(_)
+    ));
+    let mut wet = create_event(Some(true), None, None, None, None, None, None);
+    wet.dt = date.and_hms_opt(11, 0, 0).unwrap();
+    assert!(matches!(
+        write_event(connection, wet).unwrap(),
+        WriteOutcome::Inserted(_)
+    ));
+    let events = read_events(connection).unwrap();
+    let (dirty, wet) = (events[0], events[1]);
+
+    let details = DiaperDetails {
+        event_id: dirty.id,
+        stool_color: Some(StoolColor::Red),
+        stool_consistency: Some(StoolConsistency::Seedy),
+        stool_size: None,
+        urine_amount: Some(Amount::Large),
+        urate_crystals: true,
+    };
+    assert_eq!(write_diaper_details(connection, details).unwrap(), 1);
+
+    // writing again replaces the details
+    let details = DiaperDetails {
+        stool_size: Some(Amount::Small),
+        ..details
+    };
+    assert_eq!(write_diaper_details(connection, details).unwrap(), 1);
+
+    let start = date.and_hms_opt(0, 0, 0).unwrap();
+    let end = start + chrono::Duration::days(1);
+    let read = read_diaper_details_between(connection, DEFAULT_CHILD_ID, start, end).unwrap();
+    assert_eq!(read, [details]);
+    assert!(read[0].is_concerning());
+    assert!(read_diaper_details_between(
+        connection,
+        DEFAULT_CHILD_ID,
+        end,
+        end + chrono::Duration::days(1)
+    )
+    .unwrap()
+    .is_empty());
+
+    // stool details need a stool, and details need an event
+    let stool_only = DiaperDetails {
+        event_id: wet.id,
+        stool_color: Some(StoolColor::Yellow),
+        stool_consistency: None,
+        stool_size: None,
+        urine_amount: None,
+        urate_crystals: false,
+    };
+    assert!(matches!(
+        write_diaper_details(connection, stool_only),
+        Err(BabyrsError::Validation(_))
+    ));
+    assert!(matches!(
+        write_diaper_details(
+            connection,
+            DiaperDetails {
+                event_id: 99,
+                ..stool_only
+            }
+        ),
+        Err(BabyrsError::Query(_))
+    ));
+
+    // empty details delete the details noted before
+    let urine_only = DiaperDetails {
+        event_id: wet.id,
+        stool_color: None,
+        urine_amount: Some(Amount::Small),
+        ..stool_only
+    };
+    assert_eq!(write_diaper_details(connection, urine_only).unwrap(), 1);
+    assert_eq!(
+        write_diaper_details(
+            connection,
+            DiaperDetails {
+                urine_amount: None,
+                ..urine_only
+            }
+        )
+        .unwrap(),
+        1
+    );
+    assert_eq!(delete_diaper_details(connection, wet.id).unwrap(), 0);
+
+    // deleting the event keeps its details in the trash, and purging it deletes them
+    assert_eq!(delete_event(connection, dirty, None).unwrap(), 1);
+    assert!(
+        read_diaper_details_between(connection, DEFAULT_CHILD_ID, start, end)
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(purge_event(connection, dirty, None).unwrap(), 1);
+    let orphans: i64 = babyrs::schema::diaper_details::table
+        .count()
+        .get_result(connection)
+        .unwrap();
+    assert_eq!(orphans, 0);
+}
+
+/// Test editing the diapers of an event with diaper details.
+///
+/// Details that no longer match the edited event are deleted, also when an older version of the event is restored.
+#[test]
+fn test_update_event_diaper_details() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let new_event = create_event(Some(true), Some(true), None, None, None, None, None);
+    write_event(connection, new_event).unwrap();
+    let event = read_events(connection).unwrap()[0];
+    let details = DiaperDetails {
+        event_id: event.id,
+        stool_color: Some(StoolColor::Black),
+        stool_consistency: Some(StoolConsistency::Seedy),
+        stool_size: Some(Amount::Large),
+        urine_amount: Some(Amount::Small),
+        urate_crystals: true,
+    };
+    write_diaper_details(connection, details).unwrap();
+
+    let read_details = |connection: &mut SqliteConnection| {
+        babyrs::schema::diaper_details::table
+            .select(DiaperDetails::as_select())
+            .load(connection)
+            .unwrap()
+    };
+
+    // an edit that keeps the stool and urine keeps every detail
+    let longer = BabyEvent {
+        skin2skin: 10,
+        ..event
+    };
+    assert_eq!(update_event(connection, longer, None).unwrap(), 1);
+    assert_eq!(read_details(connection), [details]);
+
+    // an event without a stool loses its stool details, and the concerning color with them
+    let wet = BabyEvent {
+        stool: false,
+        ..longer
+    };
+    assert_eq!(update_event(connection, wet, None).unwrap(), 1);
+    let urine_only = DiaperDetails {
+        stool_color: None,
+        stool_consistency: None,
+        stool_size: None,
+        ..details
+    };
+    assert_eq!(read_details(connection), [urine_only]);
+    assert!(!read_details(connection)[0].is_concerning());
+
+    // an event without urine as well loses the remaining details
+    let dry = BabyEvent {
+        urine: false,
+        ..wet
+    };
+    assert_eq!(update_event(connection, dry, None).unwrap(), 1);
+    assert!(read_details(connection).is_empty());
+
+    // restoring a version of the event without urine deletes the urine details noted since
+    assert_eq!(update_event(connection, wet, None).unwrap(), 1);
+    write_diaper_details(connection, urine_only).unwrap();
+    delete_event(connection, wet, None).unwrap();
+    assert_eq!(restore_event(connection, dry, None).unwrap(), 1);
+    assert!(read_details(connection).is_empty());
+}
+
+/// Test writing and searching notes on events.
+///
+/// Notes are searched per child across all dates by the start of every word, and leave the index with their event.
+#[test]
+fn test_notes() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    for (day, hour) in [(0, 8), (0, 11), (1, 9)] {
+        let mut new_event = create_event(None, None, None, Some(10), None, None, None);
+        new_event.dt = (date + chrono::Duration::days(day))
+            .and_hms_opt(hour, 0, 0)
+            .unwrap();
+        assert!(matches!(
+            write_event(connection, new_event).unwrap(),
+            WriteOutcome::Inserted(_)
+        ));
+    }
+    let events = read_events(connection).unwrap();
+
+    assert_eq!(
+        write_note(connection, events[0].id, " Spat up after the feed ").unwrap(),
+        1
+    );
+    assert_eq!(
+        write_note(connection, events[1].id, "short feed, sleepy").unwrap(),
+        1
+    );
+    assert_eq!(
+        write_note(connection, events[2].id, "spat up again").unwrap(),
+        1
+    );
+    assert!(matches!(
+        write_note(connection, 99, "no event"),
+        Err(BabyrsError::Query(_))
+    ));
+
+    let start = date.and_hms_opt(0, 0, 0).unwrap();
+    let notes = read_notes_between(
+        connection,
+        DEFAULT_CHILD_ID,
+        start,
+        start + chrono::Duration::days(1),
+    )
+    .unwrap();
+    assert_eq!(notes.len(), 2);
+    assert_eq!(notes[0].text, "Spat up after the feed");
+
+    // every word has to match the start of a word, ignoring case, newest first
+    let search = |connection: &mut SqliteConnection, query: &str| {
+        search_notes(connection, DEFAULT_CHILD_ID, query)
+            .unwrap()
+            .into_iter()
+            .map(|(event, _)| event.id)
+            .collect::<Vec<i32>>()
+    };
+    assert_eq!(search(connection, "SPAT"), [events[2].id, events[0].id]);
+    assert_eq!(search(connection, "spa fee"), [events[0].id]);
+    assert_eq!(search(connection, "pat"), [] as [i32; 0]);
+    assert_eq!(search(connection, "feed,"), [events[1].id, events[0].id]);
+    assert_eq!(search(connection, "  "), [] as [i32; 0]);
+
+    // FTS5 operators are matched literally
+    assert_eq!(search(connection, "\"spat OR"), [] as [i32; 0]);
+    assert_eq!(search(connection, "NOT"), [] as [i32; 0]);
+
+    // other children's notes are not searched
+    assert!(search_notes(connection, 2, "spat").unwrap().is_empty());
+
+    // rewriting a note updates the index, an empty note deletes it
+    write_note(connection, events[2].id, "fussy").unwrap();
+    assert_eq!(search(connection, "spat"), [events[0].id]);
+    assert_eq!(search(connection, "fussy"), [events[2].id]);
+    assert_eq!(write_note(connection, events[2].id, "").unwrap(), 1);
+    assert!(search(connection, "fussy").is_empty());
+    assert_eq!(delete_note(connection, events[2].id).unwrap(), 0);
+
+    // deleting the event deletes its note
+    delete_event(connection, events[0], None).unwrap();
+    assert!(search(connection, "spat").is_empty());
+}
+
+/// Test attributing events to caregivers.
+///
+/// Caregiver names are unique, and events keep the caregiver who recorded them, unknown for older events.
+#[test]
+fn test_caregivers() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    assert!(read_caregivers(connection).unwrap().is_empty());
+
+    let sam = write_caregiver(
+        connection,
+        NewCaregiver {
+            name: " Sam ".to_owned(),
+        },
+    )
+    .unwrap();
+    assert_eq!(sam.name, "Sam");
+    let alex = write_caregiver(
+        connection,
+        NewCaregiver {
+            name: "Alex".to_owned(),
+        },
+    )
+    .unwrap();
+    assert_eq!(read_caregivers(connection).unwrap(), [sam.clone(), alex]);
+
+    // names are unique and not empty
+    assert!(matches!(
+        write_caregiver(
+            connection,
+            NewCaregiver {
+                name: "Sam".to_owned(),
+            }
+        ),
+        Err(BabyrsError::Query(_))
+    ));
+    assert!(matches!(
+        write_caregiver(
+            connection,
+            NewCaregiver {
+                name: " ".to_owned(),
+            }
+        ),
+        Err(BabyrsError::Validation(_))
+    ));
+
+    let mut new_event = create_event(None, None, None, None, Some(90), None, None);
+    assert_eq!(new_event.caregiver_id, None);
+    write_event(connection, new_event).unwrap();
+    new_event.dt += chrono::Duration::minutes(1);
+    new_event.caregiver_id = Some(sam.id);
+    write_event(connection, new_event).unwrap();
+
+    let events = read_events(connection).unwrap();
+    assert_eq!(events[0].caregiver_id, None);
+    assert_eq!(events[1].caregiver_id, Some(sam.id));
+
+    // updating an event keeps its caregiver
+    update_event(
+        connection,
+        BabyEvent {
+            breastmilk: 100,
+            ..events[1]
+        },
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        read_events(connection).unwrap()[1].caregiver_id,
+        Some(sam.id)
+    );
+}
+
+/// Test importing and exporting CSV files with a unit column.
+///
+/// This test imports volumes in millilitres and fluid ounces, also pumped halves of an ounce, and checks that an
+/// export in ounces imports unchanged.
+#[test]
+fn test_csv_units() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let dir = std::env::temp_dir();
+    let import_path = dir.join(format!("babyrs-units-{}.csv", std::process::id()));
+    let import_path = import_path.to_str().unwrap();
+    std::fs::write(
+        import_path,
+        "dt,urine,stool,skin2skin,breastfeed,breastmilk,formula,pump,unit\n\
+         2023-06-15T05:15:00,false,false,0,0,120,0,0,ml\n\
+         2023-06-15T06:15:00,false,false,0,0,0,4.5,0,fl oz\n\
+         2023-06-15T07:15:00,false,false,0,0,0,0,2,imp fl oz\n\
+         2023-06-15T08:15:00,false,false,0,0,90,0,0,\n",
+    )
+    .unwrap();
+
+    let summary = babyrs::process_csv(connection, import_path).expect("Error processing CSV");
+    assert_eq!(summary.inserted, 4);
+
+    let events = read_events(connection).unwrap();
+    assert_eq!(events[0].breastmilk, 120);
+    assert_eq!(events[1].formula, 133);
+    assert_eq!(events[2].pump, 57);
+    assert_eq!(events[3].breastmilk, 90);
+
+    // an export in ounces keeps every volume to the millilitre
+    let export_path = dir.join(format!("babyrs-export-{}.csv", std::process::id()));
+    let export_path = export_path.to_str().unwrap();
+    let count = babyrs::export_csv(
+        &events,
+        &[],
+        &EventRegistry::default(),
+        export_path,
+        VolumeUnit::UsFluidOunce,
+    )
+    .unwrap();
+    assert_eq!(count, 4);
+
+    let exported = std::fs::read_to_string(export_path).unwrap();
+    assert!(exported.lines().next().unwrap().ends_with(",unit"));
+    assert!(exported.contains(",4.06,"));
+
+    let mut other: SqliteConnection = establish_connection().unwrap();
+    common::run_migrations(&mut other).expect("Error running migrations");
+    babyrs::process_csv(&mut other, export_path).expect("Error processing CSV");
+    let imported = read_events(&mut other).unwrap();
+    for (event, imported) in events.iter().zip(&imported) {
+        assert_eq!(
+            (event.dt, event.breastmilk, event.formula, event.pump),
+            (
+                imported.dt,
+                imported.breastmilk,
+                imported.formula,
+                imported.pump
+            )
+        );
+    }
+
+    // halves of an ounce add up to the total once rounded to millilitres
+    std::fs::write(
+        import_path,
+        "dt,urine,stool,skin2skin,breastfeed,breastmilk,formula,pump,pump_left,pump_right,unit\n\
+         2023-06-16T05:15:00,false,false,0,0,0,0,3,1.5,1.5,fl oz\n",
+    )
+    .unwrap();
+    let summary = babyrs::process_csv(connection, import_path).expect("Error processing CSV");
+    assert_eq!(summary.inserted, 1);
+    let pumped = read_events(connection).unwrap()[4];
+    assert_eq!(
+        (pumped.pump, pumped.pump_left, pumped.pump_right),
+        (88, 44, 44)
+    );
+
+    // an unknown unit is rejected
+    std::fs::write(
+        import_path,
+        "dt,urine,stool,skin2skin,breastfeed,breastmilk,formula,pump,unit\n\
+         2023-06-16T05:15:00,false,false,0,0,1,0,0,cups\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        babyrs::process_csv(connection, import_path),
+        Err(BabyrsError::Validation(_))
+    ));
+
+    std::fs::remove_file(import_path).unwrap();
+    std::fs::remove_file(export_path).unwrap();
+}
+
+/// Test storing timestamps in UTC with the offset they were recorded at.
+///
+/// Imported timestamps keep their offset through an export, and the migration converts local timestamps both ways.
+#[test]
+fn test_utc_timestamps() {
+    use babyrs::zone;
+    use chrono::{Local, NaiveDate, NaiveDateTime};
+
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let dir = std::env::temp_dir();
+    let import_path = dir.join(format!("babyrs-offsets-{}.csv", std::process::id()));
+    let import_path = import_path.to_str().unwrap();
+    std::fs::write(
+        import_path,
+        "dt,urine,stool,skin2skin,breastfeed,breastmilk,formula,pump\n\
+         2023-06-15T05:15:00+02:00,false,false,0,0,120,0,0\n\
+         2023-06-15T05:15:00-03:30,false,false,0,0,90,0,0\n\
+         2023-06-15T12:00:00,false,false,0,0,60,0,0\n",
+    )
+    .unwrap();
+
+    babyrs::process_csv(connection, import_path).expect("Error processing CSV");
+
+    // timestamps with an offset are converted to UTC, those without are read in the local time zone
+    let date = NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    let noon = zone::to_utc(&Local, date.and_hms_opt(12, 0, 0).unwrap());
+    let events = read_events(connection).unwrap();
+    let mut stored = events
+        .iter()
+        .map(|e| (e.dt, e.utc_offset))
+        .collect::<Vec<(NaiveDateTime, i32)>>();
+    stored.sort();
+    let mut expected = vec![
+        (date.and_hms_opt(3, 15, 0).unwrap(), 7200),
+        (date.and_hms_opt(8, 45, 0).unwrap(), -12600),
+        (noon, zone::offset(&Local, noon)),
+    ];
+    expected.sort();
+    assert_eq!(stored, expected);
+
+    // the export shows the time the events were recorded at
+    let export_path = dir.join(format!("babyrs-offsets-export-{}.csv", std::process::id()));
+    let export_path = export_path.to_str().unwrap();
+    babyrs::export_csv(
+        &events,
+        &[],
+        &EventRegistry::default(),
+        export_path,
+        VolumeUnit::Millilitre,
+    )
+    .unwrap();
+    let exported = std::fs::read_to_string(export_path).unwrap();
+    assert!(exported.contains("2023-06-15T05:15:00+02:00,"));
+    assert!(exported.contains("2023-06-15T05:15:00-03:30,"));
+
+    // reverting the migration goes back to the local time zone
+    for _ in 0..5 {
+        connection
+            .revert_last_migration(MIGRATIONS)
+            .expect("Error reverting migration");
+    }
+    let mut reverted = babyrs::schema::events::table
+        .select(diesel::dsl::sql::<diesel::sql_types::Timestamp>("dt"))
+        .load::<NaiveDateTime>(connection)
+        .unwrap();
+    reverted.sort();
+    let mut local = stored
+        .iter()
+        .map(|(dt, _)| zone::to_local(&Local, *dt))
+        .collect::<Vec<NaiveDateTime>>();
+    local.sort();
+    assert_eq!(reverted, local);
+
+    // migrating again reads them in the local time zone, losing only the offsets they were recorded at
+    common::run_migrations(connection).expect("Error running migrations");
+    let mut migrated = read_events(connection)
+        .unwrap()
+        .iter()
+        .map(|e| (e.dt, e.utc_offset))
+        .collect::<Vec<(NaiveDateTime, i32)>>();
+    migrated.sort();
+    let expected = stored
+        .iter()
+        .map(|(dt, _)| (*dt, zone::offset(&Local, *dt)))
+        .collect::<Vec<(NaiveDateTime, i32)>>();
+    assert_eq!(migrated, expected);
+
+    std::fs::remove_file(import_path).unwrap();
+    std::fs::remove_file(export_path).unwrap();
+}
+
+/// Test writing events that share a timestamp.
+///
+/// Exact duplicates are skipped, and other events at the same timestamp are kept, merged or written as the policy says.
+#[test]
+fn test_duplicate_policies() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    // a bottle and a diaper entered together are both kept
+    let bottle: NewBabyEvent = create_event(None, None, None, None, Some(90), None, None);
+    let mut diaper = create_event(Some(true), Some(false), None, None, None, None, None);
+    diaper.dt = bottle.dt;
+    assert_eq!(
+        write_event(connection, bottle).unwrap(),
+        WriteOutcome::Inserted(1)
+    );
+    assert_eq!(
+        write_event(connection, diaper).unwrap(),
+        WriteOutcome::Inserted(2)
+    );
+    assert_eq!(
+        write_event(connection, diaper).unwrap(),
+        WriteOutcome::Skipped(2)
+    );
+
+    // merging fills in the first event at the timestamp, replacing a breastfeed together with its sides
+    let mut feed = diaper;
+    feed.urine = false;
+    feed.stool = true;
+    feed.breastfeed = 25;
+    feed.breastfeed_left = 10;
+    feed.breastfeed_right = 15;
+    assert_eq!(
+        write_event_with_policy(connection, feed, DuplicatePolicy::Merge).unwrap(),
+        WriteOutcome::Merged(1)
+    );
+    let merged = read_events(connection).unwrap()[0];
+    assert_eq!(
+        (merged.stool, merged.breastmilk, merged.breastfeed),
+        (true, 90, 25)
+    );
+    assert_eq!((merged.breastfeed_left, merged.breastfeed_right), (10, 15));
+
+    // an event identical to the merged one is skipped by every policy but keeping both
+    let merged_again = NewBabyEvent {
+        caregiver_id: None,
+        ..merged.to_new_event()
+    };
+    assert_eq!(
+        write_event_with_policy(connection, merged_again, DuplicatePolicy::Merge).unwrap(),
+        WriteOutcome::Skipped(1)
+    );
+    assert_eq!(
+        write_event_with_policy(connection, diaper, DuplicatePolicy::KeepBoth).unwrap(),
+        WriteOutcome::Inserted(3)
+    );
+    assert_eq!(read_events(connection).unwrap().len(), 3);
+
+    // a CSV import reports the rows it skipped
+    let path = std::env::temp_dir().join(format!("babyrs-duplicates-{}.csv", std::process::id()));
+    let path = path.to_str().unwrap();
+    std::fs::write(
+        path,
+        "dt,urine,stool,skin2skin,breastfeed,breastmilk,formula,pump\n\
+         2023-06-15T05:15:00+00:00,false,false,0,0,120,0,0\n\
+         2023-06-15T05:15:00+00:00,false,false,0,0,120,0,0\n\
+         2023-06-15T05:15:00+00:00,true,false,0,0,0,0,0\n",
+    )
+    .unwrap();
+
+    let summary = babyrs::process_csv(connection, path).unwrap();
+    assert_eq!((summary.inserted, summary.merged), (2, 0));
+    assert_eq!(summary.skipped, [2]);
+
+    let summary =
+        process_child_csv(connection, path, DEFAULT_CHILD_ID, DuplicatePolicy::Merge).unwrap();
+    assert_eq!((summary.inserted, summary.merged), (0, 0));
+    assert_eq!(summary.skipped, [1, 2, 3]);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+/// Test recording and reverting the history of an event.
+///
+/// Every insert, update and delete is appended with the caregiver and the event before and after, and can be undone.
+#[test]
+fn test_event_history() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let sam = write_caregiver(
+        connection,
+        NewCaregiver {
+            name: "Sam".to_owned(),
+        },
+    )
+    .unwrap();
+    let mut new_event: NewBabyEvent = create_event(None, None, None, None, Some(90), None, None);
+    new_event.caregiver_id = Some(sam.id);
+    let id = write_event(connection, new_event).unwrap().id();
+    let inserted = read_events(connection).unwrap()[0];
+
+    // updates record both versions, and updates changing nothing are not recorded
+    let updated = BabyEvent {
+        breastmilk: 120,
+        formula: 30,
+        ..inserted
+    };
+    assert_eq!(update_event(connection, updated, None).unwrap(), 1);
+    assert_eq!(update_event(connection, updated, None).unwrap(), 1);
+    assert_eq!(delete_event(connection, updated, Some(sam.id)).unwrap(), 1);
+
+    let history = read_event_history(connection, id).unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|e| e.action)
+            .collect::<Vec<HistoryAction>>(),
+        [
+            HistoryAction::Insert,
+            HistoryAction::Update,
+            HistoryAction::Delete
+        ]
+    );
+    assert_eq!(
+        history
+            .iter()
+            .map(|e| e.caregiver_id)
+            .collect::<Vec<Option<i32>>>(),
+        [Some(sam.id), None, Some(sam.id)]
+    );
+    assert_eq!(history[0].before_event().unwrap(), None);
+    assert_eq!(history[0].after_event().unwrap(), Some(inserted));
+    assert_eq!(history[1].before_event().unwrap(), Some(inserted));
+    assert_eq!(history[1].after_event().unwrap(), Some(updated));
+    assert_eq!(inserted.changed_fields(&updated), ["breastmilk", "formula"]);
+    assert_eq!(history[2].after_event().unwrap(), None);
+
+    // reverting the deletion restores the event, and reverting the update restores the version before it
+    assert_eq!(revert_event(connection, &history[2], None).unwrap(), 1);
+    assert_eq!(read_events(connection).unwrap(), [updated]);
+    assert_eq!(revert_event(connection, &history[1], None).unwrap(), 1);
+    assert_eq!(read_events(connection).unwrap(), [inserted]);
+
+    // reverting the insert deletes the event
+    assert_eq!(revert_event(connection, &history[0], None).unwrap(), 1);
+    assert!(read_events(connection).unwrap().is_empty());
+    assert_eq!(read_event_history(connection, id).unwrap().len(), 6);
+
+    // the history cannot be changed
+    assert!(diesel::delete(babyrs::schema::event_history::table)
+        .execute(connection)
+        .is_err());
+    assert_eq!(read_event_history(connection, id).unwrap().len(), 6);
+}
+
+/// Test bringing an event back to an earlier version.
+///
+/// Any version an insert or update left can be gone back to in a single recorded update, as long as the event is live.
+#[test]
+fn test_revert_event_to_version() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let new_event: NewBabyEvent = create_event(None, None, None, None, Some(90), None, None);
+    let id = write_event(connection, new_event).unwrap().id();
+    let inserted = read_events(connection).unwrap()[0];
+    let first = BabyEvent {
+        breastmilk: 120,
+        ..inserted
+    };
+    let second = BabyEvent {
+        formula: 30,
+        ..first
+    };
+    assert_eq!(update_event(connection, first, None).unwrap(), 1);
+    assert_eq!(update_event(connection, second, None).unwrap(), 1);
+    let history = read_event_history(connection, id).unwrap();
+
+    // the first version is gone back to at once, while undoing the first update has to wait for the second
+    assert!(revert_event(connection, &history[1], None).is_err());
+    assert_eq!(
+        revert_event_to_version(connection, &history[0], None).unwrap(),
+        1
+    );
+    assert_eq!(read_events(connection).unwrap(), [inserted]);
+    let latest = read_event_history(connection, id).unwrap();
+    assert_eq!(latest.len(), 4);
+    assert_eq!(latest[3].action, HistoryAction::Update);
+    assert_eq!(latest[3].before_event().unwrap(), Some(second));
+    assert_eq!(latest[3].after_event().unwrap(), Some(inserted));
+
+    // going back to the current version changes nothing and records nothing
+    assert_eq!(
+        revert_event_to_version(connection, &history[1], None).unwrap(),
+        1
+    );
+    assert_eq!(read_events(connection).unwrap(), [first]);
+    assert_eq!(
+        revert_event_to_version(connection, &history[1], None).unwrap(),
+        1
+    );
+    assert_eq!(read_event_history(connection, id).unwrap().len(), 5);
+
+    // a deletion leaves no version, and an event in the trash or purged is not brought back
+    assert_eq!(delete_event(connection, first, None).unwrap(), 1);
+    let history = read_event_history(connection, id).unwrap();
+    for entry in [&history[0], &history[5]] {
+        assert!(matches!(
+            revert_event_to_version(connection, entry, None),
+            Err(BabyrsError::Validation(_))
+        ));
+    }
+    let deleted = read_deleted_events(connection, DEFAULT_CHILD_ID).unwrap()[0];
+    assert_eq!(purge_event(connection, deleted, None).unwrap(), 1);
+    assert!(matches!(
+        revert_event_to_version(connection, &history[0], None),
+        Err(BabyrsError::Validation(_))
+    ));
+    assert!(read_events(connection).unwrap().is_empty());
+}
+
+/// Test that a new event never takes over the id and history of a purged event.
+///
+/// This test purges the newest event, inserts a new one, and checks that no change of the purged event is reverted
+/// and that only changes the event is still as they left it are reverted.
+#[test]
+fn test_purged_event_ids() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    for volume in [60, 90] {
+        let mut new_event: NewBabyEvent =
+            create_event(None, None, None, None, Some(volume), None, None);
+        new_event.dt += chrono::Duration::minutes(volume.into());
+        write_event(connection, new_event).unwrap();
+    }
+    let newest = read_events(connection).unwrap()[1];
+    let updated = BabyEvent {
+        formula: 30,
+        ..newest
+    };
+    assert_eq!(update_event(connection, updated, None).unwrap(), 1);
+    assert_eq!(delete_event(connection, updated, None).unwrap(), 1);
+    let deleted = read_deleted_events(connection, DEFAULT_CHILD_ID).unwrap()[0];
+    assert_eq!(purge_event(connection, deleted, None).unwrap(), 1);
+    let history = read_event_history(connection, newest.id).unwrap();
+
+    // the new event gets a fresh id, with no history but its own insert
+    let new_event = create_event(None, None, None, None, None, Some(45), None);
+    let id = write_event(connection, new_event).unwrap().id();
+    assert!(id > newest.id);
+    let new_history = read_event_history(connection, id).unwrap();
+    assert_eq!(new_history.len(), 1);
+    assert_eq!(new_history[0].action, HistoryAction::Insert);
+    assert_eq!(new_history[0].before, None);
+
+    // no change of the purged event can be reverted, and the new event is left alone
+    assert_eq!(history[1].action, HistoryAction::Update);
+    for entry in &history {
+        assert!(matches!(
+            revert_event(connection, entry, None),
+            Err(BabyrsError::Validation(_))
+        ));
+    }
+    assert_eq!(read_events(connection).unwrap()[1].id, id);
+
+    // a change followed by another one is only reverted once the later one is
+    let oldest = read_events(connection).unwrap()[0];
+    let updated = BabyEvent {
+        formula: 30,
+        ..oldest
+    };
+    assert_eq!(update_event(connection, updated, None).unwrap(), 1);
+    assert_eq!(update_event(connection, oldest, None).unwrap(), 1);
+    let history = read_event_history(connection, oldest.id).unwrap();
+    let update = &history[1];
+    assert!(revert_event(connection, update, None).is_err());
+    assert_eq!(read_events(connection).unwrap()[0], oldest);
+    assert_eq!(
+        revert_event(connection, history.last().unwrap(), None).unwrap(),
+        1
+    );
+    assert_eq!(revert_event(connection, update, None).unwrap(), 1);
+    assert_eq!(read_events(connection).unwrap()[0], oldest);
+}
+
+/// Test moving events to the trash, restoring them and purging them.
+///
+/// Deleted events are left out of reads and calculations until they are restored, and purged once they expire.
+#[test]
+fn test_trash() {
+    use babyrs::schema::events::dsl::*;
+
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    for volume in [60, 90, 120] {
+        let mut new_event: NewBabyEvent =
+            create_event(None, None, None, None, Some(volume), None, None);
+        new_event.dt += chrono::Duration::minutes(volume.into());
+        write_event(connection, new_event).unwrap();
+    }
+    let live = read_events(connection).unwrap();
+
+    // deleted events are left out, also of calculations on every stored event
+    assert_eq!(delete_event(connection, live[0], None).unwrap(), 1);
+    assert_eq!(delete_event(connection, live[0], None).unwrap(), 0);
+    assert_eq!(delete_event(connection, live[1], None).unwrap(), 1);
+    assert_eq!(read_events(connection).unwrap(), [live[2]]);
+
+    let stored: Vec<BabyEvent> = events.load(connection).unwrap();
+    assert_eq!(stored.len(), 3);
+    assert_eq!(babyrs::calculate_statistics(stored).breastmilk, 120);
+
+    let deleted = read_deleted_events(connection, DEFAULT_CHILD_ID).unwrap();
+    assert_eq!(
+        deleted.iter().map(|e| e.id).collect::<Vec<i32>>(),
+        [live[1].id, live[0].id]
+    );
+    assert!(deleted.iter().all(|e| e.is_deleted()));
+
+    // a deleted event is neither updated nor purged unless it is in the trash
+    assert_eq!(update_event(connection, deleted[0], None).unwrap(), 0);
+    assert_eq!(purge_event(connection, live[2], None).unwrap(), 0);
+
+    // restoring takes the event out of the trash
+    assert_eq!(restore_event(connection, deleted[0], None).unwrap(), 1);
+    assert_eq!(read_events(connection).unwrap(), [live[1], live[2]]);
+
+    // only the events deleted before the limit expire
+    let now = chrono::Utc::now().naive_utc();
+    assert_eq!(
+        purge_deleted_events(connection, now - chrono::Duration::days(1)).unwrap(),
+        0
+    );
+    assert_eq!(
+        purge_deleted_events(connection, now + chrono::Duration::days(1)).unwrap(),
+        1
+    );
+    assert!(read_deleted_events(connection, DEFAULT_CHILD_ID)
+        .unwrap()
+        .is_empty());
+
+    // a purge is final, so neither reverting nor restoring brings the event back
+    let history = read_event_history(connection, live[0].id).unwrap();
+    assert_eq!(history.last().unwrap().action, HistoryAction::Purge);
+    assert!(matches!(
+        revert_event(connection, history.last().unwrap(), None),
+        Err(BabyrsError::Validation(_))
+    ));
+    assert_eq!(restore_event(connection, deleted[1], None).unwrap(), 0);
+    assert_eq!(read_events(connection).unwrap().len(), 2);
+
+    // snapshots recorded before the trash existed still decode
+    let snapshot = live[0].to_snapshot().unwrap();
+    let old_snapshot = snapshot.strip_suffix(',').unwrap();
+    assert_eq!(BabyEvent::from_snapshot(old_snapshot).unwrap(), live[0]);
+}
+
+/// Test that purging an event cannot be reverted.
+///
+/// This test purges an event with a note and typed values, and checks that reverting the purge fails and leaves the
+/// event and everything noted with it gone.
+#[test]
+fn test_revert_purge() {
+    use std::collections::BTreeMap;
+
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let fields = vec![NewEventField::parse("minutes:duration").unwrap()];
+    let bath = write_event_type(
+        connection,
+        NewEventType {
+            name: "Bath".to_owned(),
+        },
+        fields,
+    )
+    .unwrap();
+    let minutes = read_event_registry(connection).unwrap().fields_of(bath.id)[0].id;
+
+    let mut new_event: NewBabyEvent = create_event(None, None, None, None, None, None, None);
+    new_event.event_type_id = Some(bath.id);
+    let values = BTreeMap::from([(minutes, "15".to_owned())]);
+    let WriteOutcome::Inserted(event_id) =
+        write_event_with_values(connection, new_event, &values, DuplicatePolicy::Merge).unwrap()
+    else {
+        panic!("the event was not inserted");
+    };
+    write_note(connection, event_id, "splashed a lot").unwrap();
+
+    let event = read_events(connection).unwrap()[0];
+    assert_eq!(delete_event(connection, event, None).unwrap(), 1);
+    let deleted = read_deleted_events(connection, DEFAULT_CHILD_ID).unwrap()[0];
+    assert_eq!(purge_event(connection, deleted, None).unwrap(), 1);
+
+    let history = read_event_history(connection, event_id).unwrap();
+    let purge = history.last().unwrap();
+    assert_eq!(purge.action, HistoryAction::Purge);
+    assert!(matches!(
+        revert_event(connection, purge, None),
+        Err(BabyrsError::Validation(_))
+    ));
+
+    assert!(read_events(connection).unwrap().is_empty());
+    assert!(read_deleted_events(connection, DEFAULT_CHILD_ID)
+        .unwrap()
+        .is_empty());
+    assert!(read_event_values(connection, event_id).unwrap().is_empty());
+    let notes: i64 = babyrs::schema::event_notes::table
+        .count()
+        .get_result(connection)
+        .unwrap();
+    assert_eq!(notes, 0);
+    assert_eq!(read_event_history(connection, event_id).unwrap().len(), 3);
+}
+
+/// Test user-defined event types and the values of their events.
+///
+/// This test defines a type, records events with values, purges one and round-trips the values through a CSV file.
+#[test]
+fn test_event_types() {
+    use std::collections::BTreeMap;
+
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let fields = ["minutes:duration", "product:enum(soap|oil)", "remark:text"]
+        .into_iter()
+        .map(|spec| NewEventField::parse(spec).unwrap())
+        .collect::<Vec<NewEventField>>();
+    let bath = write_event_type(
+        connection,
+        NewEventType {
+            name: " Bath ".to_owned(),
+        },
+        fields.clone(),
+    )
+    .unwrap();
+    assert_eq!(bath.name, "Bath");
+
+    // types need a unique name and fields with unique names
+    let new_type = |name: &str| NewEventType {
+        name: name.to_owned(),
+    };
+    assert!(write_event_type(connection, new_type("bath"), vec![]).is_err());
+    let twice = vec![fields[0].clone(), fields[0].clone()];
+    assert!(matches!(
+        write_event_type(connection, new_type("Tummy time"), twice),
+        Err(BabyrsError::Validation(_))
+    ));
+
+    let registry = read_event_registry(connection).unwrap();
+    assert_eq!(registry.types, std::slice::from_ref(&bath));
+    assert_eq!(registry.find_type("BATH"), Some(&bath));
+    let type_fields = registry.fields_of(bath.id);
+    assert_eq!(
+        type_fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.position))
+            .collect::<Vec<(&str, i32)>>(),
+        [("minutes", 0), ("product", 1), ("remark", 2)]
+    );
+    let (minutes, product, remark) = (type_fields[0].id, type_fields[1].id, type_fields[2].id);
+
+    let mut new_event: NewBabyEvent = create_event(None, None, None, None, None, None, None);
+    new_event.event_type_id = Some(bath.id);
+    let values = BTreeMap::from([
+        (minutes, "15".to_owned()),
+        (product, "oil".to_owned()),
+        (remark, "warm; a=b \\ c".to_owned()),
+    ]);
+
+    // values have to belong to the type and be stored the way the field stores them
+    let invalid = BTreeMap::from([(product, "cream".to_owned())]);
+    assert!(write_event_with_values(connection, new_event, &invalid, Default::default()).is_err());
+    let mut untyped = new_event;
+    untyped.event_type_id = None;
+    assert!(write_event_with_values(connection, untyped, &values, Default::default()).is_err());
+
+    let WriteOutcome::Inserted(event_id) =
+        write_event_with_values(connection, new_event, &values, DuplicatePolicy::Merge).unwrap()
+    else {
+        panic!("the event was not inserted");
+    };
+    let event = read_events(connection).unwrap()[0];
+    assert_eq!(event.event_type_id, Some(bath.id));
+    assert_eq!(read_event_values(connection, event_id).unwrap(), values);
+
+    // the same values are a duplicate, other values a different event that is never merged
+    assert!(matches!(
+        write_event_with_values(connection, new_event, &values, DuplicatePolicy::Merge),
+        Ok(WriteOutcome::Skipped(_))
+    ));
+    let shorter = BTreeMap::from([(minutes, "5".to_owned())]);
+    let WriteOutcome::Inserted(shorter_id) =
+        write_event_with_values(connection, new_event, &shorter, DuplicatePolicy::Merge).unwrap()
+    else {
+        panic!("the event was not inserted");
+    };
+    assert_eq!(
+        write_event_values(connection, shorter_id, &BTreeMap::new()).unwrap(),
+        0
+    );
+    assert!(read_event_values(connection, shorter_id)
+        .unwrap()
+        .is_empty());
+
+    // purging an event takes its values with it
+    let shorter_event = read_events(connection).unwrap()[1];
+    assert_eq!(shorter_event.id, shorter_id);
+    delete_event(connection, shorter_event, None).unwrap();
+    let shorter_event = read_deleted_events(connection, DEFAULT_CHILD_ID).unwrap()[0];
+    assert_eq!(purge_event(connection, shorter_event, None).unwrap(), 1);
+    let start = event.dt - chrono::Duration::days(1);
+    let end = event.dt + chrono::Duration::days(1);
+    let stored = read_event_values_between(connection, DEFAULT_CHILD_ID, start, end).unwrap();
+    assert_eq!(stored.len(), 3);
+    assert!(stored.iter().all(|value| value.event_id == event.id));
+
+    // an export names the type and its values, and imports into a database defining the same type
+    let export_path = std::env::temp_dir().join(format!("babyrs-types-{}.csv", std::process::id()));
+    let export_path = export_path.to_str().unwrap();
+    let events = read_events(connection).unwrap();
+    babyrs::export_csv(
+        &events,
+        &stored,
+        &registry,
+        export_path,
+        VolumeUnit::Millilitre,
+    )
+    .unwrap();
+    let exported = std::fs::read_to_string(export_path).unwrap();
+    assert!(exported.contains(",Bath,"));
+
+    let mut other: SqliteConnection = establish_connection().unwrap();
+    common::run_migrations(&mut other).expect("Error running migrations");
+    assert!(babyrs::process_csv(&mut other, export_path).is_err());
+
+    let other_bath = write_event_type(&mut other, new_type("bath"), fields).unwrap();
+    let summary = babyrs::process_csv(&mut other, export_path).unwrap();
+    assert_eq!(summary.inserted, 1);
+    let imported = read_events(&mut other).unwrap();
+    assert_eq!(imported[0].event_type_id, Some(other_bath.id));
+    let imported_values = read_event_values(&mut other, imported[0].id).unwrap();
+    assert_eq!(
+        imported_values.into_values().collect::<Vec<String>>(),
+        values.into_values().collect::<Vec<String>>()
+    );
+
+    std::fs::remove_file(export_path).unwrap();
+}
@@ -1,9 +1,17 @@
 //! Integration tests for application state handling.
 mod common;
 
-use babyrs::models::NewBabyEvent;
+use babyrs::growth::Measure;
+use babyrs::models::{
+    Amount, BabyEvent, DiaperDetails, NewBabyEvent, NewCaregiver, NewChild, NewEventField,
+    NewEventType, NewMeasurement, NewMedication, NewMedicationSchedule, Side, StoolColor,
+};
 use babyrs::terminal;
-use babyrs::{create_event, establish_connection, write_event};
+use babyrs::units::VolumeUnit;
+use babyrs::{
+    create_event, establish_connection, read_child_doses, write_event, DuplicatePolicy,
+    WriteOutcome, DEFAULT_CHILD_ID,
+};
 use diesel::prelude::*;
 use ratatui::widgets::ListState;
 
@@ -11,7 +19,7 @@ use ratatui::widgets::ListState;
 fn test_load_events() {
     std::env::set_var("DATABASE_URL", ":memory:");
 
-    let connection: &mut SqliteConnection = &mut establish_connection();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
 
     common::run_migrations(connection).expect("Error running migrations");
 
@@ -25,21 +33,41 @@ fn test_load_events() {
         Some(25),
     );
 
-    write_event(connection, new_event);
+    write_event(connection, new_event).unwrap();
 
     let mut state = terminal::state::AppState::initialized();
 
-    state.load_events(Some(connection));
+    state.load_events(Some(connection)).unwrap();
 
-    let results = state.get_events().expect("Error loading events");
+    let results = state.get_filtered_events().expect("Error loading events");
     assert_eq!(results.len(), 1);
 }
 
+#[test]
+fn test_load_events_empty() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let mut state = terminal::state::AppState::initialized();
+
+    state.load_events(Some(connection)).unwrap();
+
+    // an empty database starts on today
+    assert!(state.get_filtered_events().unwrap().is_empty());
+    assert_eq!(
+        state.get_filter().unwrap(),
+        &terminal::state::Filter::Day(chrono::Local::now().date_naive())
+    );
+}
+
 #[test]
 fn test_get_events() {
     std::env::set_var("DATABASE_URL", ":memory:");
 
-    let connection: &mut SqliteConnection = &mut establish_connection();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
 
     common::run_migrations(connection).expect("Error running migrations");
 
@@ -53,18 +81,18 @@ fn test_get_events() {
         Some(25),
     );
 
-    write_event(connection, new_event);
+    write_event(connection, new_event).unwrap();
 
     let mut state = terminal::state::AppState::initialized();
 
-    state.load_events(Some(connection));
+    state.load_events(Some(connection)).unwrap();
 
-    let results = state.get_events().expect("Error loading events");
+    let results = state.get_filtered_events().expect("Error loading events");
     assert_eq!(results.len(), 1);
 
     let event = &results[0];
-    assert_eq!(event.urine, true);
-    assert_eq!(event.stool, true);
+    assert!(event.urine);
+    assert!(event.stool);
     assert_eq!(event.skin2skin, 5);
     assert_eq!(event.breastfeed, 10);
     assert_eq!(event.breastmilk, 15);
@@ -77,7 +105,7 @@ fn test_get_selection() {
     std::env::set_var("DATABASE_URL", ":memory:");
     let mut state = terminal::state::AppState::initialized();
     let mut test_state = ListState::default();
-    let connection: &mut SqliteConnection = &mut establish_connection();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
 
     common::run_migrations(connection).expect("Error running migrations");
 
@@ -91,8 +119,8 @@ fn test_get_selection() {
         Some(25),
     );
 
-    write_event(connection, new_event);
-    state.load_events(Some(connection));
+    write_event(connection, new_event).unwrap();
+    state.load_events(Some(connection)).unwrap();
 
     // nothing is selected by default
     test_state.select(None);
@@ -109,7 +137,7 @@ fn test_increment_selection() {
     std::env::set_var("DATABASE_URL", ":memory:");
     let mut state = terminal::state::AppState::initialized();
     let mut test_state = ListState::default();
-    let connection: &mut SqliteConnection = &mut establish_connection();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
 
     common::run_migrations(connection).expect("Error running migrations");
 
@@ -134,8 +162,8 @@ fn test_increment_selection() {
         ),
     ];
 
-    write_event(connection, new_events[0]);
-    state.load_events(Some(connection));
+    write_event(connection, new_events[0]).unwrap();
+    state.load_events(Some(connection)).unwrap();
 
     // nothing is selected by default
     test_state.select(None);
@@ -151,8 +179,8 @@ fn test_increment_selection() {
     assert_eq!(state.get_selection(), Some(&mut test_state));
 
     // add another event
-    write_event(connection, new_events[1]);
-    state.load_events(Some(connection));
+    write_event(connection, new_events[1]).unwrap();
+    state.load_events(Some(connection)).unwrap();
 
     // reloading events should reset the selection
     state.increment_selection();
@@ -170,7 +198,7 @@ fn test_decrement_selection() {
     std::env::set_var("DATABASE_URL", ":memory:");
     let mut state = terminal::state::AppState::initialized();
     let mut test_state = ListState::default();
-    let connection: &mut SqliteConnection = &mut establish_connection();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
 
     common::run_migrations(connection).expect("Error running migrations");
 
@@ -195,8 +223,8 @@ fn test_decrement_selection() {
         ),
     ];
 
-    write_event(connection, new_events[0]);
-    state.load_events(Some(connection));
+    write_event(connection, new_events[0]).unwrap();
+    state.load_events(Some(connection)).unwrap();
 
     // nothing is selected by default
     test_state.select(None);
@@ -213,8 +241,8 @@ fn test_decrement_selection() {
     assert_eq!(state.get_selection(), Some(&mut test_state));
 
     // add another event
-    write_event(connection, new_events[1]);
-    state.load_events(Some(connection));
+    write_event(connection, new_events[1]).unwrap();
+    state.load_events(Some(connection)).unwrap();
 
     // reloading events should reset the selection
     state.decrement_selection();
@@ -232,7 +260,7 @@ fn test_unselect() {
     std::env::set_var("DATABASE_URL", ":memory:");
     let mut state = terminal::state::AppState::initialized();
     let mut test_state = ListState::default();
-    let connection: &mut SqliteConnection = &mut establish_connection();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
 
     common::run_migrations(connection).expect("Error running migrations");
 
@@ -246,8 +274,8 @@ fn test_unselect() {
         Some(25),
     );
 
-    write_event(connection, new_event);
-    state.load_events(Some(connection));
+    write_event(connection, new_event).unwrap();
+    state.load_events(Some(connection)).unwrap();
 
     // nothing is selected by default
     test_state.select(None);
@@ -263,3 +291,841 @@ fn test_unselect() {
     test_state.select(None);
     assert_eq!(state.get_selection(), Some(&mut test_state));
 }
+
+#[test]
+fn test_add_event() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let mut new_event: NewBabyEvent =
+        create_event(Some(true), Some(false), None, None, Some(90), None, None);
+    new_event.dt = chrono::NaiveDate::from_ymd_opt(2023, 6, 15)
+        .unwrap()
+        .and_hms_opt(5, 15, 0)
+        .unwrap();
+
+    assert_eq!(
+        state.add_event(new_event, Some(connection)).unwrap(),
+        Some(WriteOutcome::Inserted(1))
+    );
+
+    // the filter follows the new event and the new event is selected
+    assert_eq!(
+        state.get_filter().unwrap(),
+        &terminal::state::Filter::Day(new_event.dt.date())
+    );
+    assert_eq!(state.get_filtered_events().unwrap().len(), 1);
+    assert_eq!(state.get_selection().unwrap().selected(), Some(0));
+    assert_eq!(state.get_filtered_events().unwrap()[0].breastmilk, 90);
+
+    // writing the same event again is skipped
+    assert_eq!(
+        state.add_event(new_event, Some(connection)).unwrap(),
+        Some(WriteOutcome::Skipped(1))
+    );
+    assert_eq!(state.get_filtered_events().unwrap().len(), 1);
+
+    // another event at the same timestamp is kept next to it and selected
+    let mut diaper = create_event(Some(true), Some(true), None, None, None, None, None);
+    diaper.dt = new_event.dt;
+    assert_eq!(
+        state.add_event(diaper, Some(connection)).unwrap(),
+        Some(WriteOutcome::Inserted(2))
+    );
+    assert_eq!(state.get_filtered_events().unwrap().len(), 2);
+    assert_eq!(state.get_selected_event().unwrap().id, 2);
+
+    // or merged into the first event at that timestamp
+    state.set_duplicate_policy(DuplicatePolicy::Merge);
+    let mut formula = create_event(None, None, None, None, None, Some(30), None);
+    formula.dt = new_event.dt;
+    assert_eq!(
+        state.add_event(formula, Some(connection)).unwrap(),
+        Some(WriteOutcome::Merged(1))
+    );
+    assert_eq!(state.get_filtered_events().unwrap().len(), 2);
+    let merged = state.get_selected_event().unwrap();
+    assert_eq!((merged.id, merged.breastmilk, merged.formula), (1, 90, 30));
+
+    // or written regardless
+    state.set_duplicate_policy(DuplicatePolicy::KeepBoth);
+    assert_eq!(
+        state.add_event(diaper, Some(connection)).unwrap(),
+        Some(WriteOutcome::Inserted(3))
+    );
+    assert_eq!(state.get_filtered_events().unwrap().len(), 3);
+}
+
+#[test]
+fn test_update_event() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    for hour in [5, 8, 11] {
+        let mut new_event: NewBabyEvent =
+            create_event(None, None, None, None, Some(90), None, None);
+        new_event.dt = date.and_hms_opt(hour, 0, 0).unwrap();
+        write_event(connection, new_event).unwrap();
+    }
+    state.load_events(Some(connection)).unwrap();
+
+    // select and edit the second event
+    state.increment_selection();
+    state.increment_selection();
+    let mut event = state.get_selected_event().unwrap();
+    event.breastmilk = 60;
+
+    assert_eq!(state.update_event(event, Some(connection)).unwrap(), 1);
+
+    // the edited event is still selected after the refresh
+    assert_eq!(state.get_selection().unwrap().selected(), Some(1));
+    assert_eq!(state.get_selected_event().unwrap().id, event.id);
+    assert_eq!(state.get_selected_event().unwrap().breastmilk, 60);
+
+    // the event may be moved onto another event's timestamp, keeping both
+    event.dt = date.and_hms_opt(5, 0, 0).unwrap();
+    assert_eq!(state.update_event(event, Some(connection)).unwrap(), 1);
+    assert_eq!(state.get_selected_event().unwrap().id, event.id);
+    assert_eq!(state.get_filtered_events().unwrap().len(), 3);
+}
+
+#[test]
+fn test_event_types() {
+    use std::collections::BTreeMap;
+
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+    state.load_events(Some(connection)).unwrap();
+    assert!(state.get_event_registry().unwrap().types.is_empty());
+
+    let fields = vec![
+        NewEventField::parse("minutes:duration").unwrap(),
+        NewEventField::parse("outside:bool").unwrap(),
+    ];
+    let new_type = NewEventType {
+        name: "Tummy time".to_owned(),
+    };
+    let tummy = state
+        .add_event_type(new_type, fields, Some(connection))
+        .unwrap()
+        .unwrap();
+
+    // the registry is reloaded with the new type
+    let registry = state.get_event_registry().unwrap();
+    assert_eq!(registry.types, std::slice::from_ref(&tummy));
+    let field_ids = registry
+        .fields_of(tummy.id)
+        .iter()
+        .map(|field| field.id)
+        .collect::<Vec<i32>>();
+
+    let mut new_event: NewBabyEvent = create_event(None, None, None, None, None, None, None);
+    new_event.event_type_id = Some(tummy.id);
+    let values = BTreeMap::from([
+        (field_ids[0], "10".to_owned()),
+        (field_ids[1], "false".to_owned()),
+    ]);
+    assert_eq!(
+        state
+            .add_event_with_values(new_event, &values, Some(connection))
+            .unwrap(),
+        Some(WriteOutcome::Inserted(1))
+    );
+    assert_eq!(state.get_event_values(1), values);
+
+    // editing replaces the values of the selected event
+    let event = state.get_selected_event().unwrap();
+    let values = BTreeMap::from([(field_ids[0], "20".to_owned())]);
+    assert_eq!(
+        state
+            .update_event_with_values(event, &values, Some(connection))
+            .unwrap(),
+        1
+    );
+    assert_eq!(state.get_event_values(event.id), values);
+
+    // an invalid value leaves the event unchanged
+    let invalid = BTreeMap::from([(field_ids[1], "maybe".to_owned())]);
+    let mut moved = event;
+    moved.dt += chrono::Duration::minutes(5);
+    assert!(state
+        .update_event_with_values(moved, &invalid, Some(connection))
+        .is_err());
+    assert_eq!(state.get_selected_event().unwrap().dt, event.dt);
+    assert_eq!(state.get_event_values(event.id), values);
+}
+
+#[test]
+fn test_delete_and_restore_event() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    for hour in [5, 8, 11] {
+        let mut new_event: NewBabyEvent = create_event(None, None, None, None, None, None, None);
+        new_event.dt = date.and_hms_opt(hour, 0, 0).unwrap();
+        write_event(connection, new_event).unwrap();
+    }
+    state.load_events(Some(connection)).unwrap();
+
+    // delete the last event, the selection moves to the preceding one
+    state.decrement_selection();
+    state.decrement_selection();
+    let deleted = state.get_selected_event().unwrap();
+
+    assert_eq!(state.delete_event(deleted, Some(connection)).unwrap(), 1);
+    assert_eq!(state.get_filtered_events().unwrap().len(), 2);
+    assert_eq!(state.get_selection().unwrap().selected(), Some(1));
+
+    // restoring keeps the original id and timestamp and selects the event again
+    assert_eq!(state.restore_event(deleted, Some(connection)).unwrap(), 1);
+    assert_eq!(state.get_filtered_events().unwrap().len(), 3);
+
+    let restored = state.get_selected_event().unwrap();
+    assert_eq!(restored.id, deleted.id);
+    assert_eq!(restored.dt, deleted.dt);
+}
+
+#[test]
+fn test_trash() {
+    use babyrs::schema::events::dsl::*;
+
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    for hour in [5, 8, 11] {
+        let mut new_event: NewBabyEvent = create_event(None, None, None, None, None, None, None);
+        new_event.dt = date.and_hms_opt(hour, 0, 0).unwrap();
+        write_event(connection, new_event).unwrap();
+    }
+    state.load_events(Some(connection)).unwrap();
+
+    let first = state.get_filtered_events().unwrap()[0];
+    let second = state.get_filtered_events().unwrap()[1];
+    state.delete_event(first, Some(connection)).unwrap();
+    state.delete_event(second, Some(connection)).unwrap();
+    assert_eq!(state.get_filtered_events().unwrap().len(), 1);
+    assert_eq!(
+        state.read_deleted_events(Some(connection)).unwrap().len(),
+        2
+    );
+
+    // purging removes the event from the trash for good
+    assert_eq!(state.purge_event(second, Some(connection)).unwrap(), 1);
+    let trash = state.read_deleted_events(Some(connection)).unwrap();
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].id, first.id);
+
+    // events deleted longer ago than the retention period are purged when loading
+    diesel::update(events.find(first.id))
+        .set(deleted_at.eq(chrono::Utc::now().naive_utc() - chrono::Duration::days(31)))
+        .execute(connection)
+        .unwrap();
+    state.load_events(Some(connection)).unwrap();
+    assert!(state
+        .read_deleted_events(Some(connection))
+        .unwrap()
+        .is_empty());
+    assert_eq!(state.get_filtered_events().unwrap().len(), 1);
+}
+
+#[test]
+fn test_event_history() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+    state.load_events(Some(connection)).unwrap();
+
+    let new_caregiver = NewCaregiver {
+        name: "Sam".to_owned(),
+    };
+    state
+        .add_caregiver(new_caregiver, Some(connection))
+        .unwrap();
+    let sam = state.get_caregiver().unwrap().id;
+
+    let mut new_event: NewBabyEvent = create_event(None, None, None, None, Some(90), None, None);
+    new_event.dt = chrono::NaiveDate::from_ymd_opt(2023, 6, 15)
+        .unwrap()
+        .and_hms_opt(5, 0, 0)
+        .unwrap();
+    state.add_event(new_event, Some(connection)).unwrap();
+    let event = state.get_selected_event().unwrap();
+
+    // the changes are recorded for the current caregiver
+    let mut updated = event;
+    updated.breastmilk = 120;
+    state.update_event(updated, Some(connection)).unwrap();
+
+    let history = state
+        .read_event_history(event.id, Some(connection))
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    assert!(history.iter().all(|e| e.caregiver_id == Some(sam)));
+
+    // reverting the update restores and selects the earlier version
+    assert_eq!(
+        state.revert_event(&history[1], Some(connection)).unwrap(),
+        1
+    );
+    assert_eq!(state.get_selected_event().unwrap().breastmilk, 90);
+
+    // going back to the updated version is a single change of its own
+    assert_eq!(
+        state
+            .revert_event_to_version(&history[1], Some(connection))
+            .unwrap(),
+        1
+    );
+    assert_eq!(state.get_selected_event().unwrap().breastmilk, 120);
+    let history = state
+        .read_event_history(event.id, Some(connection))
+        .unwrap();
+    assert_eq!(history.len(), 4);
+    assert_eq!(history[3].caregiver_id, Some(sam));
+}
+
+#[test]
+fn test_move_filter() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    for day in [date.pred_opt().unwrap(), date] {
+        let mut new_event: NewBabyEvent =
+            create_event(Some(true), Some(false), None, None, None, None, None);
+        new_event.dt = day.and_hms_opt(5, 15, 0).unwrap();
+        write_event(connection, new_event).unwrap();
+    }
+
+    state.load_events(Some(connection)).unwrap();
+    state.get_selection().unwrap().select(Some(0));
+    assert_eq!(state.get_filtered_events().unwrap().len(), 1);
+
+    // stepping back a day shows the previous day's events
+    let previous = state.get_filter().unwrap().shift(-1);
+    state
+        .move_filter(previous.date(), Some(connection))
+        .unwrap();
+    assert_eq!(state.get_filter().unwrap(), &previous);
+    assert_eq!(state.get_filtered_events().unwrap().len(), 1);
+    assert_eq!(
+        state.get_filtered_events().unwrap()[0].dt.date(),
+        date.pred_opt().unwrap()
+    );
+    assert_eq!(state.get_selection().unwrap().selected(), None);
+
+    // the week holds both events
+    state.switch_filter(Some(connection)).unwrap();
+    state.move_filter(date, Some(connection)).unwrap();
+    assert_eq!(state.get_filtered_events().unwrap().len(), 2);
+
+    // nothing was logged the week after
+    let next = state.get_filter().unwrap().shift(1);
+    state.move_filter(next.date(), Some(connection)).unwrap();
+    assert!(state.get_filtered_events().unwrap().is_empty());
+}
+
+#[test]
+fn test_children() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let dt = chrono::NaiveDate::from_ymd_opt(2023, 6, 15)
+        .unwrap()
+        .and_hms_opt(5, 15, 0)
+        .unwrap();
+    let mut new_event: NewBabyEvent = create_event(None, None, None, None, Some(90), None, None);
+    new_event.dt = dt;
+    write_event(connection, new_event).unwrap();
+
+    state.load_events(Some(connection)).unwrap();
+    assert_eq!(state.get_children().unwrap().len(), 1);
+    assert_eq!(state.get_child().unwrap().id, DEFAULT_CHILD_ID);
+
+    // switching with a single child does nothing
+    state.switch_child(Some(connection)).unwrap();
+    assert_eq!(state.get_child().unwrap().id, DEFAULT_CHILD_ID);
+
+    // a new child is shown straight away, keeping the filter
+    let new_child = NewChild {
+        name: "Ada".to_owned(),
+        birth_date: None,
+        sex: None,
+        birth_weight: None,
+    };
+    state.add_child(new_child, Some(connection)).unwrap();
+    assert_eq!(state.get_children().unwrap().len(), 2);
+    assert_eq!(state.get_child().unwrap().name, "Ada");
+    assert_eq!(
+        state.get_filter().unwrap(),
+        &terminal::state::Filter::Day(dt.date())
+    );
+    assert!(state.get_filtered_events().unwrap().is_empty());
+
+    // events are added to the current child, even at a timestamp taken by another child
+    assert!(matches!(
+        state.add_event(new_event, Some(connection)).unwrap(),
+        Some(WriteOutcome::Inserted(_))
+    ));
+    assert_eq!(state.get_filtered_events().unwrap().len(), 1);
+    assert_ne!(
+        state.get_filtered_events().unwrap()[0].child_id,
+        DEFAULT_CHILD_ID
+    );
+
+    // switching wraps around to the first child
+    state.switch_child(Some(connection)).unwrap();
+    assert_eq!(state.get_child().unwrap().id, DEFAULT_CHILD_ID);
+    assert_eq!(state.get_filtered_events().unwrap().len(), 1);
+    assert_eq!(
+        state.get_filtered_events().unwrap()[0].child_id,
+        DEFAULT_CHILD_ID
+    );
+}
+
+#[test]
+fn test_measurements() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    state.load_events(Some(connection)).unwrap();
+    assert!(state.get_measurements().unwrap().is_empty());
+
+    // the growth panel cycles through the measures before it is hidden again
+    assert_eq!(state.get_growth_measure(), None);
+    state.switch_growth_measure();
+    assert_eq!(state.get_growth_measure(), Some(Measure::Weight));
+    state.switch_growth_measure();
+    state.switch_growth_measure();
+    assert_eq!(state.get_growth_measure(), Some(Measure::HeadCircumference));
+    state.switch_growth_measure();
+    assert_eq!(state.get_growth_measure(), None);
+
+    // measurements are added to the current child
+    let new_child = NewChild {
+        name: "Ada".to_owned(),
+        birth_date: None,
+        sex: None,
+        birth_weight: None,
+    };
+    state.add_child(new_child, Some(connection)).unwrap();
+    let new_measurement = NewMeasurement {
+        child_id: DEFAULT_CHILD_ID,
+        dt: chrono::NaiveDate::from_ymd_opt(2023, 6, 15)
+            .unwrap()
+            .and_hms_opt(5, 15, 0)
+            .unwrap(),
+        weight: Some(3250),
+        length: None,
+        head_circumference: None,
+    };
+    assert_eq!(
+        state
+            .add_measurement(new_measurement, Some(connection))
+            .unwrap(),
+        1
+    );
+    assert_eq!(state.get_measurements().unwrap().len(), 1);
+    assert_ne!(
+        state.get_measurements().unwrap()[0].child_id,
+        DEFAULT_CHILD_ID
+    );
+
+    // editing the child keeps it selected
+    let mut child = state.get_child().unwrap().clone();
+    child.sex = Some(babyrs::models::Sex::Female);
+    assert_eq!(
+        state.update_child(child.clone(), Some(connection)).unwrap(),
+        1
+    );
+    assert_eq!(state.get_child().unwrap(), &child);
+
+    // switching child shows the measurements of the other child
+    state.switch_child(Some(connection)).unwrap();
+    assert!(state.get_measurements().unwrap().is_empty());
+}
+
+#[test]
+fn test_toggle_sleep() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    state
+        .set_filter(terminal::state::Filter::Day(date), Some(connection))
+        .unwrap();
+    assert!(state.get_sleep_sessions().unwrap().is_empty());
+    assert_eq!(state.get_open_sleep(), None);
+
+    // falling asleep opens a session
+    let asleep = date.and_hms_opt(13, 0, 0).unwrap();
+    state.toggle_sleep(asleep, Some(connection)).unwrap();
+    assert_eq!(state.get_open_sleep().unwrap().start_dt, asleep);
+    assert_eq!(state.get_sleep_sessions().unwrap().len(), 1);
+
+    // waking up closes it
+    let awake = date.and_hms_opt(14, 30, 0).unwrap();
+    state.toggle_sleep(awake, Some(connection)).unwrap();
+    assert_eq!(state.get_open_sleep(), None);
+    assert_eq!(
+        state.get_sleep_sessions().unwrap()[0].duration(),
+        Some(chrono::Duration::minutes(90))
+    );
+
+    // waking up before falling asleep is rejected
+    state.toggle_sleep(awake, Some(connection)).unwrap();
+    assert!(state.toggle_sleep(asleep, Some(connection)).is_err());
+    assert!(state.get_open_sleep().is_some());
+}
+
+#[test]
+fn test_medications() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+    state.load_events(Some(connection)).unwrap();
+    assert!(state.get_medications().unwrap().is_empty());
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    let morning = date.and_hms_opt(9, 0, 0).unwrap();
+    state
+        .add_medication(
+            NewMedication {
+                name: "Paracetamol".to_owned(),
+                unit: "ml".to_owned(),
+                dose: 2.5,
+                min_interval_minutes: Some(240),
+                max_daily_doses: None,
+                max_daily_amount: None,
+            },
+            Some(NewMedicationSchedule {
+                child_id: 0,
+                medication_id: 0,
+                first_dt: morning,
+                interval_minutes: 6 * 60,
+            }),
+            Some(connection),
+        )
+        .unwrap();
+
+    let medication = state.get_medications().unwrap()[0].clone();
+    let status =
+        |state: &terminal::state::AppState, now| state.get_dose_statuses(now).unwrap()[0].1;
+
+    // the first scheduled dose is overdue an hour after it was due
+    let on_time = morning + chrono::Duration::minutes(60);
+    assert!(!status(&state, on_time).is_overdue(on_time));
+    let late = morning + chrono::Duration::minutes(90);
+    assert!(status(&state, late).is_overdue(late));
+
+    // giving it schedules the next one and blocks another dose for the minimum interval
+    assert_eq!(
+        state
+            .add_dose(medication.id, late, Some(connection))
+            .unwrap(),
+        1
+    );
+    let status = status(&state, late);
+    assert_eq!(status.last_dose, Some(late));
+    assert_eq!(status.next_due, Some(morning + chrono::Duration::hours(6)));
+    assert!(!status.is_overdue(late));
+
+    let early = late + chrono::Duration::hours(1);
+    assert!(matches!(
+        state.add_dose(medication.id, early, Some(connection)),
+        Err(babyrs::BabyrsError::Validation(_))
+    ));
+    assert_eq!(
+        read_child_doses(connection, DEFAULT_CHILD_ID)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_next_side() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+    state.load_events(Some(connection)).unwrap();
+    assert_eq!(state.get_next_side(), None);
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    let mut feed = |hour, left: u16, right: u16| {
+        let mut new_event: NewBabyEvent =
+            create_event(None, None, None, Some(left + right), None, None, None);
+        new_event.dt = date.and_hms_opt(hour, 0, 0).unwrap();
+        new_event.breastfeed_left = left.into();
+        new_event.breastfeed_right = right.into();
+        state.add_event(new_event, Some(connection)).unwrap();
+        state.get_next_side()
+    };
+
+    // after one side the other is next, after both the shorter one
+    assert_eq!(feed(5, 15, 0), Some(Side::Right));
+    assert_eq!(feed(8, 10, 15), Some(Side::Left));
+    assert_eq!(feed(11, 12, 8), Some(Side::Right));
+    assert_eq!(feed(14, 10, 10), None);
+}
+
+#[test]
+fn test_diaper_details() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+    state.load_events(Some(connection)).unwrap();
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    let mut new_event: NewBabyEvent =
+        create_event(Some(true), Some(true), None, None, None, None, None);
+    new_event.dt = date.and_hms_opt(5, 15, 0).unwrap();
+    state.add_event(new_event, Some(connection)).unwrap();
+    let event = state.get_selected_event().unwrap();
+    assert_eq!(state.get_diaper_details(event.id), None);
+
+    let details = DiaperDetails {
+        event_id: event.id,
+        stool_color: Some(StoolColor::White),
+        stool_consistency: None,
+        stool_size: Some(Amount::Medium),
+        urine_amount: None,
+        urate_crystals: false,
+    };
+    assert_eq!(
+        state.set_diaper_details(details, Some(connection)).unwrap(),
+        1
+    );
+    assert_eq!(state.get_diaper_details(event.id), Some(&details));
+    assert_eq!(state.get_selected_event().unwrap().id, event.id);
+
+    let concerning = state.get_concerning_stools().unwrap();
+    assert_eq!(concerning.len(), 1);
+    assert_eq!(concerning[0].0.id, event.id);
+    assert_eq!(concerning[0].1, StoolColor::White);
+
+    // a yellow stool is not concerning
+    let details = DiaperDetails {
+        stool_color: Some(StoolColor::Yellow),
+        ..details
+    };
+    state.set_diaper_details(details, Some(connection)).unwrap();
+    assert!(state.get_concerning_stools().unwrap().is_empty());
+
+    // editing the event to no stool deletes the stool details and the warning with them
+    let white = DiaperDetails {
+        stool_color: Some(StoolColor::White),
+        ..details
+    };
+    state.set_diaper_details(white, Some(connection)).unwrap();
+    let wet = BabyEvent {
+        stool: false,
+        ..state.get_selected_event().unwrap()
+    };
+    assert_eq!(state.update_event(wet, Some(connection)).unwrap(), 1);
+    assert_eq!(state.get_diaper_details(event.id), None);
+    assert!(state.get_concerning_stools().unwrap().is_empty());
+    state.update_event(event, Some(connection)).unwrap();
+    state.set_diaper_details(details, Some(connection)).unwrap();
+
+    // the details stay with the event in the trash, and purging the event deletes them
+    state.delete_event(event, Some(connection)).unwrap();
+    state.restore_event(event, Some(connection)).unwrap();
+    assert_eq!(state.get_diaper_details(event.id).copied(), Some(details));
+
+    state.delete_event(event, Some(connection)).unwrap();
+    state.purge_event(event, Some(connection)).unwrap();
+    state.restore_event(event, Some(connection)).unwrap();
+    assert_eq!(state.get_diaper_details(event.id), None);
+}
+
+#[test]
+fn test_notes() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+    state.load_events(Some(connection)).unwrap();
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    let mut new_event: NewBabyEvent = create_event(None, None, None, Some(5), None, None, None);
+    new_event.dt = date.and_hms_opt(5, 15, 0).unwrap();
+    state.add_event(new_event, Some(connection)).unwrap();
+    let event = state.get_selected_event().unwrap();
+    assert_eq!(state.get_note(event.id), None);
+
+    assert_eq!(
+        state
+            .set_note(event.id, "short feed, sleepy", Some(connection))
+            .unwrap(),
+        1
+    );
+    assert_eq!(state.get_note(event.id), Some("short feed, sleepy"));
+
+    // the search covers all dates, and shows the event on its date
+    new_event.dt = date.and_hms_opt(5, 15, 0).unwrap() + chrono::Duration::days(3);
+    state.add_event(new_event, Some(connection)).unwrap();
+    assert_eq!(state.get_note(event.id), None);
+
+    let results = state.search_notes("sleep", Some(connection)).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0.id, event.id);
+    assert_eq!(results[0].1.text, "short feed, sleepy");
+
+    state.show_event(&results[0].0, Some(connection)).unwrap();
+    assert_eq!(state.get_filter().unwrap().date(), date);
+    assert_eq!(state.get_selected_event().unwrap().id, event.id);
+    assert_eq!(state.get_note(event.id), Some("short feed, sleepy"));
+}
+
+#[test]
+fn test_caregivers() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+    state.load_events(Some(connection)).unwrap();
+    assert!(state.get_caregivers().unwrap().is_empty());
+    assert!(state.get_caregiver().is_none());
+
+    let date = chrono::NaiveDate::from_ymd_opt(2023, 6, 15).unwrap();
+    let mut new_event: NewBabyEvent = create_event(None, None, None, None, Some(90), None, None);
+    new_event.dt = date.and_hms_opt(2, 0, 0).unwrap();
+    state.add_event(new_event, Some(connection)).unwrap();
+
+    // a new caregiver records the events from then on
+    for name in ["Sam", "Alex"] {
+        let new_caregiver = NewCaregiver {
+            name: name.to_owned(),
+        };
+        state
+            .add_caregiver(new_caregiver, Some(connection))
+            .unwrap();
+    }
+    assert_eq!(state.get_caregivers().unwrap().len(), 2);
+    assert_eq!(state.get_caregiver().unwrap().name, "Alex");
+    let alex = state.get_caregiver().unwrap().id;
+
+    new_event.dt = date.and_hms_opt(3, 0, 0).unwrap();
+    state.add_event(new_event, Some(connection)).unwrap();
+    assert_eq!(state.get_selected_event().unwrap().caregiver_id, Some(alex));
+
+    // switching goes through nobody before wrapping around
+    state.switch_caregiver();
+    assert!(state.get_caregiver().is_none());
+    state.switch_caregiver();
+    assert_eq!(state.get_caregiver().unwrap().name, "Sam");
+    let sam = state.get_caregiver().unwrap().id;
+
+    new_event.dt = date.and_hms_opt(4, 0, 0).unwrap();
+    state.add_event(new_event, Some(connection)).unwrap();
+    new_event.dt = date.and_hms_opt(14, 0, 0).unwrap();
+    state.add_event(new_event, Some(connection)).unwrap();
+    assert_eq!(state.get_filtered_events().unwrap().len(), 4);
+
+    // the caregiver filter shows one caregiver at a time, then everyone
+    state.switch_caregiver_filter(Some(connection)).unwrap();
+    assert_eq!(state.get_caregiver_filter().unwrap().id, sam);
+    let events = state.get_filtered_events().unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(events.iter().all(|e| e.caregiver_id == Some(sam)));
+
+    state.switch_caregiver_filter(Some(connection)).unwrap();
+    assert_eq!(state.get_caregiver_filter().unwrap().id, alex);
+    assert_eq!(state.get_filtered_events().unwrap().len(), 1);
+
+    state.switch_caregiver_filter(Some(connection)).unwrap();
+    assert!(state.get_caregiver_filter().is_none());
+    assert_eq!(state.get_filtered_events().unwrap().len(), 4);
+
+    // who did the night feeds
+    let statistics = babyrs::calculate_caregiver_statistics(
+        state.get_filtered_events().unwrap().clone(),
+        &chrono::Local,
+    );
+    let night_feedings = statistics
+        .iter()
+        .map(|s| (s.caregiver_id, s.night_feedings))
+        .collect::<Vec<(Option<i32>, i32)>>();
+    assert_eq!(night_feedings, [(None, 1), (Some(sam), 1), (Some(alex), 1)]);
+
+    // names are unique
+    let duplicate = NewCaregiver {
+        name: "Sam".to_owned(),
+    };
+    assert!(state.add_caregiver(duplicate, Some(connection)).is_err());
+}
+
+#[test]
+fn test_volume_unit() {
+    std::env::set_var("DATABASE_URL", ":memory:");
+    let mut state = terminal::state::AppState::initialized();
+    let connection: &mut SqliteConnection = &mut establish_connection().unwrap();
+
+    common::run_migrations(connection).expect("Error running migrations");
+    state.load_events(Some(connection)).unwrap();
+    assert_eq!(state.get_volume_unit(), VolumeUnit::Millilitre);
+
+    state.switch_volume_unit();
+    assert_eq!(state.get_volume_unit(), VolumeUnit::UsFluidOunce);
+    state.switch_volume_unit();
+    assert_eq!(state.get_volume_unit(), VolumeUnit::ImperialFluidOunce);
+    state.switch_volume_unit();
+    assert_eq!(state.get_volume_unit(), VolumeUnit::Millilitre);
+
+    let mut new_event: NewBabyEvent = create_event(None, None, None, None, Some(120), None, None);
+    new_event.dt = chrono::Local::now().naive_local();
+    state.add_event(new_event, Some(connection)).unwrap();
+
+    // the shown events are exported in the current unit
+    state.switch_volume_unit();
+    let path = std::env::temp_dir().join(format!("babyrs-state-export-{}.csv", std::process::id()));
+    let path = path.to_str().unwrap();
+    assert_eq!(state.export_csv(path).unwrap(), 1);
+
+    let exported = std::fs::read_to_string(path).unwrap();
+    assert!(exported.contains(",4.06,"));
+    assert!(exported.trim_end().ends_with(",fl oz"));
+
+    std::fs::remove_file(path).unwrap();
+}